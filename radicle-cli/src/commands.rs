@@ -34,6 +34,10 @@ pub mod rad_inspect;
 pub mod rad_issue;
 #[path = "commands/ls.rs"]
 pub mod rad_ls;
+#[path = "commands/milestone.rs"]
+pub mod rad_milestone;
+#[path = "commands/mirror.rs"]
+pub mod rad_mirror;
 #[path = "commands/node.rs"]
 pub mod rad_node;
 #[path = "commands/patch.rs"]
@@ -50,6 +54,8 @@ pub mod rad_seed;
 pub mod rad_self;
 #[path = "commands/stats.rs"]
 pub mod rad_stats;
+#[path = "commands/storage.rs"]
+pub mod rad_storage;
 #[path = "commands/sync.rs"]
 pub mod rad_sync;
 #[path = "commands/unblock.rs"]
@@ -137,6 +137,7 @@ enum EmbedContent {
 enum FilteredTypeName {
     Issue,
     Patch,
+    Milestone,
     Identity,
     Other(cob::TypeName),
 }
@@ -147,6 +148,8 @@ impl From<cob::TypeName> for FilteredTypeName {
             FilteredTypeName::Issue
         } else if value == *cob::patch::TYPENAME {
             FilteredTypeName::Patch
+        } else if value == *cob::milestone::TYPENAME {
+            FilteredTypeName::Milestone
         } else if value == *cob::identity::TYPENAME {
             FilteredTypeName::Identity
         } else {
@@ -160,6 +163,7 @@ impl AsRef<cob::TypeName> for FilteredTypeName {
         match self {
             FilteredTypeName::Issue => &cob::issue::TYPENAME,
             FilteredTypeName::Patch => &cob::patch::TYPENAME,
+            FilteredTypeName::Milestone => &cob::milestone::TYPENAME,
             FilteredTypeName::Identity => &cob::identity::TYPENAME,
             FilteredTypeName::Other(value) => value,
         }
@@ -385,6 +389,15 @@ pub fn run(Options { op }: Options, ctx: impl term::Context) -> anyhow::Result<(
                     let (oid, _) = store.create(&message, actions, embeds, signer)?;
                     oid
                 }
+                Milestone => {
+                    use radicle::cob::cache::Update as _;
+
+                    let store: Store<cob::milestone::Milestone, _> = Store::open(&repo)?;
+                    let actions = read_jsonl_actions(reader)?;
+                    let (oid, milestone) = store.create(&message, actions, embeds, signer)?;
+                    profile.cobs_db_mut()?.update(&rid, &oid, &milestone)?;
+                    oid
+                }
                 Identity => anyhow::bail!(
                     "Creation of collaborative objects of type {} is not supported.",
                     &type_name
@@ -487,6 +500,16 @@ pub fn run(Options { op }: Options, ctx: impl term::Context) -> anyhow::Result<(
                         Ok(())
                     })?
                 }
+                Milestone => {
+                    let actions: Vec<cob::milestone::Action> = read_jsonl(reader)?;
+                    let mut milestones = profile.milestones_mut(&repo)?;
+                    let mut milestone = milestones.get_mut(oid)?;
+                    milestone.transaction(&message, &*profile.signer()?, |tx| {
+                        tx.extend(actions)?;
+                        tx.embed(embeds)?;
+                        Ok(())
+                    })?
+                }
                 Identity => anyhow::bail!(
                     "Update of collaborative objects of type {} is not supported.",
                     &type_name
@@ -562,6 +585,21 @@ fn show(
                 stdout.write_all(b"\n")?;
             }
         }
+        FilteredTypeName::Milestone => {
+            use radicle::milestone::cache::Milestones as _;
+            let milestones = term::cob::milestones(profile, repo)?;
+            for oid in oids {
+                let oid = &oid.resolve(&repo.backend)?;
+                let Some(milestone) = milestones.get(oid)? else {
+                    bail!(cob::store::Error::NotFound(
+                        type_name.as_ref().clone(),
+                        *oid
+                    ));
+                };
+                serde_json::to_writer(&stdout, &milestone)?;
+                stdout.write_all(b"\n")?;
+            }
+        }
         FilteredTypeName::Other(type_name) => {
             let store =
                 cob::store::Store::<cob::external::External, _>::open_for(&type_name, repo)?;
@@ -26,6 +26,8 @@ const COMMANDS: &[Help] = &[
     rad_inspect::HELP,
     rad_issue::HELP,
     rad_ls::HELP,
+    rad_milestone::HELP,
+    rad_mirror::HELP,
     rad_node::HELP,
     rad_patch::HELP,
     rad_path::HELP,
@@ -38,6 +40,7 @@ const COMMANDS: &[Help] = &[
     rad_unseed::HELP,
     rad_remote::HELP,
     rad_stats::HELP,
+    rad_storage::HELP,
     rad_sync::HELP,
 ];
 
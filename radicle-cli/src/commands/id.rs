@@ -1,3 +1,4 @@
+use std::collections::btree_map::Entry;
 use std::collections::BTreeSet;
 use std::str::FromStr;
 use std::{ffi::OsString, io};
@@ -5,7 +6,7 @@ use std::{ffi::OsString, io};
 use anyhow::{anyhow, Context};
 
 use radicle::cob::identity::{self, IdentityMut, Revision, RevisionId};
-use radicle::identity::{doc, Doc, Identity, PayloadError, RawDoc, Visibility};
+use radicle::identity::{doc, payloads, Doc, Identity, PayloadError, RawDoc, Visibility};
 use radicle::node::device::Device;
 use radicle::node::NodeId;
 use radicle::prelude::{Did, RepoId};
@@ -453,18 +454,24 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 }
 
                 for (id, key, val) in payload {
-                    if let Some(ref mut payload) = proposal.payload.get_mut(&id) {
-                        if let Some(obj) = payload.as_object_mut() {
-                            if val.is_null() {
-                                obj.remove(&key);
-                            } else {
-                                obj.insert(key, val);
-                            }
+                    // Known payloads that don't exist yet, eg. `xyz.radicle.merge`, are created.
+                    let payload = match proposal.payload.entry(id.clone()) {
+                        Entry::Occupied(e) => e.into_mut(),
+                        Entry::Vacant(e) if payloads::lookup(&id).is_some() => {
+                            e.insert(doc::Payload::from(json::json!({})))
+                        }
+                        Entry::Vacant(_) => {
+                            anyhow::bail!("payload `{id}` not found in identity document");
+                        }
+                    };
+                    if let Some(obj) = payload.as_object_mut() {
+                        if val.is_null() {
+                            obj.remove(&key);
                         } else {
-                            anyhow::bail!("payload `{id}` is not a map");
+                            obj.insert(key, val);
                         }
                     } else {
-                        anyhow::bail!("payload `{id}` not found in identity document");
+                        anyhow::bail!("payload `{id}` is not a map");
                     }
                 }
                 proposal
@@ -489,9 +496,10 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 proposal
             };
 
-            // Verify that the project payload can still be parsed into the `Project` type.
-            if let Err(PayloadError::Json(e)) = proposal.project() {
-                anyhow::bail!("failed to verify `xyz.radicle.project`, {e}");
+            // Verify that the known payloads, eg. `xyz.radicle.project`, can still be parsed
+            // into their types.
+            if let Err(PayloadError::Invalid(id, e)) = proposal.verify_payloads() {
+                anyhow::bail!("failed to verify `{id}`, {e}");
             }
             let proposal = proposal.verified()?;
             if proposal == current.doc {
@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::ffi::OsString;
 use std::path::Path;
 use std::process;
@@ -6,7 +7,8 @@ use anyhow::anyhow;
 
 use git_ref_format::Qualified;
 use localtime::LocalTime;
-use radicle::cob::TypedId;
+use radicle::cob::filter::Filter;
+use radicle::cob::{ObjectId, TypedId};
 use radicle::identity::Identity;
 use radicle::issue::cache::Issues as _;
 use radicle::node::notifications;
@@ -51,6 +53,9 @@ Options
     --sort-by <field>    Sort by `id` or `timestamp` (default: timestamp)
     --reverse, -r        Reverse the list
     --show-unknown       Show any updates that were not recognized
+    --filter <expr>      Show only updates to issues and patches matching the
+                         filter expression, eg. `label:bug state:open`.
+                         See `rad issue --help` for the filter syntax
     --help               Print help
 "#,
 };
@@ -83,6 +88,7 @@ pub struct Options {
     mode: Mode,
     sort_by: SortBy,
     show_unknown: bool,
+    filter: Option<Filter>,
 }
 
 impl Args for Options {
@@ -96,6 +102,7 @@ impl Args for Options {
         let mut reverse = None;
         let mut field = None;
         let mut show_unknown = false;
+        let mut filter = None;
 
         while let Some(arg) = parser.next()? {
             match arg {
@@ -111,6 +118,11 @@ impl Args for Options {
                 Long("show-unknown") => {
                     show_unknown = true;
                 }
+                Long("filter") => {
+                    let val = parser.value()?;
+
+                    filter = Some(args::filter(&val)?);
+                }
                 Long("sort-by") => {
                     let val = parser.value()?;
 
@@ -168,6 +180,7 @@ impl Args for Options {
                 mode,
                 sort_by,
                 show_unknown,
+                filter,
             },
             vec![],
         ))
@@ -183,6 +196,7 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         mode,
         sort_by,
         show_unknown,
+        filter,
     } = options;
 
     match op {
@@ -190,6 +204,7 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
             mode,
             sort_by,
             show_unknown,
+            filter.as_ref(),
             &notifs.read_only(),
             storage,
             &profile,
@@ -203,6 +218,7 @@ fn list(
     mode: Mode,
     sort_by: SortBy,
     show_unknown: bool,
+    filter: Option<&Filter>,
     notifs: &notifications::StoreReader,
     storage: &Storage,
     profile: &Profile,
@@ -210,17 +226,19 @@ fn list(
     let repos: Vec<term::VStack<'_>> = match mode {
         Mode::Contextual => {
             if let Ok((_, rid)) = radicle::rad::cwd() {
-                list_repo(rid, sort_by, show_unknown, notifs, storage, profile)?
+                list_repo(rid, sort_by, show_unknown, filter, notifs, storage, profile)?
                     .into_iter()
                     .collect()
             } else {
-                list_all(sort_by, show_unknown, notifs, storage, profile)?
+                list_all(sort_by, show_unknown, filter, notifs, storage, profile)?
             }
         }
-        Mode::ByRepo(rid) => list_repo(rid, sort_by, show_unknown, notifs, storage, profile)?
-            .into_iter()
-            .collect(),
-        Mode::All => list_all(sort_by, show_unknown, notifs, storage, profile)?,
+        Mode::ByRepo(rid) => {
+            list_repo(rid, sort_by, show_unknown, filter, notifs, storage, profile)?
+                .into_iter()
+                .collect()
+        }
+        Mode::All => list_all(sort_by, show_unknown, filter, notifs, storage, profile)?,
         Mode::ById(_) => anyhow::bail!("the `list` command does not take IDs"),
     };
 
@@ -237,6 +255,7 @@ fn list(
 fn list_all<'a>(
     sort_by: SortBy,
     show_unknown: bool,
+    filter: Option<&Filter>,
     notifs: &notifications::StoreReader,
     storage: &Storage,
     profile: &Profile,
@@ -246,7 +265,15 @@ fn list_all<'a>(
 
     let mut vstacks = Vec::new();
     for repo in repos {
-        let vstack = list_repo(repo.rid, sort_by, show_unknown, notifs, storage, profile)?;
+        let vstack = list_repo(
+            repo.rid,
+            sort_by,
+            show_unknown,
+            filter,
+            notifs,
+            storage,
+            profile,
+        )?;
         vstacks.extend(vstack.into_iter());
     }
     Ok(vstacks)
@@ -256,6 +283,7 @@ fn list_repo<'a, R: ReadStorage>(
     rid: RepoId,
     sort_by: SortBy,
     show_unknown: bool,
+    filter: Option<&Filter>,
     notifs: &notifications::StoreReader,
     storage: &R,
     profile: &Profile,
@@ -274,6 +302,23 @@ where
     let issues = term::cob::issues(profile, &repo)?;
     let patches = term::cob::patches(profile, &repo)?;
 
+    // Issues and patches matching the filter, if any.
+    let matching = match filter {
+        Some(filter) => {
+            let mut ids = BTreeSet::<ObjectId>::new();
+            for result in issues.filter(filter)? {
+                let (id, _) = result?;
+                ids.insert(id);
+            }
+            for result in patches.filter(filter)? {
+                let (id, _) = result?;
+                ids.insert(id);
+            }
+            Some(ids)
+        }
+        None => None,
+    };
+
     let mut notifs = notifs.by_repo(&rid, sort_by.field)?.collect::<Vec<_>>();
     if !sort_by.reverse {
         // Notifications are returned in descendant order by default.
@@ -283,6 +328,16 @@ where
     for n in notifs {
         let n: Notification = n?;
 
+        if let Some(ids) = &matching {
+            // Only updates to issues and patches can match a filter.
+            let NotificationKind::Cob { typed_id } = &n.kind else {
+                continue;
+            };
+            if !ids.contains(&typed_id.id) {
+                continue;
+            }
+        }
+
         let seen = if n.status.is_read() {
             term::Label::blank()
         } else {
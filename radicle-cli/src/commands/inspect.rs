@@ -7,12 +7,15 @@ use std::str::FromStr;
 use anyhow::Context as _;
 use chrono::prelude::*;
 
+use radicle::git;
+use radicle::identity::doc::{Payload, PayloadId};
+use radicle::identity::payloads;
 use radicle::identity::RepoId;
 use radicle::identity::{DocAt, Identity};
 use radicle::node::policy::SeedingPolicy;
 use radicle::node::AliasStore as _;
 use radicle::storage::git::{Repository, Storage};
-use radicle::storage::refs::RefsAt;
+use radicle::storage::refs::{Refs, RefsAt, SignedRefsAt};
 use radicle::storage::{ReadRepository, ReadStorage};
 
 use crate::terminal as term;
@@ -37,14 +40,16 @@ Usage
 Options
 
     --rid        Return the repository identifier (RID)
-    --payload    Inspect the repository's identity payload
+    --payload    Inspect the repository's identity payloads. Known payloads
+                 are validated, and invalid ones are marked as such
     --refs       Inspect the repository's refs on the local device
     --sigrefs    Inspect the values of `rad/sigrefs` for all remotes of this repository
     --identity   Inspect the identity document
     --visibility Inspect the repository's visibility
     --delegates  Inspect the repository's delegates
     --policy     Inspect the repository's seeding policy
-    --history    Show the history of the repository identity document
+    --history    Show the history of the repository identity document, or
+                 of `rad/sigrefs` for all remotes when used with `--sigrefs`
     --help       Print help
 "#,
 };
@@ -57,6 +62,7 @@ pub enum Target {
     Identity,
     Visibility,
     Sigrefs,
+    SigrefsHistory,
     Policy,
     History,
     #[default]
@@ -94,12 +100,18 @@ impl Args for Options {
                 Long("delegates") => {
                     target = Target::Delegates;
                 }
+                Long("history") if target == Target::Sigrefs => {
+                    target = Target::SigrefsHistory;
+                }
                 Long("history") => {
                     target = Target::History;
                 }
                 Long("identity") => {
                     target = Target::Identity;
                 }
+                Long("sigrefs") if target == Target::History => {
+                    target = Target::SigrefsHistory;
+                }
                 Long("sigrefs") => {
                     target = Target::Sigrefs;
                 }
@@ -150,7 +162,9 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
         }
         Target::Payload => {
             let (_, doc) = repo(rid, storage)?;
-            json::to_pretty(&doc.payload(), Path::new("radicle.json"))?.print();
+            for (id, payload) in doc.payload() {
+                self::payload(id, payload).print();
+            }
         }
         Target::Identity => {
             let (_, doc) = repo(rid, storage)?;
@@ -169,6 +183,10 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 );
             }
         }
+        Target::SigrefsHistory => {
+            let (repo, _) = repo(rid, storage)?;
+            sigrefs_history(&repo)?;
+        }
         Target::Policy => {
             let policies = profile.policies()?;
             let seed = policies.seed_policy(&rid)?;
@@ -226,19 +244,7 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                     continue;
                 }
                 let doc = &revision.doc;
-                let timezone = if tip.time().sign() == '+' {
-                    #[allow(deprecated)]
-                    FixedOffset::east(tip.time().offset_minutes() * 60)
-                } else {
-                    #[allow(deprecated)]
-                    FixedOffset::west(tip.time().offset_minutes() * 60)
-                };
-                let time = DateTime::<Utc>::from(
-                    std::time::UNIX_EPOCH
-                        + std::time::Duration::from_secs(tip.time().seconds() as u64),
-                )
-                .with_timezone(&timezone)
-                .to_rfc2822();
+                let time = time(&tip);
 
                 println!(
                     "{} {}",
@@ -277,6 +283,135 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Render an identity payload. Known payloads are normalized to their typed
+/// representation, see [`payloads::REGISTRY`].
+fn payload<'a>(id: &PayloadId, payload: &Payload) -> term::VStack<'a> {
+    let (name, value, error) = match payloads::lookup(id) {
+        Some(known) => match known.normalize(payload) {
+            Ok(value) => (term::format::bold(known.name.to_owned()), value, None),
+            Err(e) => (
+                term::format::bold(known.name.to_owned()),
+                (**payload).clone(),
+                Some(e),
+            ),
+        },
+        None => (
+            term::format::italic(String::from("Unknown")),
+            (**payload).clone(),
+            None,
+        ),
+    };
+    let mut fields = term::Table::<2, term::Label>::new(term::TableOptions {
+        spacing: 2,
+        ..term::TableOptions::default()
+    });
+    for (key, value) in flatten(String::new(), &value) {
+        fields.push([term::format::dim(key).into(), term::label(value)]);
+    }
+    let mut widget = term::VStack::default()
+        .border(Some(term::colors::FAINT))
+        .child(term::Line::spaced([
+            term::label(name),
+            term::label(term::format::tertiary(id.to_string())),
+        ]));
+
+    if let Some(e) = error {
+        widget = widget
+            .divider()
+            .child(term::label(term::format::negative(format!(
+                "✗ Invalid: {e}"
+            ))));
+    }
+    if !fields.is_empty() {
+        widget = widget.divider().child(fields);
+    }
+    widget
+}
+
+/// Flatten a JSON value into a list of dotted keys and their displayed values.
+fn flatten(prefix: String, value: &serde_json::Value) -> Vec<(String, String)> {
+    use serde_json::Value;
+
+    let scalar = |v: &Value| match v {
+        Value::String(s) => Some(s.clone()),
+        Value::Null | Value::Bool(_) | Value::Number(_) => Some(v.to_string()),
+        Value::Array(_) | Value::Object(_) => None,
+    };
+
+    match value {
+        Value::Object(map) if !map.is_empty() => map
+            .iter()
+            .flat_map(|(key, value)| {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(key, value)
+            })
+            .collect(),
+        Value::Array(values) => match values.iter().map(scalar).collect::<Option<Vec<_>>>() {
+            Some(values) => vec![(prefix, values.join(", "))],
+            None => vec![(prefix, value.to_string())],
+        },
+        value => vec![(prefix, scalar(value).unwrap_or_else(|| value.to_string()))],
+    }
+}
+
+/// Show the history of `rad/sigrefs` for all remotes, with the ref updates of each commit.
+fn sigrefs_history(repo: &Repository) -> anyhow::Result<()> {
+    for remote in repo.remote_ids()? {
+        let remote = remote?;
+        let Some(sigrefs) = SignedRefsAt::load(remote, repo)? else {
+            continue;
+        };
+        let history = sigrefs.history(repo).collect::<Result<Vec<_>, _>>()?;
+
+        println!("{}", term::format::tertiary(remote.to_human()));
+
+        for (i, entry) in history.iter().enumerate() {
+            let parent = history.get(i + 1);
+            let tip = repo.commit(entry.at)?;
+
+            println!(
+                "{} {}",
+                term::format::yellow("commit"),
+                term::format::yellow(entry.at),
+            );
+            if let Some(parent) = parent {
+                println!("parent {}", parent.at);
+            }
+            println!("date   {}", time(&tip));
+            println!();
+
+            let empty = Refs::default();
+            let old = parent.map(|p| &p.refs).unwrap_or(&empty);
+
+            for update in old.diff(&entry.refs, repo) {
+                term::indented(term::format::ref_update_verbose(&update));
+            }
+            println!();
+        }
+    }
+    Ok(())
+}
+
+/// Format the commit time of a commit, in its own timezone.
+fn time(commit: &git::raw::Commit) -> String {
+    let timezone = if commit.time().sign() == '+' {
+        #[allow(deprecated)]
+        FixedOffset::east(commit.time().offset_minutes() * 60)
+    } else {
+        #[allow(deprecated)]
+        FixedOffset::west(commit.time().offset_minutes() * 60)
+    };
+    DateTime::<Utc>::from(
+        std::time::UNIX_EPOCH + std::time::Duration::from_secs(commit.time().seconds() as u64),
+    )
+    .with_timezone(&timezone)
+    .to_rfc2822()
+}
+
 fn repo(rid: RepoId, storage: &Storage) -> anyhow::Result<(Repository, DocAt)> {
     let repo = storage
         .repository(rid)
@@ -290,8 +425,10 @@ fn refs(repo: &radicle::storage::git::Repository) -> anyhow::Result<()> {
     let mut refs = Vec::new();
     for r in repo.references()? {
         let r = r?;
-        if let Some(namespace) = r.namespace {
-            refs.push(format!("{}/{}", namespace, r.name));
+        match r.namespace {
+            Some(namespace) => refs.push(format!("{}/{}", namespace, r.name)),
+            // Canonical references, agreed on by the delegates.
+            None => refs.push(r.name.to_string()),
         }
     }
 
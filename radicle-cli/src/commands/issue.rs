@@ -8,9 +8,11 @@ use std::str::FromStr;
 use anyhow::{anyhow, Context as _};
 
 use radicle::cob::common::{Label, Reaction};
+use radicle::cob::filter::Filter;
 use radicle::cob::issue::{CloseReason, State};
 use radicle::cob::{issue, thread};
 use radicle::crypto;
+use radicle::identity::Template;
 use radicle::issue::cache::Issues as _;
 use radicle::node::device::Device;
 use radicle::node::NodeId;
@@ -40,16 +42,37 @@ Usage
     rad issue [<option>...]
     rad issue delete <issue-id> [<option>...]
     rad issue edit <issue-id> [--title <title>] [--description <text>] [<option>...]
-    rad issue list [--assigned <did>] [--all | --closed | --open | --solved] [<option>...]
-    rad issue open [--title <title>] [--description <text>] [--label <label>] [<option>...]
+    rad issue list [--assigned <did>] [--all | --closed | --open | --solved] [--search <query>] [--filter <expr>] [<option>...]
+    rad issue open [--title <title>] [--description <text>] [--label <label>] [--template <name>] [<option>...]
     rad issue react <issue-id> [--emoji <char>] [--to <comment>] [<option>...]
     rad issue assign <issue-id> [--add <did>] [--delete <did>] [<option>...]
     rad issue label <issue-id> [--add <label>] [--delete <label>] [<option>...]
+    rad issue link <issue-id> [<link-option>...] [<option>...]
+    rad issue unlink <issue-id> [<link-option>...] [<option>...]
     rad issue comment <issue-id> [--message <message>] [--reply-to <comment-id>] [--edit <comment-id>] [<option>...]
     rad issue show <issue-id> [<option>...]
     rad issue state <issue-id> [--closed | --open | --solved] [<option>...]
     rad issue cache [<issue-id>] [--storage] [<option>...]
 
+List options
+
+    --search <query>       Show only issues whose title, description or comments
+                           match all the terms of the query, most relevant first.
+                           Terms ending with `*` match as prefixes
+    --filter <expr>        Show only issues matching all the terms of the filter
+                           expression, eg. `label:bug -label:wontfix state:open`.
+                           Terms are `label:<label>`, `author:<did>`,
+                           `assignee:<did>`, `state:<open|closed|solved>`,
+                           `created:<date>` and `updated:<date>`, where dates are
+                           `YYYY-MM-DD`, optionally prefixed by `>`, `>=`, `<` or `<=`.
+                           Terms starting with `-` are negated; other words match
+                           the issue text
+
+Open options
+
+    --template <name>      Start from the given issue template published by the
+                           repository delegates (default: `default`, if published)
+
 Assign options
 
     -a, --add    <did>     Add an assignee to the issue (may be specified multiple times).
@@ -64,6 +87,17 @@ Label options
 
     Note: --add takes precedence over --delete
 
+Link options
+
+    --blocks <issue-id>          The issue blocks the given issue
+    --blocked-by <issue-id>      The issue is blocked by the given issue
+    --duplicate-of <issue-id>    The issue is a duplicate of the given issue
+    --fixed-by <patch-id>        The issue is fixed by the given patch
+    --references <patch-id>      The issue is referenced by the given patch
+
+    Note: when a patch that fixes an issue is merged, the issue is solved.
+          Only the issue author and delegates can use --fixed-by.
+
 Show options
 
         --debug                Show the issue as Rust debug output
@@ -86,6 +120,8 @@ pub enum OperationName {
     Comment,
     Delete,
     Label,
+    Link,
+    Unlink,
     #[default]
     List,
     React,
@@ -114,6 +150,7 @@ pub enum Operation {
         description: Option<String>,
         labels: Vec<Label>,
         assignees: Vec<Did>,
+        template: Option<String>,
     },
     Show {
         id: Rev,
@@ -150,9 +187,19 @@ pub enum Operation {
         id: Rev,
         opts: LabelOptions,
     },
+    Link {
+        id: Rev,
+        links: Vec<(issue::Relation, Rev)>,
+    },
+    Unlink {
+        id: Rev,
+        links: Vec<(issue::Relation, Rev)>,
+    },
     List {
         assigned: Option<Assigned>,
         state: Option<State>,
+        search: Option<String>,
+        filter: Option<Filter>,
     },
     Cache {
         id: Option<Rev>,
@@ -195,6 +242,10 @@ impl Args for Options {
         let mut state: Option<State> = Some(State::Open);
         let mut labels = Vec::new();
         let mut assignees = Vec::new();
+        let mut template = None;
+        let mut search = None;
+        let mut filter: Option<Filter> = None;
+        let mut list_state = false;
         let mut format = Format::default();
         let mut message = Message::default();
         let mut reply_to = None;
@@ -204,6 +255,7 @@ impl Args for Options {
         let mut debug = false;
         let mut assign_opts = AssignOptions::default();
         let mut label_opts = LabelOptions::default();
+        let mut links = Vec::new();
         let mut repo = None;
         let mut cache_storage = false;
 
@@ -216,19 +268,33 @@ impl Args for Options {
                 // List options.
                 Long("all") if op.is_none() || op == Some(OperationName::List) => {
                     state = None;
+                    list_state = true;
                 }
                 Long("closed") if op.is_none() || op == Some(OperationName::List) => {
                     state = Some(State::Closed {
                         reason: CloseReason::Other,
                     });
+                    list_state = true;
                 }
                 Long("open") if op.is_none() || op == Some(OperationName::List) => {
                     state = Some(State::Open);
+                    list_state = true;
                 }
                 Long("solved") if op.is_none() || op == Some(OperationName::List) => {
                     state = Some(State::Closed {
                         reason: CloseReason::Solved,
                     });
+                    list_state = true;
+                }
+                Long("search") if op.is_none() || op == Some(OperationName::List) => {
+                    let val = parser.value()?;
+
+                    search = Some(term::args::string(&val));
+                }
+                Long("filter") if op.is_none() || op == Some(OperationName::List) => {
+                    let val = parser.value()?;
+
+                    filter = Some(term::args::filter(&val)?);
                 }
 
                 // Open/Edit options.
@@ -255,6 +321,11 @@ impl Args for Options {
 
                     assignees.push(did);
                 }
+                Long("template") if op == Some(OperationName::Open) => {
+                    let val = parser.value()?;
+
+                    template = Some(term::args::string(&val));
+                }
 
                 // State options.
                 Long("closed") if op == Some(OperationName::State) => {
@@ -352,6 +423,24 @@ impl Args for Options {
                     label_opts.delete.insert(label);
                 }
 
+                // Link options.
+                Long(
+                    relation @ ("blocks" | "blocked-by" | "duplicate-of" | "fixed-by"
+                    | "references"),
+                ) if matches!(op, Some(OperationName::Link | OperationName::Unlink)) => {
+                    let relation = match relation {
+                        "blocks" => issue::Relation::Blocks,
+                        "blocked-by" => issue::Relation::BlockedBy,
+                        "duplicate-of" => issue::Relation::DuplicateOf,
+                        "fixed-by" => issue::Relation::FixedBy,
+                        _ => issue::Relation::References,
+                    };
+                    let val = parser.value()?;
+                    let rev = term::args::rev(&val)?;
+
+                    links.push((relation, rev));
+                }
+
                 // Cache options.
                 Long("storage") if matches!(op, Some(OperationName::Cache)) => {
                     cache_storage = true;
@@ -382,6 +471,8 @@ impl Args for Options {
                     "s" | "state" => op = Some(OperationName::State),
                     "assign" => op = Some(OperationName::Assign),
                     "label" => op = Some(OperationName::Label),
+                    "link" => op = Some(OperationName::Link),
+                    "unlink" => op = Some(OperationName::Unlink),
                     "cache" => op = Some(OperationName::Cache),
 
                     unknown => anyhow::bail!("unknown operation '{}'", unknown),
@@ -407,6 +498,7 @@ impl Args for Options {
                 description,
                 labels,
                 assignees,
+                template,
             },
             OperationName::Comment => match (reply_to, edit_comment) {
                 (None, None) => Operation::Comment {
@@ -451,7 +543,24 @@ impl Args for Options {
                 id: id.ok_or_else(|| anyhow!("an issue to label must be provided"))?,
                 opts: label_opts,
             },
-            OperationName::List => Operation::List { assigned, state },
+            OperationName::Link => Operation::Link {
+                id: id.ok_or_else(|| anyhow!("an issue to link must be provided"))?,
+                links,
+            },
+            OperationName::Unlink => Operation::Unlink {
+                id: id.ok_or_else(|| anyhow!("an issue to unlink must be provided"))?,
+                links,
+            },
+            OperationName::List => Operation::List {
+                assigned,
+                // A `state` term in the filter overrides the default state.
+                state: match &filter {
+                    Some(f) if f.has_state() && !list_state => None,
+                    _ => state,
+                },
+                search,
+                filter,
+            },
             OperationName::Cache => Operation::Cache {
                 id,
                 storage: cache_storage,
@@ -487,6 +596,8 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 | Operation::Delete { .. }
                 | Operation::Assign { .. }
                 | Operation::Label { .. }
+                | Operation::Link { .. }
+                | Operation::Unlink { .. }
                 | Operation::Edit { .. }
                 | Operation::Comment { .. }
         );
@@ -509,8 +620,11 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
             description: Some(description),
             labels,
             assignees,
+            template,
         } => {
             let signer = term::signer(&profile)?;
+            let template = issue_template(&repo, template.as_deref())?;
+            let labels = template_labels(labels, template.as_ref());
             let issue = issues.create(title, description, &labels, &assignees, [], &signer)?;
             if !options.quiet {
                 term::issue::show(&issue, issue.id(), Format::Header, &profile)?;
@@ -610,12 +724,29 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
             ref description,
             ref labels,
             ref assignees,
+            ref template,
         } => {
             let signer = term::signer(&profile)?;
+            let template = issue_template(&repo, template.as_deref())?;
+            let labels = template_labels(labels.to_vec(), template.as_ref());
+            let body = template.map(|t| t.body).filter(|b| !b.trim().is_empty());
+            // Without a description, the template body pre-fills the editor.
+            let (title, description) = match (description.clone(), body) {
+                (None, Some(body)) => {
+                    match term::issue::get_title_description(title.clone(), Some(body))? {
+                        Some((title, description)) => (Some(title), Some(description)),
+                        None => anyhow::bail!(
+                            "aborting issue creation due to empty title or description"
+                        ),
+                    }
+                }
+                (description, _) => (title.clone(), description),
+            };
+
             open(
-                title.clone(),
-                description.clone(),
-                labels.to_vec(),
+                title,
+                description,
+                labels,
                 assignees.to_vec(),
                 &options,
                 &mut issues,
@@ -657,8 +788,48 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
                 .collect::<Vec<_>>();
             issue.label(labels, &signer)?;
         }
-        Operation::List { assigned, state } => {
-            list(issues, &assigned, &state, &profile)?;
+        Operation::Link { id, links } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let links = resolve_links(links, &repo)?;
+            let Ok(mut issue) = issues.get_mut(&id) else {
+                anyhow::bail!("Issue `{id}` not found");
+            };
+            issue.transaction("Link", &signer, |tx| {
+                for (relation, object) in links {
+                    tx.link(relation, object)?;
+                }
+                Ok(())
+            })?;
+        }
+        Operation::Unlink { id, links } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let links = resolve_links(links, &repo)?;
+            let Ok(mut issue) = issues.get_mut(&id) else {
+                anyhow::bail!("Issue `{id}` not found");
+            };
+            issue.transaction("Unlink", &signer, |tx| {
+                for (relation, object) in links {
+                    tx.unlink(relation, object)?;
+                }
+                Ok(())
+            })?;
+        }
+        Operation::List {
+            assigned,
+            state,
+            search,
+            filter,
+        } => {
+            list(
+                issues,
+                &assigned,
+                &state,
+                search.as_deref(),
+                filter.as_ref(),
+                &profile,
+            )?;
         }
         Operation::Delete { id } => {
             let signer = term::signer(&profile)?;
@@ -699,6 +870,8 @@ fn list<C>(
     cache: C,
     assigned: &Option<Assigned>,
     state: &Option<State>,
+    search: Option<&str>,
+    filter: Option<&Filter>,
     profile: &profile::Profile,
 ) -> anyhow::Result<()>
 where
@@ -716,7 +889,11 @@ where
     };
 
     let mut all = Vec::new();
-    let issues = cache.list()?;
+    let issues = match (search, filter) {
+        (Some(query), _) => cache.search(query)?,
+        (None, Some(filter)) => cache.filter(filter)?,
+        (None, None) => cache.list()?,
+    };
     for result in issues {
         let (id, issue) = match result {
             Ok((id, issue)) => (id, issue),
@@ -737,15 +914,24 @@ where
                 continue;
             }
         }
+        // Search results still need to be filtered.
+        if let (Some(_), Some(filter)) = (search, filter) {
+            if !filter.matches(&issue) {
+                continue;
+            }
+        }
         all.push((id, issue))
     }
 
-    all.sort_by(|(id1, i1), (id2, i2)| {
-        let by_timestamp = i2.timestamp().cmp(&i1.timestamp());
-        let by_id = id1.cmp(id2);
+    // Search results are ordered by relevance.
+    if search.is_none() {
+        all.sort_by(|(id1, i1), (id2, i2)| {
+            let by_timestamp = i2.timestamp().cmp(&i1.timestamp());
+            let by_id = id1.cmp(id2);
 
-        by_timestamp.then(by_id)
-    });
+            by_timestamp.then(by_id)
+        });
+    }
 
     let mut table = term::Table::new(term::table::TableOptions::bordered());
     table.header([
@@ -805,6 +991,34 @@ where
     Ok(())
 }
 
+/// Get the issue template with the given name, or the default issue template if any.
+fn issue_template(
+    repo: &storage::git::Repository,
+    name: Option<&str>,
+) -> anyhow::Result<Option<Template>> {
+    let templates = repo.identity_doc()?.templates()?.unwrap_or_default();
+
+    match templates.issue(name) {
+        Some(template) => Ok(Some(template.clone())),
+        None => match name {
+            Some(name) => anyhow::bail!("issue template `{name}` not found"),
+            None => Ok(None),
+        },
+    }
+}
+
+/// Add the labels of the template, if any, to the given labels.
+fn template_labels(mut labels: Vec<Label>, template: Option<&Template>) -> Vec<Label> {
+    if let Some(template) = template {
+        for label in &template.labels {
+            if !labels.contains(label) {
+                labels.push(label.clone());
+            }
+        }
+    }
+    labels
+}
+
 fn open<R, G>(
     title: Option<String>,
     description: Option<String>,
@@ -891,6 +1105,20 @@ where
     Ok(issue)
 }
 
+/// Resolve the objects of the given links.
+fn resolve_links(
+    links: Vec<(issue::Relation, Rev)>,
+    repo: &storage::git::Repository,
+) -> anyhow::Result<Vec<(issue::Relation, cob::ObjectId)>> {
+    if links.is_empty() {
+        anyhow::bail!("at least one link option must be provided, eg. `--fixed-by <patch-id>`");
+    }
+    links
+        .into_iter()
+        .map(|(relation, rev)| Ok((relation, rev.resolve(&repo.backend)?)))
+        .collect()
+}
+
 /// Get a comment from the user, by prompting.
 pub fn prompt_comment<R: WriteRepository + radicle::cob::Store<Namespace = NodeId>>(
     message: Message,
@@ -0,0 +1,546 @@
+#[path = "milestone/cache.rs"]
+mod cache;
+
+use std::ffi::OsString;
+
+use anyhow::{anyhow, Context as _};
+use chrono::prelude::*;
+
+use radicle::cob;
+use radicle::cob::issue::cache::Issues as _;
+use radicle::cob::milestone::cache::Milestones;
+use radicle::cob::milestone::State;
+use radicle::cob::patch::cache::Patches as _;
+use radicle::cob::Timestamp;
+use radicle::prelude::RepoId;
+use radicle::storage;
+use radicle::storage::WriteStorage;
+use radicle::{profile, Node};
+
+use crate::git::Rev;
+use crate::node;
+use crate::terminal as term;
+use crate::terminal::args::{Args, Error, Help};
+use crate::terminal::format::Author;
+use crate::terminal::Element as _;
+
+pub const HELP: Help = Help {
+    name: "milestone",
+    description: "Manage milestones",
+    version: env!("RADICLE_VERSION"),
+    usage: r#"
+Usage
+
+    rad milestone [<option>...]
+    rad milestone list [--all | --closed | --open] [<option>...]
+    rad milestone open --title <title> [--description <text>] [--due <date>] [<option>...]
+    rad milestone show <milestone-id> [<option>...]
+    rad milestone edit <milestone-id> [--title <title>] [--description <text>] [--due <date> | --no-due] [<option>...]
+    rad milestone attach <milestone-id> <issue-or-patch-id>... [<option>...]
+    rad milestone detach <milestone-id> <issue-or-patch-id>... [<option>...]
+    rad milestone close <milestone-id> [<option>...]
+    rad milestone reopen <milestone-id> [<option>...]
+    rad milestone delete <milestone-id> [<option>...]
+    rad milestone cache [<milestone-id>] [--storage] [<option>...]
+
+    A milestone groups issues and patches that are worked towards together.
+    Its progress is the number of closed issues and merged patches, out of
+    all the attached issues and patches.
+
+Open/Edit options
+
+    --title <title>            Milestone title
+    --description <text>       Milestone description
+    --due <date>               Due date, in the format YYYY-MM-DD
+    --no-due                   Remove the due date (edit only)
+
+Options
+
+        --repo <rid>       Operate on the given repository (default: cwd)
+        --no-announce      Don't announce milestone changes to peers
+    -q, --quiet            Don't print anything
+        --help             Print help
+"#,
+};
+
+#[derive(Default, Debug, PartialEq, Eq)]
+pub enum OperationName {
+    Attach,
+    Cache,
+    Close,
+    Delete,
+    Detach,
+    Edit,
+    #[default]
+    List,
+    Open,
+    Reopen,
+    Show,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Operation {
+    Attach {
+        id: Rev,
+        objects: Vec<Rev>,
+    },
+    Cache {
+        id: Option<Rev>,
+        storage: bool,
+    },
+    Delete {
+        id: Rev,
+    },
+    Detach {
+        id: Rev,
+        objects: Vec<Rev>,
+    },
+    Edit {
+        id: Rev,
+        title: Option<String>,
+        description: Option<String>,
+        due: Option<Option<Timestamp>>,
+    },
+    List {
+        state: Option<State>,
+    },
+    Open {
+        title: String,
+        description: String,
+        due: Option<Timestamp>,
+    },
+    Show {
+        id: Rev,
+    },
+    State {
+        id: Rev,
+        state: State,
+    },
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub op: Operation,
+    pub repo: Option<RepoId>,
+    pub announce: bool,
+    pub quiet: bool,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<OperationName> = None;
+        let mut id: Option<Rev> = None;
+        let mut objects: Vec<Rev> = Vec::new();
+        let mut title: Option<String> = None;
+        let mut description: Option<String> = None;
+        let mut due: Option<Option<Timestamp>> = None;
+        let mut state: Option<State> = Some(State::Open);
+        let mut announce = true;
+        let mut quiet = false;
+        let mut cache_storage = false;
+        let mut repo = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") | Short('h') => {
+                    return Err(Error::Help.into());
+                }
+
+                // List options.
+                Long("all") if op.is_none() || op == Some(OperationName::List) => {
+                    state = None;
+                }
+                Long("closed") if op.is_none() || op == Some(OperationName::List) => {
+                    state = Some(State::Closed);
+                }
+                Long("open") if op.is_none() || op == Some(OperationName::List) => {
+                    state = Some(State::Open);
+                }
+
+                // Open/Edit options.
+                Long("title")
+                    if op == Some(OperationName::Open) || op == Some(OperationName::Edit) =>
+                {
+                    title = Some(parser.value()?.to_string_lossy().into());
+                }
+                Long("description")
+                    if op == Some(OperationName::Open) || op == Some(OperationName::Edit) =>
+                {
+                    description = Some(parser.value()?.to_string_lossy().into());
+                }
+                Long("due")
+                    if op == Some(OperationName::Open) || op == Some(OperationName::Edit) =>
+                {
+                    let val = parser.value()?;
+                    let val = term::args::string(&val);
+
+                    due = Some(Some(date(&val)?));
+                }
+                Long("no-due") if op == Some(OperationName::Edit) => {
+                    due = Some(None);
+                }
+
+                // Cache options.
+                Long("storage") if op == Some(OperationName::Cache) => {
+                    cache_storage = true;
+                }
+
+                // Options.
+                Long("no-announce") => {
+                    announce = false;
+                }
+                Long("quiet") | Short('q') => {
+                    quiet = true;
+                }
+                Long("repo") => {
+                    let val = parser.value()?;
+                    let rid = term::args::rid(&val)?;
+
+                    repo = Some(rid);
+                }
+
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "l" | "list" => op = Some(OperationName::List),
+                    "o" | "open" => op = Some(OperationName::Open),
+                    "w" | "show" => op = Some(OperationName::Show),
+                    "e" | "edit" => op = Some(OperationName::Edit),
+                    "d" | "delete" => op = Some(OperationName::Delete),
+                    "attach" => op = Some(OperationName::Attach),
+                    "detach" => op = Some(OperationName::Detach),
+                    "close" => op = Some(OperationName::Close),
+                    "reopen" => op = Some(OperationName::Reopen),
+                    "cache" => op = Some(OperationName::Cache),
+
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val) if id.is_none() => {
+                    id = Some(term::args::rev(&val)?);
+                }
+                Value(val) if matches!(op, Some(OperationName::Attach | OperationName::Detach)) => {
+                    objects.push(term::args::rev(&val)?);
+                }
+                _ => {
+                    return Err(anyhow!(arg.unexpected()));
+                }
+            }
+        }
+
+        // The milestone is optional when caching.
+        let cache_id = id.clone();
+        let id = || id.ok_or_else(|| anyhow!("a milestone must be provided"));
+        let op = match op.unwrap_or_default() {
+            OperationName::List => Operation::List { state },
+            OperationName::Open => Operation::Open {
+                title: title.ok_or_else(|| anyhow!("a milestone title must be provided"))?,
+                description: description.unwrap_or_default(),
+                due: due.flatten(),
+            },
+            OperationName::Show => Operation::Show { id: id()? },
+            OperationName::Edit => Operation::Edit {
+                id: id()?,
+                title,
+                description,
+                due,
+            },
+            OperationName::Delete => Operation::Delete { id: id()? },
+            OperationName::Attach | OperationName::Detach if objects.is_empty() => {
+                anyhow::bail!("at least one issue or patch must be provided");
+            }
+            OperationName::Attach => Operation::Attach { id: id()?, objects },
+            OperationName::Detach => Operation::Detach { id: id()?, objects },
+            OperationName::Close => Operation::State {
+                id: id()?,
+                state: State::Closed,
+            },
+            OperationName::Reopen => Operation::State {
+                id: id()?,
+                state: State::Open,
+            },
+            OperationName::Cache => Operation::Cache {
+                id: cache_id,
+                storage: cache_storage,
+            },
+        };
+
+        Ok((
+            Options {
+                op,
+                repo,
+                announce,
+                quiet,
+            },
+            vec![],
+        ))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let rid = if let Some(rid) = options.repo {
+        rid
+    } else {
+        radicle::rad::cwd().map(|(_, rid)| rid)?
+    };
+    let repo = profile.storage.repository_mut(rid)?;
+    let announce = options.announce
+        && !matches!(
+            &options.op,
+            Operation::List { .. } | Operation::Show { .. } | Operation::Cache { .. }
+        );
+    let mut milestones = term::cob::milestones_mut(&profile, &repo)?;
+
+    match options.op {
+        Operation::List { state } => {
+            list(&milestones, state, &repo, &profile)?;
+        }
+        Operation::Open {
+            title,
+            description,
+            due,
+        } => {
+            let signer = term::signer(&profile)?;
+            let milestone = milestones.create(title, description, due, &signer)?;
+            let id = *milestone.id();
+
+            if options.quiet {
+                term::print(id);
+            } else {
+                term::success!("Milestone {} opened", term::format::tertiary(id));
+            }
+        }
+        Operation::Show { id } => {
+            let id = id.resolve(&repo.backend)?;
+            let milestone = milestones
+                .get(&id)?
+                .context("No milestone with the given ID exists")?;
+            let issues = term::cob::issues(&profile, &repo)?;
+            let patches = term::cob::patches(&profile, &repo)?;
+
+            term::milestone::show(&milestone, &id, &issues, &patches, &profile)?;
+        }
+        Operation::Edit {
+            id,
+            title,
+            description,
+            due,
+        } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let mut milestone = milestones.get_mut(&id)?;
+
+            if title.is_none() && description.is_none() && due.is_none() {
+                anyhow::bail!("nothing to edit, see `rad milestone --help`");
+            }
+            let edit = (title.is_some() || description.is_some()).then(|| {
+                (
+                    title.unwrap_or(milestone.title().to_owned()),
+                    description.unwrap_or(milestone.description().to_owned()),
+                )
+            });
+            milestone.transaction("Edit", &signer, |tx| {
+                if let Some((title, description)) = edit {
+                    tx.edit(title, description)?;
+                }
+                if let Some(due) = due {
+                    tx.due(due)?;
+                }
+                Ok(())
+            })?;
+        }
+        Operation::Attach { id, objects } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let (issues, patches) = resolve_objects(objects, &repo, &profile)?;
+            let mut milestone = milestones.get_mut(&id)?;
+
+            milestone.attach(issues, patches, &signer)?;
+        }
+        Operation::Detach { id, objects } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let (issues, patches) = resolve_objects(objects, &repo, &profile)?;
+            let mut milestone = milestones.get_mut(&id)?;
+
+            milestone.detach(issues, patches, &signer)?;
+        }
+        Operation::State { id, state } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+            let mut milestone = milestones.get_mut(&id)?;
+
+            milestone.lifecycle(state, &signer)?;
+
+            if !options.quiet {
+                term::success!("Milestone {} is now {state}", term::format::cob(&id));
+            }
+        }
+        Operation::Delete { id } => {
+            let signer = term::signer(&profile)?;
+            let id = id.resolve(&repo.backend)?;
+
+            milestones.remove(&id, &signer)?;
+        }
+        Operation::Cache { id, storage } => {
+            let mode = if storage {
+                cache::CacheMode::Storage
+            } else {
+                let milestone_id = id.map(|id| id.resolve(&repo.backend)).transpose()?;
+                milestone_id.map_or(cache::CacheMode::Repository { repository: &repo }, |id| {
+                    cache::CacheMode::Milestone {
+                        id,
+                        repository: &repo,
+                    }
+                })
+            };
+            cache::run(mode, &profile)?;
+        }
+    }
+
+    if announce {
+        let mut node = Node::new(profile.socket());
+        node::announce(
+            &repo,
+            node::SyncSettings::default(),
+            node::SyncReporting::default(),
+            &mut node,
+            &profile,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn list<C>(
+    milestones: &C,
+    state: Option<State>,
+    repo: &storage::git::Repository,
+    profile: &profile::Profile,
+) -> anyhow::Result<()>
+where
+    C: Milestones,
+{
+    if milestones.is_empty()? {
+        term::print(term::format::italic("Nothing to show."));
+        return Ok(());
+    }
+    let issues = term::cob::issues(profile, repo)?;
+    let patches = term::cob::patches(profile, repo)?;
+
+    let mut all = Vec::new();
+    for result in milestones.list()? {
+        let (id, milestone) = match result {
+            Ok((id, milestone)) => (id, milestone),
+            Err(e) => {
+                // Skip milestones that failed to load.
+                log::error!(target: "cli", "Milestone load error: {e}");
+                continue;
+            }
+        };
+        if let Some(s) = state {
+            if &s != milestone.state() {
+                continue;
+            }
+        }
+        all.push((id, milestone))
+    }
+
+    // Milestones with the nearest due date come first, then the most recent ones.
+    all.sort_by(|(id1, m1), (id2, m2)| {
+        let by_due = match (m1.due(), m2.due()) {
+            (Some(d1), Some(d2)) => d1.cmp(&d2),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        };
+        let by_timestamp = m2.timestamp().cmp(&m1.timestamp());
+        let by_id = id1.cmp(id2);
+
+        by_due.then(by_timestamp).then(by_id)
+    });
+
+    let mut table = term::Table::new(term::table::TableOptions::bordered());
+    table.header([
+        term::format::dim(String::from("●")).into(),
+        term::format::bold(String::from("ID")).into(),
+        term::format::bold(String::from("Title")).into(),
+        term::format::bold(String::from("Author")).into(),
+        term::Line::blank(),
+        term::format::bold(String::from("Due")).into(),
+        term::format::bold(String::from("Progress")).into(),
+        term::format::bold(String::from("Opened")).into(),
+    ]);
+    table.divider();
+
+    for (id, milestone) in all {
+        let (alias, did) = Author::new(milestone.author().id(), profile).labels();
+        let progress = term::milestone::progress(&milestone, &issues, &patches)?;
+
+        table.push([
+            match milestone.state() {
+                State::Open => term::format::positive("●").into(),
+                State::Closed => term::format::negative("●").into(),
+            },
+            term::format::tertiary(term::format::cob(&id)).into(),
+            term::format::default(milestone.title().to_owned()).into(),
+            alias.into(),
+            did.into(),
+            term::format::secondary(
+                milestone
+                    .due()
+                    .map(term::milestone::due)
+                    .unwrap_or_default(),
+            )
+            .into(),
+            term::format::default(progress.to_string()).into(),
+            term::format::timestamp(milestone.timestamp())
+                .dim()
+                .italic()
+                .into(),
+        ]);
+    }
+    table.print();
+
+    Ok(())
+}
+
+/// Parse a due date in the format `YYYY-MM-DD`.
+fn date(val: &str) -> anyhow::Result<Timestamp> {
+    let date = NaiveDate::parse_from_str(val, "%Y-%m-%d")
+        .map_err(|_| anyhow!("invalid date '{val}', expected the format YYYY-MM-DD"))?;
+    let secs = date
+        .and_hms_opt(0, 0, 0)
+        .ok_or_else(|| anyhow!("invalid date '{val}'"))?
+        .and_utc()
+        .timestamp();
+    let secs = u64::try_from(secs).map_err(|_| anyhow!("invalid date '{val}'"))?;
+
+    Ok(Timestamp::from_secs(secs))
+}
+
+/// Resolve the given objects into issues and patches.
+fn resolve_objects(
+    objects: Vec<Rev>,
+    repo: &storage::git::Repository,
+    profile: &profile::Profile,
+) -> anyhow::Result<(Vec<cob::ObjectId>, Vec<cob::ObjectId>)> {
+    let issues = term::cob::issues(profile, repo)?;
+    let patches = term::cob::patches(profile, repo)?;
+    let mut issue_ids = Vec::new();
+    let mut patch_ids = Vec::new();
+
+    for rev in objects {
+        let id: cob::ObjectId = rev.resolve(&repo.backend)?;
+
+        if issues.get(&id)?.is_some() {
+            issue_ids.push(id);
+        } else if patches.get(&id)?.is_some() {
+            patch_ids.push(id);
+        } else {
+            anyhow::bail!("no issue or patch with id {id} was found");
+        }
+    }
+    Ok((issue_ids, patch_ids))
+}
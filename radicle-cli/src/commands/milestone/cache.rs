@@ -0,0 +1,65 @@
+use std::ops::ControlFlow;
+
+use radicle::cob::milestone::MilestoneId;
+use radicle::storage::git::Repository;
+use radicle::storage::ReadStorage as _;
+use radicle::Profile;
+
+use crate::terminal as term;
+
+pub enum CacheMode<'a> {
+    Storage,
+    Repository {
+        repository: &'a Repository,
+    },
+    Milestone {
+        id: MilestoneId,
+        repository: &'a Repository,
+    },
+}
+
+pub fn run(mode: CacheMode, profile: &Profile) -> anyhow::Result<()> {
+    match mode {
+        CacheMode::Storage => {
+            let repos = profile.storage.repositories()?;
+            for info in repos {
+                term::info!("Caching all milestones for {}", info.rid);
+                cache(None, &profile.storage.repository(info.rid)?, profile)?
+            }
+        }
+        CacheMode::Repository { repository: repo } => cache(None, repo, profile)?,
+        CacheMode::Milestone {
+            id,
+            repository: repo,
+        } => cache(Some(id), repo, profile)?,
+    }
+    Ok(())
+}
+
+fn cache(
+    id: Option<MilestoneId>,
+    repository: &Repository,
+    profile: &Profile,
+) -> anyhow::Result<()> {
+    let mut milestones = term::cob::milestones_mut(profile, repository)?;
+
+    match id {
+        Some(id) => {
+            milestones.write(&id)?;
+            term::success!("Successfully cached milestone `{id}`");
+        }
+        None => milestones.write_all(|result, progress| {
+            match result {
+                Ok((id, _)) => term::success!(
+                    "Successfully cached milestone {id} ({}/{})",
+                    progress.current(),
+                    progress.total()
+                ),
+                Err(e) => term::warning(format!("Failed to retrieve milestone: {e}")),
+            };
+            ControlFlow::Continue(())
+        })?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use std::ffi::OsString;
+use std::path::Path;
+
+use anyhow::{anyhow, Context as _};
+
+use radicle::git;
+use radicle::node::{Handle as _, Node};
+use radicle::prelude::RepoId;
+use radicle::storage::git::mirror;
+use radicle::storage::{ReadStorage as _, WriteRepository as _};
+use radicle::Profile;
+
+use crate::terminal as term;
+use crate::terminal::args::{Args, Error, Help};
+
+pub const HELP: Help = Help {
+    name: "mirror",
+    description: "Mirror a repository to and from plain Git remotes",
+    version: env!("RADICLE_VERSION"),
+    usage: r#"
+Usage
+
+    rad mirror push [<rid>] [--url <url>] [<option>...]
+    rad mirror import [<rid>] [--url <url>] [<option>...]
+
+    Mirrors are configured per repository, under `node.mirrors` in the
+    configuration, eg.
+
+        "mirrors": {
+          "rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5": {
+            "push": "git@example.com:acme/heartwood.git",
+            "import": "https://example.com/acme/heartwood.git"
+          }
+        }
+
+    The `push` operation pushes the canonical branches and tags of the
+    repository to its mirror. When a mirror is configured, this also
+    happens every time the canonical refs are updated, either by the
+    node after a fetch, or by a `git push`.
+
+    The `import` operation fetches the branches and tags of the upstream
+    Git remote into your namespace, signs them, and announces them to the
+    network if the node is running. Only new refs and fast-forwards of your
+    branches are imported; other upstream refs are reported and skipped.
+
+    If no RID is given, the repository in the current directory is used.
+
+Options
+
+    --url <url>          Git URL to use, instead of the configured one
+    --help               Print help
+"#,
+};
+
+pub enum Operation {
+    Push,
+    Import,
+}
+
+pub struct Options {
+    op: Operation,
+    rid: Option<RepoId>,
+    url: Option<String>,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<Operation> = None;
+        let mut rid: Option<RepoId> = None;
+        let mut url: Option<String> = None;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") | Short('h') => {
+                    return Err(Error::Help.into());
+                }
+                Long("url") if op.is_some() => {
+                    let val = parser.value()?;
+                    url = Some(term::args::string(&val));
+                }
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "push" => op = Some(Operation::Push),
+                    "import" => op = Some(Operation::Import),
+
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Value(val) if rid.is_none() => {
+                    rid = Some(term::args::rid(&val)?);
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+        let op =
+            op.ok_or_else(|| anyhow!("an operation must be provided; see `rad mirror --help`"))?;
+
+        Ok((Options { op, rid, url }, vec![]))
+    }
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+    let rid = match options.rid {
+        Some(rid) => rid,
+        None => radicle::rad::cwd()
+            .map(|(_, rid)| rid)
+            .context("Current directory is not a Radicle repository")?,
+    };
+    let config = profile.config.node.mirrors.get(&rid);
+
+    match options.op {
+        Operation::Push => {
+            let url = options
+                .url
+                .or_else(|| config.and_then(|m| m.push.clone()))
+                .ok_or_else(|| anyhow!("no push URL is configured for {rid}; use `--url`"))?;
+
+            push(rid, &url, &profile)
+        }
+        Operation::Import => {
+            let url = options
+                .url
+                .or_else(|| config.and_then(|m| m.import.clone()))
+                .ok_or_else(|| anyhow!("no import URL is configured for {rid}; use `--url`"))?;
+
+            import(rid, &url, &profile)
+        }
+    }
+}
+
+fn push(rid: RepoId, url: &str, profile: &Profile) -> anyhow::Result<()> {
+    let repo = profile.storage.repository(rid)?;
+    let pushed = mirror::push(&repo, &self::url(url)?, mirror::DEFAULT_PUSH_TIMEOUT)?;
+
+    if pushed.is_empty() {
+        term::success!("Mirror {} is up to date", term::format::tertiary(url));
+    } else {
+        for name in &pushed {
+            term::info!("{}", term::format::secondary(name));
+        }
+        term::success!(
+            "Pushed {} ref(s) to {}",
+            pushed.len(),
+            term::format::tertiary(url)
+        );
+    }
+    Ok(())
+}
+
+fn import(rid: RepoId, url: &str, profile: &Profile) -> anyhow::Result<()> {
+    let repo = profile.storage.repository(rid)?;
+    let signer = profile.signer()?;
+    let mirror::Import { updates, rejected } = mirror::import(
+        &repo,
+        &self::url(url)?,
+        &signer,
+        mirror::DEFAULT_IMPORT_TIMEOUT,
+    )?;
+
+    for name in &rejected {
+        term::warning(format!(
+            "Rejected {}: not a fast-forward of the local ref",
+            term::format::secondary(name)
+        ));
+    }
+    if updates.is_empty() {
+        if rejected.is_empty() {
+            term::success!("Already up to date with {}", term::format::tertiary(url));
+        }
+        return Ok(());
+    }
+    for update in &updates {
+        term::info!("{}", term::format::ref_update_verbose(update));
+    }
+    term::success!(
+        "Imported {} ref(s) from {}",
+        updates.len(),
+        term::format::tertiary(url)
+    );
+
+    // N.b. if an error occurs then there may be no quorum.
+    if let Ok(head) = repo.set_head() {
+        if head.is_updated() {
+            term::success!(
+                "Canonical head updated to {}",
+                term::format::secondary(head.new)
+            );
+            let mirror = profile.config.node.mirrors.get(&rid);
+
+            if let Some(url) = mirror.and_then(|m| m.push.as_deref()) {
+                push(rid, url, profile)?;
+            }
+        }
+    }
+
+    let mut node = Node::new(profile.socket());
+    if node.is_running() {
+        node.announce_refs(rid)?;
+        term::success!("Announced refs of {}", term::format::tertiary(rid));
+    }
+    Ok(())
+}
+
+/// Git URLs that point to local paths are made absolute, since `git` runs from the storage.
+fn url(url: &str) -> anyhow::Result<String> {
+    let path = Path::new(url);
+    if path.exists() {
+        let path = path.canonicalize()?;
+        return Ok(git::url::File::new(path).to_string());
+    }
+    Ok(url.to_owned())
+}
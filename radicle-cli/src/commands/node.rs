@@ -38,7 +38,7 @@ Usage
     rad node connect <nid>@<addr> [<option>...]
     rad node routing [--rid <rid>] [--nid <nid>] [--json] [<option>...]
     rad node inventory [--nid <nid>] [<option>...]
-    rad node events [--timeout <secs>] [-n <count>] [<option>...]
+    rad node events [--timeout <secs>] [-n <count>] [--since <duration>] [--rid <rid>] [--nid <nid>] [<option>...]
     rad node config [--addresses]
     rad node db <command> [<option>..]
 
@@ -64,6 +64,9 @@ Events options
 
     --timeout <secs>     How long to wait to receive an event before giving up
     --count, -n <count>  Exit after <count> events
+    --since <duration>   Show logged events from the given period, eg. '30m', '12h' or '7d'
+    --rid <rid>          Only show events relating to the given RID
+    --nid <nid>          Only show events relating to the given NID
 
 General options
 
@@ -89,6 +92,9 @@ pub enum Operation {
     Events {
         timeout: time::Duration,
         count: usize,
+        since: Option<time::Duration>,
+        rid: Option<RepoId>,
+        nid: Option<NodeId>,
     },
     Routing {
         json: bool,
@@ -145,6 +151,7 @@ impl Args for Options {
         let mut lines: usize = 60;
         let mut count: usize = usize::MAX;
         let mut timeout = time::Duration::MAX;
+        let mut since = None;
         let mut addresses = false;
         let mut path = None;
         let mut verbose = false;
@@ -177,6 +184,10 @@ impl Args for Options {
                     let val = parser.value()?;
                     rid = term::args::rid(&val).ok();
                 }
+                Long("rid") if matches!(op, Some(OperationName::Events)) => {
+                    let val = parser.value()?;
+                    rid = Some(term::args::rid(&val)?);
+                }
                 Long("nid")
                     if matches!(op, Some(OperationName::Routing))
                         || matches!(op, Some(OperationName::Inventory)) =>
@@ -184,6 +195,14 @@ impl Args for Options {
                     let val = parser.value()?;
                     nid = term::args::nid(&val).ok();
                 }
+                Long("nid") if matches!(op, Some(OperationName::Events)) => {
+                    let val = parser.value()?;
+                    nid = Some(term::args::nid(&val)?);
+                }
+                Long("since") if matches!(op, Some(OperationName::Events)) => {
+                    let val = parser.value()?;
+                    since = Some(term::args::duration(&val)?);
+                }
                 Long("json") if matches!(op, Some(OperationName::Routing)) => json = true,
                 Long("timeout")
                     if op == Some(OperationName::Events) || op == Some(OperationName::Connect) =>
@@ -230,7 +249,13 @@ impl Args for Options {
             },
             OperationName::Config => Operation::Config { addresses },
             OperationName::Db => Operation::Db { args: options },
-            OperationName::Events => Operation::Events { timeout, count },
+            OperationName::Events => Operation::Events {
+                timeout,
+                count,
+                since,
+                rid,
+                nid,
+            },
             OperationName::Routing => Operation::Routing { rid, nid, json },
             OperationName::Logs => Operation::Logs { lines },
             OperationName::Start => Operation::Start {
@@ -274,13 +299,19 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
             control::debug(&mut node)?;
         }
         Operation::Sessions => {
-            let sessions = control::sessions(&node)?;
+            let sessions = control::sessions(&node, &profile)?;
             if let Some(table) = sessions {
                 table.print();
             }
         }
-        Operation::Events { timeout, count } => {
-            events::run(node, count, timeout)?;
+        Operation::Events {
+            timeout,
+            count,
+            since,
+            rid,
+            nid,
+        } => {
+            events::run(node, count, timeout, since, rid, nid)?;
         }
         Operation::Routing { rid, nid, json } => {
             let store = profile.database()?;
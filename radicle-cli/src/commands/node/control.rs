@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::ffi::OsString;
 use std::fs::{File, OpenOptions};
 use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
@@ -7,6 +8,7 @@ use anyhow::{anyhow, Context};
 use localtime::LocalTime;
 
 use radicle::node;
+use radicle::node::address::Store as _;
 use radicle::node::{Address, ConnectResult, Handle as _, NodeId};
 use radicle::Node;
 use radicle::{profile, Profile};
@@ -231,11 +233,15 @@ pub fn status(node: &Node, profile: &Profile) -> anyhow::Result<()> {
         return Ok(());
     }
 
-    let sessions = sessions(node)?;
+    let sessions = sessions(node, profile)?;
     if let Some(table) = sessions {
         term::blank();
         table.print();
     }
+    if let Some(table) = traffic(node)? {
+        term::blank();
+        table.print();
+    }
 
     if profile.hints() {
         const COLUMN_WIDTH: usize = 12;
@@ -290,11 +296,15 @@ pub fn status(node: &Node, profile: &Profile) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn sessions(node: &Node) -> Result<Option<term::Table<5, term::Label>>, node::Error> {
+pub fn sessions(
+    node: &Node,
+    profile: &Profile,
+) -> anyhow::Result<Option<term::Table<8, term::Label>>> {
     let sessions = node.sessions()?;
     if sessions.is_empty() {
         return Ok(None);
     }
+    let db = profile.database()?;
     let mut table = term::Table::new(term::table::TableOptions::bordered());
     let now = LocalTime::now();
 
@@ -304,6 +314,9 @@ pub fn sessions(node: &Node) -> Result<Option<term::Table<5, term::Label>>, node
         state_label().into(),
         link_direction_label().bold().into(),
         term::format::bold("Since").into(),
+        term::format::bold("Score").into(),
+        term::format::bold("Sent").into(),
+        term::format::bold("Received").into(),
     ]);
     table.divider();
 
@@ -335,7 +348,44 @@ pub fn sessions(node: &Node) -> Result<Option<term::Table<5, term::Label>>, node
             node::Link::Inbound => term::Label::from(link_direction_inbound()),
             node::Link::Outbound => term::Label::from(link_direction_outbound()),
         };
-        table.push([nid, addr, state, direction, time]);
+        let score = term::format::dim(db.score(&sess.nid)?.value()).into();
+        let sent = term::format::dim(term::format::bytes(sess.traffic.sent as usize)).into();
+        let received =
+            term::format::dim(term::format::bytes(sess.traffic.received as usize)).into();
+
+        table.push([nid, addr, state, direction, time, score, sent, received]);
+    }
+    Ok(Some(table))
+}
+
+/// Git traffic per repository, across all sessions.
+pub fn traffic(node: &Node) -> anyhow::Result<Option<term::Table<3, term::Label>>> {
+    let mut repos = BTreeMap::<_, node::RepoTraffic>::new();
+    for sess in node.sessions()? {
+        for (rid, t) in sess.traffic.repos {
+            let total = repos.entry(rid).or_default();
+            total.sent += t.sent;
+            total.received += t.received;
+        }
+    }
+    if repos.is_empty() {
+        return Ok(None);
+    }
+    let mut table = term::Table::new(term::table::TableOptions::bordered());
+
+    table.header([
+        term::format::bold("Repository").into(),
+        term::format::bold("Sent").into(),
+        term::format::bold("Received").into(),
+    ]);
+    table.divider();
+
+    for (rid, t) in repos {
+        table.push([
+            term::format::tertiary(rid).into(),
+            term::format::dim(term::format::bytes(t.sent as usize)).into(),
+            term::format::dim(term::format::bytes(t.received as usize)).into(),
+        ]);
     }
     Ok(Some(table))
 }
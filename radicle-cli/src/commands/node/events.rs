@@ -1,13 +1,48 @@
 use std::time;
 
-use radicle::node::{Event, Handle};
+use localtime::LocalTime;
+use radicle::node::events::Filter;
+use radicle::node::{Event, Handle, NodeId, Timestamp};
+use radicle::prelude::RepoId;
 
-pub fn run<H>(node: H, count: usize, timeout: time::Duration) -> anyhow::Result<()>
+pub fn run<H>(
+    node: H,
+    count: usize,
+    timeout: time::Duration,
+    since: Option<time::Duration>,
+    rid: Option<RepoId>,
+    nid: Option<NodeId>,
+) -> anyhow::Result<()>
 where
     H: Handle<Event = Result<Event, <H as Handle>::Error>>,
 {
+    let mut filter = Filter {
+        rid,
+        nid,
+        ..Filter::default()
+    };
+
+    // Show logged events from the given period.
+    if let Some(since) = since {
+        let now = LocalTime::now().as_millis();
+        let since = now.saturating_sub(since.as_millis() as u64);
+
+        filter.since =
+            Some(Timestamp::try_from(since).map_err(|_| anyhow::anyhow!("invalid timestamp"))?);
+        filter.limit = (count != usize::MAX).then_some(count);
+
+        for logged in node.event_log(filter)? {
+            println!("{}", serde_json::to_string(&logged)?);
+        }
+        return Ok(());
+    }
+
     let events = node.subscribe(timeout)?;
-    for (i, event) in events.into_iter().enumerate() {
+    let events = events
+        .into_iter()
+        .filter(|e| e.as_ref().map_or(true, |e| filter.matches(e)));
+
+    for (i, event) in events.enumerate() {
         let event = event?;
         let obj = serde_json::to_string(&event)?;
 
@@ -4,17 +4,20 @@ use radicle::prelude::{NodeId, RepoId};
 use crate::terminal as term;
 use crate::terminal::Element;
 
-pub fn run<S: node::routing::Store>(
-    routing: &S,
+pub fn run<S: node::routing::Store + node::address::Store>(
+    store: &S,
     rid: Option<RepoId>,
     nid: Option<NodeId>,
     json: bool,
 ) -> anyhow::Result<()> {
     // Filters entries by RID or NID exclusively, or show all of them if none given.
-    let entries = routing.entries()?.filter(|(rid_, nid_)| {
-        (nid.is_none() || Some(nid_) == nid.as_ref())
-            && (rid.is_none() || Some(rid_) == rid.as_ref())
-    });
+    let entries = node::routing::Store::entries(store)?
+        .filter(|(rid_, nid_)| {
+            (nid.is_none() || Some(nid_) == nid.as_ref())
+                && (rid.is_none() || Some(rid_) == rid.as_ref())
+        })
+        .map(|(rid, nid)| Ok((rid, nid, store.score(&nid)?.value())))
+        .collect::<Result<Vec<_>, node::address::Error>>()?;
 
     if json {
         print_json(entries);
@@ -25,25 +28,30 @@ pub fn run<S: node::routing::Store>(
     Ok(())
 }
 
-fn print_table(entries: impl IntoIterator<Item = (RepoId, NodeId)>) {
+fn print_table(entries: impl IntoIterator<Item = (RepoId, NodeId, i64)>) {
     let mut t = term::Table::new(term::table::TableOptions::bordered());
     t.header([
         term::format::default(String::from("RID")),
         term::format::default(String::from("NID")),
+        term::format::default(String::from("Score")),
     ]);
     t.divider();
 
-    for (rid, nid) in entries {
+    for (rid, nid, score) in entries {
         t.push([
             term::format::highlight(rid.to_string()),
             term::format::node(&nid),
+            term::format::dim(score.to_string()),
         ]);
     }
     t.print();
 }
 
-fn print_json(entries: impl IntoIterator<Item = (RepoId, NodeId)>) {
-    for (rid, nid) in entries {
-        println!("{}", serde_json::json!({ "rid": rid, "nid": nid }));
+fn print_json(entries: impl IntoIterator<Item = (RepoId, NodeId, i64)>) {
+    for (rid, nid, score) in entries {
+        println!(
+            "{}",
+            serde_json::json!({ "rid": rid, "nid": nid, "score": score })
+        );
     }
 }
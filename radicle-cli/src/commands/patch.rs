@@ -39,6 +39,7 @@ use std::str::FromStr as _;
 
 use anyhow::anyhow;
 
+use radicle::cob::filter::Filter;
 use radicle::cob::patch::PatchId;
 use radicle::cob::{patch, Label, Reaction};
 use radicle::git::RefString;
@@ -60,7 +61,7 @@ pub const HELP: Help = Help {
 Usage
 
     rad patch [<option>...]
-    rad patch list [--all|--merged|--open|--archived|--draft|--authored] [--author <did>]... [<option>...]
+    rad patch list [--all|--merged|--open|--archived|--draft|--authored] [--author <did>]... [--search <query>] [--filter <expr>] [<option>...]
     rad patch show <patch-id> [<option>...]
     rad patch diff <patch-id> [<option>...]
     rad patch archive <patch-id> [--undo] [<option>...]
@@ -101,6 +102,7 @@ Comment options
 Edit options
 
     -m, --message [<string>]   Provide a comment message to the patch or revision (default: prompt)
+        --target <branch>      Change the branch the patch is intended to be merged into
 
 Review options
 
@@ -155,6 +157,17 @@ List options
         --authored             Show only patches that you have authored
         --author <did>         Show only patched where the given user is an author
                                (may be specified multiple times)
+        --search <query>       Show only patches whose title, description, comments or
+                               review comments match all the terms of the query, most
+                               relevant first. Terms ending with `*` match as prefixes
+        --filter <expr>        Show only patches matching all the terms of the filter
+                               expression, eg. `label:bug -label:wontfix state:draft`.
+                               Terms are `label:<label>`, `author:<did>`,
+                               `assignee:<did>`, `state:<draft|open|archived|merged>`,
+                               `created:<date>` and `updated:<date>`, where dates are
+                               `YYYY-MM-DD`, optionally prefixed by `>`, `>=`, `<` or `<=`.
+                               Terms starting with `-` are negated; other words match
+                               the patch text
 
 Ready options
 
@@ -304,11 +317,14 @@ pub enum Operation {
     },
     List {
         filter: Option<patch::Status>,
+        search: Option<String>,
+        expr: Option<Filter>,
     },
     Edit {
         patch_id: Rev,
         revision_id: Option<Rev>,
         message: Message,
+        target: Option<RefString>,
     },
     Redact {
         revision_id: Rev,
@@ -379,6 +395,9 @@ impl Args for Options {
         let mut comment_id = None;
         let mut message = Message::default();
         let mut filter = Some(patch::Status::Open);
+        let mut search = None;
+        let mut expr: Option<Filter> = None;
+        let mut list_state = false;
         let mut diff = false;
         let mut debug = false;
         let mut undo = false;
@@ -387,6 +406,7 @@ impl Args for Options {
         let mut comment_op: Option<(CommentOperation, Rev)> = None;
         let mut checkout_opts = checkout::Options::default();
         let mut remote: Option<RefString> = None;
+        let mut target: Option<RefString> = None;
         let mut assign_opts = AssignOptions::default();
         let mut label_opts = LabelOptions::default();
         let mut review_op = review::Operation::default();
@@ -502,6 +522,10 @@ impl Args for Options {
 
                     revision_id = Some(rev);
                 }
+                Long("target") if op == Some(OperationName::Edit) => {
+                    let val = parser.value()?;
+                    target = Some(term::args::refstring("target", val)?);
+                }
 
                 // Review/diff options.
                 Long("revision") | Short('r')
@@ -640,18 +664,23 @@ impl Args for Options {
                 // List options.
                 Long("all") => {
                     filter = None;
+                    list_state = true;
                 }
                 Long("draft") => {
                     filter = Some(patch::Status::Draft);
+                    list_state = true;
                 }
                 Long("archived") => {
                     filter = Some(patch::Status::Archived);
+                    list_state = true;
                 }
                 Long("merged") => {
                     filter = Some(patch::Status::Merged);
+                    list_state = true;
                 }
                 Long("open") => {
                     filter = Some(patch::Status::Open);
+                    list_state = true;
                 }
                 Long("authored") => {
                     authored = true;
@@ -659,6 +688,12 @@ impl Args for Options {
                 Long("author") if op == Some(OperationName::List) => {
                     authors.push(term::args::did(&parser.value()?)?);
                 }
+                Long("search") if op.is_none() || op == Some(OperationName::List) => {
+                    search = Some(string(&parser.value()?));
+                }
+                Long("filter") if op.is_none() || op == Some(OperationName::List) => {
+                    expr = Some(term::args::filter(&parser.value()?)?);
+                }
 
                 // Cache options.
                 Long("storage") if op == Some(OperationName::Cache) => {
@@ -738,7 +773,15 @@ impl Args for Options {
         }
 
         let op = match op.unwrap_or_default() {
-            OperationName::List => Operation::List { filter },
+            OperationName::List => Operation::List {
+                // A `state` term in the filter expression overrides the default status.
+                filter: match &expr {
+                    Some(e) if e.has_state() && !list_state => None,
+                    _ => filter,
+                },
+                search,
+                expr,
+            },
             OperationName::Show => Operation::Show {
                 patch_id: patch_id.ok_or_else(|| anyhow!("a patch must be provided"))?,
                 diff,
@@ -822,6 +865,7 @@ impl Args for Options {
                 patch_id: patch_id.ok_or_else(|| anyhow!("a patch must be provided"))?,
                 revision_id,
                 message,
+                target,
             },
             OperationName::Redact => Operation::Redact {
                 revision_id: revision_id.ok_or_else(|| anyhow!("a revision must be provided"))?,
@@ -875,12 +919,23 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
     transport::local::register(profile.storage.clone());
 
     match options.op {
-        Operation::List { filter } => {
+        Operation::List {
+            filter,
+            search,
+            expr,
+        } => {
             let mut authors: BTreeSet<Did> = options.authors.iter().cloned().collect();
             if options.authored {
                 authors.insert(profile.did());
             }
-            list::run(filter.as_ref(), authors, &repository, &profile)?;
+            list::run(
+                filter.as_ref(),
+                search.as_deref(),
+                expr.as_ref(),
+                authors,
+                &repository,
+                &profile,
+            )?;
         }
         Operation::Show {
             patch_id,
@@ -1022,13 +1077,21 @@ pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
             patch_id,
             revision_id,
             message,
+            target,
         } => {
             let patch_id = patch_id.resolve(&repository.backend)?;
             let revision_id = revision_id
                 .map(|id| id.resolve::<radicle::git::Oid>(&repository.backend))
                 .transpose()?
                 .map(patch::RevisionId::from);
-            edit::run(&patch_id, revision_id, message, &profile, &repository)?;
+            edit::run(
+                &patch_id,
+                revision_id,
+                message,
+                target,
+                &profile,
+                &repository,
+            )?;
         }
         Operation::Redact { revision_id } => {
             redact::run(&revision_id, &profile, &repository)?;
@@ -3,6 +3,7 @@ use super::*;
 use radicle::cob;
 use radicle::cob::patch;
 use radicle::crypto;
+use radicle::git;
 use radicle::node::device::Device;
 use radicle::prelude::*;
 use radicle::storage::git::Repository;
@@ -13,6 +14,7 @@ pub fn run(
     patch_id: &PatchId,
     revision_id: Option<patch::RevisionId>,
     message: term::patch::Message,
+    target: Option<git::RefString>,
     profile: &Profile,
     repository: &Repository,
 ) -> anyhow::Result<()> {
@@ -21,11 +23,23 @@ pub fn run(
     let Ok(patch) = patches.get_mut(patch_id) else {
         anyhow::bail!("Patch `{patch_id}` not found");
     };
-    let (title, description) = term::patch::get_edit_message(message, &patch)?;
+    let target = if let Some(branch) = target {
+        let doc = repository.identity_doc()?;
+        Some(patch::MergeTarget::branch(branch, &doc)?)
+    } else {
+        None
+    };
+
+    let (title, description) = if target.is_some() && message == term::patch::Message::Edit {
+        // When only changing the target, there's no need to open an editor.
+        (patch.title().to_owned(), patch.description().to_owned())
+    } else {
+        term::patch::get_edit_message(message, &patch)?
+    };
 
     match revision_id {
         Some(id) => edit_revision(patch, id, title, description, &signer),
-        None => edit_root(patch, title, description, &signer),
+        None => edit_root(patch, title, description, target, &signer),
     }
 }
 
@@ -33,6 +47,7 @@ fn edit_root<G>(
     mut patch: patch::PatchMut<'_, '_, Repository, cob::cache::StoreWriter>,
     title: String,
     description: String,
+    target: Option<patch::MergeTarget>,
     signer: &Device<G>,
 ) -> anyhow::Result<()>
 where
@@ -48,18 +63,27 @@ where
     } else {
         None
     };
+    let target = target.filter(|t| t != patch.target());
 
-    if title.is_none() && description.is_none() {
+    if title.is_none() && description.is_none() && target.is_none() {
         // Nothing to do.
         return Ok(());
     }
 
     let (root, _) = patch.root();
-    let target = patch.target();
     let embeds = patch.embeds().to_owned();
+    // The title and target are always edited together.
+    let edit = if title.is_some() || target.is_some() {
+        Some((
+            title.unwrap_or_else(|| patch.title().to_owned()),
+            target.unwrap_or_else(|| patch.target().clone()),
+        ))
+    } else {
+        None
+    };
 
     patch.transaction("Edit root", signer, |tx| {
-        if let Some(t) = title {
+        if let Some((t, target)) = edit {
             tx.edit(t, target)?;
         }
         if let Some(d) = description {
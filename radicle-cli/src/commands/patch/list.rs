@@ -1,5 +1,6 @@
 use std::collections::{BTreeMap, BTreeSet};
 
+use radicle::cob::filter::Filter;
 use radicle::cob::patch;
 use radicle::cob::patch::{Patch, PatchId};
 use radicle::patch::cache::Patches as _;
@@ -17,6 +18,8 @@ use crate::terminal::patch as common;
 /// List patches.
 pub fn run(
     filter: Option<&patch::Status>,
+    search: Option<&str>,
+    expr: Option<&Filter>,
     authors: BTreeSet<Did>,
     repository: &Repository,
     profile: &Profile,
@@ -24,9 +27,11 @@ pub fn run(
     let patches = term::cob::patches(profile, repository)?;
 
     let mut all = Vec::new();
-    let iter = match filter {
-        Some(status) => patches.list_by_status(status)?,
-        None => patches.list()?,
+    let iter = match (search, expr, filter) {
+        (Some(query), _, _) => patches.search(query)?,
+        (None, Some(expr), _) => patches.filter(expr)?,
+        (None, None, Some(status)) => patches.list_by_status(status)?,
+        (None, None, None) => patches.list()?,
     };
     for patch in iter {
         let (id, patch) = match patch {
@@ -42,6 +47,18 @@ pub fn run(
                 continue;
             }
         }
+        if search.is_some() || expr.is_some() {
+            if let Some(status) = filter {
+                if patch::Status::from(patch.state()) != *status {
+                    continue;
+                }
+            }
+        }
+        if let (Some(_), Some(expr)) = (search, expr) {
+            if !expr.matches(&patch) {
+                continue;
+            }
+        }
         all.push((id, patch));
     }
 
@@ -50,13 +67,7 @@ pub fn run(
         return Ok(());
     }
 
-    let mut table = Table::<10, term::Line>::new(TableOptions {
-        spacing: 2,
-        border: Some(term::colors::FAINT),
-        ..TableOptions::default()
-    });
-
-    table.header([
+    let header = [
         term::format::dim(String::from("●")).into(),
         term::format::bold(String::from("ID")).into(),
         term::format::bold(String::from("Title")).into(),
@@ -67,26 +78,51 @@ pub fn run(
         term::format::bold(String::from("+")).into(),
         term::format::bold(String::from("-")).into(),
         term::format::bold(String::from("Updated")).into(),
-    ]);
-    table.divider();
+    ];
 
-    let me = *profile.id();
-    all.sort_by(|(id1, p1), (id2, p2)| {
-        let is_me = (p2.author().id().as_key() == &me).cmp(&(p1.author().id().as_key() == &me));
-        let by_id = id1.cmp(id2);
-        let by_rev_time = p2.updated_at().cmp(&p1.updated_at());
+    // Search results are ordered by relevance.
+    if search.is_none() {
+        let me = *profile.id();
+        all.sort_by(|(id1, p1), (id2, p2)| {
+            let is_me = (p2.author().id().as_key() == &me).cmp(&(p1.author().id().as_key() == &me));
+            let by_id = id1.cmp(id2);
+            let by_rev_time = p2.updated_at().cmp(&p1.updated_at());
 
-        is_me.then(by_rev_time).then(by_id)
-    });
+            is_me.then(by_rev_time).then(by_id)
+        });
+    }
 
+    let mut rows = Vec::new();
     let mut errors = Vec::new();
     for (id, patch) in &mut all {
         match row(id, patch, repository, profile) {
-            Ok(r) => table.push(r),
+            Ok(r) => rows.push((r, &*patch)),
             Err(e) => errors.push((patch.title(), id, e.to_string())),
         }
     }
-    table.print();
+
+    // Only show merge readiness if the repository has a merge policy.
+    let doc = repository.identity_doc()?;
+    let policy = doc.merge_policy().unwrap_or_else(|e| {
+        term::warning(format!("ignoring invalid merge policy: {e}"));
+        None
+    });
+    if let Some(policy) = policy {
+        let persons = repository.persons()?;
+        let header = with_readiness(header, term::format::bold(String::from("Merge")).into());
+        let rows = rows.into_iter().map(|(r, patch)| {
+            let readiness = if patch.is_open() || patch.is_draft() {
+                term::format::patch::readiness(&patch.merge_readiness(&policy, &doc, &persons))
+                    .into()
+            } else {
+                term::Line::blank()
+            };
+            with_readiness(r, readiness)
+        });
+        print(header, rows);
+    } else {
+        print(header, rows.into_iter().map(|(r, _)| r));
+    }
 
     if !errors.is_empty() {
         for (title, id, error) in errors {
@@ -158,3 +194,30 @@ pub fn row(
             .into(),
     ])
 }
+
+/// Print the patch table.
+fn print<const W: usize>(header: [term::Line; W], rows: impl IntoIterator<Item = [term::Line; W]>) {
+    let mut table = Table::<W, term::Line>::new(TableOptions {
+        spacing: 2,
+        border: Some(term::colors::FAINT),
+        ..TableOptions::default()
+    });
+
+    table.header(header);
+    table.divider();
+
+    for row in rows {
+        table.push(row);
+    }
+    table.print();
+}
+
+/// Add the merge readiness column to a patch row, after the reviews.
+fn with_readiness(
+    [state, id, title, alias, did, reviews, head, plus, minus, updated]: [term::Line; 10],
+    readiness: term::Line,
+) -> [term::Line; 11] {
+    [
+        state, id, title, alias, did, reviews, readiness, head, plus, minus, updated,
+    ]
+}
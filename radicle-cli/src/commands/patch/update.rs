@@ -18,11 +18,11 @@ pub fn run(
     // `HEAD`; This is what we are proposing as a patch.
     let head_branch = try_branch(workdir.head()?)?;
 
-    let (_, target_oid) = get_merge_target(repository, &head_branch)?;
     let mut patches = term::cob::patches_mut(profile, repository)?;
     let Ok(mut patch) = patches.get_mut(&patch_id) else {
         anyhow::bail!("Patch `{patch_id}` not found");
     };
+    let (_, target_oid) = get_merge_target(repository, &head_branch, patch.target())?;
 
     let head_oid = branch_oid(&head_branch)?;
     let base_oid = match base_id {
@@ -0,0 +1,308 @@
+use std::ffi::OsString;
+use std::ops::ControlFlow;
+use std::time;
+
+use anyhow::anyhow;
+use serde::Serialize;
+
+use radicle::node::{Handle as _, Node, NodeId};
+use radicle::prelude::RepoId;
+use radicle::storage::git::fsck::{self, Problem, Report};
+use radicle::storage::git::Repository;
+use radicle::storage::ReadStorage as _;
+use radicle::Profile;
+
+use crate::terminal as term;
+use crate::terminal::args::{Args, Error, Help};
+
+pub const HELP: Help = Help {
+    name: "storage",
+    description: "Manage the local storage",
+    version: env!("RADICLE_VERSION"),
+    usage: r#"
+Usage
+
+    rad storage fsck [<rid>] [--repair] [--json] [<option>...]
+
+    Audits repositories in storage: the identity, the signed refs of
+    every remote, the history of every ref and every collaborative
+    object are checked. If no RID is given, all repositories are
+    audited.
+
+    With `--repair`, remotes that fail the audit are removed, except
+    for the local one and those of delegates, and are fetched again
+    from seeds if the node is running. Collaborative object caches
+    are then rebuilt, and the repository is audited again.
+
+Fsck options
+
+    --repair             Attempt to repair the problems found
+    --timeout <secs>     How long to wait when fetching from a seed (default: 9)
+    --json               Output one JSON report per repository
+
+Options
+
+    --help               Print help
+"#,
+};
+
+/// Default time to wait when fetching from a seed.
+pub const DEFAULT_FETCH_TIMEOUT: time::Duration = time::Duration::from_secs(9);
+
+pub enum Operation {
+    Fsck {
+        rid: Option<RepoId>,
+        repair: bool,
+        timeout: time::Duration,
+        json: bool,
+    },
+}
+
+#[derive(PartialEq, Eq)]
+pub enum OperationName {
+    Fsck,
+}
+
+pub struct Options {
+    op: Operation,
+}
+
+impl Args for Options {
+    fn from_args(args: Vec<OsString>) -> anyhow::Result<(Self, Vec<OsString>)> {
+        use lexopt::prelude::*;
+
+        let mut parser = lexopt::Parser::from_args(args);
+        let mut op: Option<OperationName> = None;
+        let mut rid: Option<RepoId> = None;
+        let mut repair = false;
+        let mut timeout = DEFAULT_FETCH_TIMEOUT;
+        let mut json = false;
+
+        while let Some(arg) = parser.next()? {
+            match arg {
+                Long("help") | Short('h') => {
+                    return Err(Error::Help.into());
+                }
+                Value(val) if op.is_none() => match val.to_string_lossy().as_ref() {
+                    "fsck" => op = Some(OperationName::Fsck),
+
+                    unknown => anyhow::bail!("unknown operation '{}'", unknown),
+                },
+                Long("repair") if op == Some(OperationName::Fsck) => repair = true,
+                Long("json") if op == Some(OperationName::Fsck) => json = true,
+                Long("timeout") if op == Some(OperationName::Fsck) => {
+                    let val = parser.value()?;
+                    timeout = term::args::seconds(&val)?;
+                }
+                Value(val) if op == Some(OperationName::Fsck) && rid.is_none() => {
+                    rid = Some(term::args::rid(&val)?);
+                }
+                _ => return Err(anyhow!(arg.unexpected())),
+            }
+        }
+
+        let op = match op {
+            Some(OperationName::Fsck) => Operation::Fsck {
+                rid,
+                repair,
+                timeout,
+                json,
+            },
+            None => anyhow::bail!("an operation must be provided; see `rad storage --help`"),
+        };
+        Ok((Options { op }, vec![]))
+    }
+}
+
+/// Repairs made to a repository.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Repairs {
+    /// Remotes removed from the repository.
+    removed: Vec<NodeId>,
+    /// Seeds the repository was fetched from.
+    fetched: Vec<NodeId>,
+    /// Whether the COB caches were rebuilt.
+    caches_rebuilt: bool,
+}
+
+pub fn run(options: Options, ctx: impl term::Context) -> anyhow::Result<()> {
+    let profile = ctx.profile()?;
+
+    match options.op {
+        Operation::Fsck {
+            rid,
+            repair,
+            timeout,
+            json,
+        } => fsck(rid, repair, timeout, json, &profile),
+    }
+}
+
+fn fsck(
+    rid: Option<RepoId>,
+    repair: bool,
+    timeout: time::Duration,
+    json: bool,
+    profile: &Profile,
+) -> anyhow::Result<()> {
+    let storage = &profile.storage;
+    let rids = match rid {
+        Some(rid) => vec![rid],
+        None => storage.repositories()?.into_iter().map(|r| r.rid).collect(),
+    };
+    let mut node = Node::new(profile.socket());
+    let mut problems = 0;
+    let mut failed = 0;
+
+    for rid in rids {
+        // Nb. a repository we can't open or audit doesn't stop us from auditing the others.
+        let result = storage
+            .repository(rid)
+            .map_err(anyhow::Error::from)
+            .and_then(|repo| check(&repo, repair, timeout, json, &mut node, profile));
+
+        match result {
+            Ok(remaining) => problems += remaining,
+            Err(e) => {
+                failed += 1;
+
+                if json {
+                    println!(
+                        "{}",
+                        serde_json::json!({ "rid": rid, "error": e.to_string() })
+                    );
+                } else {
+                    term::error(format!("{}: {e}", term::format::tertiary(rid)));
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("failed to audit {failed} repository(ies)");
+    }
+    if problems > 0 {
+        if repair {
+            anyhow::bail!("{problems} problem(s) remain after repairs");
+        }
+        anyhow::bail!("found {problems} problem(s); run with `--repair` to attempt repairs");
+    }
+    Ok(())
+}
+
+/// Audit a repository and print the report, repairing it if asked to.
+/// Returns the number of problems that remain.
+fn check(
+    repo: &Repository,
+    repair: bool,
+    timeout: time::Duration,
+    json: bool,
+    node: &mut Node,
+    profile: &Profile,
+) -> anyhow::Result<usize> {
+    let report = fsck::audit(repo)?;
+
+    if !repair || report.is_ok() {
+        if json {
+            println!("{}", serde_json::to_value(&report)?);
+        } else {
+            print(&report, None);
+        }
+        return Ok(report.problems.len());
+    }
+    let repairs = self::repair(repo, &report, timeout, node, profile)?;
+    // Repairs can fall short, eg. when a remote can't be removed or fetched again.
+    let after = fsck::audit(repo)?;
+
+    if json {
+        let mut obj = serde_json::to_value(&report)?;
+        obj["repairs"] = serde_json::to_value(&repairs)?;
+        obj["remaining"] = serde_json::to_value(&after.problems)?;
+        println!("{obj}");
+    } else {
+        print(&report, Some(&repairs));
+        print(&after, None);
+    }
+    Ok(after.problems.len())
+}
+
+/// Remove the remotes that failed the audit, fetch them again and rebuild the COB caches.
+fn repair(
+    repo: &Repository,
+    report: &Report,
+    timeout: time::Duration,
+    node: &mut Node,
+    profile: &Profile,
+) -> anyhow::Result<Repairs> {
+    let mut repairs = Repairs {
+        removed: fsck::prune(repo, profile.id(), report)?,
+        ..Repairs::default()
+    };
+
+    if node.is_running() {
+        let seeds = node.seeds(repo.id)?;
+        for seed in seeds.connected() {
+            match node.fetch(repo.id, seed.nid, timeout) {
+                Ok(result) if result.is_success() => repairs.fetched.push(seed.nid),
+                Ok(result) => {
+                    log::warn!(target: "cli", "Failed to fetch {} from {}: {result:?}", repo.id, seed.nid);
+                }
+                Err(e) => {
+                    log::warn!(target: "cli", "Failed to fetch {} from {}: {e}", repo.id, seed.nid);
+                }
+            }
+        }
+    }
+
+    // Nb. errors on individual objects are ignored: they were already reported by the audit.
+    term::cob::issues_mut(profile, repo)?.write_all(|_, _| ControlFlow::Continue(()))?;
+    term::cob::patches_mut(profile, repo)?.write_all(|_, _| ControlFlow::Continue(()))?;
+    term::cob::milestones_mut(profile, repo)?.write_all(|_, _| ControlFlow::Continue(()))?;
+    repairs.caches_rebuilt = true;
+
+    Ok(repairs)
+}
+
+fn print(report: &Report, repairs: Option<&Repairs>) {
+    let rid = term::format::tertiary(report.rid);
+
+    if report.is_ok() {
+        term::success!(
+            "{rid} is healthy ({} remote(s), {} ref(s), {} COB(s))",
+            report.remotes,
+            report.refs,
+            report.cobs
+        );
+        return;
+    }
+    term::warning(format!("{rid} has {} problem(s)", report.problems.len()));
+
+    for problem in &report.problems {
+        let line = match problem {
+            Problem::Identity { error } => format!("identity: {error}"),
+            Problem::Sigrefs { remote, error } | Problem::Validation { remote, error } => {
+                format!("{}: {error}", term::format::node(remote))
+            }
+            Problem::Object { refname, error, .. } => format!("{refname}: {error}"),
+            Problem::Cob {
+                type_name,
+                id,
+                error,
+            } => format!("{type_name} {id}: {error}"),
+        };
+        term::indented(term::format::dim(line));
+    }
+
+    if let Some(repairs) = repairs {
+        for remote in &repairs.removed {
+            term::info!("Removed remote {}", term::format::node(remote));
+        }
+        for seed in &repairs.fetched {
+            term::info!("Fetched {rid} from {}", term::format::node(seed));
+        }
+        if repairs.caches_rebuilt {
+            term::info!("Rebuilt COB caches for {rid}");
+        }
+    }
+}
@@ -4,7 +4,8 @@ use std::collections::BTreeSet;
 use std::collections::HashSet;
 use std::ffi::OsString;
 use std::str::FromStr;
-use std::time;
+use std::sync::mpsc;
+use std::{thread, time};
 
 use anyhow::{anyhow, Context as _};
 
@@ -26,6 +27,11 @@ use crate::terminal::args::{Args, Error, Help};
 use crate::terminal::format::Author;
 use crate::terminal::{Table, TableOptions};
 
+/// How long to wait for seed hints from connected peers, when no seed is known.
+const SEED_HINTS_TIMEOUT: time::Duration = time::Duration::from_secs(2);
+/// Maximum number of seeds to fetch from at the same time.
+const MAX_CONCURRENT_FETCHES: usize = 3;
+
 pub const HELP: Help = Help {
     name: "sync",
     description: "Sync repositories to the network",
@@ -465,6 +471,36 @@ pub fn announce_inventory(mut node: Node) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Wait until a hint from one of our peers makes a seed of the given repository known,
+/// or until the timeout is reached. Returns the seeds known at that point.
+fn wait_for_seed_hints(
+    rid: RepoId,
+    events: impl Iterator<Item = Result<node::Event, node::Error>>,
+    node: &mut Node,
+) -> Result<node::Seeds, node::Error> {
+    let deadline = time::Instant::now() + SEED_HINTS_TIMEOUT;
+
+    for event in events {
+        match event {
+            Ok(node::Event::SeedsHinted {
+                rid: hinted, seeds, ..
+            }) if hinted == rid && !seeds.is_empty() => {
+                let seeds = node.seeds(rid)?;
+                if !seeds.is_empty() {
+                    return Ok(seeds);
+                }
+            }
+            Ok(_) => {}
+            Err(node::Error::TimedOut) => break,
+            Err(e) => return Err(e),
+        }
+        if time::Instant::now() >= deadline {
+            break;
+        }
+    }
+    node.seeds(rid)
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum FetchError {
     #[error(transparent)]
@@ -494,7 +530,15 @@ pub fn fetch(
         None => {
             // We push nodes that are in our seed list in attempt to fulfill the
             // replicas, if needed.
-            let seeds = node.seeds(rid)?;
+            //
+            // Nb. We subscribe before looking up seeds, so that we don't miss any hints.
+            let events = node.subscribe(SEED_HINTS_TIMEOUT)?;
+            let mut seeds = node.seeds(rid)?;
+            if seeds.is_empty() && node.sessions()?.iter().any(|s| s.is_connected()) {
+                // No seeds are known, so our node asks its peers for seeds. Wait for
+                // their hints.
+                seeds = wait_for_seed_hints(rid, events, node)?;
+            }
             let (connected, disconnected) = seeds.partition();
             let candidates = connected
                 .into_iter()
@@ -515,40 +559,71 @@ pub fn fetch(
     );
     let mut spinner = FetcherSpinner::new(fetcher.target(), &progress);
 
-    while let Some(nid) = fetcher.next_node() {
-        match node.session(nid)? {
-            Some(session) if session.is_connected() => fetcher.ready_to_fetch(nid, session.addr),
-            _ => {
-                let addrs = db.addresses_of(&nid)?;
-                if addrs.is_empty() {
-                    fetcher.fetch_failed(nid, "Could not connect. No addresses known.");
-                } else if let Some(addr) = connect(
-                    nid,
-                    addrs.into_iter().map(|ka| ka.addr),
-                    settings.timeout,
-                    node,
-                    &mut spinner,
-                    &fetcher.progress(),
-                ) {
-                    fetcher.ready_to_fetch(nid, addr)
-                } else {
-                    fetcher
-                        .fetch_failed(nid, "Could not connect. At least one address is known but all attempts timed out.");
+    // Fetches run on their own threads, each with its own control socket, so that we
+    // can fetch from as many seeds as are still needed to reach the target at once. The
+    // node runs fetches of the same repository concurrently, up to its
+    // `limits.fetchSources`, and queues the rest.
+    let (results_tx, results_rx) = mpsc::channel();
+
+    loop {
+        while fetcher.remaining() > 0 && fetcher.in_flight().len() < MAX_CONCURRENT_FETCHES {
+            let Some(nid) = fetcher.next_node() else {
+                break;
+            };
+            match node.session(nid)? {
+                Some(session) if session.is_connected() => {
+                    fetcher.ready_to_fetch(nid, session.addr)
+                }
+                _ => {
+                    let addrs = db.addresses_of(&nid)?;
+                    if addrs.is_empty() {
+                        fetcher.fetch_failed(nid, "Could not connect. No addresses known.");
+                    } else if let Some(addr) = connect(
+                        nid,
+                        addrs.into_iter().map(|ka| ka.addr),
+                        settings.timeout,
+                        node,
+                        &mut spinner,
+                        &fetcher.progress(),
+                    ) {
+                        fetcher.ready_to_fetch(nid, addr)
+                    } else {
+                        fetcher
+                            .fetch_failed(nid, "Could not connect. At least one address is known but all attempts timed out.");
+                    }
                 }
             }
+            if let Some((nid, addr)) = fetcher.next_fetch() {
+                spinner.emit_fetching(&nid, &addr, &progress);
+
+                let mut node = node.clone();
+                let results = results_tx.clone();
+                let timeout = settings.timeout;
+
+                thread::spawn(move || {
+                    let result = node.fetch(rid, nid, timeout);
+                    // The receiver is dropped once the target is reached.
+                    results.send((nid, result)).ok();
+                });
+            }
         }
-        if let Some((nid, addr)) = fetcher.next_fetch() {
-            spinner.emit_fetching(&nid, &addr, &progress);
-            let result = node.fetch(rid, nid, settings.timeout)?;
-            match fetcher.fetch_complete(nid, result) {
-                std::ops::ControlFlow::Continue(update) => {
-                    spinner.emit_progress(&update);
-                    progress = update
-                }
-                std::ops::ControlFlow::Break(success) => {
-                    spinner.finished(success.outcome());
-                    return Ok(sync::FetcherResult::TargetReached(success));
+        if fetcher.in_flight().is_empty() {
+            break;
+        }
+        let Ok((nid, result)) = results_rx.recv() else {
+            break;
+        };
+        match fetcher.fetch_complete(nid, result?) {
+            std::ops::ControlFlow::Continue(update) => {
+                spinner.emit_progress(&update);
+                progress = update
+            }
+            std::ops::ControlFlow::Break(success) => {
+                for nid in success.pending() {
+                    log::debug!(target: "cli", "Target reached, not waiting for {nid}");
                 }
+                spinner.finished(success.outcome());
+                return Ok(sync::FetcherResult::TargetReached(success));
             }
         }
     }
@@ -235,6 +235,20 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
         "ls" => {
             term::run_command_args::<rad_ls::Options, _>(rad_ls::HELP, rad_ls::run, args.to_vec());
         }
+        "milestone" => {
+            term::run_command_args::<rad_milestone::Options, _>(
+                rad_milestone::HELP,
+                rad_milestone::run,
+                args.to_vec(),
+            );
+        }
+        "mirror" => {
+            term::run_command_args::<rad_mirror::Options, _>(
+                rad_mirror::HELP,
+                rad_mirror::run,
+                args.to_vec(),
+            );
+        }
         "node" => {
             term::run_command_args::<rad_node::Options, _>(
                 rad_node::HELP,
@@ -277,6 +291,13 @@ fn run_other(exe: &str, args: &[OsString]) -> Result<(), Option<anyhow::Error>>
                 args.to_vec(),
             );
         }
+        "storage" => {
+            term::run_command_args::<rad_storage::Options, _>(
+                rad_storage::HELP,
+                rad_storage::run,
+                args.to_vec(),
+            );
+        }
         "sync" => {
             term::run_command_args::<rad_sync::Options, _>(
                 rad_sync::HELP,
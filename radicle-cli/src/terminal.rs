@@ -8,6 +8,7 @@ pub mod comment;
 pub mod highlight;
 pub mod issue;
 pub mod json;
+pub mod milestone;
 pub mod patch;
 pub mod upload_pack;
 
@@ -159,6 +159,29 @@ pub fn seconds(val: &OsString) -> anyhow::Result<time::Duration> {
     Ok(time::Duration::from_secs(secs))
 }
 
+/// Parse a duration such as `90s`, `30m`, `12h` or `7d`.
+pub fn duration(val: &OsString) -> anyhow::Result<time::Duration> {
+    let val = val.to_string_lossy();
+    let err = || {
+        anyhow!(
+            "invalid duration '{}', expected eg. '30m', '12h' or '7d'",
+            val
+        )
+    };
+    let (n, unit) = val.split_at(val.find(|c: char| !c.is_ascii_digit()).ok_or_else(err)?);
+    let n = u64::from_str(n).map_err(|_| err())?;
+    let unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        _ => return Err(err()),
+    };
+    let secs = n.checked_mul(unit).ok_or_else(err)?;
+
+    Ok(time::Duration::from_secs(secs))
+}
+
 pub fn milliseconds(val: &OsString) -> anyhow::Result<time::Duration> {
     let val = val.to_string_lossy();
     let secs =
@@ -206,3 +229,8 @@ pub fn cob(val: &OsString) -> anyhow::Result<cob::ObjectId> {
     let val = val.to_string_lossy();
     cob::ObjectId::from_str(&val).map_err(|_| anyhow!("invalid Object ID '{}'", val))
 }
+
+pub fn filter(val: &OsString) -> anyhow::Result<cob::filter::Filter> {
+    let val = val.to_string_lossy();
+    cob::filter::Filter::from_str(&val).map_err(|e| anyhow!("invalid filter '{}': {e}", val))
+}
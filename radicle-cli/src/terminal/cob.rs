@@ -100,6 +100,34 @@ where
     profile.issues_mut(repository).map_err(with_hint)
 }
 
+/// Return a read-only handle for the milestones cache.
+pub fn milestones<'a, R>(
+    profile: &Profile,
+    repository: &'a R,
+) -> Result<
+    cob::milestone::Cache<cob::milestone::Milestones<'a, R>, cob::cache::StoreReader>,
+    anyhow::Error,
+>
+where
+    R: ReadRepository + cob::Store<Namespace = NodeId>,
+{
+    profile.milestones(repository).map_err(with_hint)
+}
+
+/// Return a read-write handle for the milestones cache.
+pub fn milestones_mut<'a, R>(
+    profile: &Profile,
+    repository: &'a R,
+) -> Result<
+    cob::milestone::Cache<cob::milestone::Milestones<'a, R>, cob::cache::StoreWriter>,
+    anyhow::Error,
+>
+where
+    R: ReadRepository + cob::Store<Namespace = NodeId>,
+{
+    profile.milestones_mut(repository).map_err(with_hint)
+}
+
 /// Adds a hint to the COB out-of-date database error.
 fn with_hint(e: profile::Error) -> anyhow::Error {
     match e {
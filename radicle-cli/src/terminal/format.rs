@@ -102,6 +102,7 @@ pub fn bytes(size: usize) -> Paint<String> {
 pub fn ref_update(update: &RefUpdate) -> Paint<&'static str> {
     match update {
         RefUpdate::Updated { .. } => term::format::tertiary("updated"),
+        RefUpdate::Forced { .. } => term::format::yellow("forced"),
         RefUpdate::Created { .. } => term::format::positive("created"),
         RefUpdate::Deleted { .. } => term::format::negative("deleted"),
         RefUpdate::Skipped { .. } => term::format::dim("skipped"),
@@ -122,6 +123,13 @@ pub fn ref_update_verbose(update: &RefUpdate) -> Paint<String> {
             term::format::secondary(name),
         )
         .into(),
+        RefUpdate::Forced { name, old, new } => format!(
+            "{: <17} {} {}",
+            format!("{}...{}", term::format::oid(*old), term::format::oid(*new)),
+            term::format::secondary(name),
+            term::format::yellow("(forced update)"),
+        )
+        .into(),
         RefUpdate::Deleted { name, .. } => format!(
             "{: <17} {}",
             term::format::negative("- [deleted]"),
@@ -309,7 +317,7 @@ pub mod issue {
 /// Patch formatting
 pub mod patch {
     use super::*;
-    use radicle::patch::{State, Verdict};
+    use radicle::patch::{MergeReadiness, State, Verdict};
 
     pub fn verdict(v: Option<Verdict>) -> term::Paint<String> {
         match v {
@@ -319,6 +327,15 @@ pub mod patch {
         }
     }
 
+    /// Format patch merge readiness.
+    pub fn readiness(r: &MergeReadiness) -> term::Paint<String> {
+        if r.is_ready() {
+            term::format::positive(r.to_string())
+        } else {
+            term::format::yellow(r.to_string())
+        }
+    }
+
     /// Format patch state.
     pub fn state(s: &State) -> term::Paint<String> {
         match s {
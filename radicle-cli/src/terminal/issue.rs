@@ -87,6 +87,30 @@ pub fn show(
         ]);
     }
 
+    for relation in [
+        issue::Relation::Blocks,
+        issue::Relation::BlockedBy,
+        issue::Relation::DuplicateOf,
+        issue::Relation::FixedBy,
+        issue::Relation::References,
+    ] {
+        let linked = issue
+            .links()
+            .filter(|link| link.relation == relation)
+            .map(|link| term::format::cob(&link.object).to_string())
+            .collect::<Vec<_>>();
+        if linked.is_empty() {
+            continue;
+        }
+        let mut label = relation.to_string();
+        label[..1].make_ascii_uppercase();
+
+        attrs.push([
+            term::format::tertiary(label).into(),
+            term::format::secondary(linked.join(", ")).into(),
+        ]);
+    }
+
     attrs.push([
         term::format::tertiary("Status".to_owned()).into(),
         match issue.state() {
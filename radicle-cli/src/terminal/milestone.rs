@@ -0,0 +1,185 @@
+use std::fmt;
+
+use chrono::prelude::*;
+
+use radicle_term::table::TableOptions;
+use radicle_term::{Table, VStack};
+
+use radicle::cob;
+use radicle::cob::issue::cache::Issues;
+use radicle::cob::milestone;
+use radicle::cob::patch::cache::Patches;
+use radicle::cob::Timestamp;
+use radicle::Profile;
+
+use crate::terminal as term;
+use crate::terminal::format::Author;
+use crate::terminal::Element;
+
+/// Progress of a milestone, ie. how many of its issues and patches are done.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Closed issues and merged patches.
+    pub done: usize,
+    /// All attached issues and patches.
+    pub total: usize,
+}
+
+impl Progress {
+    /// Percentage of attached items that are done.
+    pub fn percentage(&self) -> usize {
+        if self.total == 0 {
+            return 0;
+        }
+        self.done * 100 / self.total
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} ({}%)", self.done, self.total, self.percentage())
+    }
+}
+
+/// Compute the progress of a milestone. Issues are done when closed, and
+/// patches are done when merged. Items that can't be found count as not done.
+pub fn progress(
+    milestone: &milestone::Milestone,
+    issues: &impl Issues,
+    patches: &impl Patches,
+) -> anyhow::Result<Progress> {
+    let mut progress = Progress::default();
+
+    for id in milestone.issues() {
+        progress.total += 1;
+        if let Some(issue) = issues.get(id)? {
+            if matches!(issue.state(), cob::issue::State::Closed { .. }) {
+                progress.done += 1;
+            }
+        }
+    }
+    for id in milestone.patches() {
+        progress.total += 1;
+        if let Some(patch) = patches.get(id)? {
+            if patch.is_merged() {
+                progress.done += 1;
+            }
+        }
+    }
+    Ok(progress)
+}
+
+/// Format a due date.
+pub fn due(time: Timestamp) -> String {
+    let time = DateTime::<Utc>::from(
+        std::time::UNIX_EPOCH + std::time::Duration::from_secs(time.as_secs()),
+    );
+    time.format("%Y-%m-%d").to_string()
+}
+
+/// Format a milestone state.
+pub fn state(state: &milestone::State) -> term::Paint<String> {
+    match state {
+        milestone::State::Open => term::format::positive("open".to_owned()),
+        milestone::State::Closed => term::format::negative("closed".to_owned()),
+    }
+}
+
+pub fn show(
+    milestone: &milestone::Milestone,
+    id: &cob::ObjectId,
+    issues: &impl Issues,
+    patches: &impl Patches,
+    profile: &Profile,
+) -> anyhow::Result<()> {
+    let author = Author::new(milestone.author().id(), profile);
+    let mut attrs = Table::<2, term::Line>::new(TableOptions {
+        spacing: 2,
+        ..TableOptions::default()
+    });
+
+    attrs.push([
+        term::format::tertiary("Title".to_owned()).into(),
+        term::format::bold(milestone.title().to_owned()).into(),
+    ]);
+    attrs.push([
+        term::format::tertiary("Milestone".to_owned()).into(),
+        term::format::bold(id.to_string()).into(),
+    ]);
+    attrs.push([
+        term::format::tertiary("Author".to_owned()).into(),
+        author.line(),
+    ]);
+    if let Some(time) = milestone.due() {
+        attrs.push([
+            term::format::tertiary("Due".to_owned()).into(),
+            term::format::secondary(due(time)).into(),
+        ]);
+    }
+    attrs.push([
+        term::format::tertiary("Status".to_owned()).into(),
+        state(milestone.state()).into(),
+    ]);
+    attrs.push([
+        term::format::tertiary("Progress".to_owned()).into(),
+        term::format::default(progress(milestone, issues, patches)?.to_string()).into(),
+    ]);
+
+    let mut items = Table::<4, term::Line>::new(TableOptions {
+        spacing: 2,
+        ..TableOptions::default()
+    });
+    for id in milestone.issues() {
+        let (dot, title) = match issues.get(id)? {
+            Some(issue) => (
+                match issue.state() {
+                    cob::issue::State::Open => term::format::positive("●"),
+                    cob::issue::State::Closed { .. } => term::format::negative("●"),
+                },
+                issue.title().to_owned(),
+            ),
+            None => (term::format::dim("●"), String::default()),
+        };
+        items.push([
+            dot.into(),
+            term::format::tertiary(term::format::cob(id)).into(),
+            term::format::dim("issue".to_owned()).into(),
+            term::format::default(title).into(),
+        ]);
+    }
+    for id in milestone.patches() {
+        let (dot, title) = match patches.get(id)? {
+            Some(patch) => (
+                match patch.state() {
+                    cob::patch::State::Draft => term::format::dim("●"),
+                    cob::patch::State::Open { .. } => term::format::positive("●"),
+                    cob::patch::State::Archived => term::format::yellow("●"),
+                    cob::patch::State::Merged { .. } => term::format::primary("●"),
+                },
+                patch.title().to_owned(),
+            ),
+            None => (term::format::dim("●"), String::default()),
+        };
+        items.push([
+            dot.into(),
+            term::format::tertiary(term::format::cob(id)).into(),
+            term::format::dim("patch".to_owned()).into(),
+            term::format::default(title).into(),
+        ]);
+    }
+
+    let description = milestone.description().trim();
+    let mut widget = VStack::default()
+        .border(Some(term::colors::FAINT))
+        .child(attrs);
+
+    if !description.is_empty() {
+        widget = widget.divider().child(term::textarea(description).wrap(60));
+    }
+    if !items.is_empty() {
+        widget = widget.divider().child(items);
+    }
+    widget.print();
+
+    Ok(())
+}
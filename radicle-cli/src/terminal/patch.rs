@@ -1,6 +1,7 @@
 mod common;
 mod timeline;
 
+use std::collections::BTreeSet;
 use std::fmt;
 use std::fmt::Write;
 use std::io;
@@ -11,10 +12,13 @@ use thiserror::Error;
 use radicle::cob;
 use radicle::cob::patch;
 use radicle::git;
+use radicle::identity::Did;
+use radicle::node::AliasStore as _;
+use radicle::patch::cache::Patches as _;
 use radicle::patch::{Patch, PatchId};
 use radicle::prelude::Profile;
 use radicle::storage::git::Repository;
-use radicle::storage::WriteRepository as _;
+use radicle::storage::{ReadRepository as _, WriteRepository as _};
 
 use crate::terminal as term;
 use crate::terminal::Element;
@@ -195,20 +199,26 @@ pub fn patch_commits<'a>(
 }
 
 /// The message shown in the editor when creating a `Patch`.
+///
+/// If a template body is given, it follows the summary of the commits.
 fn create_display_message(
     repo: &git::raw::Repository,
     base: &git::Oid,
     head: &git::Oid,
+    template: Option<&str>,
 ) -> Result<String, Error> {
     let commits = patch_commits(repo, base, head)?;
-    if commits.is_empty() {
-        return Ok(PATCH_MSG.trim_start().to_string());
-    }
-
     let summary = message_from_commits("patch", commits)?;
     let summary = summary.trim();
+    let template = template.map(str::trim).unwrap_or_default();
 
-    Ok(format!("{summary}\n{PATCH_MSG}"))
+    let message = match (summary.is_empty(), template.is_empty()) {
+        (true, true) => return Ok(PATCH_MSG.trim_start().to_string()),
+        (true, false) => format!("\n\n{template}"),
+        (false, true) => summary.to_owned(),
+        (false, false) => format!("{summary}\n\n{template}"),
+    };
+    Ok(format!("{message}\n{PATCH_MSG}"))
 }
 
 /// Get the Patch title and description from the command line arguments, or request it from the
@@ -220,8 +230,9 @@ pub fn get_create_message(
     repo: &git::raw::Repository,
     base: &git::Oid,
     head: &git::Oid,
+    template: Option<&str>,
 ) -> Result<(String, String), Error> {
-    let display_msg = create_display_message(repo, base, head)?;
+    let display_msg = create_display_message(repo, base, head, template)?;
     let message = message.get(&display_msg)?;
 
     let (title, description) = message.split_once('\n').unwrap_or((&message, ""));
@@ -365,6 +376,39 @@ pub fn show(
     let author = patch.author();
     let author = term::format::Author::new(author.id(), profile);
     let labels = patch.labels().map(|l| l.to_string()).collect::<Vec<_>>();
+    let patches = term::cob::patches(profile, stored)?;
+    let dependencies = patch
+        .dependencies()
+        .map(|dep| Ok((*dep, patches.get(dep)?)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let dependents = patches
+        .dependents(id)?
+        .into_iter()
+        .map(|(dep, patch)| (dep, Some(patch)))
+        .collect::<Vec<_>>();
+    let (readiness, pending) = if patch.is_open() || patch.is_draft() {
+        let doc = stored.identity_doc()?;
+        let persons = stored.persons()?;
+        let readiness = doc
+            .merge_policy()
+            .unwrap_or_else(|e| {
+                term::warning(format!("ignoring invalid merge policy: {e}"));
+                None
+            })
+            .map(|policy| patch.merge_readiness(&policy, &doc, &persons));
+        // Reviews are pending from the suggested reviewers, as well as from the current
+        // owners of the paths changed by the latest revision.
+        let mut pending: BTreeSet<Did> = match doc.code_owners()? {
+            Some(owners) => revision.owners(stored.raw(), &owners)?,
+            None => BTreeSet::new(),
+        };
+        pending.retain(|did| revision.review_by(did).is_none() && did != patch.author().id());
+        pending.extend(patch.pending_reviewers());
+
+        (readiness, pending)
+    } else {
+        (None, BTreeSet::new())
+    };
 
     let mut attrs = term::Table::<2, term::Line>::new(term::TableOptions {
         spacing: 2,
@@ -398,6 +442,24 @@ pub fn show(
             term::format::secondary(revision.base().to_string()).into(),
         ]);
     }
+    if let patch::MergeTarget::Branch(target) = patch.target() {
+        attrs.push([
+            term::format::tertiary("Target".to_owned()).into(),
+            term::format::yellow(target.to_string()).into(),
+        ]);
+    }
+    if !dependencies.is_empty() {
+        attrs.push([
+            term::format::tertiary("Depends on".to_owned()).into(),
+            term::format::secondary(related(&dependencies)).into(),
+        ]);
+    }
+    if !dependents.is_empty() {
+        attrs.push([
+            term::format::tertiary("Dependents".to_owned()).into(),
+            term::format::secondary(related(&dependents)).into(),
+        ]);
+    }
     if !branches.is_empty() {
         attrs.push([
             term::format::tertiary("Branches".to_owned()).into(),
@@ -418,6 +480,25 @@ pub fn show(
         }
         .into(),
     ]);
+    if let Some(readiness) = readiness {
+        attrs.push([
+            term::format::tertiary("Merge".to_owned()).into(),
+            term::format::patch::readiness(&readiness).into(),
+        ]);
+    }
+    if !pending.is_empty() {
+        let reviewers = pending
+            .iter()
+            .map(|did| match profile.alias(did) {
+                Some(alias) => alias.to_string(),
+                None => term::format::did(did).to_string(),
+            })
+            .collect::<Vec<_>>();
+        attrs.push([
+            term::format::tertiary("Reviews".to_owned()).into(),
+            term::format::yellow(format!("pending from {}", reviewers.join(", "))).into(),
+        ]);
+    }
 
     let commits = patch_commit_lines(patch, stored)?;
     let description = patch.description().trim();
@@ -454,6 +535,18 @@ pub fn show(
     Ok(())
 }
 
+/// Format a list of related patches along with their state, eg. `4eec343 (merged)`.
+fn related(patches: &[(PatchId, Option<Patch>)]) -> String {
+    patches
+        .iter()
+        .map(|(id, patch)| match patch {
+            Some(patch) => format!("{} ({})", term::format::cob(id), patch.state()),
+            None => format!("{} (unknown)", term::format::cob(id)),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 fn patch_commit_lines(
     patch: &patch::Patch,
     stored: &Repository,
@@ -521,7 +614,7 @@ mod test {
             "Commit 2\n\nDescription\n",
         );
 
-        let res = create_display_message(&repo, &commit_0, &commit_0).unwrap();
+        let res = create_display_message(&repo, &commit_0, &commit_0, None).unwrap();
         assert_eq!(
             "\
             <!--\n\
@@ -537,7 +630,7 @@ mod test {
             res
         );
 
-        let res = create_display_message(&repo, &commit_0, &commit_1).unwrap();
+        let res = create_display_message(&repo, &commit_0, &commit_1, None).unwrap();
         assert_eq!(
             "\
             Commit 1\n\
@@ -557,7 +650,30 @@ mod test {
             res
         );
 
-        let res = create_display_message(&repo, &commit_0, &commit_2).unwrap();
+        let res =
+            create_display_message(&repo, &commit_0, &commit_1, Some("## Testing\n")).unwrap();
+        assert_eq!(
+            "\
+            Commit 1\n\
+            \n\
+            Description\n\
+            \n\
+            ## Testing\n\
+            \n\
+            <!--\n\
+            Please enter a patch message for your changes. An empty\n\
+            message aborts the patch proposal.\n\
+            \n\
+            The first line is the patch title. The patch description\n\
+            follows, and must be separated with a blank line, just\n\
+            like a commit message. Markdown is supported in the title\n\
+            and description.\n\
+            -->\n\
+            ",
+            res
+        );
+
+        let res = create_display_message(&repo, &commit_0, &commit_2, None).unwrap();
         assert_eq!(
             "\
             <!--\n\
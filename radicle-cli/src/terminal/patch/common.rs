@@ -1,5 +1,6 @@
 use anyhow::anyhow;
 
+use radicle::cob::patch;
 use radicle::git;
 use radicle::git::raw::Oid;
 use radicle::prelude::*;
@@ -24,12 +25,21 @@ fn get_branch(git_ref: git::Qualified) -> git::RefString {
 }
 
 /// Determine the merge target for this patch. This can be any followed remote's "default" branch,
-/// as well as your own (eg. `rad/master`).
+/// as well as your own (eg. `rad/master`), or the canonical head of the patch's target branch.
 pub fn get_merge_target(
     storage: &Repository,
     head_branch: &git::raw::Branch,
+    target: &patch::MergeTarget,
 ) -> anyhow::Result<(git::RefString, git::Oid)> {
-    let (qualified_ref, target_oid) = storage.canonical_head()?;
+    let (qualified_ref, target_oid) = match target {
+        patch::MergeTarget::Delegates => storage.canonical_head()?,
+        patch::MergeTarget::Branch(name) => {
+            let qualified_ref = git::refs::branch(name);
+            let target_oid = storage.canonical_reference(&qualified_ref)?;
+
+            (qualified_ref, target_oid)
+        }
+    };
     let head_oid = branch_oid(head_branch)?;
     let merge_base = storage.raw().merge_base(*head_oid, *target_oid)?;
 
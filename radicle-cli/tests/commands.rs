@@ -158,6 +158,76 @@ fn rad_issue() {
     test("examples/rad-issue.md", &working, Some(home), []).unwrap();
 }
 
+#[test]
+fn rad_issue_link() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-issue-link.md", &working, Some(home), []).unwrap();
+}
+
+#[test]
+fn rad_milestone() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-milestone.md", &working, Some(home), []).unwrap();
+}
+
+#[test]
+fn rad_templates() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-templates.md", &working, Some(home), []).unwrap();
+}
+
+#[test]
+fn rad_search() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-search.md", &working, Some(home), []).unwrap();
+}
+
+#[test]
+fn rad_filter() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let home = &profile.home;
+    let working = environment.tmp().join("working");
+
+    // Setup a test repository.
+    fixtures::repository(&working);
+
+    test("examples/rad-init.md", &working, Some(home), []).unwrap();
+    test("examples/rad-filter.md", &working, Some(home), []).unwrap();
+}
+
 #[test]
 fn rad_cob_update() {
     let mut environment = Environment::new();
@@ -409,6 +479,60 @@ fn rad_inspect() {
     test("examples/rad-inspect-noauth.md", working.path(), None, []).unwrap();
 }
 
+#[test]
+fn rad_storage_fsck() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let working = tempfile::tempdir().unwrap();
+    let acme = RepoId::from_str("z42hL2jL4XNk6K8oHQaSWfMgCL7ji").unwrap();
+
+    // Setup a test repository.
+    fixtures::repository(working.path());
+
+    test(
+        "examples/rad-init.md",
+        working.path(),
+        Some(&profile.home),
+        [],
+    )
+    .unwrap();
+
+    // Corrupt the storage, by adding a remote without signed refs.
+    let repo = profile.storage.repository(acme).unwrap();
+    let head = repo.backend.head().unwrap().target().unwrap();
+    repo.backend
+        .reference(
+            "refs/namespaces/z6Mkt67GdsW7715MEfRuP4pSZxJRJh6kj6Y48WRqVv4N1tRk/refs/heads/master",
+            head,
+            false,
+            "corrupt",
+        )
+        .unwrap();
+
+    test(
+        "examples/rad-storage-fsck.md",
+        working.path(),
+        Some(&profile.home),
+        [],
+    )
+    .unwrap();
+}
+
+#[test]
+fn rad_mirror() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let tmp = tempfile::tempdir().unwrap();
+    let working = tmp.path().join("heartwood");
+
+    // Setup a test repository, and a bare repository to mirror it to.
+    fixtures::repository(&working);
+    radicle::git::raw::Repository::init_bare(tmp.path().join("mirror.git")).unwrap();
+
+    test("examples/rad-init.md", &working, Some(&profile.home), []).unwrap();
+    test("examples/rad-mirror.md", &working, Some(&profile.home), []).unwrap();
+}
+
 #[test]
 fn rad_config() {
     let mut environment = Environment::new();
@@ -656,6 +780,60 @@ fn rad_id_update_delete_field() {
     .unwrap();
 }
 
+#[test]
+fn rad_canonical_refs() {
+    let mut environment = Environment::new();
+    let alice = environment.node(config::node("alice"));
+    let working = tempfile::tempdir().unwrap();
+    let working = working.path();
+
+    // Setup a test repository.
+    fixtures::repository(working.join("alice"));
+
+    test(
+        "examples/rad-init.md",
+        working.join("alice"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+
+    test(
+        "examples/rad-canonical-refs.md",
+        working.join("alice"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+}
+
+#[test]
+fn rad_id_payloads() {
+    let mut environment = Environment::new();
+    let alice = environment.node(config::node("alice"));
+    let working = tempfile::tempdir().unwrap();
+    let working = working.path();
+
+    // Setup a test repository.
+    fixtures::repository(working.join("alice"));
+
+    test(
+        "examples/rad-init.md",
+        working.join("alice"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+
+    test(
+        "examples/rad-id-payloads.md",
+        working.join("alice"),
+        Some(&alice.home),
+        [],
+    )
+    .unwrap();
+}
+
 #[test]
 fn rad_id_multi_delegate() {
     let mut environment = Environment::new();
@@ -1216,6 +1394,86 @@ fn rad_patch_via_push() {
     .unwrap();
 }
 
+#[test]
+fn rad_patch_target() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let working = tempfile::tempdir().unwrap();
+    let home = &profile.home;
+
+    // Setup a test repository.
+    fixtures::repository(working.path());
+
+    test("examples/rad-init.md", working.path(), Some(home), []).unwrap();
+    test(
+        "examples/rad-patch-target.md",
+        working.path(),
+        Some(home),
+        [],
+    )
+    .unwrap();
+}
+
+#[test]
+fn rad_patch_merge_policy() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let working = tempfile::tempdir().unwrap();
+    let home = &profile.home;
+
+    // Setup a test repository.
+    fixtures::repository(working.path());
+
+    test("examples/rad-init.md", working.path(), Some(home), []).unwrap();
+    test(
+        "examples/rad-patch-merge-policy.md",
+        working.path(),
+        Some(home),
+        [],
+    )
+    .unwrap();
+}
+
+#[test]
+fn rad_patch_code_owners() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let working = tempfile::tempdir().unwrap();
+    let home = &profile.home;
+
+    // Setup a test repository.
+    fixtures::repository(working.path());
+
+    test("examples/rad-init.md", working.path(), Some(home), []).unwrap();
+    test(
+        "examples/rad-patch-code-owners.md",
+        working.path(),
+        Some(home),
+        [],
+    )
+    .unwrap();
+}
+
+#[test]
+fn rad_patch_stack() {
+    let mut environment = Environment::new();
+    let profile = environment.profile(config::profile("alice"));
+    let working = tempfile::tempdir().unwrap();
+    let home = &profile.home;
+
+    // Setup a test repository.
+    fixtures::repository(working.path());
+
+    test("examples/rad-init.md", working.path(), Some(home), []).unwrap();
+    test(
+        "examples/rad-patch-stack.md",
+        working.path(),
+        Some(home),
+        [],
+    )
+    .unwrap();
+}
+
 #[test]
 fn rad_patch_detached_head() {
     let mut environment = Environment::new();
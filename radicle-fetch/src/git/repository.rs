@@ -141,9 +141,16 @@ fn direct<'a>(
                 .into()),
                 Ancestry::Ahead => {
                     // N.b. the update is a fast-forward so we can safely
-                    // pass `force: true`.
+                    // pass `force: true`. The reference must still point to
+                    // `prev`, in case another fetch updated it concurrently.
                     repo.backend
-                        .reference(name.as_ref(), target.into(), true, "radicle: update")
+                        .reference_matching(
+                            name.as_ref(),
+                            target.into(),
+                            true,
+                            prev.into(),
+                            "radicle: update",
+                        )
                         .map_err(|err| error::Update::Create {
                             name: name.to_owned(),
                             target,
@@ -155,13 +162,24 @@ fn direct<'a>(
                     // N.b. the update is a non-fast-forward but
                     // we allow it, so we pass `force: true`.
                     repo.backend
-                        .reference(name.as_ref(), target.into(), true, "radicle: update")
+                        .reference_matching(
+                            name.as_ref(),
+                            target.into(),
+                            true,
+                            prev.into(),
+                            "radicle: forced update",
+                        )
                         .map_err(|err| error::Update::Create {
                             name: name.to_owned(),
                             target,
                             err,
                         })?;
-                    Ok(RefUpdate::from(name.to_ref_string(), prev, target).into())
+                    Ok(RefUpdate::Forced {
+                        name: name.to_ref_string(),
+                        old: prev,
+                        new: target,
+                    }
+                    .into())
                 }
                 // N.b. if the target is behind, we simply reject the update
                 Ancestry::Behind => Ok(Update::Direct {
@@ -1,5 +1,5 @@
 use std::sync::atomic::{self, AtomicBool};
-use std::sync::Arc;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
 
 use bstr::BString;
 use radicle::crypto::PublicKey;
@@ -27,6 +27,11 @@ pub struct Handle<S> {
     pub(crate) blocked: BlockList,
     // Signals to the pack writer to interrupt the process
     pub(crate) interrupt: Arc<AtomicBool>,
+    /// Lock shared with other fetches of the same repository, taken before
+    /// writing to the repository.
+    pub(crate) write_lock: Option<WriteLock>,
+    /// Held from the moment the fetch starts writing to the repository.
+    pub(crate) write_guard: Option<WriteGuard>,
 }
 
 impl<S> Handle<S> {
@@ -50,9 +55,33 @@ impl<S> Handle<S> {
             transport,
             blocked,
             interrupt: Arc::new(AtomicBool::new(false)),
+            write_lock: None,
+            write_guard: None,
         })
     }
 
+    /// Write to the repository under the given lock. Fetches of the same repository
+    /// that share a lock transfer data concurrently, but validate and apply the fetched
+    /// references one at a time, so that they don't race each other's updates.
+    pub fn with_write_lock(mut self, lock: WriteLock) -> Self {
+        self.write_lock = Some(lock);
+        self
+    }
+
+    /// Take the write lock, if any, blocking until it is available. The lock is held
+    /// until the handle is dropped, or the guard is taken with [`Handle::take_write_guard`].
+    pub(crate) fn lock_writes(&mut self) {
+        if self.write_guard.is_none() {
+            self.write_guard = self.write_lock.as_ref().map(WriteLock::lock);
+        }
+    }
+
+    /// Take the guard of the write lock, if it is held, so that further writes to the
+    /// repository, eg. of canonical references, happen under the same lock.
+    pub fn take_write_guard(&mut self) -> Option<WriteGuard> {
+        self.write_guard.take()
+    }
+
     pub fn is_blocked(&self, key: &PublicKey) -> bool {
         self.blocked.is_blocked(key)
     }
@@ -82,6 +111,45 @@ impl<S> Handle<S> {
     }
 }
 
+/// Lock serializing writes to a repository, across fetches.
+#[derive(Clone, Debug, Default)]
+pub struct WriteLock(Arc<(Mutex<bool>, Condvar)>);
+
+impl WriteLock {
+    /// Take the lock, blocking until it is available.
+    pub fn lock(&self) -> WriteGuard {
+        let (locked, released) = &*self.0;
+        let mut locked = locked.lock().unwrap_or_else(PoisonError::into_inner);
+
+        while *locked {
+            locked = released
+                .wait(locked)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        *locked = true;
+
+        WriteGuard(self.0.clone())
+    }
+
+    /// Whether this is the only reference to the lock, ie. it isn't shared nor held.
+    pub fn is_unused(&self) -> bool {
+        Arc::strong_count(&self.0) == 1
+    }
+}
+
+/// Guard of a [`WriteLock`]. The lock is released when the guard is dropped.
+#[derive(Debug)]
+pub struct WriteGuard(Arc<(Mutex<bool>, Condvar)>);
+
+impl Drop for WriteGuard {
+    fn drop(&mut self) {
+        let (locked, released) = &*self.0;
+
+        *locked.lock().unwrap_or_else(PoisonError::into_inner) = false;
+        released.notify_one();
+    }
+}
+
 pub mod error {
     use std::io;
 
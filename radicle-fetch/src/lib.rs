@@ -15,7 +15,7 @@ use std::time::Instant;
 use gix_protocol::handshake;
 
 pub use gix_protocol::{transport::bstr::ByteSlice, RemoteProgress};
-pub use handle::Handle;
+pub use handle::{Handle, WriteGuard, WriteLock};
 pub use policy::{Allowed, BlockList, Scope};
 pub use state::{FetchLimit, FetchResult};
 pub use transport::Transport;
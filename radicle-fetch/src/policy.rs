@@ -40,6 +40,15 @@ impl Allowed {
     }
 }
 
+impl Extend<PublicKey> for Allowed {
+    fn extend<T: IntoIterator<Item = PublicKey>>(&mut self, iter: T) {
+        match self {
+            Self::All => {}
+            Self::Followed { remotes } => remotes.extend(iter),
+        }
+    }
+}
+
 /// A set of [`PublicKey`]s to ignore when fetching from a remote.
 #[derive(Clone, Debug)]
 pub struct BlockList(HashSet<PublicKey>);
@@ -1,10 +1,13 @@
 use std::collections::{BTreeMap, BTreeSet};
 use std::ops::{Deref, Not as _};
 
+use radicle::git::Oid;
+use radicle::storage::git::Repository;
 pub use radicle::storage::refs::SignedRefsAt;
 pub use radicle::storage::{git::Validation, Validations};
 use radicle::{crypto::PublicKey, storage::ValidateRepository};
 
+use crate::git::repository;
 use crate::state::Cached;
 
 pub mod error {
@@ -21,6 +24,14 @@ pub mod error {
     }
 
     pub type Load = radicle::storage::refs::Error;
+
+    #[derive(Debug, Error)]
+    pub enum History {
+        #[error(transparent)]
+        Ancestry(#[from] crate::git::repository::error::Ancestry),
+        #[error(transparent)]
+        Load(#[from] Load),
+    }
 }
 
 /// A data carrier that associates that data with whether a given
@@ -91,6 +102,38 @@ pub(crate) fn validate(
     Ok(validations.is_empty().not().then_some(validations))
 }
 
+/// How the `rad/sigrefs` received from a remote relate to the ones
+/// found in storage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum History {
+    /// There are no `rad/sigrefs` in storage, or the received ones are
+    /// equal to, or descend from them.
+    Monotonic,
+    /// The received `rad/sigrefs` are an ancestor of the ones in
+    /// storage, ie. the node we are fetching from is out of date.
+    Behind { current: Oid },
+    /// The received `rad/sigrefs` do not descend from the ones in
+    /// storage, ie. the remote's history was rewritten.
+    Diverged { current: Oid },
+}
+
+/// Verify that the `received` `rad/sigrefs` of `remote` extend the
+/// history of the ones found in `repo`.
+pub(crate) fn history(
+    repo: &Repository,
+    remote: PublicKey,
+    received: Oid,
+) -> Result<History, error::History> {
+    let Some(SignedRefsAt { at: current, .. }) = SignedRefsAt::load(remote, repo)? else {
+        return Ok(History::Monotonic);
+    };
+    match repository::ancestry(repo, current, received)? {
+        repository::Ancestry::Equal | repository::Ancestry::Ahead => Ok(History::Monotonic),
+        repository::Ancestry::Behind => Ok(History::Behind { current }),
+        repository::Ancestry::Diverged => Ok(History::Diverged { current }),
+    }
+}
+
 /// The sigrefs found for each remote.
 ///
 /// Construct using [`RemoteRefs::load`].
@@ -10,7 +10,8 @@ use radicle::prelude::Verified;
 use radicle::storage;
 use radicle::storage::refs::RefsAt;
 use radicle::storage::{
-    git::Validation, Remote, RemoteId, RemoteRepository, Remotes, ValidateRepository, Validations,
+    git::Validation, ReadRepository, Remote, RemoteId, RemoteRepository, Remotes,
+    ValidateRepository, Validations,
 };
 
 use crate::git;
@@ -72,8 +73,12 @@ pub mod error {
         Resolve(#[from] repository::error::Resolve),
         #[error(transparent)]
         Refs(#[from] radicle::storage::refs::Error),
+        #[error("failed to load persons: {0}")]
+        Persons(#[source] radicle::storage::RepositoryError),
         #[error(transparent)]
         RemoteRefs(#[from] sigrefs::error::RemoteRefs),
+        #[error(transparent)]
+        History(#[from] sigrefs::error::History),
         #[error("failed to get remote namespaces: {0}")]
         RemoteIds(#[source] radicle::git::raw::Error),
         #[error(transparent)]
@@ -282,7 +287,8 @@ impl FetchState {
     ///
     /// If `refs_at` is `None`, then run the [`SpecialRefs`] stage,
     /// which fetches `rad/sigrefs` and `rad/id` from all tracked and
-    /// delegate peers (scope dependent).
+    /// delegate peers (scope dependent), as well as the `devices` of
+    /// delegates.
     ///
     /// The resulting [`sigrefs::RemoteRefs`] will be the set of
     /// `rad/sigrefs` of the fetched remotes.
@@ -292,6 +298,7 @@ impl FetchState {
         handle: &mut Handle<S>,
         handshake: &handshake::Outcome,
         delegates: BTreeSet<PublicKey>,
+        devices: BTreeSet<PublicKey>,
         threshold: usize,
         limit: &FetchLimit,
         remote: PublicKey,
@@ -317,7 +324,8 @@ impl FetchState {
                 Ok(signed_refs)
             }
             None => {
-                let followed = handle.allowed();
+                let mut followed = handle.allowed();
+                followed.extend(devices);
                 log::trace!(target: "fetch", "Followed nodes {:?}", followed);
                 let special_refs = stage::SpecialRefs {
                     blocked: handle.blocked.clone(),
@@ -401,6 +409,25 @@ impl FetchState {
 
         log::trace!(target: "fetch", "Identity delegates {delegates:?}");
 
+        // Delegates that are persons sign with their devices, which are fetched
+        // along with followed nodes, so that canonical references can be
+        // computed. Devices don't count towards the threshold.
+        //
+        // N.b. devices are looked up in the persons that are already stored,
+        // hence a device added by this fetch is only fetched the next time.
+        let persons = handle
+            .repository()
+            .persons()
+            .map_err(error::Protocol::Persons)?;
+        let devices = anchor
+            .delegates()
+            .iter()
+            .flat_map(|did| persons.devices(did, anchor.person_of(did).as_ref()))
+            .filter(|key| !delegates.contains(key) && !handle.is_blocked(key))
+            .collect::<BTreeSet<_>>();
+
+        log::trace!(target: "fetch", "Delegate devices {devices:?}");
+
         // The local peer does not need to count towards the threshold
         // since they must be valid already.
         let threshold = if is_delegate {
@@ -412,6 +439,7 @@ impl FetchState {
             handle,
             handshake,
             delegates.clone(),
+            devices,
             threshold,
             &limit,
             remote,
@@ -446,6 +474,11 @@ impl FetchState {
                 log::warn!(target: "fetch", "Attempted to send done to remote {remote}: {err}")
             }
         }
+        // N.b. validation is against the references we currently have, so it must happen
+        // under the same lock as the updates, in case other fetches of this repository are
+        // writing to it.
+        handle.lock_writes();
+        log::debug!(target: "fetch", "Acquired write lock ({}ms)", start.elapsed().as_millis());
 
         // Run validation of signed refs, pruning any offending
         // remotes from the tips, thus not updating the production Git
@@ -500,15 +533,23 @@ impl FetchState {
                     remote,
                     data: Some(sigrefs),
                 } => {
-                    if let Some(SignedRefsAt { at, .. }) = SignedRefsAt::load(remote, &handle.repo)?
-                    {
-                        // Prune non-delegates if they're behind or
-                        // diverged. A diverged case is non-fatal for
-                        // delegates.
-                        if matches!(
-                            repository::ancestry(&handle.repo, at, sigrefs.at)?,
-                            repository::Ancestry::Behind | repository::Ancestry::Diverged
-                        ) {
+                    // Prune non-delegates if they're behind or
+                    // diverged. A diverged case is non-fatal for
+                    // non-delegates, but is reported.
+                    match sigrefs::history(&handle.repo, remote, sigrefs.at)? {
+                        sigrefs::History::Monotonic => {}
+                        sigrefs::History::Behind { current } => {
+                            log::trace!(target: "fetch", "Advertised `rad/sigrefs` {} is behind {current} for {remote}", sigrefs.at);
+                            self.prune(&remote);
+                            continue;
+                        }
+                        sigrefs::History::Diverged { current } => {
+                            log::warn!(target: "fetch", "Pruning non-delegate {remote} tips, `rad/sigrefs` diverged from {current}");
+                            failures.push(sigrefs::Validation::DivergedRadSigRefs {
+                                remote,
+                                current,
+                                received: sigrefs.at,
+                            });
                             self.prune(&remote);
                             continue;
                         }
@@ -530,17 +571,17 @@ impl FetchState {
                     remote,
                     data: Some(sigrefs),
                 } => {
-                    if let Some(SignedRefsAt { at, .. }) = SignedRefsAt::load(remote, &handle.repo)?
-                    {
-                        let ancestry = repository::ancestry(&handle.repo, at, sigrefs.at)?;
-                        if matches!(ancestry, repository::Ancestry::Behind) {
-                            log::trace!(target: "fetch", "Advertised `rad/sigrefs` {} is behind {at} for {remote}", sigrefs.at);
+                    match sigrefs::history(&handle.repo, remote, sigrefs.at)? {
+                        sigrefs::History::Monotonic => {}
+                        sigrefs::History::Behind { current } => {
+                            log::trace!(target: "fetch", "Advertised `rad/sigrefs` {} is behind {current} for {remote}", sigrefs.at);
                             self.prune(&remote);
                             continue;
-                        } else if matches!(ancestry, repository::Ancestry::Diverged) {
+                        }
+                        sigrefs::History::Diverged { current } => {
                             return Err(error::Protocol::Diverged {
                                 remote,
-                                current: at,
+                                current,
                                 received: sigrefs.at,
                             });
                         }
@@ -195,6 +195,11 @@ where
             }
             Err(e) => return Err(CommandError::Runtime(e)),
         },
+        Command::EventLog { filter } => {
+            let events = handle.event_log(filter)?;
+
+            CommandResult::Okay(events).to_writer(writer)?;
+        }
         Command::Status => {
             CommandResult::ok().to_writer(writer).ok();
         }
@@ -0,0 +1,298 @@
+//! Local network peer discovery.
+//!
+//! Nodes with discovery enabled periodically multicast a *beacon* to the local network: their
+//! signed node announcement, along with the ports they accept connections on. Nodes listening on
+//! the same multicast group verify the beacon and add the sender to their address book, using
+//! the source address of the datagram and the announced ports.
+use std::collections::HashSet;
+use std::{io, net, time};
+
+use radicle::node::config::DiscoveryConfig;
+use radicle::node::{address, ConnectOptions, Database, KnownAddress};
+
+use crate::crypto::Signature;
+use crate::node::{Address, NodeId};
+use crate::prelude::BoundedVec;
+use crate::runtime::Handle;
+use crate::service;
+use crate::service::message::{AnnouncementMessage, NodeAnnouncement};
+use crate::wire;
+use crate::PROTOCOL_VERSION;
+
+/// Magic sequence all beacons start with.
+pub const BEACON_MAGIC: [u8; 4] = [b'r', b'a', b'd', PROTOCOL_VERSION];
+/// Maximum number of ports in a beacon.
+pub const MAX_PORTS: usize = 8;
+
+/// A node announcing itself on the local network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Beacon {
+    /// Announcing node.
+    pub node: NodeId,
+    /// Signature over the node announcement.
+    pub signature: Signature,
+    /// The node's announcement.
+    pub announcement: NodeAnnouncement,
+    /// Ports the node is listening on.
+    pub ports: BoundedVec<u16, MAX_PORTS>,
+}
+
+impl Beacon {
+    /// Create a new beacon from a signed node announcement.
+    pub fn new(
+        node: NodeId,
+        signature: Signature,
+        announcement: NodeAnnouncement,
+        ports: impl IntoIterator<Item = u16>,
+    ) -> Self {
+        let mut ports = ports.into_iter().collect::<Vec<_>>();
+        ports.sort_unstable();
+        ports.dedup();
+
+        Self {
+            node,
+            signature,
+            announcement,
+            ports: BoundedVec::truncate(ports),
+        }
+    }
+
+    /// Verify the signature on the announcement.
+    pub fn verify(&self) -> bool {
+        let msg = wire::serialize(&AnnouncementMessage::Node(self.announcement.clone()));
+        self.node.verify(msg, &self.signature).is_ok()
+    }
+}
+
+impl wire::Encode for Beacon {
+    fn encode<W: io::Write + ?Sized>(&self, writer: &mut W) -> Result<usize, io::Error> {
+        let mut n = 0;
+
+        n += BEACON_MAGIC.encode(writer)?;
+        n += self.node.encode(writer)?;
+        n += self.signature.encode(writer)?;
+        n += self.announcement.encode(writer)?;
+        n += self.ports.encode(writer)?;
+
+        Ok(n)
+    }
+}
+
+impl wire::Decode for Beacon {
+    fn decode<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, wire::Error> {
+        let magic = <[u8; 4]>::decode(reader)?;
+        if magic != BEACON_MAGIC {
+            return Err(wire::Error::InvalidProtocolVersion(magic));
+        }
+        let node = NodeId::decode(reader)?;
+        let signature = Signature::decode(reader)?;
+        let announcement = NodeAnnouncement::decode(reader)?;
+        let ports = BoundedVec::decode(reader)?;
+
+        Ok(Self {
+            node,
+            signature,
+            announcement,
+            ports,
+        })
+    }
+}
+
+/// Local network discovery. Sends our beacon to the multicast group, and processes
+/// the beacons of other nodes.
+pub struct Discovery {
+    /// Our node id.
+    nid: NodeId,
+    /// Socket bound to the multicast group port.
+    socket: net::UdpSocket,
+    /// Multicast group.
+    group: net::SocketAddrV4,
+    /// Time between beacons.
+    interval: time::Duration,
+    /// Our encoded beacon.
+    beacon: Vec<u8>,
+    /// Whether we should connect to the nodes we discover.
+    connect: bool,
+    /// Nodes discovered since startup.
+    discovered: HashSet<NodeId>,
+    /// Node database, holding the address book.
+    db: Database,
+    /// Handle to the node.
+    handle: Handle,
+}
+
+impl Discovery {
+    /// Join the configured multicast group.
+    pub fn bind(
+        config: &DiscoveryConfig,
+        beacon: Beacon,
+        connect: bool,
+        db: Database,
+        handle: Handle,
+    ) -> io::Result<Self> {
+        let socket = socket2::Socket::new(
+            socket2::Domain::IPV4,
+            socket2::Type::DGRAM,
+            Some(socket2::Protocol::UDP),
+        )?;
+        // Multiple nodes on the same host must be able to share the group port.
+        socket.set_reuse_address(true)?;
+        socket.bind(
+            &net::SocketAddr::from((net::Ipv4Addr::UNSPECIFIED, config.group.port())).into(),
+        )?;
+        socket.join_multicast_v4(config.group.ip(), &config.interface)?;
+        socket.set_multicast_if_v4(&config.interface)?;
+        socket.set_multicast_loop_v4(true)?;
+
+        Ok(Self {
+            nid: beacon.node,
+            socket: socket.into(),
+            group: config.group,
+            interval: time::Duration::from_secs(config.interval.max(1)),
+            beacon: wire::serialize(&beacon),
+            connect,
+            discovered: HashSet::new(),
+            db,
+            handle,
+        })
+    }
+
+    /// Run the discovery loop. Returns once the node is no longer running.
+    pub fn run(mut self) -> io::Result<()> {
+        let mut buf = vec![0; wire::Size::MAX as usize];
+        let mut next = time::Instant::now();
+
+        log::debug!(target: "discovery", "Discovering peers on {}..", self.group);
+
+        loop {
+            let now = time::Instant::now();
+            if now >= next {
+                if let Err(e) = self.socket.send_to(&self.beacon, self.group) {
+                    log::warn!(target: "discovery", "Failed to send beacon to {}: {e}", self.group);
+                }
+                next = now + self.interval;
+            }
+            self.socket.set_read_timeout(Some(
+                next.saturating_duration_since(now)
+                    .max(time::Duration::from_millis(1)),
+            ))?;
+
+            let (n, from) = match self.socket.recv_from(&mut buf) {
+                Ok(recv) => recv,
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            match wire::deserialize::<Beacon>(&buf[..n]) {
+                Ok(beacon) => {
+                    if let Err(e) = self.discovered(beacon, from.ip()) {
+                        log::debug!(target: "discovery", "Node is no longer running: {e}");
+                        return Ok(());
+                    }
+                }
+                Err(e) => {
+                    log::debug!(target: "discovery", "Received invalid beacon from {from}: {e}");
+                }
+            }
+        }
+    }
+
+    /// Process a beacon received from the given IP.
+    fn discovered(&mut self, beacon: Beacon, ip: net::IpAddr) -> io::Result<()> {
+        if beacon.node == self.nid {
+            return Ok(());
+        }
+        if !beacon.verify() {
+            log::debug!(target: "discovery", "Received beacon with invalid signature from {ip}");
+            return Ok(());
+        }
+        let Beacon {
+            node,
+            announcement: ann,
+            ports,
+            ..
+        } = beacon;
+
+        if ann.version != PROTOCOL_VERSION {
+            return Ok(());
+        }
+        let addrs = ports
+            .iter()
+            .map(|port| Address::from(net::SocketAddr::new(ip, *port)))
+            .collect::<Vec<_>>();
+        let Some(addr) = addrs.first().cloned() else {
+            return Ok(());
+        };
+
+        if let Err(e) = address::Store::insert(
+            &mut self.db,
+            &node,
+            ann.version,
+            ann.features,
+            &ann.alias,
+            ann.work(),
+            &ann.agent,
+            ann.timestamp,
+            addrs
+                .into_iter()
+                .map(|a| KnownAddress::new(a, address::Source::Local)),
+        ) {
+            log::error!(target: "discovery", "Error updating address book with {node}: {e}");
+            return Ok(());
+        }
+        if self.discovered.insert(node) {
+            log::info!(target: "discovery", "Discovered {node} ({addr}) on the local network");
+
+            if self.connect {
+                self.handle.command(service::Command::Connect(
+                    node,
+                    addr,
+                    ConnectOptions::default(),
+                ))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use radicle::node::device::Device;
+
+    #[test]
+    fn test_beacon_verify() {
+        let signer = Device::mock();
+        let ann = NodeAnnouncement {
+            version: PROTOCOL_VERSION,
+            features: radicle::node::Features::SEED,
+            timestamp: radicle::node::Timestamp::EPOCH + 1,
+            alias: radicle::node::Alias::new("alice"),
+            addresses: BoundedVec::new(),
+            nonce: 0,
+            agent: radicle::node::UserAgent::default(),
+        };
+        let signed = AnnouncementMessage::Node(ann.clone()).signed(&signer);
+        let beacon = Beacon::new(*signer.public_key(), signed.signature, ann, [8776, 8776, 9]);
+
+        assert_eq!(beacon.ports.as_slice(), &[9, 8776]);
+        assert!(beacon.verify());
+
+        let decoded = wire::deserialize::<Beacon>(&wire::serialize(&beacon)).unwrap();
+        assert_eq!(decoded, beacon);
+
+        let mut forged = beacon.clone();
+        forged.announcement.alias = radicle::node::Alias::new("eve");
+        assert!(!forged.verify());
+
+        let mut bytes = wire::serialize(&beacon);
+        bytes[0] = b'x';
+        assert!(wire::deserialize::<Beacon>(&bytes).is_err());
+    }
+}
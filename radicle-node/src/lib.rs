@@ -6,6 +6,8 @@
 pub mod bounded;
 pub mod control;
 pub mod deserializer;
+pub mod discovery;
+pub mod metrics;
 pub mod runtime;
 pub mod service;
 #[cfg(any(test, feature = "test"))]
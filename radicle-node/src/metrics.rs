@@ -0,0 +1,430 @@
+//! Node metrics, served over HTTP in the Prometheus text exposition format.
+//!
+//! Metrics are gathered on every scrape, from the service state and the node database. Upload-pack
+//! events are counted as they are emitted, since the service doesn't keep track of them.
+use std::fmt::Write as _;
+use std::io::{Read as _, Write as _};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::{fmt, io, net, time};
+
+use crossbeam_channel as chan;
+use radicle::node::events::UploadPack;
+use radicle::node::routing::Store as _;
+use radicle::node::{Database, State};
+
+use crate::node::NodeId;
+use crate::runtime::{thread, Handle};
+use crate::service;
+use crate::service::Event;
+
+/// Path metrics are served on.
+pub const METRICS_PATH: &str = "/metrics";
+/// Time a scraper has to send its whole request.
+pub const REQUEST_TIMEOUT: time::Duration = time::Duration::from_secs(3);
+/// Time to wait for a scraper to accept each write of the response.
+pub const RESPONSE_TIMEOUT: time::Duration = time::Duration::from_secs(3);
+/// Maximum size of a request we're willing to read.
+pub const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+/// Metrics taken from the service state.
+#[derive(Debug, Default)]
+struct Snapshot {
+    /// Sessions in each state: initial, attempted, connected, disconnected.
+    sessions: [usize; 4],
+    /// Ongoing fetches.
+    fetching: usize,
+    /// Service counters.
+    stats: service::Stats,
+    /// Wire protocol metrics.
+    metrics: service::Metrics,
+}
+
+/// Upload-pack event counters.
+#[derive(Debug, Default)]
+struct UploadPacks {
+    /// Upload-pack processes that finished.
+    done: AtomicU64,
+    /// Upload-pack processes that failed.
+    failed: AtomicU64,
+}
+
+/// Serves node metrics.
+pub struct Exporter {
+    /// Our node id.
+    nid: NodeId,
+    /// Metrics listener.
+    listener: net::TcpListener,
+    /// Number of fetch workers.
+    workers: usize,
+    /// Node database, holding the routing table.
+    db: Database,
+    /// Upload-pack event counters.
+    upload_packs: Arc<UploadPacks>,
+    /// Handle to the node.
+    handle: Handle,
+}
+
+impl Exporter {
+    /// Bind the metrics listener to the given address.
+    pub fn bind(
+        addr: net::SocketAddr,
+        nid: NodeId,
+        workers: usize,
+        db: Database,
+        handle: Handle,
+    ) -> io::Result<Self> {
+        let listener = net::TcpListener::bind(addr)?;
+
+        Ok(Self {
+            nid,
+            listener,
+            workers,
+            db,
+            upload_packs: Arc::default(),
+            handle,
+        })
+    }
+
+    /// Address the metrics listener is bound to.
+    pub fn local_addr(&self) -> io::Result<net::SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serve metrics. Returns once the node is no longer running.
+    pub fn run(self) -> io::Result<()> {
+        let events = self.handle.events();
+        let upload_packs = self.upload_packs.clone();
+
+        thread::spawn(&self.nid, "metrics-events", move || {
+            for event in events {
+                match event {
+                    Event::UploadPack(UploadPack::Done { .. }) => {
+                        upload_packs.done.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Event::UploadPack(UploadPack::Error { .. }) => {
+                        upload_packs.failed.fetch_add(1, Ordering::Relaxed);
+                    }
+                    _ => {}
+                }
+            }
+        });
+        log::info!(target: "metrics", "Serving metrics on {}..", self.listener.local_addr()?);
+
+        for stream in self.listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = self.respond(stream) {
+                        if e.kind() == io::ErrorKind::NotConnected {
+                            log::debug!(target: "metrics", "Node is no longer running: {e}");
+                            return Ok(());
+                        }
+                        log::debug!(target: "metrics", "Error serving metrics: {e}");
+                    }
+                }
+                Err(e) => log::warn!(target: "metrics", "Error accepting connection: {e}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Respond to a single request. Since requests are served one at a time, slow scrapers
+    /// are bounded by [`REQUEST_TIMEOUT`] and [`RESPONSE_TIMEOUT`].
+    fn respond(&self, mut stream: net::TcpStream) -> io::Result<()> {
+        let deadline = time::Instant::now() + REQUEST_TIMEOUT;
+        stream.set_write_timeout(Some(RESPONSE_TIMEOUT))?;
+
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+            let remaining = deadline
+                .checked_duration_since(time::Instant::now())
+                .filter(|d| !d.is_zero())
+                .ok_or(io::ErrorKind::TimedOut)?;
+            stream.set_read_timeout(Some(remaining))?;
+
+            let n = stream.read(&mut buf)?;
+            if n == 0 || request.len() + n > MAX_REQUEST_SIZE {
+                return Err(io::ErrorKind::InvalidData.into());
+            }
+            request.extend_from_slice(&buf[..n]);
+        }
+        let request = String::from_utf8_lossy(&request);
+        let mut line = request
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .split_whitespace();
+
+        let (status, body) = match (line.next(), line.next()) {
+            (Some("GET"), Some(METRICS_PATH)) => {
+                let snapshot = self.snapshot()?;
+                ("200 OK", self.render(snapshot).map_err(io::Error::other)?)
+            }
+            (Some("GET"), Some(_)) => ("404 Not Found", String::new()),
+            _ => ("405 Method Not Allowed", String::new()),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\n\
+            Content-Type: text/plain; version=0.0.4; charset=utf-8\r\n\
+            Content-Length: {}\r\n\
+            Connection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+        stream.flush()
+    }
+
+    /// Take a snapshot of the service state.
+    fn snapshot(&self) -> io::Result<Snapshot> {
+        let (sender, receiver) = chan::bounded(1);
+        let query: Arc<service::QueryState> = Arc::new(move |state| {
+            let mut snapshot = Snapshot {
+                fetching: state.fetching().len(),
+                stats: state.stats().clone(),
+                metrics: state.metrics().clone(),
+                ..Snapshot::default()
+            };
+            for session in state.sessions().values() {
+                let ix = match session.state {
+                    State::Initial => 0,
+                    State::Attempted => 1,
+                    State::Connected { .. } => 2,
+                    State::Disconnected { .. } => 3,
+                };
+                snapshot.sessions[ix] += 1;
+            }
+            sender.send(snapshot).ok();
+
+            Ok(())
+        });
+        let (err_sender, err_receiver) = chan::bounded(1);
+        let disconnected = || io::Error::from(io::ErrorKind::NotConnected);
+
+        self.handle
+            .command(service::Command::QueryState(query, err_sender))
+            .map_err(|_| disconnected())?;
+        err_receiver
+            .recv()
+            .map_err(|_| disconnected())?
+            .map_err(io::Error::other)?;
+
+        receiver.recv().map_err(|_| disconnected())
+    }
+
+    /// Render metrics in the Prometheus text format.
+    fn render(&self, snapshot: Snapshot) -> Result<String, fmt::Error> {
+        let Snapshot {
+            sessions,
+            fetching,
+            stats,
+            metrics,
+        } = snapshot;
+        let peers = metrics.peers.values();
+        let (mut sent, mut received, mut gossip_sent, mut gossip_received) = (0, 0, 0, 0);
+        for peer in peers {
+            sent += peer.sent_bytes;
+            received += peer.received_bytes;
+            gossip_sent += peer.sent_gossip_messages;
+            gossip_received += peer.received_gossip_messages;
+        }
+        let routing = self.db.len().unwrap_or_else(|e| {
+            log::warn!(target: "metrics", "Error getting routing table size: {e}");
+            0
+        });
+        let inventory = self
+            .db
+            .get_inventory(&self.nid)
+            .map(|i| i.len())
+            .unwrap_or_else(|e| {
+                log::warn!(target: "metrics", "Error getting inventory: {e}");
+                0
+            });
+        let mut out = String::new();
+
+        metric(
+            &mut out,
+            "radicle_sessions",
+            "gauge",
+            "Peer sessions, by state.",
+            ["initial", "attempted", "connected", "disconnected"]
+                .into_iter()
+                .zip(sessions)
+                .map(|(state, n)| (Some(("state", state)), n as u64)),
+        )?;
+        metric(
+            &mut out,
+            "radicle_fetches_total",
+            "counter",
+            "Completed fetches, by result.",
+            [
+                (Some(("result", "success")), stats.fetches_succeeded as u64),
+                (Some(("result", "failure")), stats.fetches_failed as u64),
+            ],
+        )?;
+        writeln!(
+            out,
+            "# HELP radicle_fetch_duration_seconds Time spent on completed fetches.\n\
+            # TYPE radicle_fetch_duration_seconds summary\n\
+            radicle_fetch_duration_seconds_sum {}\n\
+            radicle_fetch_duration_seconds_count {}",
+            stats.fetch_time as f64 / 1000.,
+            stats.fetches_succeeded + stats.fetches_failed,
+        )?;
+        metric(
+            &mut out,
+            "radicle_fetches_in_progress",
+            "gauge",
+            "Ongoing fetches.",
+            [(None, fetching as u64)],
+        )?;
+        metric(
+            &mut out,
+            "radicle_gossip_messages_total",
+            "counter",
+            "Gossip messages exchanged with peers, by direction.",
+            [
+                (Some(("direction", "sent")), gossip_sent as u64),
+                (Some(("direction", "received")), gossip_received as u64),
+            ],
+        )?;
+        metric(
+            &mut out,
+            "radicle_gossip_relayed_total",
+            "counter",
+            "Announcements relayed to peers.",
+            [(None, stats.relayed_announcements as u64)],
+        )?;
+        metric(
+            &mut out,
+            "radicle_gossip_rate_limited_total",
+            "counter",
+            "Messages dropped by the rate limiter.",
+            [(None, stats.rate_limited_messages as u64)],
+        )?;
+        metric(
+            &mut out,
+            "radicle_bytes_total",
+            "counter",
+            "Bytes exchanged with peers, by direction.",
+            [
+                (Some(("direction", "sent")), sent as u64),
+                (Some(("direction", "received")), received as u64),
+            ],
+        )?;
+        metric(
+            &mut out,
+            "radicle_routing_entries",
+            "gauge",
+            "Entries in the routing table.",
+            [(None, routing as u64)],
+        )?;
+        metric(
+            &mut out,
+            "radicle_inventory_repositories",
+            "gauge",
+            "Repositories in our inventory.",
+            [(None, inventory as u64)],
+        )?;
+        metric(
+            &mut out,
+            "radicle_workers",
+            "gauge",
+            "Fetch workers.",
+            [(None, self.workers as u64)],
+        )?;
+        metric(
+            &mut out,
+            "radicle_worker_queue_size",
+            "gauge",
+            "Tasks waiting for a fetch worker.",
+            [(None, metrics.worker_queue_size as u64)],
+        )?;
+        metric(
+            &mut out,
+            "radicle_open_channels",
+            "gauge",
+            "Open worker channels.",
+            [(None, metrics.open_channels as u64)],
+        )?;
+        metric(
+            &mut out,
+            "radicle_upload_packs_total",
+            "counter",
+            "Upload-pack processes served to peers, by result.",
+            [
+                (
+                    Some(("result", "done")),
+                    self.upload_packs.done.load(Ordering::Relaxed),
+                ),
+                (
+                    Some(("result", "error")),
+                    self.upload_packs.failed.load(Ordering::Relaxed),
+                ),
+            ],
+        )?;
+
+        Ok(out)
+    }
+}
+
+/// Write a metric with its samples, each with an optional label.
+fn metric<'a>(
+    out: &mut String,
+    name: &str,
+    kind: &str,
+    help: &str,
+    samples: impl IntoIterator<Item = (Option<(&'a str, &'a str)>, u64)>,
+) -> fmt::Result {
+    writeln!(out, "# HELP {name} {help}")?;
+    writeln!(out, "# TYPE {name} {kind}")?;
+
+    for (label, value) in samples {
+        match label {
+            Some((key, val)) => writeln!(out, "{name}{{{key}=\"{val}\"}} {value}")?,
+            None => writeln!(out, "{name} {value}")?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metric_format() {
+        let mut out = String::new();
+
+        metric(
+            &mut out,
+            "radicle_sessions",
+            "gauge",
+            "Peer sessions, by state.",
+            [
+                (Some(("state", "connected")), 3),
+                (Some(("state", "initial")), 0),
+            ],
+        )
+        .unwrap();
+        metric(
+            &mut out,
+            "radicle_workers",
+            "gauge",
+            "Fetch workers.",
+            [(None, 8)],
+        )
+        .unwrap();
+
+        assert_eq!(
+            out,
+            "# HELP radicle_sessions Peer sessions, by state.\n\
+            # TYPE radicle_sessions gauge\n\
+            radicle_sessions{state=\"connected\"} 3\n\
+            radicle_sessions{state=\"initial\"} 0\n\
+            # HELP radicle_workers Fetch workers.\n\
+            # TYPE radicle_workers gauge\n\
+            radicle_workers 8\n"
+        );
+    }
+}
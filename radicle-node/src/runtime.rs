@@ -3,7 +3,7 @@ pub mod thread;
 
 use std::os::unix::net::UnixListener;
 use std::path::PathBuf;
-use std::{fs, io, net};
+use std::{fs, io, iter, net};
 
 use crossbeam_channel as chan;
 use cyphernet::Ecdh;
@@ -27,13 +27,15 @@ use radicle::profile::Home;
 use radicle::{cob, git, storage, Storage};
 
 use crate::control;
+use crate::discovery::{Beacon, Discovery};
+use crate::metrics::Exporter;
 use crate::node::{routing, NodeId};
-use crate::service::message::NodeAnnouncement;
+use crate::service::message::{AnnouncementMessage, NodeAnnouncement};
 use crate::service::{gossip, policy, Event, INITIAL_SUBSCRIBE_BACKLOG_DELTA};
 use crate::wire;
 use crate::wire::{Decode, Wire};
 use crate::worker;
-use crate::{service, LocalTime};
+use crate::{service, LocalTime, Timestamp};
 
 pub use handle::Error as HandleError;
 pub use handle::Handle;
@@ -63,6 +65,9 @@ pub enum Error {
     /// A notifications database error.
     #[error("notifications database error: {0}")]
     Notifications(#[from] notifications::Error),
+    /// An event log error.
+    #[error("event log error: {0}")]
+    Events(#[from] node::events::store::Error),
     /// A gossip database error.
     #[error("gossip database error: {0}")]
     Gossip(#[from] gossip::Error),
@@ -108,6 +113,12 @@ pub struct Runtime {
     pub reactor: Reactor<wire::Control, popol::Poller>,
     pub pool: worker::Pool,
     pub local_addrs: Vec<net::SocketAddr>,
+    pub discovery: Option<Discovery>,
+    pub metrics: Option<Exporter>,
+    /// Events to be recorded in the event log.
+    pub events: chan::Receiver<Event>,
+    /// Event log.
+    pub log: node::events::Store,
     pub signals: chan::Receiver<Signal>,
 }
 
@@ -228,7 +239,10 @@ impl Runtime {
             log::info!(target: "node", "{} nodes added to address book", stores.addresses().len()?);
         }
 
+        let signed = AnnouncementMessage::Node(announcement.clone()).signed(&signer);
         let emitter: Emitter<Event> = Default::default();
+        // Subscribe before the service starts, so that no event is missing from the event log.
+        let events = emitter.subscribe();
         let mut service = service::Service::new(
             config.clone(),
             stores,
@@ -236,7 +250,7 @@ impl Runtime {
             policies,
             signer.clone(),
             rng,
-            announcement,
+            announcement.clone(),
             emitter.clone(),
         );
         service.initialize(clock)?;
@@ -255,11 +269,43 @@ impl Runtime {
         let reactor = Reactor::named(wire, popol::Poller::new(), thread::name(&id, "service"))?;
         let handle = Handle::new(home.clone(), reactor.controller(), emitter);
 
+        let discovery = if let Some(discovery) = &config.discovery {
+            let beacon = Beacon::new(
+                id,
+                signed.signature,
+                announcement,
+                local_addrs.iter().map(|a| a.port()),
+            );
+            let connect = matches!(config.peers, node::config::PeerConfig::Dynamic);
+
+            log::info!(target: "node", "Joining local network discovery group {}..", discovery.group);
+
+            Some(Discovery::bind(
+                discovery,
+                beacon,
+                connect,
+                db.clone(),
+                handle.clone(),
+            )?)
+        } else {
+            None
+        };
+
+        let metrics = if let Some(addr) = config.metrics {
+            let exporter = Exporter::bind(addr, id, config.workers, db.clone(), handle.clone())?;
+            log::info!(target: "node", "Metrics listener bound to {}..", exporter.local_addr()?);
+
+            Some(exporter)
+        } else {
+            None
+        };
+
         let nid = *signer.public_key();
         let fetch = worker::FetchConfig {
             limit: FetchLimit::default(),
             local: nid,
             expiry: worker::garbage::Expiry::default(),
+            mirrors: config.mirrors.clone(),
         };
         let pool = worker::Pool::with(
             worker_recv,
@@ -277,6 +323,9 @@ impl Runtime {
             },
         )?;
         let control = Self::bind(home.socket())?;
+        // Nb. The event log is written from its own thread, so it gets its own connection
+        // to the node database, which waits for the service's transactions to finish.
+        let log = home.events_mut()?;
 
         Ok(Runtime {
             id,
@@ -288,6 +337,10 @@ impl Runtime {
             pool,
             signals,
             local_addrs,
+            discovery,
+            metrics,
+            events,
+            log,
         })
     }
 
@@ -304,6 +357,41 @@ impl Runtime {
             let handle = self.handle.clone();
             || control::listen(listener, handle)
         });
+        if let Some(discovery) = self.discovery {
+            thread::spawn(&self.id, "discovery", || {
+                if let Err(e) = discovery.run() {
+                    log::error!(target: "node", "Local network discovery failed: {e}");
+                }
+            });
+        }
+        if let Some(metrics) = self.metrics {
+            thread::spawn(&self.id, "metrics", || {
+                if let Err(e) = metrics.run() {
+                    log::error!(target: "node", "Metrics listener failed: {e}");
+                }
+            });
+        }
+        thread::spawn(&self.id, "events", {
+            let (events, mut db) = (self.events, self.log);
+            move || {
+                // Record events in batches, to keep the number of writes down.
+                while let Ok(event) = events.recv() {
+                    let now = Timestamp::from(LocalTime::now());
+                    let batch = iter::once(event)
+                        .chain(events.try_iter())
+                        .filter(|e| e.is_loggable())
+                        .map(|e| (e, now))
+                        .collect::<Vec<_>>();
+
+                    if batch.is_empty() {
+                        continue;
+                    }
+                    if let Err(e) = db.record(&batch) {
+                        log::warn!(target: "node", "Failed to record events: {e}");
+                    }
+                }
+            }
+        });
         let _signals = thread::spawn(&self.id, "signals", move || loop {
             match self.signals.recv() {
                 Ok(Signal::Terminate | Signal::Interrupt) => {
@@ -5,7 +5,7 @@ use std::sync::Arc;
 use std::{fmt, io, time};
 
 use crossbeam_channel as chan;
-use radicle::node::{ConnectOptions, ConnectResult, Seeds};
+use radicle::node::{events, ConnectOptions, ConnectResult, Seeds};
 use radicle::storage::refs::RefsAt;
 use reactor::poller::popol::PopolWaker;
 use serde_json::json;
@@ -39,6 +39,9 @@ pub enum Error {
     /// An I/O error occured.
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    /// An event log error.
+    #[error("event log error: {0}")]
+    EventLog(#[from] events::store::Error),
 }
 
 impl From<chan::RecvError> for Error {
@@ -270,13 +273,20 @@ impl radicle::node::Handle for Handle {
         Ok(self.events())
     }
 
+    fn event_log(&self, filter: events::Filter) -> Result<Vec<events::Logged>, Self::Error> {
+        let db = self.home.events()?;
+        let events = db.events(&filter)?;
+
+        Ok(events)
+    }
+
     fn sessions(&self) -> Result<Self::Sessions, Error> {
         let (sender, receiver) = chan::unbounded();
         let query: Arc<QueryState> = Arc::new(move |state| {
             let sessions = state
                 .sessions()
                 .iter()
-                .map(|(_, s)| radicle::node::Session::from(s))
+                .map(|(nid, s)| session(s, state.metrics().peers.get(nid)))
                 .collect();
             sender.send(sessions).ok();
 
@@ -294,7 +304,10 @@ impl radicle::node::Handle for Handle {
     fn session(&self, nid: NodeId) -> Result<Option<radicle::node::Session>, Self::Error> {
         let (sender, receiver) = chan::bounded(1);
         let query: Arc<QueryState> = Arc::new(move |state| {
-            let session = state.sessions().get(&nid).map(radicle::node::Session::from);
+            let session = state
+                .sessions()
+                .get(&nid)
+                .map(|s| session(s, state.metrics().peers.get(&nid)));
             sender.send(session).ok();
 
             Ok(())
@@ -337,9 +350,14 @@ impl radicle::node::Handle for Handle {
                 "fetching": state.fetching().iter().map(|(rid, state)| {
                     json!({
                         "rid": rid,
-                        "from": state.from,
                         "refsAt": state.refs_at,
-                        "subscribers": state.subscribers.len(),
+                        "sources": state.sources.iter().map(|(from, source)| {
+                            json!({
+                                "from": from,
+                                "subscribers": source.subscribers.len(),
+                                "cancelled": source.cancelled,
+                            })
+                        }).collect::<Vec<_>>(),
                     })
                 }).collect::<Vec<_>>(),
                 "queue": state.sessions().values().map(|sess| {
@@ -379,3 +397,14 @@ impl radicle::node::Handle for Handle {
         Ok(debug)
     }
 }
+
+/// Get the public session of a peer, along with its traffic.
+fn session(
+    session: &service::Session,
+    metrics: Option<&service::PeerMetrics>,
+) -> radicle::node::Session {
+    radicle::node::Session {
+        traffic: metrics.map(|m| m.traffic()).unwrap_or_default(),
+        ..radicle::node::Session::from(session)
+    }
+}
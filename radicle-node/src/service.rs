@@ -10,11 +10,11 @@ pub mod message;
 pub mod session;
 
 use std::collections::hash_map::Entry;
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::net::IpAddr;
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
-use std::{fmt, net, time};
+use std::{cmp, fmt, net, time};
 
 use crossbeam_channel as chan;
 use fastrand::Rng;
@@ -26,7 +26,7 @@ use radicle::identity::Doc;
 use radicle::node;
 use radicle::node::address;
 use radicle::node::address::Store as _;
-use radicle::node::address::{AddressBook, AddressType, KnownAddress};
+use radicle::node::address::{AddressBook, AddressType, KnownAddress, Score};
 use radicle::node::config::PeerConfig;
 use radicle::node::device::Device;
 use radicle::node::refs::Store as _;
@@ -49,6 +49,7 @@ use crate::runtime::Emitter;
 use crate::service::gossip::Store as _;
 use crate::service::message::{
     Announcement, AnnouncementMessage, Info, NodeAnnouncement, Ping, RefsAnnouncement, RefsStatus,
+    Subscribe, SubscribeDelta, SUBSCRIBE_DELTA_LIMIT,
 };
 use crate::service::policy::{store::Write, Scope};
 use crate::storage;
@@ -106,6 +107,8 @@ pub const CONNECTION_RETRY_DELTA: LocalDuration = LocalDuration::from_mins(10);
 pub const FETCH_TIMEOUT: time::Duration = time::Duration::from_secs(3);
 /// Target number of peers to maintain connections to.
 pub const TARGET_OUTBOUND_PEERS: usize = 8;
+/// Minimum amount of time to wait before asking peers for seeds of the same repository again.
+pub const SEEDS_REQUEST_INTERVAL: LocalDuration = LocalDuration::from_secs(30);
 
 /// Maximum external address limit imposed by message size limits.
 pub use message::ADDRESS_LIMIT;
@@ -113,6 +116,8 @@ pub use message::ADDRESS_LIMIT;
 pub use message::INVENTORY_LIMIT;
 /// Maximum number of project git references imposed by message size limits.
 pub use message::REF_REMOTE_LIMIT;
+/// Maximum number of seeds that can be hinted by a peer.
+pub use message::SEEDS_LIMIT;
 
 /// Metrics we track.
 #[derive(Clone, Debug, Default, serde::Serialize)]
@@ -133,6 +138,21 @@ impl Metrics {
     }
 }
 
+/// Counters maintained by the service.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    /// Fetches that succeeded.
+    pub fetches_succeeded: usize,
+    /// Fetches that failed.
+    pub fetches_failed: usize,
+    /// Total time spent on fetches, successful or not, in milliseconds.
+    pub fetch_time: u64,
+    /// Announcements relayed to our peers.
+    pub relayed_announcements: usize,
+    /// Messages dropped because of rate limiting.
+    pub rate_limited_messages: usize,
+}
+
 /// Per-peer metrics we track.
 #[derive(Clone, Debug, Default, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -149,6 +169,19 @@ pub struct PeerMetrics {
     pub inbound_connection_attempts: usize,
     pub outbound_connection_attempts: usize,
     pub disconnects: usize,
+    /// Git traffic exchanged with the peer, per repository.
+    pub repos: HashMap<RepoId, node::RepoTraffic>,
+}
+
+impl PeerMetrics {
+    /// Traffic exchanged with the peer.
+    pub fn traffic(&self) -> node::Traffic {
+        node::Traffic {
+            sent: self.sent_bytes as u64,
+            received: self.received_bytes as u64,
+            repos: self.repos.iter().map(|(rid, t)| (*rid, *t)).collect(),
+        }
+    }
 }
 
 /// Result of syncing our routing table with a node's inventory.
@@ -174,6 +207,7 @@ struct Peer {
     nid: NodeId,
     addresses: Vec<KnownAddress>,
     penalty: Penalty,
+    score: Score,
 }
 
 /// General service error.
@@ -282,9 +316,9 @@ pub enum CommandError {
 
 /// Error returned by [`Service::try_fetch`].
 #[derive(thiserror::Error, Debug)]
-enum TryFetchError<'a> {
+enum TryFetchError {
     #[error("ongoing fetch for repository exists")]
-    AlreadyFetching(&'a mut FetchState),
+    AlreadyFetching,
     #[error("peer is not connected; cannot initiate fetch")]
     SessionNotConnected,
     #[error("peer fetch capacity reached; cannot initiate fetch")]
@@ -293,18 +327,30 @@ enum TryFetchError<'a> {
     Namespaces(#[from] NamespacesError),
 }
 
-/// Fetch state for an ongoing fetch.
+/// Fetch state for an ongoing fetch of a repository.
 #[derive(Debug)]
 pub struct FetchState {
-    /// Node we're fetching from.
-    pub from: NodeId,
     /// What refs we're fetching.
     pub refs_at: Vec<RefsAt>,
+    /// Nodes we're fetching from. When there is more than one, the fetches race each
+    /// other, and the ones nobody is waiting on are cancelled once one of them succeeds.
+    pub sources: BTreeMap<NodeId, FetchSource>,
+}
+
+/// Node an ongoing fetch is fetching from.
+#[derive(Debug)]
+pub struct FetchSource {
+    /// Relay node the fetch is spliced through, if we aren't connected to the node.
+    pub relay: Option<NodeId>,
     /// Channels waiting for fetch results.
     pub subscribers: Vec<chan::Sender<FetchResult>>,
+    /// When the fetch was started.
+    pub started: LocalTime,
+    /// Whether the fetch was cancelled, because a fetch from another node succeeded first.
+    pub cancelled: bool,
 }
 
-impl FetchState {
+impl FetchSource {
     /// Add a subscriber to this fetch.
     fn subscribe(&mut self, c: chan::Sender<FetchResult>) {
         if !self.subscribers.iter().any(|s| s.same_channel(&c)) {
@@ -442,6 +488,11 @@ pub struct Service<D, S, G> {
     listening: Vec<net::SocketAddr>,
     /// Latest metrics for all nodes connected to since the last start.
     metrics: Metrics,
+    /// Counters maintained by the service.
+    stats: Stats,
+    /// Seeds hinted by our peers for repositories we couldn't find seeds for,
+    /// along with the last time we asked for them.
+    seed_hints: HashMap<RepoId, (LocalTime, HashSet<NodeId>)>,
 }
 
 impl<D, S, G> Service<D, S, G> {
@@ -510,6 +561,8 @@ where
             emitter,
             listening: vec![],
             metrics: Metrics::default(),
+            stats: Stats::default(),
+            seed_hints: HashMap::new(),
         }
     }
 
@@ -859,6 +912,11 @@ where
                         "Found {} connected seed(s) and {} disconnected seed(s) for {}",
                         connected.len(), disconnected.len(),  rid
                     );
+                    // If we can't reach any seed, ask our peers if they know of any, so that
+                    // subsequent lookups can make use of their hints.
+                    if connected.is_empty() {
+                        self.request_seeds(rid);
+                    }
                     resp.send(seeds).ok();
                 }
                 Err(e) => {
@@ -876,16 +934,17 @@ where
                 resp.send(seeded).ok();
 
                 // Let all our peers know that we're interested in this repo from now on.
-                self.outbox.broadcast(
-                    Message::subscribe(self.filter(), self.clock.into(), Timestamp::MAX),
-                    self.sessions.connected().map(|(_, s)| s),
-                );
+                self.update_subscriptions(SubscribeDelta::added(rid, self.clock.into()));
             }
             Command::Unseed(id, resp) => {
                 let updated = self
                     .unseed(&id)
                     .expect("Service::command: error unseeding repository");
                 resp.send(updated).ok();
+
+                if updated {
+                    self.update_subscriptions(SubscribeDelta::removed(id));
+                }
             }
             Command::Follow(id, alias, resp) => {
                 let seeded = self
@@ -991,21 +1050,28 @@ where
         channel: Option<chan::Sender<FetchResult>>,
     ) -> bool {
         match self.try_fetch(rid, &from, refs_at.clone(), timeout) {
-            Ok(fetching) => {
+            Ok(source) => {
                 if let Some(c) = channel {
-                    fetching.subscribe(c);
+                    source.subscribe(c);
                 }
                 return true;
             }
-            Err(TryFetchError::AlreadyFetching(fetching)) => {
+            Err(TryFetchError::AlreadyFetching) => {
                 // If we're already fetching the same refs from the requested peer, there's nothing
                 // to do, we simply add the supplied channel to the list of subscribers so that it
                 // is notified on completion. Otherwise, we queue a fetch with the requested peer.
-                if fetching.from == from && fetching.refs_at == refs_at {
+                // This includes fetches that were cancelled, since their result is discarded.
+                if let Some(source) = self
+                    .fetching
+                    .get_mut(&rid)
+                    .filter(|fetching| fetching.refs_at == refs_at)
+                    .and_then(|fetching| fetching.sources.get_mut(&from))
+                    .filter(|source| !source.cancelled)
+                {
                     debug!(target: "service", "Ignoring redundant fetch of {rid} from {from}");
 
                     if let Some(c) = channel {
-                        fetching.subscribe(c);
+                        source.subscribe(c);
                     }
                 } else {
                     let fetch = QueuedFetch {
@@ -1060,25 +1126,37 @@ where
         from: &NodeId,
         refs_at: Vec<RefsAt>,
         timeout: time::Duration,
-    ) -> Result<&mut FetchState, TryFetchError> {
+    ) -> Result<&mut FetchSource, TryFetchError> {
         let from = *from;
-        let Some(session) = self.sessions.get_mut(&from) else {
+        // If we aren't connected to the remote, try to reach it through a relay.
+        let relay = if self.sessions.get(&from).is_some_and(|s| s.is_connected()) {
+            None
+        } else {
+            self.relay_for(&from)
+        };
+        let Some(session) = self.sessions.get_mut(&relay.unwrap_or(from)) else {
             return Err(TryFetchError::SessionNotConnected);
         };
-        let fetching = self.fetching.entry(rid);
-
         trace!(target: "service", "Trying to fetch {refs_at:?} for {rid}..");
 
-        let fetching = match fetching {
-            Entry::Vacant(fetching) => fetching,
-            Entry::Occupied(fetching) => {
-                // We're already fetching this repo from some peer.
-                return Err(TryFetchError::AlreadyFetching(fetching.into_mut()));
+        if let Some(fetching) = self.fetching.get(&rid) {
+            // We're already fetching this repo from some peer. We only fetch it from this peer
+            // at the same time if it's for the same refs, and the repo isn't being cloned, since
+            // a clone can't be raced.
+            let race = !fetching.sources.contains_key(&from)
+                && fetching.refs_at == refs_at
+                && fetching.sources.len() < self.config.limits.fetch_sources
+                && !session.is_fetching(&rid)
+                && self.storage.contains(&rid).unwrap_or(false);
+
+            if !race {
+                return Err(TryFetchError::AlreadyFetching);
             }
-        };
-        // Sanity check: We shouldn't be fetching from this session, since we return above if we're
-        // fetching from any session.
-        debug_assert!(!session.is_fetching(&rid));
+        } else {
+            // Sanity check: We shouldn't be fetching from this session, since we're not fetching
+            // this repo from any session.
+            debug_assert!(!session.is_fetching(&rid));
+        }
 
         if !session.is_connected() {
             // This can happen if a session disconnects in the time between asking for seeds to
@@ -1090,20 +1168,48 @@ where
             return Err(TryFetchError::SessionCapacityReached);
         }
 
-        let fetching = fetching.insert(FetchState {
-            from,
-            refs_at: refs_at.clone(),
+        let reader_limit = self.config.limits.fetch_pack_receive;
+
+        if relay.is_some() {
+            self.outbox
+                .fetch_via(session, from, rid, refs_at.clone(), timeout, reader_limit);
+        } else {
+            self.outbox
+                .fetch(session, rid, refs_at.clone(), timeout, reader_limit);
+        }
+        let fetching = self.fetching.entry(rid).or_insert_with(|| FetchState {
+            refs_at,
+            sources: BTreeMap::new(),
+        });
+        if !fetching.sources.is_empty() {
+            debug!(
+                target: "service",
+                "Racing fetch of {rid} from {from} against {} other seed(s)..", fetching.sources.len()
+            );
+        }
+        let source = fetching.sources.entry(from).or_insert(FetchSource {
+            relay,
             subscribers: vec![],
+            started: self.clock,
+            cancelled: false,
         });
-        self.outbox.fetch(
-            session,
-            rid,
-            refs_at,
-            timeout,
-            self.config.limits.fetch_pack_receive,
-        );
 
-        Ok(fetching)
+        Ok(source)
+    }
+
+    /// Check whether we have the given signed refs of a repository, eg. after a fetch.
+    /// Fetches that aren't for any refs in particular are satisfied by any successful fetch.
+    fn has_refs(&self, rid: RepoId, refs_at: &[RefsAt]) -> bool {
+        let Some(refs) = NonEmpty::from_slice(refs_at) else {
+            return true;
+        };
+        match RefsStatus::new(rid, refs, self.db.refs()) {
+            Ok(status) => status.want.is_empty(),
+            Err(e) => {
+                error!(target: "service", "Error getting refs status of {rid}: {e}");
+                false
+            }
+        }
     }
 
     pub fn fetched(
@@ -1112,20 +1218,49 @@ where
         remote: NodeId,
         result: Result<fetch::FetchResult, FetchError>,
     ) {
-        let Some(fetching) = self.fetching.remove(&rid) else {
+        let Some(source) = self
+            .fetching
+            .get_mut(&rid)
+            .and_then(|fetching| fetching.sources.remove(&remote))
+        else {
             error!(target: "service", "Received unexpected fetch result for {rid}, from {remote}");
             return;
         };
-        debug_assert_eq!(fetching.from, remote);
+        if self
+            .fetching
+            .get(&rid)
+            .is_some_and(|fetching| fetching.sources.is_empty())
+        {
+            self.fetching.remove(&rid);
+        }
 
-        if let Some(s) = self.sessions.get_mut(&remote) {
+        if let Some(s) = self.sessions.get_mut(&source.relay.unwrap_or(remote)) {
             // Mark this RID as fetched for this session.
             s.fetched(rid);
         }
 
+        // Subscribers are never added to cancelled fetches, but if there are any, they are
+        // notified of the result like for any other fetch.
+        if source.cancelled && source.subscribers.is_empty() {
+            if let Err(e) = &result {
+                // The fetch lost the race, so its failure says nothing about the remote.
+                debug!(target: "service", "Cancelled fetch of {rid} from {remote} ended: {e}");
+
+                self.dequeue_fetches();
+                return;
+            }
+        }
+
+        self.stats.fetch_time += (self.clock - source.started).as_millis() as u64;
+        if result.is_ok() {
+            self.stats.fetches_succeeded += 1;
+        } else {
+            self.stats.fetches_failed += 1;
+        }
+
         // Notify all fetch subscribers of the fetch result. This is used when the user requests
         // a fetch via the CLI, for example.
-        for sub in &fetching.subscribers {
+        for sub in &source.subscribers {
             debug!(target: "service", "Found existing fetch request from {remote}, sending result..");
 
             let result = match &result {
@@ -1151,8 +1286,37 @@ where
                 namespaces,
                 clone,
                 doc,
+                bytes,
+                ..
             }) => {
                 info!(target: "service", "Fetched {rid} from {remote} successfully");
+
+                // This fetch won the race: cancel the others, unless someone is waiting on them,
+                // or the winner didn't get us the refs the fetch was for. Only the winner is
+                // credited with a successful fetch in its score.
+                let covered = self
+                    .fetching
+                    .get(&rid)
+                    .is_some_and(|fetching| self.has_refs(rid, &fetching.refs_at));
+
+                if let Some(fetching) = self.fetching.get_mut(&rid).filter(|_| covered) {
+                    for (nid, racer) in fetching.sources.iter_mut() {
+                        if racer.subscribers.is_empty() && !racer.cancelled {
+                            debug!(target: "service", "Cancelling fetch of {rid} from {nid}, {remote} was faster");
+
+                            racer.cancelled = true;
+                            self.outbox.cancel_fetch(rid, *nid, racer.relay);
+                        }
+                    }
+                }
+
+                if let Err(e) =
+                    self.db
+                        .addresses()
+                        .fetch_succeeded(&remote, self.clock - source.started, bytes)
+                {
+                    error!(target: "service", "Error updating score of {remote}: {e}");
+                }
                 // Update our routing table in case this fetch was user-initiated and doesn't
                 // come from an announcement.
                 self.seed_discovered(rid, remote, self.clock.into());
@@ -1194,6 +1358,10 @@ where
             Err(err) => {
                 error!(target: "service", "Fetch failed for {rid} from {remote}: {err}");
 
+                if let Err(e) = self.db.addresses().fetch_failed(&remote) {
+                    error!(target: "service", "Error updating score of {remote}: {e}");
+                }
+
                 // For now, we only disconnect the remote in case of timeout. In the future,
                 // there may be other reasons to disconnect.
                 if err.is_timeout() {
@@ -1310,7 +1478,7 @@ where
         info!(target: "service", "Connected to {remote} ({addr}) ({link:?})");
         self.emitter.emit(Event::PeerConnected { nid: remote });
 
-        let msgs = self.initial(link);
+        let msgs = self.initial(&remote, link);
 
         if link.is_outbound() {
             if let Some(peer) = self.sessions.get_mut(&remote) {
@@ -1387,18 +1555,41 @@ where
         let link = session.link;
         let addr = session.addr.clone();
 
-        self.fetching.retain(|_, fetching| {
-            if fetching.from != remote {
-                return true;
+        if let session::State::Connected {
+            since: connected, ..
+        } = session.state
+        {
+            if let Err(e) = self
+                .db
+                .addresses()
+                .record_uptime(&remote, since - connected)
+            {
+                error!(target: "service", "Error updating score of {remote}: {e}");
             }
-            // Remove and fail any pending fetches from this remote node.
-            for resp in &fetching.subscribers {
-                resp.send(FetchResult::Failed {
-                    reason: format!("disconnected: {reason}"),
-                })
-                .ok();
+        }
+        if let DisconnectReason::Session(e) = reason {
+            if e.severity() == Severity::High {
+                if let Err(e) = self.db.addresses().misbehaved(&remote) {
+                    error!(target: "service", "Error updating score of {remote}: {e}");
+                }
             }
-            false
+        }
+
+        self.fetching.retain(|_, fetching| {
+            fetching.sources.retain(|from, source| {
+                if *from != remote && source.relay != Some(remote) {
+                    return true;
+                }
+                // Remove and fail any pending fetches from or through this remote node.
+                for resp in &source.subscribers {
+                    resp.send(FetchResult::Failed {
+                        reason: format!("disconnected: {reason}"),
+                    })
+                    .ok();
+                }
+                false
+            });
+            !fetching.sources.is_empty()
         });
 
         // Attempt to re-connect to persistent peers.
@@ -1581,10 +1772,12 @@ where
                 if let Some(sess) = self.sessions.get_mut(announcer) {
                     for id in message.inventory.as_slice() {
                         // If we are connected to the announcer of this inventory, update the peer's
-                        // subscription filter to include all inventory items. This way, we'll
+                        // subscription to include all inventory items. This way, we'll
                         // relay messages relating to the peer's inventory.
-                        if let Some(sub) = &mut sess.subscribe {
-                            sub.filter.insert(id);
+                        if let Err(e @ session::SubscriptionError::LimitReached) =
+                            sess.subscribe_to(id)
+                        {
+                            warn!(target: "service", "Subscription of {announcer} to {id} is inexact: {e}");
                         }
 
                         // If we're seeding and connected to the announcer, and we don't have
@@ -1767,11 +1960,162 @@ where
                     at: *at,
                 });
             }
+            Info::SeedsRequest { rid } => {
+                self.send_seeds(*rid, remote);
+            }
+            Info::Seeds { rid, seeds } => {
+                let local = self.node_id();
+                // Accept hints we asked for, as well as hints from peers that we tried to fetch
+                // a repository we want from, but that don't have it.
+                let hints = match self.seed_hints.entry(*rid) {
+                    Entry::Occupied(e) => &mut e.into_mut().1,
+                    Entry::Vacant(e) => match self.policies.is_seeding(rid) {
+                        Ok(true) => &mut e.insert((self.clock, HashSet::new())).1,
+                        Ok(false) => {
+                            debug!(target: "service", "Ignoring unsolicited seeds for {rid} from {remote}");
+                            return Ok(());
+                        }
+                        Err(e) => {
+                            error!(target: "service", "Error getting seeding policy for {rid}: {e}");
+                            return Ok(());
+                        }
+                    },
+                };
+                let seeds = seeds
+                    .iter()
+                    .filter(|nid| **nid != local)
+                    .copied()
+                    .collect::<Vec<_>>();
+                hints.extend(seeds.iter().copied());
+
+                self.emitter.emit(Event::SeedsHinted {
+                    rid: *rid,
+                    nid: remote,
+                    seeds,
+                });
+            }
         }
 
         Ok(())
     }
 
+    /// Called when a peer tried to fetch a repository we don't have. Instead of only failing
+    /// their fetch, we let them know of other seeds for it.
+    pub fn upload_not_found(&mut self, rid: RepoId, remote: NodeId) {
+        // Nb. Peers that don't support seed hints would disconnect us.
+        if !self.supports(&remote, Features::SEED_HINTS) {
+            return;
+        }
+        debug!(target: "service", "Sending seed hints for {rid} to {remote}, since we don't have it");
+
+        self.send_seeds(rid, remote);
+    }
+
+    /// Tell a peer which seeds we know for the given repository.
+    fn send_seeds(&mut self, rid: RepoId, remote: NodeId) {
+        let seeds = match self.db.routing().get(&rid) {
+            Ok(seeds) => seeds,
+            Err(e) => {
+                error!(target: "service", "Error getting seeds for {rid}: {e}");
+                return;
+            }
+        };
+        let seeds = BoundedVec::collect_from(&mut seeds.into_iter().filter(|nid| *nid != remote));
+        if let Some(peer) = self.sessions.get(&remote) {
+            self.outbox.write(peer, Info::Seeds { rid, seeds }.into());
+        }
+    }
+
+    /// Ask our connected peers for seeds of the given repository.
+    fn request_seeds(&mut self, rid: RepoId) {
+        let now = self.clock;
+        if let Some((requested_at, _)) = self.seed_hints.get(&rid) {
+            if now - *requested_at < SEEDS_REQUEST_INTERVAL {
+                return;
+            }
+        }
+        debug!(target: "service", "Requesting seeds for {rid} from connected peers..");
+
+        // Previous hints are discarded, since they didn't help.
+        self.seed_hints.insert(rid, (now, HashSet::new()));
+        // Nb. Peers that don't support seed hints would disconnect us.
+        let peers = self
+            .sessions
+            .connected()
+            .filter(|(nid, _)| self.supports(nid, Features::SEED_HINTS))
+            .map(|(_, s)| s.clone())
+            .collect::<Vec<_>>();
+        self.outbox
+            .broadcast(Info::SeedsRequest { rid }, peers.iter());
+    }
+
+    /// Update our subscription with our connected peers.
+    ///
+    /// Peers that support it are sent the change only. Other peers are sent our whole
+    /// subscription filter, which means they can't be unsubscribed from repositories.
+    /// If we seed everything, our subscription filter matches all repositories, and there
+    /// is nothing to change.
+    fn update_subscriptions(&mut self, delta: SubscribeDelta) {
+        if self.config.seeding_policy.is_allow() {
+            return;
+        }
+        let (incremental, full): (Vec<_>, Vec<_>) = self
+            .sessions
+            .connected()
+            .map(|(_, s)| s.clone())
+            .partition(|s| self.supports(&s.id, Features::SUBSCRIBE_DELTA));
+
+        if !delta.added.is_empty() {
+            self.outbox.broadcast(
+                Message::subscribe(self.filter(), self.clock.into(), Timestamp::MAX),
+                full.iter(),
+            );
+        }
+        self.outbox.broadcast(delta, incremental.iter());
+    }
+
+    /// Sort nodes by score, highest first. Nodes with equal scores keep their order.
+    fn by_score(&self, nids: impl IntoIterator<Item = NodeId>) -> Vec<NodeId> {
+        let mut scored = nids
+            .into_iter()
+            .map(|nid| match self.db.addresses().score(&nid) {
+                Ok(score) => (score.value(), nid),
+                Err(e) => {
+                    error!(target: "service", "Error getting score of {nid}: {e}");
+                    (0, nid)
+                }
+            })
+            .collect::<Vec<_>>();
+        scored.sort_by_key(|(score, _)| cmp::Reverse(*score));
+        scored.into_iter().map(|(_, nid)| nid).collect()
+    }
+
+    /// Find the best connected peer to relay a stream to the given node. Only peers we
+    /// connected to, and which advertized the relay feature, are considered, since a peer
+    /// that can't accept connections can't be a relay either.
+    fn relay_for(&self, nid: &NodeId) -> Option<NodeId> {
+        let relays = self
+            .sessions
+            .connected()
+            .filter(|(id, s)| *id != nid && s.link.is_outbound())
+            .map(|(id, _)| *id)
+            .filter(|id| self.supports(id, Features::RELAY))
+            .collect::<Vec<_>>();
+
+        self.by_score(relays).into_iter().next()
+    }
+
+    /// Check whether a node advertized support for the given features.
+    pub fn supports(&self, nid: &NodeId, features: Features) -> bool {
+        match self.db.addresses().get(nid) {
+            Ok(node) => node.is_some_and(|n| n.features.has(features)),
+            Err(e) => {
+                error!(target: "service", "Error getting features of {nid}: {e}");
+                false
+            }
+        }
+    }
+
     pub fn handle_message(
         &mut self,
         remote: &NodeId,
@@ -1794,6 +2138,8 @@ where
             .limit(peer.addr.clone().into(), Some(remote), limit, self.clock)
         {
             debug!(target: "service", "Rate limiting message from {remote} ({})", peer.addr);
+            self.stats.rate_limited_messages += 1;
+
             return Ok(());
         }
         message.log(log::Level::Debug, remote, Link::Inbound);
@@ -1878,6 +2224,79 @@ where
                     }
                 }
                 peer.subscribe = Some(subscribe);
+                peer.subscribed.clear();
+            }
+            Message::SubscribeDelta(SubscribeDelta {
+                added,
+                removed,
+                since,
+            }) => {
+                let Some(Subscribe {
+                    since: subscribed_since,
+                    until,
+                    ..
+                }) = peer.subscribe
+                else {
+                    debug!(target: "service", "Ignoring subscription change from {remote}: no subscription");
+                    return Ok(());
+                };
+                for rid in removed.iter() {
+                    if let Err(e) = peer.unsubscribe_from(rid) {
+                        warn!(target: "service", "Unsubscription of {remote} from {rid} is inexact: {e}");
+                    }
+                }
+                let added = added
+                    .iter()
+                    .filter(|rid| !peer.is_subscribed_to(rid))
+                    .copied()
+                    .collect::<HashSet<_>>();
+                if added.is_empty() {
+                    return Ok(());
+                }
+                for rid in &added {
+                    if let Err(e) = peer.subscribe_to(rid) {
+                        warn!(target: "service", "Subscription of {remote} to {rid} is inexact: {e}");
+                    }
+                }
+                // Send the peer the refs announcements it requested for the repositories
+                // it just subscribed to, as we would have for a new subscription.
+                let since = since.max(subscribed_since);
+                if since >= until {
+                    return Ok(());
+                }
+                let filter = Filter::new(added.iter().copied());
+                let anns = self
+                    .db
+                    .gossip()
+                    .filtered(&filter, since, until)
+                    .map(|anns| anns.collect::<Vec<_>>());
+                match anns {
+                    Ok(anns) => {
+                        for ann in anns {
+                            let ann = match ann {
+                                Ok(a) => a,
+                                Err(e) => {
+                                    error!(target: "service", "Error reading gossip message from store: {e}");
+                                    continue;
+                                }
+                            };
+                            let AnnouncementMessage::Refs(RefsAnnouncement { rid, .. }) =
+                                &ann.message
+                            else {
+                                continue;
+                            };
+                            if !added.contains(rid) || ann.node == *remote {
+                                continue;
+                            }
+                            if relay || ann.node == local {
+                                self.outbox.write(peer, ann.into());
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        error!(target: "service", "Error querying gossip messages from store: {e}");
+                    }
+                }
             }
             Message::Info(info) => {
                 self.handle_info(*remote, &info)?;
@@ -1957,9 +2376,8 @@ where
     }
 
     /// Set of initial messages to send to a peer.
-    fn initial(&mut self, _link: Link) -> Vec<Message> {
+    fn initial(&mut self, remote: &NodeId, _link: Link) -> Vec<Message> {
         let now = self.clock();
-        let filter = self.filter();
 
         // TODO: Only subscribe to outbound connections, otherwise we will consume too
         // much bandwidth.
@@ -1977,11 +2395,49 @@ where
         };
         debug!(target: "service", "Subscribing to messages since timestamp {since}..");
 
-        vec![
+        let mut msgs = vec![
             Message::node(self.node.clone(), &self.signer),
             Message::inventory(self.inventory.clone(), &self.signer),
-            Message::subscribe(filter, since, Timestamp::MAX),
-        ]
+        ];
+        msgs.extend(self.subscription(remote, since));
+        msgs
+    }
+
+    /// Our subscription, as sent to the given peer.
+    ///
+    /// Peers that support it are sent the exact set of repositories we seed, as an empty
+    /// subscription filter followed by subscription changes, so that we can unsubscribe from
+    /// repositories later on. Other peers are sent our subscription filter, as are all peers
+    /// if we seed everything, or more repositories than can be subscribed to exactly.
+    fn subscription(&self, remote: &NodeId, since: Timestamp) -> Vec<Message> {
+        let filtered = vec![Message::subscribe(self.filter(), since, Timestamp::MAX)];
+
+        if self.config.seeding_policy.is_allow()
+            || !self.supports(remote, Features::SUBSCRIBE_DELTA)
+        {
+            return filtered;
+        }
+        let seeded = match self.policies.seed_policies() {
+            Ok(policies) => policies
+                .filter_map(|p| p.policy.is_allow().then_some(p.rid))
+                .collect::<Vec<_>>(),
+            Err(e) => {
+                error!(target: "service", "Error getting seeding policies: {e}");
+                return filtered;
+            }
+        };
+        if seeded.len() > session::MAX_SUBSCRIBED {
+            return filtered;
+        }
+        let mut msgs = vec![Message::subscribe(Filter::empty(), since, Timestamp::MAX)];
+        msgs.extend(seeded.chunks(SUBSCRIBE_DELTA_LIMIT).map(|chunk| {
+            Message::from(SubscribeDelta {
+                added: BoundedVec::collect_from(&mut chunk.iter().copied()),
+                removed: BoundedVec::new(),
+                since,
+            })
+        }));
+        msgs
     }
 
     /// Try to guess whether we're online or not.
@@ -2291,6 +2747,20 @@ where
 
             seeds.insert(Seed::new(nid, addrs, state, None));
         }
+
+        // Finally, add seeds hinted by our peers, if we know how to reach them.
+        for nid in self.seed_hints.get(rid).into_iter().flat_map(|(_, h)| h) {
+            if seeds.contains(nid) {
+                continue;
+            }
+            let addrs = self.db.addresses().addresses_of(nid)?;
+            if addrs.is_empty() {
+                continue;
+            }
+            let state = self.sessions.get(nid).map(|s| s.state.clone());
+
+            seeds.insert(Seed::new(*nid, addrs, state, None));
+        }
         Ok(seeds)
     }
 
@@ -2354,6 +2824,7 @@ where
             })
             .map(|(_, p)| p);
 
+        self.stats.relayed_announcements += 1;
         self.outbox.relay(ann, relay_to);
     }
 
@@ -2442,7 +2913,8 @@ where
         }
     }
 
-    /// Get a list of peers available to connect to, sorted by lowest penalty.
+    /// Get a list of peers available to connect to, sorted by lowest penalty, and then
+    /// by highest score.
     fn available_peers(&mut self) -> Vec<Peer> {
         match self.db.addresses().entries() {
             Ok(entries) => {
@@ -2462,12 +2934,13 @@ where
                                 nid: entry.node,
                                 addresses: vec![entry.address],
                                 penalty: entry.penalty,
+                                score: entry.score,
                             });
                         acc
                     })
                     .into_values()
                     .collect::<Vec<_>>();
-                peers.sort_by_key(|p| p.penalty);
+                peers.sort_by_key(|p| (p.penalty, cmp::Reverse(p.score.value())));
                 peers
             }
             Err(e) => {
@@ -2493,9 +2966,12 @@ where
             }
             match self.seeds(&rid) {
                 Ok(seeds) => {
-                    if let Some(connected) = NonEmpty::from_vec(seeds.connected().collect()) {
-                        for seed in connected {
-                            self.fetch(rid, seed.nid, FETCH_TIMEOUT, None);
+                    let connected = self.by_score(seeds.connected().map(|s| s.nid));
+                    if let Some(connected) = NonEmpty::from_vec(connected) {
+                        // Nb. Only one fetch per repository runs at a time, and the others are
+                        // queued, so the highest scoring seed is fetched from first.
+                        for nid in connected {
+                            self.fetch(rid, nid, FETCH_TIMEOUT, None);
                         }
                     } else {
                         // TODO: We should make sure that this fetch is retried later, either
@@ -2650,6 +3126,8 @@ pub trait ServiceState {
     fn config(&self) -> &Config;
     /// Get service metrics.
     fn metrics(&self) -> &Metrics;
+    /// Get service counters.
+    fn stats(&self) -> &Stats;
 }
 
 impl<D, S, G> ServiceState for Service<D, S, G>
@@ -2701,6 +3179,10 @@ where
     fn metrics(&self) -> &Metrics {
         &self.metrics
     }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
 }
 
 /// Disconnect reason.
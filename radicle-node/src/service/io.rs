@@ -27,6 +27,8 @@ pub enum Io {
         rid: RepoId,
         /// Remote node being fetched from.
         remote: NodeId,
+        /// Relay node to splice the fetch stream through, if we aren't connected to the remote.
+        relay: Option<NodeId>,
         /// If the node is fetching specific `rad/sigrefs`.
         refs_at: Option<Vec<RefsAt>>,
         /// Fetch timeout.
@@ -34,6 +36,15 @@ pub enum Io {
         /// Limit the number of bytes fetched.
         reader_limit: FetchPackSizeLimit,
     },
+    /// Cancel an ongoing fetch of a repository from a peer.
+    CancelFetch {
+        /// Repo being fetched.
+        rid: RepoId,
+        /// Remote node being fetched from.
+        remote: NodeId,
+        /// Relay node the fetch stream is spliced through, if any.
+        relay: Option<NodeId>,
+    },
     /// Ask for a wakeup in a specified amount of time.
     Wakeup(LocalDuration),
 }
@@ -78,8 +89,8 @@ impl Outbox {
 
         for peer in peers {
             if let AnnouncementMessage::Refs(refs) = &ann.message {
-                if let Some(subscribe) = &peer.subscribe {
-                    if subscribe.filter.contains(&refs.rid) {
+                if peer.subscribe.is_some() {
+                    if peer.is_subscribed_to(&refs.rid) {
                         self.write(peer, ann.clone().into());
                     } else {
                         debug!(
@@ -128,6 +139,36 @@ impl Outbox {
         refs_at: Vec<RefsAt>,
         timeout: time::Duration,
         reader_limit: FetchPackSizeLimit,
+    ) {
+        let remote = peer.id;
+        self._fetch(peer, remote, None, rid, refs_at, timeout, reader_limit)
+    }
+
+    /// Fetch from a remote we aren't connected to, by having the given relay peer splice
+    /// the fetch stream.
+    pub fn fetch_via(
+        &mut self,
+        relay: &mut Session,
+        remote: NodeId,
+        rid: RepoId,
+        refs_at: Vec<RefsAt>,
+        timeout: time::Duration,
+        reader_limit: FetchPackSizeLimit,
+    ) {
+        let via = Some(relay.id);
+        self._fetch(relay, remote, via, rid, refs_at, timeout, reader_limit)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn _fetch(
+        &mut self,
+        peer: &mut Session,
+        remote: NodeId,
+        relay: Option<NodeId>,
+        rid: RepoId,
+        refs_at: Vec<RefsAt>,
+        timeout: time::Duration,
+        reader_limit: FetchPackSizeLimit,
     ) {
         peer.fetching(rid);
 
@@ -141,16 +182,28 @@ impl Outbox {
         } else {
             debug!(target: "service", "Fetch initiated for {rid} with {peer} (all remotes)..");
         }
+        if relay.is_some() {
+            debug!(target: "service", "Fetch of {rid} is relayed to {remote}..");
+        }
 
         self.io.push_back(Io::Fetch {
             rid,
             refs_at,
-            remote: peer.id,
+            remote,
+            relay,
             timeout,
             reader_limit,
         });
     }
 
+    /// Cancel an ongoing fetch. The fetch still completes, with an error, unless it was
+    /// already done.
+    pub fn cancel_fetch(&mut self, rid: RepoId, remote: NodeId, relay: Option<NodeId>) {
+        debug!(target: "service", "Fetch of {rid} from {remote} cancelled..");
+
+        self.io.push_back(Io::CancelFetch { rid, remote, relay });
+    }
+
     /// Broadcast a message to a list of peers.
     pub fn broadcast<'a>(
         &mut self,
@@ -167,15 +220,9 @@ impl Outbox {
     pub fn relay<'a>(&mut self, ann: Announcement, peers: impl IntoIterator<Item = &'a Session>) {
         if let AnnouncementMessage::Refs(msg) = &ann.message {
             let id = msg.rid;
-            let peers = peers.into_iter().filter(|p| {
-                if let Some(subscribe) = &p.subscribe {
-                    subscribe.filter.contains(&id)
-                } else {
-                    // If the peer did not send us a `subscribe` message, we don't
-                    // relay any messages to them.
-                    false
-                }
-            });
+            // If the peer did not send us a `subscribe` message, we don't
+            // relay any messages to them.
+            let peers = peers.into_iter().filter(|p| p.is_subscribed_to(&id));
             self.broadcast(ann, peers);
         } else {
             self.broadcast(ann, peers);
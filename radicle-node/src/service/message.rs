@@ -21,6 +21,10 @@ pub const ADDRESS_LIMIT: usize = 16;
 pub const REF_REMOTE_LIMIT: usize = 1024;
 /// Maximum number of inventory which can be announced to other nodes.
 pub const INVENTORY_LIMIT: usize = 2973;
+/// Maximum number of seeds which can be included in an [`Info::Seeds`] message.
+pub const SEEDS_LIMIT: usize = 32;
+/// Maximum number of repositories which can be added or removed in a [`SubscribeDelta`] message.
+pub const SUBSCRIBE_DELTA_LIMIT: usize = 1024;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Subscribe {
@@ -42,6 +46,45 @@ impl Subscribe {
     }
 }
 
+/// Incremental change to a subscription, sent after the initial [`Subscribe`] message,
+/// eg. when a repository is seeded or unseeded.
+///
+/// Unlike the subscription filter, the change is exact, and repositories can be removed
+/// from a subscription, unless they match the filter. This is why nodes send an empty
+/// filter to peers that support it, followed by the repositories they seed, as changes.
+/// This should only be sent to nodes that advertize the
+/// [`node::Features::SUBSCRIBE_DELTA`] feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscribeDelta {
+    /// Repositories to subscribe to.
+    pub added: BoundedVec<RepoId, SUBSCRIBE_DELTA_LIMIT>,
+    /// Repositories to unsubscribe from.
+    pub removed: BoundedVec<RepoId, SUBSCRIBE_DELTA_LIMIT>,
+    /// Request messages about the added repositories since this time, within the
+    /// time range of the subscription.
+    pub since: Timestamp,
+}
+
+impl SubscribeDelta {
+    /// Subscribe to a repository, requesting messages since the given time.
+    pub fn added(rid: RepoId, since: Timestamp) -> Self {
+        Self {
+            added: Some(rid).into(),
+            removed: BoundedVec::new(),
+            since,
+        }
+    }
+
+    /// Unsubscribe from a repository.
+    pub fn removed(rid: RepoId) -> Self {
+        Self {
+            added: BoundedVec::new(),
+            removed: Some(rid).into(),
+            since: Timestamp::MAX,
+        }
+    }
+}
+
 /// Node announcing itself to the network.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NodeAnnouncement {
@@ -246,6 +289,16 @@ pub enum Info {
     /// Tell a node that sent a refs announcement that it was already synced at the given `Oid`,
     /// for this particular `rid`.
     RefsAlreadySynced { rid: RepoId, at: git::Oid },
+    /// Ask a node for the seeds it knows for the given `rid`, eg. when we can't reach
+    /// any seed for it.
+    SeedsRequest { rid: RepoId },
+    /// Tell a node which seeds we know for the given `rid`, in response to a
+    /// [`Info::SeedsRequest`], or when it tried to fetch a repository we don't have.
+    /// If we don't have the repository ourselves, we aren't part of the list.
+    Seeds {
+        rid: RepoId,
+        seeds: BoundedVec<NodeId, SEEDS_LIMIT>,
+    },
 }
 
 /// Announcement messages are messages that are relayed between peers.
@@ -397,6 +450,9 @@ pub enum Message {
     /// Subscribe to gossip messages matching the filter and time range.
     Subscribe(Subscribe),
 
+    /// Change an existing subscription, without resending the filter.
+    SubscribeDelta(SubscribeDelta),
+
     /// Gossip announcement. These messages are relayed to peers, and filtered
     /// using [`Message::Subscribe`].
     Announcement(Announcement),
@@ -501,11 +557,27 @@ impl Message {
                     "{verb} `refs-already-synced` info {prep} {remote} for {rid}"
                 )
             },
+            Self::Info(Info::SeedsRequest { rid }) => {
+                format!("{verb} `seeds-request` info {prep} {remote} for {rid}")
+            },
+            Self::Info(Info::Seeds { rid, seeds }) => {
+                format!(
+                    "{verb} `seeds` info with {} seed(s) {prep} {remote} for {rid}",
+                    seeds.len()
+                )
+            },
             Self::Ping { .. } => format!("{verb} ping {prep} {remote}"),
             Self::Pong { .. } => format!("{verb} pong {prep} {remote}"),
             Self::Subscribe(Subscribe { .. }) => {
                 format!("{verb} subscription filter {prep} {remote}")
             }
+            Self::SubscribeDelta(SubscribeDelta { added, removed, .. }) => {
+                format!(
+                    "{verb} subscription change with {} addition(s) and {} removal(s) {prep} {remote}",
+                    added.len(),
+                    removed.len()
+                )
+            }
         };
         log::log!(target: "service", level, "{msg}");
     }
@@ -547,6 +619,12 @@ impl From<Announcement> for Message {
     }
 }
 
+impl From<SubscribeDelta> for Message {
+    fn from(delta: SubscribeDelta) -> Self {
+        Self::SubscribeDelta(delta)
+    }
+}
+
 impl From<Info> for Message {
     fn from(info: Info) -> Self {
         Self::Info(info)
@@ -559,6 +637,9 @@ impl fmt::Debug for Message {
             Self::Subscribe(Subscribe { since, until, .. }) => {
                 write!(f, "Subscribe({since}..{until})")
             }
+            Self::SubscribeDelta(SubscribeDelta { added, removed, .. }) => {
+                write!(f, "SubscribeDelta(+{}, -{})", added.len(), removed.len())
+            }
             Self::Announcement(Announcement { node, message, .. }) => {
                 write!(f, "Announcement({node}, {message:?})")
             }
@@ -17,6 +17,9 @@ pub use crate::node::{PingState, State};
 pub const CONNECTION_STABLE_THRESHOLD: LocalDuration = LocalDuration::from_mins(1);
 /// Maximum items in the fetch queue.
 pub const MAX_FETCH_QUEUE_SIZE: usize = 128;
+/// Maximum number of repositories a peer can subscribe to exactly, ie. outside of its
+/// subscription filter.
+pub const MAX_SUBSCRIBED: usize = 1 << 16;
 
 #[derive(thiserror::Error, Debug, Clone, Copy)]
 pub enum Error {
@@ -48,6 +51,22 @@ impl Error {
     }
 }
 
+/// Error when changing a peer's subscription.
+#[derive(thiserror::Error, Debug, Clone, Copy)]
+pub enum SubscriptionError {
+    /// The peer didn't send us a subscription.
+    #[error("peer has no subscription")]
+    NoSubscription,
+    /// The peer has too many exact subscriptions. The repository was added to the
+    /// subscription filter instead, so it can no longer be unsubscribed from.
+    #[error("exact subscription limit of {MAX_SUBSCRIBED} repositories reached")]
+    LimitReached,
+    /// The repository matches the peer's subscription filter, so it can't be
+    /// unsubscribed from.
+    #[error("repository matches the subscription filter")]
+    Filtered,
+}
+
 /// Error when trying to queue a fetch.
 #[derive(thiserror::Error, Debug, Clone)]
 pub enum QueueError {
@@ -110,6 +129,9 @@ pub struct Session {
     pub state: State,
     /// Peer subscription.
     pub subscribe: Option<message::Subscribe>,
+    /// Repositories the peer subscribed to since its last subscription, outside
+    /// of its subscription filter. Unlike the filter, this set is exact.
+    pub subscribed: HashSet<RepoId>,
     /// Last time a message was received from the peer.
     pub last_active: LocalTime,
     /// Fetch queue.
@@ -155,6 +177,7 @@ impl From<&Session> for radicle::node::Session {
             },
             addr: s.addr.clone(),
             state: s.state.clone(),
+            traffic: radicle::node::Traffic::default(),
         }
     }
 }
@@ -167,6 +190,7 @@ impl Session {
             state: State::Initial,
             link: Link::Outbound,
             subscribe: None,
+            subscribed: HashSet::default(),
             persistent,
             last_active: LocalTime::default(),
             queue: VecDeque::with_capacity(MAX_FETCH_QUEUE_SIZE),
@@ -196,6 +220,7 @@ impl Session {
             },
             link: Link::Inbound,
             subscribe: None,
+            subscribed: HashSet::default(),
             persistent,
             last_active: time,
             queue: VecDeque::new(),
@@ -205,6 +230,45 @@ impl Session {
         }
     }
 
+    /// Check whether the peer is subscribed to the given repository.
+    /// Returns `false` if the peer didn't send us a subscription.
+    pub fn is_subscribed_to(&self, rid: &RepoId) -> bool {
+        self.subscribe
+            .as_ref()
+            .is_some_and(|sub| self.subscribed.contains(rid) || sub.filter.contains(rid))
+    }
+
+    /// Subscribe the peer to the given repository, if it sent us a subscription.
+    pub fn subscribe_to(&mut self, rid: &RepoId) -> Result<(), SubscriptionError> {
+        let Some(sub) = &mut self.subscribe else {
+            return Err(SubscriptionError::NoSubscription);
+        };
+        if sub.filter.contains(rid) || self.subscribed.contains(rid) {
+            return Ok(());
+        }
+        if self.subscribed.len() >= MAX_SUBSCRIBED {
+            sub.filter.insert(rid);
+
+            return Err(SubscriptionError::LimitReached);
+        }
+        self.subscribed.insert(*rid);
+
+        Ok(())
+    }
+
+    /// Unsubscribe the peer from the given repository.
+    pub fn unsubscribe_from(&mut self, rid: &RepoId) -> Result<(), SubscriptionError> {
+        let Some(sub) = &self.subscribe else {
+            return Err(SubscriptionError::NoSubscription);
+        };
+        self.subscribed.remove(rid);
+
+        if sub.filter.contains(rid) {
+            return Err(SubscriptionError::Filtered);
+        }
+        Ok(())
+    }
+
     pub fn is_connecting(&self) -> bool {
         matches!(self.state, State::Attempted { .. })
     }
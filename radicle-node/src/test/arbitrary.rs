@@ -11,7 +11,7 @@ use crate::prelude::{BoundedVec, NodeId, RepoId, Timestamp};
 use crate::service::filter::{Filter, FILTER_SIZE_L, FILTER_SIZE_M, FILTER_SIZE_S};
 use crate::service::message::{
     Announcement, Info, InventoryAnnouncement, Message, NodeAnnouncement, Ping, RefsAnnouncement,
-    Subscribe, ZeroBytes,
+    Subscribe, SubscribeDelta, ZeroBytes,
 };
 use crate::wire::MessageType;
 use crate::worker::fetch::FetchResult;
@@ -38,7 +38,9 @@ impl Arbitrary for FetchResult {
             updated: vec![],
             namespaces: HashSet::arbitrary(g),
             clone: bool::arbitrary(g),
+            canonical: bool::arbitrary(g),
             doc: DocAt::arbitrary(g),
+            bytes: u64::arbitrary(g),
         }
     }
 }
@@ -52,6 +54,7 @@ impl Arbitrary for Message {
                 MessageType::RefsAnnouncement,
                 MessageType::Info,
                 MessageType::Subscribe,
+                MessageType::SubscribeDelta,
                 MessageType::Ping,
                 MessageType::Pong,
             ])
@@ -101,9 +104,18 @@ impl Arbitrary for Message {
                 .into()
             }
             MessageType::Info => {
-                let message = Info::RefsAlreadySynced {
-                    rid: RepoId::arbitrary(g),
-                    at: oid(),
+                let message = match g.choose(&[1, 2, 3]).unwrap() {
+                    1 => Info::RefsAlreadySynced {
+                        rid: RepoId::arbitrary(g),
+                        at: oid(),
+                    },
+                    2 => Info::SeedsRequest {
+                        rid: RepoId::arbitrary(g),
+                    },
+                    _ => Info::Seeds {
+                        rid: RepoId::arbitrary(g),
+                        seeds: BoundedVec::arbitrary(g),
+                    },
                 };
                 Self::Info(message)
             }
@@ -112,6 +124,11 @@ impl Arbitrary for Message {
                 since: Timestamp::arbitrary(g),
                 until: Timestamp::arbitrary(g),
             }),
+            MessageType::SubscribeDelta => Self::SubscribeDelta(SubscribeDelta {
+                added: BoundedVec::arbitrary(g),
+                removed: BoundedVec::arbitrary(g),
+                since: Timestamp::arbitrary(g),
+            }),
             MessageType::Ping => {
                 let mut rng = fastrand::Rng::with_seed(u64::arbitrary(g));
 
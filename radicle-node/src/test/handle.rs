@@ -25,6 +25,13 @@ impl radicle::node::Handle for Handle {
     type Events = Vec<Self::Event>;
     type Event = Result<Event, Self::Error>;
 
+    fn event_log(
+        &self,
+        _filter: radicle::node::events::Filter,
+    ) -> Result<Vec<radicle::node::events::Logged>, Self::Error> {
+        unimplemented!();
+    }
+
     fn nid(&self) -> Result<NodeId, Self::Error> {
         Ok(NodeId::from_str("z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK").unwrap())
     }
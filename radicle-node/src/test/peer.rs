@@ -254,7 +254,7 @@ where
                 .insert(
                     &peer.node_id(),
                     PROTOCOL_VERSION,
-                    radicle::node::Features::default(),
+                    peer.service.config().features(),
                     &Alias::from_str(peer.name).unwrap(),
                     0,
                     &UserAgent::default(),
@@ -308,7 +308,7 @@ where
         Message::node(
             NodeAnnouncement {
                 version: PROTOCOL_VERSION,
-                features: node::Features::SEED,
+                features: self.service.config().features(),
                 timestamp: self.timestamp(),
                 alias: Alias::from_str(self.name).unwrap(),
                 addresses: Some(net::SocketAddr::from((self.ip, node::DEFAULT_PORT)).into()).into(),
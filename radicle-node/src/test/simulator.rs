@@ -664,13 +664,23 @@ where
                                     updated: vec![],
                                     namespaces: HashSet::new(),
                                     clone: true,
+                                    canonical: false,
                                     doc: arbitrary::gen(1),
+                                    bytes: 0,
                                 })),
                             ),
                         },
                     );
                 }
             }
+            Io::CancelFetch { rid, remote, .. } => {
+                // Simulated fetches can't be interrupted, they complete as scheduled.
+                log::info!(
+                    target: "sim",
+                    "{:05} {} ~> {} ({}): Fetch cancelled",
+                    self.elapsed().as_millis(), node, remote, rid
+                );
+            }
         }
     }
 
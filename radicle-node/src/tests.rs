@@ -1328,6 +1328,77 @@ fn test_maintain_connections() {
     );
 }
 
+#[test]
+fn test_maintain_connections_score() {
+    let bob = Peer::new("bob", [8, 8, 8, 1]);
+    let eve = Peer::new("eve", [9, 9, 9, 1]);
+    let carol = Peer::new("carol", [9, 9, 9, 2]);
+    let mut alice = Peer::new("alice", [7, 7, 7, 7]);
+
+    alice.connect_to(&bob);
+    alice.import_addresses([&eve, &carol]);
+    alice
+        .database()
+        .addresses()
+        .fetch_succeeded(&carol.id, LocalDuration::from_secs(1), 0)
+        .unwrap();
+    alice.database().addresses().fetch_failed(&eve.id).unwrap();
+    alice.disconnected(
+        bob.id,
+        Link::Outbound,
+        &DisconnectReason::Session(session::Error::Misbehavior),
+    );
+
+    let mut connects = alice.outbox().filter_map(|o| match o {
+        Io::Connect(id, _) => Some(id),
+        _ => None,
+    });
+    assert_eq!(
+        connects.next(),
+        Some(carol.id),
+        "the highest scoring peer is preferred"
+    );
+    assert_eq!(connects.next(), Some(eve.id));
+}
+
+#[test]
+fn test_peer_score() {
+    let storage = arbitrary::nonempty_storage(1);
+    let rid = *storage.repos.keys().next().unwrap();
+    let doc = storage.repos.get(&rid).unwrap().doc.clone();
+    let mut alice = Peer::with_storage("alice", [7, 7, 7, 7], storage);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+
+    alice.connect_to(&bob);
+
+    let (send, _recv) = chan::bounded::<node::FetchResult>(1);
+    alice.command(Command::Fetch(rid, bob.id, DEFAULT_TIMEOUT, send));
+    alice.elapse(LocalDuration::from_secs(2));
+    alice.fetched(
+        rid,
+        bob.id,
+        Ok(fetch::FetchResult {
+            bytes: 1024,
+            ..fetch::FetchResult::new(doc)
+        }),
+    );
+    let score = alice.database().addresses().score(&bob.id).unwrap();
+    assert_eq!(score.fetches, 1);
+    assert_eq!(score.bytes, 1024);
+    assert_eq!(score.latency(), Some(LocalDuration::from_secs(2)));
+
+    alice.elapse(LocalDuration::from_mins(1));
+    alice.disconnected(
+        bob.id,
+        Link::Outbound,
+        &DisconnectReason::Session(session::Error::Misbehavior),
+    );
+    let score = alice.database().addresses().score(&bob.id).unwrap();
+    assert_eq!(score.violations, 1);
+    assert!(score.uptime >= LocalDuration::from_mins(1).as_millis() as u64);
+    assert!(score.value() < 0);
+}
+
 #[test]
 fn test_maintain_connections_transient() {
     // Peers alice starts out connected to.
@@ -1402,6 +1473,24 @@ fn test_seed_repo_subscribe() {
     let (send, recv) = chan::bounded(1);
 
     alice.connect_to(&bob);
+    // Bob is running an older version, and doesn't support subscription changes.
+    alice.elapse(LocalDuration::from_secs(1));
+    let timestamp = alice.timestamp();
+    alice
+        .database_mut()
+        .addresses_mut()
+        .insert(
+            &bob.id,
+            crate::PROTOCOL_VERSION,
+            node::Features::SEED,
+            &node::Alias::new("bob"),
+            0,
+            &node::UserAgent::default(),
+            timestamp,
+            [],
+        )
+        .unwrap();
+    alice.outbox().for_each(drop);
     alice.command(Command::Seed(rid, policy::Scope::default(), send));
     assert!(recv.recv().unwrap());
 
@@ -1415,6 +1504,220 @@ fn test_seed_repo_subscribe() {
     );
 }
 
+#[test]
+fn test_seed_repo_subscribe_delta() {
+    let mut alice = Peer::new("alice", [7, 7, 7, 7]);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let rid = arbitrary::gen::<RepoId>(1);
+
+    alice.connect_to(&bob);
+
+    let (send, recv) = chan::bounded(1);
+    alice.command(Command::Seed(rid, policy::Scope::default(), send));
+    assert!(recv.recv().unwrap());
+    assert_matches!(
+        alice.messages(bob.id).next(),
+        Some(Message::SubscribeDelta(delta)) if delta == SubscribeDelta::added(rid, alice.timestamp())
+    );
+
+    let (send, recv) = chan::bounded(1);
+    alice.command(Command::Unseed(rid, send));
+    assert!(recv.recv().unwrap());
+    assert_matches!(
+        alice.messages(bob.id).next(),
+        Some(Message::SubscribeDelta(delta)) if delta == SubscribeDelta::removed(rid)
+    );
+}
+
+#[test]
+fn test_subscribe_delta() {
+    let mut alice = Peer::new("alice", [7, 7, 7, 7]);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let rid = arbitrary::gen::<RepoId>(1);
+    let subscribed = |alice: &Peer<_, _>| {
+        alice
+            .sessions()
+            .get(&bob.id)
+            .is_some_and(|s| s.is_subscribed_to(&rid))
+    };
+
+    alice.connect_from(&bob);
+    // Changes are ignored until the peer subscribes.
+    alice.receive(bob.id, SubscribeDelta::added(rid, Timestamp::MIN).into());
+    assert!(!subscribed(&alice));
+
+    alice.receive(
+        bob.id,
+        Message::subscribe(Filter::empty(), Timestamp::MIN, Timestamp::MAX),
+    );
+    assert!(!subscribed(&alice));
+
+    alice.receive(bob.id, SubscribeDelta::added(rid, Timestamp::MIN).into());
+    assert!(subscribed(&alice));
+
+    alice.receive(bob.id, SubscribeDelta::removed(rid).into());
+    assert!(!subscribed(&alice));
+
+    alice.receive(bob.id, SubscribeDelta::added(rid, Timestamp::MIN).into());
+    assert!(subscribed(&alice));
+
+    // Repositories matching the subscription filter can't be unsubscribed from.
+    alice.receive(bob.id, Message::Subscribe(Subscribe::all()));
+    alice.receive(bob.id, SubscribeDelta::removed(rid).into());
+    assert!(subscribed(&alice));
+}
+
+#[test]
+fn test_subscribe_exact() {
+    let mut alice = Peer::new("alice", [7, 7, 7, 7]);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let rid = arbitrary::gen::<RepoId>(1);
+
+    alice.seed(&rid, policy::Scope::All).unwrap();
+    alice.import_addresses([&bob]);
+    alice.connected(bob.id, bob.address(), Link::Inbound);
+
+    // Bob supports subscription changes, so he's sent the exact set of repositories
+    // Alice seeds, instead of a filter.
+    let mut msgs = alice
+        .messages(bob.id)
+        .skip_while(|m| !matches!(m, Message::Subscribe(_)));
+    assert_matches!(
+        msgs.next(),
+        Some(Message::Subscribe(Subscribe { filter, .. })) if filter == Filter::empty()
+    );
+    assert_matches!(
+        msgs.next(),
+        Some(Message::SubscribeDelta(SubscribeDelta { added, removed, .. }))
+            if added.as_slice() == [rid] && removed.is_empty()
+    );
+}
+
+#[test]
+fn test_seeds_request() {
+    let rid = arbitrary::gen::<RepoId>(1);
+    let mut alice = Peer::new("alice", [7, 7, 7, 7]);
+    let mut bob = Peer::new("bob", [8, 8, 8, 8]);
+    let eve = Peer::new("eve", [9, 9, 9, 9]);
+    let now = LocalTime::now();
+
+    // Bob knows that Eve seeds the repository.
+    bob.connect_to(&eve);
+    bob.connect_from(&alice);
+    bob.receive(
+        eve.id(),
+        Message::inventory(
+            InventoryAnnouncement {
+                inventory: vec![rid].try_into().unwrap(),
+                timestamp: now.into(),
+            },
+            eve.signer(),
+        ),
+    );
+
+    // Alice can't find any seed, so she asks Bob.
+    let (send, recv) = chan::bounded(1);
+    alice.connect_to(&bob);
+    alice.import_addresses([&eve]);
+    alice.command(Command::Seeds(rid, send));
+    assert!(recv.recv().unwrap().is_empty());
+
+    let request = alice
+        .messages(bob.id())
+        .find(|m| matches!(m, Message::Info(Info::SeedsRequest { .. })))
+        .unwrap();
+    assert_matches!(request, Message::Info(Info::SeedsRequest { rid: r }) if r == rid);
+
+    // Asking again right away doesn't send a new request.
+    let (send, _recv) = chan::bounded(1);
+    alice.command(Command::Seeds(rid, send));
+    assert!(alice
+        .messages(bob.id())
+        .all(|m| !matches!(m, Message::Info(Info::SeedsRequest { .. }))));
+
+    bob.receive(alice.id(), request);
+    let hints = bob
+        .messages(alice.id())
+        .find(|m| matches!(m, Message::Info(Info::Seeds { .. })))
+        .unwrap();
+    assert_matches!(
+        &hints,
+        Message::Info(Info::Seeds { rid: r, seeds }) if *r == rid && seeds.as_slice() == [eve.id()]
+    );
+
+    // Alice now knows about Eve.
+    alice.receive(bob.id(), hints);
+    let (send, recv) = chan::bounded(1);
+    alice.command(Command::Seeds(rid, send));
+    let seeds = recv.recv().unwrap();
+    assert!(seeds.contains(&eve.id()));
+    assert_eq!(seeds.len(), 1);
+}
+
+#[test]
+fn test_seeds_hinted_on_missing_repo() {
+    let rid = arbitrary::gen::<RepoId>(1);
+    let mut alice = Peer::new("alice", [7, 7, 7, 7]);
+    let mut bob = Peer::new("bob", [8, 8, 8, 8]);
+    let eve = Peer::new("eve", [9, 9, 9, 9]);
+    let now = LocalTime::now();
+    let events = alice.events();
+
+    // Bob doesn't have the repository, but knows that Eve seeds it.
+    bob.connect_to(&eve);
+    bob.connect_from(&alice);
+    bob.import_addresses([&alice]);
+    bob.receive(
+        eve.id(),
+        Message::inventory(
+            InventoryAnnouncement {
+                inventory: vec![rid].try_into().unwrap(),
+                timestamp: now.into(),
+            },
+            eve.signer(),
+        ),
+    );
+
+    // Alice tries to fetch the repository from Bob, who tells her about Eve instead.
+    bob.upload_not_found(rid, alice.id());
+    let hints = bob
+        .messages(alice.id())
+        .find(|m| matches!(m, Message::Info(Info::Seeds { .. })))
+        .unwrap();
+    assert_matches!(
+        &hints,
+        Message::Info(Info::Seeds { rid: r, seeds }) if *r == rid && seeds.as_slice() == [eve.id()]
+    );
+
+    // Alice didn't ask for these hints, so she only accepts them once she seeds the repository.
+    alice.connect_to(&bob);
+    alice.import_addresses([&eve]);
+    alice.receive(bob.id(), hints.clone());
+    let (send, recv) = chan::bounded(1);
+    alice.command(Command::Seeds(rid, send));
+    assert!(recv.recv().unwrap().is_empty());
+
+    alice.seed(&rid, node::policy::Scope::All).unwrap();
+    alice.receive(bob.id(), hints);
+    let (send, recv) = chan::bounded(1);
+    alice.command(Command::Seeds(rid, send));
+    assert!(recv.recv().unwrap().contains(&eve.id()));
+
+    events
+        .wait(
+            |e| {
+                matches!(
+                    e,
+                    Event::SeedsHinted { rid: r, nid, seeds }
+                    if r == &rid && nid == &bob.id() && seeds == &[eve.id()]
+                )
+                .then_some(())
+            },
+            time::Duration::from_secs(3),
+        )
+        .unwrap();
+}
+
 #[test]
 fn test_fetch_missing_inventory_on_gossip() {
     let rid = arbitrary::gen::<RepoId>(1);
@@ -1527,7 +1830,8 @@ fn test_queued_fetch_from_ann_same_rid() {
     let storage = arbitrary::nonempty_storage(1); // We're testing both public and private repos.
     let mut repo_keys = storage.repos.keys();
     let rid = *repo_keys.next().unwrap();
-    let mut alice = Peer::with_storage("alice", [7, 7, 7, 7], storage);
+    // Fetch from one seed at a time.
+    let mut alice = Peer::config("alice", [7, 7, 7, 7], storage, one_source()).initialized();
     let bob = Peer::new("bob", [8, 8, 8, 8]);
     let eve = Peer::new("eve", [9, 9, 9, 9]);
     let carol = Peer::new("carol", [10, 10, 10, 10]);
@@ -1590,19 +1894,87 @@ fn test_queued_fetch_from_ann_same_rid() {
             }],
             namespaces: [carol.id()].into_iter().collect(),
             clone: false,
+            canonical: false,
             doc: arbitrary::gen(1),
+            bytes: 0,
         }),
     );
     // Now the 1st fetch is done, but the 2nd and 3rd fetches are redundant.
     assert_matches!(alice.fetches().next(), None);
 }
 
+#[test]
+fn test_fetch_via_relay() {
+    let storage = arbitrary::nonempty_storage(1);
+    let rid = *storage.repos.keys().next().unwrap();
+    let doc = storage.repos.get(&rid).unwrap().doc.clone();
+    let mut alice = Peer::with_storage("alice", [7, 7, 7, 7], storage);
+    let bob = Peer::config(
+        "bob",
+        [8, 8, 8, 8],
+        MockStorage::empty(),
+        peer::Config {
+            config: Config {
+                relay: node::config::Relay::Always,
+                relay_streams: true,
+                ..Config::test(node::Alias::new("bob"))
+            },
+            ..peer::Config::default()
+        },
+    )
+    .initialized();
+    let eve = Peer::new("eve", [9, 9, 9, 9]);
+
+    logger::init(log::Level::Debug);
+
+    alice.connect_to(&bob);
+    alice.outbox().for_each(drop);
+
+    // Alice isn't connected to Eve, so the fetch goes through Bob.
+    let (send, recv) = chan::bounded::<node::FetchResult>(1);
+    alice.command(Command::Fetch(rid, eve.id, DEFAULT_TIMEOUT, send));
+    assert_matches!(
+        alice.outbox().find(|io| matches!(io, Io::Fetch { .. })),
+        Some(Io::Fetch { remote, relay: Some(relay), .. }) if remote == eve.id && relay == bob.id
+    );
+    assert!(alice.sessions().get(&bob.id).unwrap().is_fetching(&rid));
+
+    alice.fetched(rid, eve.id, Ok(fetch::FetchResult::new(doc)));
+    assert_matches!(recv.try_recv(), Ok(node::FetchResult::Success { .. }));
+    assert!(!alice.sessions().get(&bob.id).unwrap().is_fetching(&rid));
+
+    // Bob is running an older version, and can't relay.
+    alice.elapse(LocalDuration::from_secs(1));
+    let timestamp = alice.timestamp();
+    alice
+        .database_mut()
+        .addresses_mut()
+        .insert(
+            &bob.id,
+            crate::PROTOCOL_VERSION,
+            node::Features::SEED,
+            &node::Alias::new("bob"),
+            0,
+            &node::UserAgent::default(),
+            timestamp,
+            [],
+        )
+        .unwrap();
+    alice.outbox().for_each(drop);
+
+    let (send, recv) = chan::bounded::<node::FetchResult>(1);
+    alice.command(Command::Fetch(rid, eve.id, DEFAULT_TIMEOUT, send));
+    assert!(!alice.outbox().any(|io| matches!(io, Io::Fetch { .. })));
+    assert_matches!(recv.try_recv(), Ok(node::FetchResult::Failed { .. }));
+}
+
 #[test]
 fn test_queued_fetch_from_command_same_rid() {
     let storage = arbitrary::nonempty_storage(3);
     let mut repo_keys = storage.repos.keys();
     let rid1 = *repo_keys.next().unwrap();
-    let mut alice = Peer::with_storage("alice", [7, 7, 7, 7], storage);
+    // Fetch from one seed at a time.
+    let mut alice = Peer::config("alice", [7, 7, 7, 7], storage, one_source()).initialized();
     let bob = Peer::new("bob", [8, 8, 8, 8]);
     let eve = Peer::new("eve", [9, 9, 9, 9]);
     let carol = Peer::new("carol", [10, 10, 10, 10]);
@@ -1659,6 +2031,196 @@ fn test_queued_fetch_from_command_same_rid() {
     assert!(peers.is_empty());
 }
 
+/// Peer configuration that fetches a repository from one seed at a time.
+fn one_source() -> peer::Config<crate::crypto::test::signer::MockSigner> {
+    peer::Config {
+        config: Config {
+            limits: Limits {
+                fetch_sources: 1,
+                ..Limits::default()
+            },
+            ..Config::test(node::Alias::new("alice"))
+        },
+        ..peer::Config::default()
+    }
+}
+
+#[test]
+fn test_fetch_race_from_ann_same_rid() {
+    let storage = arbitrary::nonempty_storage(1);
+    let rid = *storage.repos.keys().next().unwrap();
+    let doc = storage.repos.get(&rid).unwrap().doc.clone();
+    let mut alice = Peer::with_storage("alice", [7, 7, 7, 7], storage);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let eve = Peer::new("eve", [9, 9, 9, 9]);
+    let carol = Peer::new("carol", [10, 10, 10, 10]);
+    let oid = arbitrary::oid();
+    let ann = RefsAnnouncement {
+        rid,
+        refs: vec![RefsAt {
+            remote: carol.id(),
+            at: oid,
+        }]
+        .try_into()
+        .unwrap(),
+        timestamp: bob.timestamp(),
+    };
+
+    alice.seed(&rid, policy::Scope::All).unwrap();
+    alice.connect_to(&bob);
+    alice.connect_to(&eve);
+    alice.connect_to(&carol);
+    alice.outbox().for_each(drop);
+
+    alice.receive(bob.id, bob.announcement(ann.clone()));
+    alice.receive(eve.id, eve.announcement(ann.clone()));
+    alice.receive(carol.id, carol.announcement(ann));
+
+    // The same refs are fetched from all three seeds at once.
+    let fetches = alice.fetches().map(|(_, nid)| nid).collect::<BTreeSet<_>>();
+    assert_eq!(fetches, BTreeSet::from([bob.id, eve.id, carol.id]));
+    assert_eq!(alice.fetching().get(&rid).unwrap().sources.len(), 3);
+
+    // Eve is the fastest, but didn't have the refs we want, so nothing is cancelled.
+    alice.fetched(rid, eve.id, Ok(fetch::FetchResult::new(doc.clone())));
+    assert!(!alice
+        .outbox()
+        .any(|io| matches!(io, Io::CancelFetch { .. })));
+    assert_eq!(alice.fetching().get(&rid).unwrap().sources.len(), 2);
+
+    // Bob fetched the refs we want, so the fetch from Carol is cancelled.
+    alice
+        .database_mut()
+        .refs_mut()
+        .set(&rid, &carol.id, &SIGREFS_BRANCH, oid, LocalTime::now())
+        .unwrap();
+    alice.fetched(rid, bob.id, Ok(fetch::FetchResult::new(doc)));
+    let cancelled = alice
+        .outbox()
+        .filter_map(|io| match io {
+            Io::CancelFetch { rid: r, remote, .. } if r == rid => Some(remote),
+            _ => None,
+        })
+        .collect::<BTreeSet<_>>();
+    assert_eq!(cancelled, BTreeSet::from([carol.id]));
+
+    // Cancelled fetches end with an error, which doesn't count against the seed.
+    alice.fetched(
+        rid,
+        carol.id,
+        Err(worker::FetchError::Io(
+            io::ErrorKind::ConnectionReset.into(),
+        )),
+    );
+    assert!(alice.fetching().is_empty());
+    assert_eq!(
+        alice
+            .database()
+            .addresses()
+            .score(&carol.id)
+            .unwrap()
+            .failures,
+        0
+    );
+    assert_eq!(
+        alice.database().addresses().score(&eve.id).unwrap().fetches,
+        1
+    );
+}
+
+#[test]
+fn test_fetch_race_cancelled_source() {
+    let storage = arbitrary::nonempty_storage(1);
+    let rid = *storage.repos.keys().next().unwrap();
+    let doc = storage.repos.get(&rid).unwrap().doc.clone();
+    let mut alice = Peer::with_storage("alice", [7, 7, 7, 7], storage);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let carol = Peer::new("carol", [10, 10, 10, 10]);
+    let oid = arbitrary::oid();
+    let ann = RefsAnnouncement {
+        rid,
+        refs: vec![RefsAt {
+            remote: carol.id(),
+            at: oid,
+        }]
+        .try_into()
+        .unwrap(),
+        timestamp: bob.timestamp(),
+    };
+
+    alice.seed(&rid, policy::Scope::All).unwrap();
+    alice.connect_to(&bob);
+    alice.connect_to(&carol);
+    alice.outbox().for_each(drop);
+
+    alice.receive(bob.id, bob.announcement(ann.clone()));
+    alice.receive(carol.id, carol.announcement(ann));
+    alice.outbox().for_each(drop);
+
+    // Bob wins the race, so the fetch from Carol is cancelled.
+    alice
+        .database_mut()
+        .refs_mut()
+        .set(&rid, &carol.id, &SIGREFS_BRANCH, oid, LocalTime::now())
+        .unwrap();
+    alice.fetched(rid, bob.id, Ok(fetch::FetchResult::new(doc.clone())));
+    assert!(alice
+        .outbox()
+        .any(|io| matches!(io, Io::CancelFetch { remote, .. } if remote == carol.id)));
+
+    // A fetch from Carol requested while the cancelled one is winding down is queued.
+    let (send, recv) = chan::bounded::<node::FetchResult>(1);
+    alice.command(Command::Fetch(rid, carol.id, DEFAULT_TIMEOUT, send));
+    assert_eq!(alice.fetches().count(), 0);
+
+    // Once the cancelled fetch ends, the requested fetch is started, and its result is sent.
+    alice.fetched(
+        rid,
+        carol.id,
+        Err(worker::FetchError::Io(
+            io::ErrorKind::ConnectionReset.into(),
+        )),
+    );
+    assert!(recv.try_recv().is_err());
+    assert_eq!(alice.fetches().collect::<Vec<_>>(), vec![(rid, carol.id)]);
+    alice.fetched(rid, carol.id, Ok(fetch::FetchResult::new(doc)));
+    assert_matches!(recv.try_recv(), Ok(node::FetchResult::Success { .. }));
+    assert!(alice.fetching().is_empty());
+}
+
+#[test]
+fn test_fetch_race_from_command_same_rid() {
+    let storage = arbitrary::nonempty_storage(1);
+    let rid = *storage.repos.keys().next().unwrap();
+    let doc = storage.repos.get(&rid).unwrap().doc.clone();
+    let mut alice = Peer::with_storage("alice", [7, 7, 7, 7], storage);
+    let bob = Peer::new("bob", [8, 8, 8, 8]);
+    let eve = Peer::new("eve", [9, 9, 9, 9]);
+
+    alice.connect_to(&bob);
+    alice.connect_to(&eve);
+    alice.outbox().for_each(drop);
+
+    let (send1, recv1) = chan::bounded::<node::FetchResult>(1);
+    alice.command(Command::Fetch(rid, bob.id, DEFAULT_TIMEOUT, send1));
+    let (send2, recv2) = chan::bounded::<node::FetchResult>(1);
+    alice.command(Command::Fetch(rid, eve.id, DEFAULT_TIMEOUT, send2));
+
+    let fetches = alice.fetches().map(|(_, nid)| nid).collect::<BTreeSet<_>>();
+    assert_eq!(fetches, BTreeSet::from([bob.id, eve.id]));
+
+    // Someone is waiting on the fetch from Eve, so it isn't cancelled when Bob is faster.
+    alice.fetched(rid, bob.id, Ok(fetch::FetchResult::new(doc.clone())));
+    assert_matches!(recv1.try_recv(), Ok(node::FetchResult::Success { .. }));
+    assert!(!alice
+        .outbox()
+        .any(|io| matches!(io, Io::CancelFetch { .. })));
+
+    alice.fetched(rid, eve.id, Ok(fetch::FetchResult::new(doc)));
+    assert_matches!(recv2.try_recv(), Ok(node::FetchResult::Success { .. }));
+    assert!(alice.fetching().is_empty());
+}
+
 #[test]
 fn test_refs_synced_event() {
     let temp = tempfile::tempdir().unwrap();
@@ -1,5 +1,8 @@
 use std::{collections::HashSet, thread, time};
 
+use radicle::cob::person::Persons;
+use radicle::identity::doc::PayloadId;
+use radicle::identity::{DelegatePersons, Identity};
 use radicle::node::device::Device;
 use radicle::node::{Alias, ConnectResult, FetchResult, Handle as _, DEFAULT_TIMEOUT};
 use radicle::storage::{
@@ -462,6 +465,76 @@ fn test_fetch_followed_remotes() {
     assert!(bob_remotes.contains(&alice.id));
 }
 
+#[test]
+fn test_fetch_person_devices() {
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let mut alice = Node::init(tmp.path(), config::relay("alice"));
+    let bob = Node::init(tmp.path(), config::relay("bob"));
+    let acme = alice.project("acme", "");
+    let laptop = Device::mock();
+    let stranger = Device::mock();
+
+    rad::fork_remote(acme, &alice.id, &laptop, &alice.storage).unwrap();
+    rad::fork_remote(acme, &alice.id, &stranger, &alice.storage).unwrap();
+
+    // Alice also signs with her laptop.
+    let repo = alice.storage.repository(acme).unwrap();
+    let person = *Persons::open(&repo)
+        .unwrap()
+        .create([*laptop.public_key()], &alice.signer)
+        .unwrap()
+        .id();
+    let mut identity = Identity::load_mut(&repo).unwrap();
+    let doc = repo
+        .identity_doc()
+        .unwrap()
+        .doc
+        .with_edits(|doc| {
+            doc.payload.insert(
+                PayloadId::of::<DelegatePersons>(),
+                DelegatePersons {
+                    persons: [(alice.id.into(), person)].into(),
+                }
+                .into(),
+            );
+        })
+        .unwrap();
+    let rev = identity
+        .update("Pin person", "", &doc, &alice.signer)
+        .unwrap();
+    repo.set_identity_head_to(rev).unwrap();
+
+    let mut alice = alice.spawn();
+    let mut bob = bob.spawn();
+
+    alice.connect(&bob);
+    converge([&alice, &bob]);
+
+    assert!(bob.handle.seed(acme, Scope::Followed).unwrap());
+
+    // The first fetch brings in the person, the second one its devices.
+    for _ in 0..2 {
+        let result = bob.handle.fetch(acme, alice.id, DEFAULT_TIMEOUT).unwrap();
+        assert!(result.is_success());
+    }
+    let bob_remotes = bob
+        .storage
+        .repository(acme)
+        .unwrap()
+        .remote_ids()
+        .unwrap()
+        .collect::<Result<HashSet<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        bob_remotes,
+        HashSet::from([alice.id, *laptop.public_key()]),
+        "Bob fetches the devices of delegates, but not other remotes"
+    );
+}
+
 #[test]
 fn test_missing_remote() {
     logger::init(log::Level::Debug);
@@ -653,6 +726,63 @@ fn test_fetch_unseeded() {
     assert_matches!(result, FetchResult::Failed { .. });
 }
 
+#[test]
+//
+//     alice -> bob <- eve
+//
+// Alice can't accept connections, so Eve fetches from her through Bob.
+//
+fn test_relayed_fetch() {
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let mut alice = Node::init(tmp.path(), config::relay("alice"));
+    let bob = Node::init(
+        tmp.path(),
+        Config {
+            relay_streams: true,
+            ..config::relay("bob")
+        },
+    );
+    let eve = Node::init(tmp.path(), config::relay("eve"));
+    let acme = alice.project("acme", "");
+
+    let mut alice = alice.spawn();
+    let bob = bob.spawn();
+    let mut eve = eve.spawn();
+
+    alice.connect(&bob);
+    eve.connect(&bob);
+    converge([&alice, &bob, &eve]);
+
+    let _ = eve.handle.seed(acme, Scope::All).unwrap();
+    let result = eve.handle.fetch(acme, alice.id, DEFAULT_TIMEOUT).unwrap();
+    assert!(result.is_success(), "{result:?}");
+    assert!(eve.storage.contains(&acme).unwrap());
+    assert!(!eve
+        .handle
+        .sessions()
+        .unwrap()
+        .iter()
+        .any(|s| s.nid == alice.id));
+
+    let eve_refs = eve
+        .storage
+        .repository(acme)
+        .unwrap()
+        .remote(&alice.id)
+        .unwrap()
+        .refs;
+    let alice_refs = alice
+        .storage
+        .repository(acme)
+        .unwrap()
+        .remote(&alice.id)
+        .unwrap()
+        .refs;
+    assert_eq!(eve_refs, alice_refs);
+}
+
 #[test]
 fn test_large_fetch() {
     logger::init(log::Level::Debug);
@@ -1157,6 +1287,130 @@ fn test_outdated_delegate_sigrefs() {
     assert_eq!(alice_refs_expected, alice_refs);
 }
 
+#[test]
+fn test_forced_update() {
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+
+    let mut alice = Node::init(tmp.path(), config::relay("alice"));
+    let bob = Node::init(tmp.path(), config::relay("bob"));
+
+    let rid = alice.project("acme", "");
+
+    let mut alice = alice.spawn();
+    let mut bob = bob.spawn();
+
+    bob.handle.seed(rid, Scope::All).unwrap();
+    alice.connect(&bob);
+    converge([&alice, &bob]);
+
+    bob.handle.fetch(rid, alice.id, DEFAULT_TIMEOUT).unwrap();
+    assert!(bob.storage.contains(&rid).unwrap());
+
+    // Alice rewrites the history of her master branch.
+    let master = git::refs::storage::branch_of(&alice.id, &git::refname!("master"));
+    let (old, new) = {
+        let repo = alice.storage.repository_mut(rid).unwrap();
+        let mut r = repo.backend.find_reference(master.as_str()).unwrap();
+        let head = r.peel_to_commit().unwrap();
+        let sig = git::raw::Signature::now("alice", "alice@radicle.xyz").unwrap();
+        let new = repo
+            .backend
+            .commit(None, &sig, &sig, "Rewritten", &head.tree().unwrap(), &[])
+            .unwrap();
+        r.set_target(new, "test: force push").unwrap();
+        repo.sign_refs(&alice.signer).unwrap();
+
+        (head.id(), new)
+    };
+
+    let result = bob.handle.fetch(rid, alice.id, DEFAULT_TIMEOUT).unwrap();
+    let up = result.find_updated(&master).unwrap();
+
+    assert!(up.is_forced(), "{up:?}");
+    assert_eq!(up.old(), Some(old.into()));
+    assert_eq!(up.new(), Some(new.into()));
+}
+
+#[test]
+fn test_mirror() {
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let mirror = git::raw::Repository::init_bare(tmp.path().join("mirror.git")).unwrap();
+
+    let mut alice = Node::init(tmp.path(), config::relay("alice"));
+    let rid = alice.project("acme", "");
+    let bob = Node::init(
+        tmp.path(),
+        Config {
+            mirrors: [(
+                rid,
+                radicle::node::config::Mirror {
+                    push: Some(git::url::File::new(mirror.path()).to_string()),
+                    import: None,
+                },
+            )]
+            .into_iter()
+            .collect(),
+            ..config::relay("bob")
+        },
+    );
+
+    let mut alice = alice.spawn();
+    let mut bob = bob.spawn();
+
+    bob.handle.seed(rid, Scope::All).unwrap();
+    alice.connect(&bob);
+    converge([&alice, &bob]);
+
+    // The mirror is pushed to in the background, after the fetch completes.
+    let mirrored = |oid: git::raw::Oid| {
+        let timeout = time::Instant::now() + DEFAULT_TIMEOUT;
+        while mirror.refname_to_id("refs/heads/master").ok() != Some(oid) {
+            assert!(
+                time::Instant::now() < timeout,
+                "mirror wasn't updated to {oid}"
+            );
+            thread::sleep(time::Duration::from_millis(100));
+        }
+    };
+
+    // Bob clones the repository, which sets its canonical head, and pushes it to the mirror.
+    bob.handle.fetch(rid, alice.id, DEFAULT_TIMEOUT).unwrap();
+    let (_, head) = bob.storage.repository(rid).unwrap().head().unwrap();
+    mirrored(*head);
+
+    // Alice, the only delegate, updates her master branch.
+    let master = git::refs::storage::branch_of(&alice.id, &git::refname!("master"));
+    let new = {
+        let repo = alice.storage.repository_mut(rid).unwrap();
+        let mut r = repo.backend.find_reference(master.as_str()).unwrap();
+        let parent = r.peel_to_commit().unwrap();
+        let sig = git::raw::Signature::now("alice", "alice@radicle.xyz").unwrap();
+        let new = repo
+            .backend
+            .commit(
+                None,
+                &sig,
+                &sig,
+                "Update",
+                &parent.tree().unwrap(),
+                &[&parent],
+            )
+            .unwrap();
+        r.set_target(new, "test: update").unwrap();
+        repo.sign_refs(&alice.signer).unwrap();
+
+        new
+    };
+
+    // When Bob fetches the update, the mirror follows the canonical head.
+    bob.handle.fetch(rid, alice.id, DEFAULT_TIMEOUT).unwrap();
+    mirrored(new);
+}
+
 #[test]
 fn missing_default_branch() {
     logger::init(log::Level::Debug);
@@ -1215,7 +1469,6 @@ fn missing_default_branch() {
 #[test]
 fn missing_delegate_default_branch() {
     use radicle::git::raw;
-    use radicle::identity::Identity;
     use radicle::storage::git::Repository;
 
     logger::init(log::Level::Debug);
@@ -1537,3 +1790,228 @@ fn test_channel_reader_limit() {
         reason
     );
 }
+
+#[test]
+//
+//     alice ~~ bob (local network)
+//
+fn test_local_network_discovery() {
+    use radicle::node::address::{Source, Store as _};
+    use radicle::node::config::DiscoveryConfig;
+
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let discovery = DiscoveryConfig {
+        group: std::net::SocketAddrV4::new([239, 255, 82, 65].into(), 18776),
+        interface: [127, 0, 0, 1].into(),
+        interval: 1,
+    };
+    let mut alice = Node::init(
+        tmp.path(),
+        Config {
+            discovery: Some(discovery.clone()),
+            ..config::relay("alice")
+        },
+    );
+    let bob = Node::init(
+        tmp.path(),
+        Config {
+            discovery: Some(discovery),
+            ..config::relay("bob")
+        },
+    );
+    let acme = alice.project("acme", "");
+
+    let alice = alice.spawn();
+    let mut bob = bob.spawn();
+
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], alice.addr.port()));
+    let timeout = time::Instant::now() + DEFAULT_TIMEOUT;
+    let db = bob.home.database().unwrap();
+
+    // Bob discovers alice's listen address, and connects to her.
+    while !db
+        .addresses_of(&alice.id)
+        .unwrap()
+        .iter()
+        .any(|ka| ka.source == Source::Local && ka.addr == addr.into())
+        || !bob
+            .handle
+            .sessions()
+            .unwrap()
+            .iter()
+            .any(|s| s.nid == alice.id && s.state.is_connected())
+    {
+        assert!(time::Instant::now() < timeout, "bob didn't discover alice");
+        thread::sleep(time::Duration::from_millis(100));
+    }
+
+    let _ = bob.handle.seed(acme, Scope::All).unwrap();
+    let result = bob.handle.fetch(acme, alice.id, DEFAULT_TIMEOUT).unwrap();
+    assert!(result.is_success(), "{result:?}");
+    assert!(bob.storage.contains(&acme).unwrap());
+}
+
+#[test]
+fn test_bandwidth_limits() {
+    use radicle::node::config::{BandwidthLimits, ByteRate};
+
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let limit = Some(ByteRate::bytes_per_second(1024));
+    let alice = Node::init(
+        tmp.path(),
+        Config {
+            limits: Limits {
+                bandwidth: BandwidthLimits {
+                    download: limit,
+                    ..BandwidthLimits::default()
+                },
+                ..Limits::default()
+            },
+            ..config::relay("alice")
+        },
+    );
+    let mut bob = Node::init(
+        tmp.path(),
+        Config {
+            limits: Limits {
+                bandwidth: BandwidthLimits {
+                    peer_upload: limit,
+                    ..BandwidthLimits::default()
+                },
+                ..Limits::default()
+            },
+            ..config::relay("bob")
+        },
+    );
+    let acme = bob.project("acme", "");
+
+    let mut alice = alice.spawn();
+    let bob = bob.spawn();
+
+    alice.connect(&bob);
+    converge([&alice, &bob]);
+
+    alice.handle.seed(acme, Scope::All).unwrap();
+    let result = alice.handle.fetch(acme, bob.id, DEFAULT_TIMEOUT).unwrap();
+    assert!(result.is_success());
+
+    // Traffic is accounted for once the stream is closed.
+    let traffic = loop {
+        let session = alice.handle.session(bob.id).unwrap().unwrap();
+        if let Some(traffic) = session.traffic.repos.get(&acme).copied() {
+            assert!(session.traffic.received >= traffic.received);
+            break traffic;
+        }
+        thread::sleep(time::Duration::from_millis(100));
+    };
+    assert!(traffic.received > 0);
+    assert!(traffic.sent > 0);
+
+    let session = bob.handle.session(alice.id).unwrap().unwrap();
+    assert!(session.traffic.sent > 0);
+}
+
+#[test]
+fn test_metrics() {
+    use std::io::{Read as _, Write as _};
+    use std::net;
+
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let addr = net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let alice = Node::init(
+        tmp.path(),
+        Config {
+            metrics: Some(addr),
+            ..config::relay("alice")
+        },
+    );
+    let mut bob = Node::init(tmp.path(), config::relay("bob"));
+    let acme = bob.project("acme", "");
+
+    let mut alice = alice.spawn();
+    let bob = bob.spawn();
+
+    alice.connect(&bob);
+    converge([&alice, &bob]);
+
+    alice.handle.seed(acme, Scope::All).unwrap();
+    let result = alice.handle.fetch(acme, bob.id, DEFAULT_TIMEOUT).unwrap();
+    assert!(result.is_success());
+
+    let scrape = |path: &str| {
+        let mut stream = net::TcpStream::connect(addr).unwrap();
+        let mut response = String::new();
+
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: {addr}\r\n\r\n").unwrap();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    };
+    let response = scrape("/metrics");
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("radicle_sessions{state=\"connected\"} 1\n"));
+    assert!(response.contains("radicle_fetches_total{result=\"success\"} 1\n"));
+    assert!(response.contains("radicle_fetch_duration_seconds_count 1\n"));
+    assert!(response.contains("radicle_inventory_repositories 1\n"));
+    assert!(scrape("/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+}
+
+#[test]
+fn test_event_log() {
+    use radicle::node::events::Filter;
+
+    logger::init(log::Level::Debug);
+
+    let tmp = tempfile::tempdir().unwrap();
+    let alice = Node::init(tmp.path(), config::relay("alice"));
+    let mut bob = Node::init(tmp.path(), config::relay("bob"));
+    let acme = bob.project("acme", "");
+
+    let mut alice = alice.spawn();
+    let bob = bob.spawn();
+
+    alice.connect(&bob);
+    converge([&alice, &bob]);
+
+    alice.handle.seed(acme, Scope::All).unwrap();
+    let result = alice.handle.fetch(acme, bob.id, DEFAULT_TIMEOUT).unwrap();
+    assert!(result.is_success());
+
+    // Events are recorded asynchronously, so we wait for them to show up in the log.
+    let logged = |filter: Filter| loop {
+        let events = alice.handle.event_log(filter.clone()).unwrap();
+        if !events.is_empty() {
+            break events;
+        }
+        thread::sleep(time::Duration::from_millis(100));
+    };
+    let events = logged(Filter {
+        nid: Some(bob.id),
+        ..Filter::default()
+    });
+    assert_matches!(events[0].event, service::Event::PeerConnected { nid } if nid == bob.id);
+
+    let events = logged(Filter {
+        rid: Some(acme),
+        ..Filter::default()
+    });
+    assert!(events.iter().all(|e| e.event.rid() == Some(acme)));
+
+    let events = alice
+        .handle
+        .event_log(Filter {
+            since: Some(radicle::node::Timestamp::MAX),
+            ..Filter::default()
+        })
+        .unwrap();
+    assert!(events.is_empty());
+}
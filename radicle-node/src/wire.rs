@@ -1,3 +1,4 @@
+mod bandwidth;
 mod frame;
 mod message;
 mod protocol;
@@ -5,7 +6,7 @@ mod varint;
 
 pub use frame::StreamId;
 pub use message::{AddressType, MessageType};
-pub use protocol::{Control, Wire, WireReader, WireSession, WireWriter};
+pub use protocol::{Control, Transport, Wire, WireReader, WireSession, WireWriter};
 use radicle::node::UserAgent;
 
 use std::collections::BTreeMap;
@@ -0,0 +1,262 @@
+//! Bandwidth shaping.
+//!
+//! Upload and download bandwidth is limited with token buckets holding bytes, one for all
+//! traffic, and one for each peer. Unlike the message rate limiter, data that has already been
+//! sent or received is always accounted for, so buckets can go into debt. Traffic in a direction
+//! is held back until the buckets for that direction are out of debt.
+use std::collections::HashMap;
+
+use localtime::{LocalDuration, LocalTime};
+use radicle::node::config::BandwidthLimits;
+use radicle::node::NodeId;
+
+/// Token bucket holding bytes.
+#[derive(Debug)]
+struct Bucket {
+    /// Bytes added per second.
+    rate: f64,
+    /// Bytes the bucket can hold. Allows for bursts of up to one second of traffic.
+    capacity: f64,
+    /// Bytes available. Negative when in debt.
+    tokens: f64,
+    /// Time of last refill.
+    refilled_at: LocalTime,
+}
+
+impl Bucket {
+    fn new(rate: u64, now: LocalTime) -> Self {
+        let rate = rate.max(1) as f64;
+
+        Self {
+            rate,
+            capacity: rate,
+            tokens: rate,
+            refilled_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: LocalTime) {
+        if now > self.refilled_at {
+            let elapsed = now.duration_since(self.refilled_at).as_millis() as f64 / 1000.;
+
+            self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+            self.refilled_at = now;
+        }
+    }
+
+    fn consume(&mut self, bytes: usize, now: LocalTime) {
+        self.refill(now);
+        self.tokens -= bytes as f64;
+    }
+
+    /// Time until the bucket is out of debt, if it is in debt.
+    fn delay(&mut self, now: LocalTime) -> Option<LocalDuration> {
+        self.refill(now);
+
+        if self.tokens >= 0. {
+            return None;
+        }
+        let millis = (-self.tokens / self.rate * 1000.).ceil() as u128;
+
+        Some(LocalDuration::from_millis(millis.max(1)))
+    }
+}
+
+/// Upload and download buckets.
+#[derive(Debug, Default)]
+struct Buckets {
+    upload: Option<Bucket>,
+    download: Option<Bucket>,
+}
+
+impl Buckets {
+    fn new(upload: Option<u64>, download: Option<u64>, now: LocalTime) -> Self {
+        Self {
+            upload: upload.map(|r| Bucket::new(r, now)),
+            download: download.map(|r| Bucket::new(r, now)),
+        }
+    }
+}
+
+/// Bandwidth shaper for all peers.
+#[derive(Debug)]
+pub struct Shaper {
+    /// Configured limits.
+    limits: BandwidthLimits,
+    /// Buckets shared by all peers.
+    global: Buckets,
+    /// Buckets of each peer.
+    peers: HashMap<NodeId, Buckets>,
+}
+
+impl Shaper {
+    /// Create a new shaper.
+    pub fn new(limits: BandwidthLimits, now: LocalTime) -> Self {
+        let global = Buckets::new(
+            limits.upload.map(|l| l.as_bytes_per_second()),
+            limits.download.map(|l| l.as_bytes_per_second()),
+            now,
+        );
+
+        Self {
+            limits,
+            global,
+            peers: HashMap::new(),
+        }
+    }
+
+    /// Whether any limit is configured.
+    pub fn is_enabled(&self) -> bool {
+        let BandwidthLimits {
+            upload,
+            download,
+            peer_upload,
+            peer_download,
+        } = &self.limits;
+
+        upload.is_some() || download.is_some() || peer_upload.is_some() || peer_download.is_some()
+    }
+
+    /// Account for bytes sent to a peer.
+    pub fn sent(&mut self, nid: NodeId, bytes: usize, now: LocalTime) {
+        let (global, peer) = self.buckets(nid, now);
+
+        for bucket in [&mut global.upload, &mut peer.upload].into_iter().flatten() {
+            bucket.consume(bytes, now);
+        }
+    }
+
+    /// Account for bytes received from a peer.
+    pub fn received(&mut self, nid: NodeId, bytes: usize, now: LocalTime) {
+        let (global, peer) = self.buckets(nid, now);
+
+        for bucket in [&mut global.download, &mut peer.download]
+            .into_iter()
+            .flatten()
+        {
+            bucket.consume(bytes, now);
+        }
+    }
+
+    /// Time to wait before sending more data to a peer, if any.
+    pub fn upload_delay(&mut self, nid: NodeId, now: LocalTime) -> Option<LocalDuration> {
+        let (global, peer) = self.buckets(nid, now);
+
+        [&mut global.upload, &mut peer.upload]
+            .into_iter()
+            .flatten()
+            .filter_map(|b| b.delay(now))
+            .max()
+    }
+
+    /// Time to wait before reading more data from a peer, if any.
+    pub fn download_delay(&mut self, nid: NodeId, now: LocalTime) -> Option<LocalDuration> {
+        let (global, peer) = self.buckets(nid, now);
+
+        [&mut global.download, &mut peer.download]
+            .into_iter()
+            .flatten()
+            .filter_map(|b| b.delay(now))
+            .max()
+    }
+
+    /// Forget about a peer.
+    pub fn remove(&mut self, nid: &NodeId) {
+        self.peers.remove(nid);
+    }
+
+    fn buckets(&mut self, nid: NodeId, now: LocalTime) -> (&mut Buckets, &mut Buckets) {
+        let peer = self.peers.entry(nid).or_insert_with(|| {
+            Buckets::new(
+                self.limits.peer_upload.map(|l| l.as_bytes_per_second()),
+                self.limits.peer_download.map(|l| l.as_bytes_per_second()),
+                now,
+            )
+        });
+        (&mut self.global, peer)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use radicle::node::config::ByteRate;
+    use radicle::test::arbitrary;
+
+    use super::*;
+
+    #[test]
+    fn test_shaper_peer_limits() {
+        let now = LocalTime::from_secs(0);
+        let alice = arbitrary::gen::<NodeId>(1);
+        let bob = arbitrary::gen::<NodeId>(1);
+        let mut shaper = Shaper::new(
+            BandwidthLimits {
+                peer_upload: Some(ByteRate::bytes_per_second(1000)),
+                ..BandwidthLimits::default()
+            },
+            now,
+        );
+        assert!(shaper.is_enabled());
+
+        // One second worth of burst.
+        shaper.sent(alice, 1000, now);
+        assert_eq!(shaper.upload_delay(alice, now), None);
+
+        // Half a second in debt.
+        shaper.sent(alice, 500, now);
+        assert_eq!(
+            shaper.upload_delay(alice, now),
+            Some(LocalDuration::from_millis(500))
+        );
+        assert_eq!(
+            shaper.upload_delay(alice, now + LocalDuration::from_millis(250)),
+            Some(LocalDuration::from_millis(250))
+        );
+        assert_eq!(
+            shaper.upload_delay(alice, now + LocalDuration::from_millis(500)),
+            None
+        );
+
+        // Other peers and directions are unaffected.
+        shaper.sent(alice, 5000, now);
+        assert_eq!(shaper.upload_delay(bob, now), None);
+        assert_eq!(shaper.download_delay(alice, now), None);
+    }
+
+    #[test]
+    fn test_shaper_global_limits() {
+        let now = LocalTime::from_secs(0);
+        let alice = arbitrary::gen::<NodeId>(1);
+        let bob = arbitrary::gen::<NodeId>(1);
+        let mut shaper = Shaper::new(
+            BandwidthLimits {
+                download: Some(ByteRate::bytes_per_second(100)),
+                peer_download: Some(ByteRate::bytes_per_second(1000)),
+                ..BandwidthLimits::default()
+            },
+            now,
+        );
+
+        shaper.received(alice, 200, now);
+        assert_eq!(
+            shaper.download_delay(bob, now),
+            Some(LocalDuration::from_secs(1))
+        );
+        assert_eq!(
+            shaper.download_delay(alice, now),
+            Some(LocalDuration::from_secs(1))
+        );
+        assert_eq!(
+            shaper.download_delay(alice, now + LocalDuration::from_secs(1)),
+            None
+        );
+        // Buckets don't fill up beyond their capacity.
+        shaper.received(bob, 200, now + LocalDuration::from_secs(60));
+        assert_eq!(
+            shaper.download_delay(bob, now + LocalDuration::from_secs(60)),
+            Some(LocalDuration::from_secs(1))
+        );
+        assert!(!Shaper::new(BandwidthLimits::default(), now).is_enabled());
+    }
+}
@@ -2,6 +2,8 @@
 #![warn(clippy::missing_docs_in_private_items)]
 use std::{fmt, io};
 
+use radicle::node::NodeId;
+
 use crate::{wire, wire::varint, wire::varint::VarInt, wire::Message, Link, PROTOCOL_VERSION};
 
 /// Protocol version strings all start with the magic sequence `rad`, followed
@@ -14,6 +16,10 @@ const CONTROL_OPEN: u8 = 0;
 const CONTROL_CLOSE: u8 = 1;
 /// Control EOF byte.
 const CONTROL_EOF: u8 = 2;
+/// Control relay byte.
+const CONTROL_RELAY: u8 = 3;
+/// Control relayed byte.
+const CONTROL_RELAYED: u8 = 4;
 
 /// Protocol version.
 #[derive(Debug, PartialEq, Eq)]
@@ -75,6 +81,10 @@ impl wire::Decode for Version {
 /// +-------+----------------------------------+
 /// | 0b101 | Inbound Git stream               |
 /// +-------+----------------------------------+
+/// | 0b110 | Outbound Relay stream            |
+/// +-------+----------------------------------+
+/// | 0b111 | Inbound Relay stream             |
+/// +-------+----------------------------------+
 ///
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct StreamId(VarInt);
@@ -116,6 +126,12 @@ impl StreamId {
         Self(VarInt::from(((StreamKind::Git as u8) << 1) | link))
     }
 
+    /// Create a relay identifier.
+    pub fn relay(link: Link) -> Self {
+        let link = if link.is_outbound() { 0 } else { 1 };
+        Self(VarInt::from(((StreamKind::Relay as u8) << 1) | link))
+    }
+
     /// Get the nth identifier while preserving the stream type and initiator.
     pub fn nth(self, n: u64) -> Result<Self, varint::BoundsExceeded> {
         let id = *self.0 + (n << 3);
@@ -164,6 +180,9 @@ pub enum StreamKind {
     Gossip = 0b01,
     /// Git stream, used for replication.
     Git = 0b10,
+    /// Relay stream, used for replication with a peer we aren't directly connected to.
+    /// Carries the same data as a git stream, spliced through a relay node.
+    Relay = 0b11,
 }
 
 impl TryFrom<u8> for StreamKind {
@@ -174,6 +193,7 @@ impl TryFrom<u8> for StreamKind {
             0b00 => Ok(StreamKind::Control),
             0b01 => Ok(StreamKind::Gossip),
             0b10 => Ok(StreamKind::Git),
+            0b11 => Ok(StreamKind::Relay),
             n => Err(n),
         }
     }
@@ -267,6 +287,22 @@ pub enum Control {
         /// The stream to send an EOF on.
         stream: StreamId,
     },
+    /// Ask a relay node to splice a new stream to one of its peers.
+    /// Only sent to peers advertising the relay feature.
+    Relay {
+        /// The relay stream to open.
+        stream: StreamId,
+        /// The peer the stream should be spliced to.
+        to: NodeId,
+    },
+    /// Open a stream spliced by a relay node, on behalf of one of its peers.
+    /// Only sent to peers advertising the relay feature.
+    Relayed {
+        /// The relay stream to open.
+        stream: StreamId,
+        /// The peer on the other end of the stream, as claimed by the relay.
+        from: NodeId,
+    },
 }
 
 impl wire::Decode for Control {
@@ -285,6 +321,16 @@ impl wire::Decode for Control {
                 let stream = StreamId::decode(reader)?;
                 Ok(Control::Eof { stream })
             }
+            CONTROL_RELAY => {
+                let stream = StreamId::decode(reader)?;
+                let to = NodeId::decode(reader)?;
+                Ok(Control::Relay { stream, to })
+            }
+            CONTROL_RELAYED => {
+                let stream = StreamId::decode(reader)?;
+                let from = NodeId::decode(reader)?;
+                Ok(Control::Relayed { stream, from })
+            }
             other => Err(wire::Error::InvalidControlMessage(other)),
         }
     }
@@ -307,6 +353,16 @@ impl wire::Encode for Control {
                 n += CONTROL_CLOSE.encode(writer)?;
                 n += id.encode(writer)?;
             }
+            Self::Relay { stream: id, to } => {
+                n += CONTROL_RELAY.encode(writer)?;
+                n += id.encode(writer)?;
+                n += to.encode(writer)?;
+            }
+            Self::Relayed { stream: id, from } => {
+                n += CONTROL_RELAYED.encode(writer)?;
+                n += id.encode(writer)?;
+                n += from.encode(writer)?;
+            }
         }
         Ok(n)
     }
@@ -345,7 +401,7 @@ impl<M: wire::Decode> wire::Decode for Frame<M> {
 
                 Ok(frame)
             }
-            Ok(StreamKind::Git { .. }) => {
+            Ok(StreamKind::Git | StreamKind::Relay) => {
                 let data = varint::payload::decode(reader)?;
                 Ok(Frame::git(stream, data))
             }
@@ -391,5 +447,37 @@ mod test {
         assert_eq!(StreamId::git(Link::Inbound), StreamId(VarInt(0b101)));
         assert_eq!(StreamId::control(Link::Inbound), StreamId(VarInt(0b001)));
         assert_eq!(StreamId::gossip(Link::Inbound), StreamId(VarInt(0b011)));
+
+        assert_eq!(StreamId(VarInt(0b110)).kind().unwrap(), StreamKind::Relay);
+        assert_eq!(StreamId::relay(Link::Outbound), StreamId(VarInt(0b110)));
+        assert_eq!(StreamId::relay(Link::Inbound), StreamId(VarInt(0b111)));
+        assert_eq!(
+            StreamId::relay(Link::Inbound)
+                .nth(2)
+                .unwrap()
+                .kind()
+                .unwrap(),
+            StreamKind::Relay
+        );
+    }
+
+    #[test]
+    fn test_relay_control() {
+        let to = radicle::test::arbitrary::gen::<NodeId>(1);
+        let stream = StreamId::relay(Link::Outbound).nth(1).unwrap();
+
+        for ctrl in [
+            Control::Relay { stream, to },
+            Control::Relayed { stream, from: to },
+        ] {
+            let frame = Frame::<Message>::control(Link::Outbound, ctrl);
+            let bytes = frame.to_bytes();
+
+            assert_eq!(wire::deserialize::<Frame>(&bytes).unwrap(), frame);
+        }
+        let frame = Frame::<Message>::git(stream, vec![1, 2, 3]);
+        let bytes = frame.to_bytes();
+
+        assert_eq!(wire::deserialize::<Frame>(&bytes).unwrap(), frame);
     }
 }
@@ -21,6 +21,7 @@ pub enum MessageType {
     Ping = 10,
     Pong = 12,
     Info = 14,
+    SubscribeDelta = 16,
 }
 
 impl From<MessageType> for u16 {
@@ -41,6 +42,7 @@ impl TryFrom<u16> for MessageType {
             10 => Ok(MessageType::Ping),
             12 => Ok(MessageType::Pong),
             14 => Ok(MessageType::Info),
+            16 => Ok(MessageType::SubscribeDelta),
             _ => Err(other),
         }
     }
@@ -54,6 +56,7 @@ impl Message {
     pub fn type_id(&self) -> u16 {
         match self {
             Self::Subscribe { .. } => MessageType::Subscribe,
+            Self::SubscribeDelta { .. } => MessageType::SubscribeDelta,
             Self::Announcement(Announcement { message, .. }) => match message {
                 AnnouncementMessage::Node(_) => MessageType::NodeAnnouncement,
                 AnnouncementMessage::Inventory(_) => MessageType::InventoryAnnouncement,
@@ -190,6 +193,8 @@ impl wire::Decode for InventoryAnnouncement {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InfoType {
     RefsAlreadySynced = 1,
+    SeedsRequest = 2,
+    Seeds = 3,
 }
 
 impl From<InfoType> for u16 {
@@ -204,6 +209,8 @@ impl TryFrom<u16> for InfoType {
     fn try_from(other: u16) -> Result<Self, Self::Error> {
         match other {
             1 => Ok(Self::RefsAlreadySynced),
+            2 => Ok(Self::SeedsRequest),
+            3 => Ok(Self::Seeds),
             n => Err(n),
         }
     }
@@ -219,6 +226,8 @@ impl From<&Info> for InfoType {
     fn from(info: &Info) -> Self {
         match info {
             Info::RefsAlreadySynced { .. } => Self::RefsAlreadySynced,
+            Info::SeedsRequest { .. } => Self::SeedsRequest,
+            Info::Seeds { .. } => Self::Seeds,
         }
     }
 }
@@ -232,6 +241,13 @@ impl wire::Encode for Info {
                 n += rid.encode(writer)?;
                 n += at.encode(writer)?;
             }
+            Info::SeedsRequest { rid } => {
+                n += rid.encode(writer)?;
+            }
+            Info::Seeds { rid, seeds } => {
+                n += rid.encode(writer)?;
+                n += seeds.encode(writer)?;
+            }
         }
 
         Ok(n)
@@ -249,6 +265,17 @@ impl wire::Decode for Info {
 
                 Ok(Self::RefsAlreadySynced { rid, at })
             }
+            Ok(InfoType::SeedsRequest) => {
+                let rid = RepoId::decode(reader)?;
+
+                Ok(Self::SeedsRequest { rid })
+            }
+            Ok(InfoType::Seeds) => {
+                let rid = RepoId::decode(reader)?;
+                let seeds = BoundedVec::<NodeId, SEEDS_LIMIT>::decode(reader)?;
+
+                Ok(Self::Seeds { rid, seeds })
+            }
             Err(other) => Err(wire::Error::UnknownInfoType(other)),
         }
     }
@@ -268,6 +295,15 @@ impl wire::Encode for Message {
                 n += since.encode(writer)?;
                 n += until.encode(writer)?;
             }
+            Self::SubscribeDelta(SubscribeDelta {
+                added,
+                removed,
+                since,
+            }) => {
+                n += added.encode(writer)?;
+                n += removed.encode(writer)?;
+                n += since.encode(writer)?;
+            }
             Self::Announcement(Announcement {
                 node,
                 message,
@@ -315,6 +351,17 @@ impl wire::Decode for Message {
                     until,
                 }))
             }
+            Ok(MessageType::SubscribeDelta) => {
+                let added = BoundedVec::<RepoId, SUBSCRIBE_DELTA_LIMIT>::decode(reader)?;
+                let removed = BoundedVec::<RepoId, SUBSCRIBE_DELTA_LIMIT>::decode(reader)?;
+                let since = Timestamp::decode(reader)?;
+
+                Ok(Self::SubscribeDelta(SubscribeDelta {
+                    added,
+                    removed,
+                    since,
+                }))
+            }
             Ok(MessageType::NodeAnnouncement) => {
                 let node = NodeId::decode(reader)?;
                 let signature = Signature::decode(reader)?;
@@ -518,6 +565,20 @@ mod tests {
         assert!(data.len() < wire::Size::MAX as usize);
     }
 
+    #[test]
+    fn test_subscribe_delta_max_size() {
+        let added: [RepoId; SUBSCRIBE_DELTA_LIMIT] = arbitrary::gen(1);
+        let removed: [RepoId; SUBSCRIBE_DELTA_LIMIT] = arbitrary::gen(1);
+        let msg = Message::SubscribeDelta(SubscribeDelta {
+            added: BoundedVec::collect_from(&mut added.into_iter()),
+            removed: BoundedVec::collect_from(&mut removed.into_iter()),
+            since: Timestamp::MAX,
+        });
+        let data = wire::serialize(&msg);
+
+        assert!(data.len() < wire::Size::MAX as usize);
+    }
+
     #[test]
     fn test_pingpong_encode_max_size() {
         let mut buf = Vec::new();
@@ -3,8 +3,9 @@
 //! We use the Noise XK handshake pattern to establish an encrypted stream with a remote peer.
 //! The handshake itself is implemented in the external [`cyphernet`] and [`netservices`] crates.
 use std::collections::hash_map::Entry;
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::{io, net, time};
 
@@ -19,12 +20,14 @@ use netservices::resource::{ListenerEvent, NetAccept, NetTransport, SessionEvent
 use netservices::session::{NoiseSession, ProtocolArtifact, Socks5Session};
 use netservices::{NetConnection, NetReader, NetWriter};
 use radicle::node::device::Device;
-use reactor::{ResourceId, ResourceType, Timestamp};
+use reactor::poller::IoType;
+use reactor::{Io as ReactorIo, Resource, ResourceId, ResourceType, Timestamp, WriteAtomic};
 
 use radicle::collections::RandomMap;
 use radicle::crypto;
+use radicle::identity::RepoId;
 use radicle::node::config::AddressConfig;
-use radicle::node::NodeId;
+use radicle::node::{Features, NodeId};
 use radicle::storage::WriteStorage;
 
 use crate::prelude::Deserializer;
@@ -32,8 +35,9 @@ use crate::service;
 use crate::service::io::Io;
 use crate::service::FETCH_TIMEOUT;
 use crate::service::{session, DisconnectReason, Metrics, Service};
+use crate::wire::bandwidth::Shaper;
 use crate::wire::frame;
-use crate::wire::frame::{Frame, FrameData, StreamId};
+use crate::wire::frame::{Frame, FrameData, StreamId, StreamKind};
 use crate::wire::Encode;
 use crate::worker;
 use crate::worker::{ChannelEvent, ChannelsConfig, FetchRequest, FetchResult, Task, TaskResult};
@@ -53,6 +57,11 @@ pub const DEFAULT_DIAL_TIMEOUT: time::Duration = time::Duration::from_secs(6);
 
 /// Maximum size of a peer inbox, in bytes.
 pub const MAX_INBOX_SIZE: usize = 1024 * 1024 * 2;
+/// Maximum number of bytes waiting to be written to a peer, above which we stop reading
+/// from the peers whose streams we relay to it.
+pub const MAX_RELAY_BACKLOG: usize = 1024 * 1024;
+/// How often to check whether a stalled relay can resume.
+pub const RELAY_STALL_INTERVAL: time::Duration = time::Duration::from_millis(100);
 
 /// Control message used internally between workers, users, and the service.
 #[allow(clippy::large_enum_variant)]
@@ -74,7 +83,105 @@ pub type WireReader = NetReader<Socks5Session<net::TcpStream>>;
 pub type WireWriter<G> = NetWriter<NoiseState<G, Sha256>, Socks5Session<net::TcpStream>>;
 
 /// Reactor action.
-type Action<G> = reactor::Action<NetAccept<WireSession<G>>, NetTransport<WireSession<G>>>;
+type Action<G> = reactor::Action<NetAccept<WireSession<G>>, Transport<G>>;
+
+/// Flow control state of a peer transport, shared with the wire protocol.
+#[derive(Debug, Default)]
+struct Flow {
+    /// Set while the peer is over its download bandwidth limit.
+    throttled: AtomicBool,
+    /// Set while a peer we relay this peer's streams to isn't keeping up.
+    stalled: AtomicBool,
+    /// Number of bytes waiting to be written to the peer.
+    backlog: AtomicUsize,
+}
+
+impl Flow {
+    /// Whether reading from the transport is paused.
+    fn is_paused(&self) -> bool {
+        self.throttled.load(Ordering::Relaxed) || self.stalled.load(Ordering::Relaxed)
+    }
+
+    /// Number of bytes waiting to be written to the peer.
+    fn backlog(&self) -> usize {
+        self.backlog.load(Ordering::Relaxed)
+    }
+}
+
+/// Peer transport. Reading from the transport is paused while the peer is over its
+/// download bandwidth limit, or while its relayed streams can't be written out fast enough.
+pub struct Transport<G: Ecdh> {
+    inner: NetTransport<WireSession<G>>,
+    flow: Arc<Flow>,
+}
+
+impl<G: Ecdh> Transport<G> {
+    fn new(inner: NetTransport<WireSession<G>>) -> Self {
+        Self {
+            inner,
+            flow: Arc::default(),
+        }
+    }
+
+    /// Publish the size of the write buffer to the wire protocol.
+    fn update_backlog(&self) {
+        self.flow
+            .backlog
+            .store(self.inner.write_buf_len(), Ordering::Relaxed);
+    }
+}
+
+impl<G: Ecdh> AsRawFd for Transport<G> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl<G: Ecdh> io::Write for Transport<G> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<G: Ecdh> WriteAtomic for Transport<G> {
+    fn is_ready_to_write(&self) -> bool {
+        self.inner.is_ready_to_write()
+    }
+
+    fn empty_write_buf(&mut self) -> io::Result<bool> {
+        let result = self.inner.empty_write_buf();
+        self.update_backlog();
+        result
+    }
+
+    fn write_or_buf(&mut self, buf: &[u8]) -> io::Result<()> {
+        let result = self.inner.write_or_buf(buf);
+        self.update_backlog();
+        result
+    }
+}
+
+impl<G: Ecdh + Send> Resource for Transport<G> {
+    type Event = SessionEvent<WireSession<G>>;
+
+    fn interests(&self) -> IoType {
+        let mut interests = self.inner.interests();
+        if self.flow.is_paused() {
+            interests.read = false;
+        }
+        interests
+    }
+
+    fn handle_io(&mut self, io: ReactorIo) -> Option<Self::Event> {
+        let event = self.inner.handle_io(io);
+        self.update_backlog();
+        event
+    }
+}
 
 /// A worker stream.
 struct Stream {
@@ -84,6 +191,8 @@ struct Stream {
     sent_bytes: usize,
     /// Data received.
     received_bytes: usize,
+    /// Repository and remote of the fetch we initiated on this stream, if any.
+    fetch: Option<(RepoId, NodeId)>,
 }
 
 impl Stream {
@@ -92,6 +201,7 @@ impl Stream {
             channels,
             sent_bytes: 0,
             received_bytes: 0,
+            fetch: None,
         }
     }
 }
@@ -102,6 +212,8 @@ struct Streams {
     /// Note that the gossip and control streams are not included here as they are always
     /// implied to exist.
     streams: RandomMap<StreamId, Stream>,
+    /// Bytes sent and received on streams closed by the remote, whose worker isn't done yet.
+    closed: RandomMap<StreamId, (usize, usize)>,
     /// Connection direction.
     link: Link,
     /// Sequence number used to compute the next stream id.
@@ -113,6 +225,7 @@ impl Streams {
     fn new(link: Link) -> Self {
         Self {
             streams: RandomMap::default(),
+            closed: RandomMap::default(),
             link,
             seq: 0,
         }
@@ -130,11 +243,7 @@ impl Streams {
 
     /// Open a new stream.
     fn open(&mut self, config: ChannelsConfig) -> (StreamId, worker::Channels) {
-        self.seq += 1;
-
-        let id = StreamId::git(self.link)
-            .nth(self.seq)
-            .expect("Streams::open: too many streams");
+        let id = self.next(StreamId::git(self.link));
         let channels = self
             .register(id, config)
             .expect("Streams::open: stream was already open");
@@ -142,6 +251,22 @@ impl Streams {
         (id, channels)
     }
 
+    /// Open a new relay stream.
+    fn open_relay(&mut self, config: ChannelsConfig) -> (StreamId, worker::Channels) {
+        let id = self.next(StreamId::relay(self.link));
+        let channels = self
+            .register(id, config)
+            .expect("Streams::open_relay: stream was already open");
+
+        (id, channels)
+    }
+
+    /// Get the next stream id, of the same kind as the given one.
+    fn next(&mut self, base: StreamId) -> StreamId {
+        self.seq += 1;
+        base.nth(self.seq).expect("Streams::next: too many streams")
+    }
+
     /// Register an open stream.
     fn register(&mut self, stream: StreamId, config: ChannelsConfig) -> Option<worker::Channels> {
         let (wire, worker) = worker::Channels::pair(config)
@@ -156,11 +281,29 @@ impl Streams {
         }
     }
 
+    /// Find the stream of a fetch we initiated.
+    fn fetch(&self, rid: &RepoId, remote: &NodeId) -> Option<StreamId> {
+        self.streams
+            .iter()
+            .find(|(_, s)| s.fetch == Some((*rid, *remote)))
+            .map(|(id, _)| *id)
+    }
+
     /// Unregister an open stream.
     fn unregister(&mut self, stream: &StreamId) -> Option<Stream> {
         self.streams.remove(stream)
     }
 
+    /// Unregister a stream closed by the remote. Its byte counts are kept until the worker
+    /// is done with the stream.
+    fn close(&mut self, stream: &StreamId) -> Option<Stream> {
+        let s = self.streams.remove(stream)?;
+        self.closed
+            .insert(*stream, (s.sent_bytes, s.received_bytes));
+
+        Some(s)
+    }
+
     /// Close all streams.
     fn shutdown(&mut self) {
         for (sid, stream) in self.streams.drain() {
@@ -170,6 +313,101 @@ impl Streams {
     }
 }
 
+/// One end of a stream spliced between two peers, when acting as a relay.
+#[derive(Debug)]
+struct Circuit {
+    /// Peer on the other end.
+    peer: NodeId,
+    /// Resource ID of the peer on the other end.
+    id: ResourceId,
+    /// Connection direction of the peer on the other end.
+    link: Link,
+    /// Stream ID on the other end.
+    stream: StreamId,
+    /// Bytes relayed towards the other end.
+    bytes: usize,
+}
+
+impl Circuit {
+    /// Control frame to send to the other end.
+    fn control(&self, ctrl: frame::Control) -> Vec<u8> {
+        Frame::<service::Message>::control(self.link, ctrl).to_bytes()
+    }
+}
+
+/// Streams spliced between peers. Each circuit has an entry for both of its ends, keyed by
+/// the peer and stream ID of that end.
+#[derive(Default)]
+struct Circuits(RandomMap<(NodeId, StreamId), Circuit>);
+
+impl Circuits {
+    /// Number of circuits.
+    fn len(&self) -> usize {
+        self.0.len() / 2
+    }
+
+    /// Check whether a stream is part of a circuit.
+    fn contains(&self, nid: &NodeId, stream: &StreamId) -> bool {
+        self.0.contains_key(&(*nid, *stream))
+    }
+
+    /// Get the other end of a circuit.
+    fn get_mut(&mut self, nid: &NodeId, stream: &StreamId) -> Option<&mut Circuit> {
+        self.0.get_mut(&(*nid, *stream))
+    }
+
+    /// Splice two streams together.
+    fn insert(
+        &mut self,
+        (a, a_id, a_link, a_stream): (NodeId, ResourceId, Link, StreamId),
+        (b, b_id, b_link, b_stream): (NodeId, ResourceId, Link, StreamId),
+    ) {
+        self.0.insert(
+            (a, a_stream),
+            Circuit {
+                peer: b,
+                id: b_id,
+                link: b_link,
+                stream: b_stream,
+                bytes: 0,
+            },
+        );
+        self.0.insert(
+            (b, b_stream),
+            Circuit {
+                peer: a,
+                id: a_id,
+                link: a_link,
+                stream: a_stream,
+                bytes: 0,
+            },
+        );
+    }
+
+    /// Remove a circuit, returning its other end.
+    fn remove(&mut self, nid: &NodeId, stream: &StreamId) -> Option<Circuit> {
+        let other = self.0.remove(&(*nid, *stream))?;
+        self.0.remove(&(other.peer, other.stream));
+
+        Some(other)
+    }
+
+    /// Remove all circuits of a peer, returning their other ends.
+    fn remove_peer(&mut self, nid: &NodeId) -> Vec<Circuit> {
+        let streams = self
+            .0
+            .keys()
+            .filter(|(n, _)| n == nid)
+            .map(|(_, stream)| *stream)
+            .collect::<Vec<_>>();
+
+        streams
+            .iter()
+            .filter_map(|stream| self.remove(nid, stream))
+            .collect()
+    }
+}
+
 /// The initial state of an outbound peer before handshake is completed.
 #[derive(Debug)]
 struct Outbound {
@@ -179,6 +417,8 @@ struct Outbound {
     addr: NetAddr<HostName>,
     /// Remote Node ID.
     nid: NodeId,
+    /// Flow control state of the transport.
+    flow: Arc<Flow>,
 }
 
 /// The initial state of an inbound peer before handshake is completed.
@@ -188,6 +428,8 @@ struct Inbound {
     id: Option<ResourceId>,
     /// Remote address.
     addr: NetAddr<HostName>,
+    /// Flow control state of the transport.
+    flow: Arc<Flow>,
 }
 
 /// Peer connection state machine.
@@ -201,6 +443,8 @@ enum Peer {
         nid: NodeId,
         inbox: Deserializer<MAX_INBOX_SIZE, Frame>,
         streams: Streams,
+        /// Flow control state of the transport.
+        flow: Arc<Flow>,
     },
     /// The peer was scheduled for disconnection. Once the transport is handed over
     /// by the reactor, we can consider it disconnected.
@@ -237,13 +481,14 @@ impl Peer {
     }
 
     /// Connected peer.
-    fn connected(nid: NodeId, addr: NetAddr<HostName>, link: Link) -> Self {
+    fn connected(nid: NodeId, addr: NetAddr<HostName>, link: Link, flow: Arc<Flow>) -> Self {
         Self::Connected {
             link,
             addr,
             nid,
             inbox: Deserializer::default(),
             streams: Streams::new(link),
+            flow,
         }
     }
 }
@@ -252,6 +497,10 @@ impl Peer {
 struct Peers(RandomMap<ResourceId, Peer>);
 
 impl Peers {
+    fn get(&self, id: &ResourceId) -> Option<&Peer> {
+        self.0.get(id)
+    }
+
     fn get_mut(&mut self, id: &ResourceId) -> Option<&mut Peer> {
         self.0.get_mut(id)
     }
@@ -326,6 +575,14 @@ pub struct Wire<D, S, G: crypto::signature::Signer<crypto::Signature> + Ecdh> {
     listening: RandomMap<RawFd, net::SocketAddr>,
     /// Peer (established) sessions.
     peers: Peers,
+    /// Streams we are relaying between peers.
+    circuits: Circuits,
+    /// Bandwidth shaper.
+    shaper: Shaper,
+    /// Streams that weren't flushed because their peer is over its upload bandwidth limit.
+    deferred: HashSet<(NodeId, StreamId)>,
+    /// Peers we stopped reading from, and the backed up peer we relay their streams to.
+    stalled: HashMap<ResourceId, ResourceId>,
 }
 
 impl<D, S, G> Wire<D, S, G>
@@ -336,6 +593,10 @@ where
 {
     pub fn new(service: Service<D, S, G>, worker: chan::Sender<Task>, signer: Device<G>) -> Self {
         assert!(service.started().is_some(), "Service must be initialized");
+        let shaper = Shaper::new(
+            service.config().limits.bandwidth.clone(),
+            service.local_time(),
+        );
 
         Self {
             service,
@@ -347,6 +608,10 @@ where
             outbound: RandomMap::default(),
             listening: RandomMap::default(),
             peers: Peers(RandomMap::default()),
+            circuits: Circuits::default(),
+            shaper,
+            deferred: HashSet::new(),
+            stalled: HashMap::new(),
         }
     }
 
@@ -390,6 +655,8 @@ where
                         reason,
                     });
                     self.actions.push_back(Action::UnregisterTransport(id));
+                    self.close_circuits(&nid);
+                    self.unshape(&nid);
 
                     Some((nid, link))
                 }
@@ -397,7 +664,7 @@ where
         }
     }
 
-    fn worker_result(&mut self, task: TaskResult) {
+    fn worker_result(&mut self, mut task: TaskResult) {
         log::debug!(
             target: "wire",
             "Received fetch result from worker for stream {}, remote {}: {:?}",
@@ -405,7 +672,8 @@ where
         );
 
         let nid = task.remote;
-        let Some((fd, peer)) = self.peers.lookup_mut(&nid) else {
+        // For relayed streams, the stream belongs to our connection with the relay.
+        let Some((fd, peer)) = self.peers.lookup_mut(&task.relay.unwrap_or(nid)) else {
             log::warn!(target: "wire", "Peer {nid} not found; ignoring fetch result");
             return;
         };
@@ -414,7 +682,7 @@ where
             // Nb. It's possible that the stream would already be unregistered if we received an
             // early "close" from the remote. Otherwise, we unregister it here and send the "close"
             // ourselves.
-            if let Some(s) = streams.unregister(&task.stream) {
+            let bytes = if let Some(s) = streams.unregister(&task.stream) {
                 log::debug!(
                     target: "wire", "Stream {} of {} closing with {} byte(s) sent and {} byte(s) received",
                     task.stream, task.remote, s.sent_bytes, s.received_bytes
@@ -426,6 +694,33 @@ where
                     },
                 );
                 self.actions.push_back(Action::Send(fd, frame.to_bytes()));
+
+                Some((s.sent_bytes, s.received_bytes))
+            } else {
+                streams.closed.remove(&task.stream)
+            };
+
+            if let Some((sent, received)) = bytes {
+                if let FetchResult::Initiator {
+                    result: Ok(result), ..
+                } = &mut task.result
+                {
+                    result.bytes = received as u64;
+                }
+                let rid = match &task.result {
+                    FetchResult::Initiator { rid, .. } => Some(*rid),
+                    FetchResult::Responder { rid, .. } => *rid,
+                };
+                if let Some(rid) = rid {
+                    let traffic = self
+                        .metrics
+                        .peer(task.relay.unwrap_or(nid))
+                        .repos
+                        .entry(rid)
+                        .or_default();
+                    traffic.sent += sent as u64;
+                    traffic.received += received as u64;
+                }
             }
         } else {
             // If the peer disconnected, we'll get here, but we still want to let the service know
@@ -443,6 +738,10 @@ where
                 if let Some(rid) = rid {
                     if let Some(err) = result.err() {
                         log::info!(target: "wire", "Peer {nid} failed to fetch {rid} from us: {err}");
+
+                        if err.is_not_found() {
+                            self.service.upload_not_found(rid, nid);
+                        }
                     } else {
                         log::info!(target: "wire", "Peer {nid} fetched {rid} from us successfully");
                     }
@@ -452,6 +751,19 @@ where
     }
 
     fn flush(&mut self, remote: NodeId, stream: StreamId) {
+        let now = self.service.local_time();
+        let shaping = self.shaper.is_enabled();
+
+        if shaping {
+            if let Some(delay) = self.shaper.upload_delay(remote, now) {
+                // Data stays in the stream channels until the peer is within its limits, which
+                // in turn blocks the worker writing to them.
+                if self.deferred.insert((remote, stream)) {
+                    self.actions.push_back(Action::SetTimer(delay.into()));
+                }
+                return;
+            }
+        }
         let Some((fd, peer)) = self.peers.lookup_mut(&remote) else {
             log::warn!(target: "wire", "Peer {remote} is not known; ignoring flush");
             return;
@@ -471,6 +783,10 @@ where
                 ChannelEvent::Data(data) => {
                     metrics.sent_git_bytes += data.len();
                     metrics.sent_bytes += data.len();
+                    s.sent_bytes += data.len();
+                    if shaping {
+                        self.shaper.sent(remote, data.len(), now);
+                    }
                     Frame::<service::Message>::git(stream, data)
                 }
                 ChannelEvent::Close => Frame::control(*link, frame::Control::Close { stream }),
@@ -478,6 +794,164 @@ where
             };
             self.actions
                 .push_back(reactor::Action::Send(fd, frame.to_bytes()));
+
+            if shaping {
+                if let Some(delay) = self.shaper.upload_delay(remote, now) {
+                    if self.deferred.insert((remote, stream)) {
+                        self.actions.push_back(Action::SetTimer(delay.into()));
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Resume reading from peers that are back within their download limits, and flush
+    /// streams of peers that are back within their upload limits.
+    fn shape(&mut self) {
+        if !self.shaper.is_enabled() {
+            return;
+        }
+        let now = self.service.local_time();
+        let mut delay = None;
+
+        for peer in self.peers.iter() {
+            let Peer::Connected { nid, flow, .. } = peer else {
+                continue;
+            };
+            if !flow.throttled.load(Ordering::Relaxed) {
+                continue;
+            }
+            match self.shaper.download_delay(*nid, now) {
+                Some(d) => delay = Some(delay.map_or(d, |m: localtime::LocalDuration| m.min(d))),
+                None => {
+                    log::trace!(target: "wire", "Resuming reads from {nid}");
+                    flow.throttled.store(false, Ordering::Relaxed);
+                }
+            }
+        }
+        if let Some(delay) = delay {
+            self.actions.push_back(Action::SetTimer(delay.into()));
+        }
+        for (remote, stream) in std::mem::take(&mut self.deferred) {
+            self.flush(remote, stream);
+        }
+    }
+
+    /// Forget the bandwidth usage of a disconnected peer.
+    fn unshape(&mut self, nid: &NodeId) {
+        self.shaper.remove(nid);
+        self.deferred.retain(|(remote, _)| remote != nid);
+    }
+
+    /// Stop reading from a peer if any of the peers we relayed its data to has too much data
+    /// waiting to be written. Reading resumes once that peer catches up.
+    fn stall(&mut self, id: ResourceId, sinks: &[ResourceId]) {
+        let Some(Peer::Connected { nid, flow, .. }) = self.peers.get(&id) else {
+            return;
+        };
+        let Some(sink) = sinks.iter().find(|sink| {
+            matches!(
+                self.peers.get(sink),
+                Some(Peer::Connected { flow, .. }) if flow.backlog() > MAX_RELAY_BACKLOG
+            )
+        }) else {
+            return;
+        };
+        log::trace!(target: "wire", "Pausing reads from {nid}: relayed streams are backed up");
+
+        flow.stalled.store(true, Ordering::Relaxed);
+        self.stalled.insert(id, *sink);
+        self.actions
+            .push_back(Action::SetTimer(RELAY_STALL_INTERVAL));
+    }
+
+    /// Resume reading from peers whose relayed streams have caught up.
+    fn unstall(&mut self) {
+        let peers = &self.peers;
+
+        self.stalled.retain(|source, sink| {
+            if let Some(Peer::Connected { flow, .. }) = peers.get(sink) {
+                if flow.backlog() > MAX_RELAY_BACKLOG {
+                    return true;
+                }
+            }
+            if let Some(Peer::Connected { nid, flow, .. }) = peers.get(source) {
+                log::trace!(target: "wire", "Resuming reads from {nid}");
+                flow.stalled.store(false, Ordering::Relaxed);
+            }
+            false
+        });
+        if !self.stalled.is_empty() {
+            self.actions
+                .push_back(Action::SetTimer(RELAY_STALL_INTERVAL));
+        }
+    }
+
+    /// Splice a stream from a peer to another peer, if we can act as a relay between them.
+    /// Otherwise, the stream is closed.
+    fn relay(&mut self, id: ResourceId, nid: NodeId, link: Link, stream: StreamId, to: NodeId) {
+        let config = self.service.config();
+        let refusal = if !config.is_stream_relay() {
+            Some("we are not a relay")
+        } else if self.circuits.len() >= config.limits.relay.circuits {
+            Some("relay capacity reached")
+        } else if stream.kind() != Ok(StreamKind::Relay) || self.circuits.contains(&nid, &stream) {
+            Some("invalid stream")
+        } else if to == nid || !self.service.supports(&to, Features::RELAYED) {
+            Some("peer does not accept relayed streams")
+        } else {
+            None
+        };
+        let target = match (refusal, self.peers.lookup_mut(&to)) {
+            (None, Some((to_id, Peer::Connected { link, streams, .. }))) => {
+                Ok((to_id, *link, streams.next(StreamId::relay(*link))))
+            }
+            (None, _) => Err("peer is not connected"),
+            (Some(reason), _) => Err(reason),
+        };
+
+        match target {
+            Ok((to_id, to_link, to_stream)) => {
+                log::debug!(target: "wire", "Relaying stream {stream} of {nid} to stream {to_stream} of {to}");
+
+                self.circuits
+                    .insert((nid, id, link, stream), (to, to_id, to_link, to_stream));
+                self.actions.push_back(Action::Send(
+                    to_id,
+                    Frame::<service::Message>::control(
+                        to_link,
+                        frame::Control::Relayed {
+                            stream: to_stream,
+                            from: nid,
+                        },
+                    )
+                    .to_bytes(),
+                ));
+            }
+            Err(reason) => {
+                log::debug!(target: "wire", "Refusing to relay stream {stream} of {nid} to {to}: {reason}");
+
+                self.actions.push_back(Action::Send(
+                    id,
+                    Frame::<service::Message>::control(link, frame::Control::Close { stream })
+                        .to_bytes(),
+                ));
+            }
+        }
+    }
+
+    /// Close all relayed streams of a peer, and let the peers on the other ends know.
+    fn close_circuits(&mut self, nid: &NodeId) {
+        for circuit in self.circuits.remove_peer(nid) {
+            log::debug!(target: "wire", "Closing relayed stream {} of {}", circuit.stream, circuit.peer);
+
+            self.actions.push_back(Action::Send(
+                circuit.id,
+                circuit.control(frame::Control::Close {
+                    stream: circuit.stream,
+                }),
+            ));
         }
     }
 
@@ -504,7 +978,7 @@ where
     G: crypto::signature::Signer<crypto::Signature> + Ecdh<Pk = NodeId> + Clone + Send,
 {
     type Listener = NetAccept<WireSession<G>>;
-    type Transport = NetTransport<WireSession<G>>;
+    type Transport = Transport<G>;
     type Command = Control;
 
     fn tick(&mut self, time: Timestamp) {
@@ -528,6 +1002,8 @@ where
 
     fn handle_timer(&mut self) {
         self.service.wake();
+        self.shape();
+        self.unstall();
     }
 
     fn handle_listener_event(
@@ -574,7 +1050,7 @@ where
                     }
                 };
                 let transport = match NetTransport::with_session(session, Link::Inbound) {
-                    Ok(transport) => transport,
+                    Ok(transport) => Transport::new(transport),
                     Err(err) => {
                         log::error!(target: "wire", "Failed to create transport for accepted connection: {err}");
                         return;
@@ -587,6 +1063,7 @@ where
                     Inbound {
                         id: None,
                         addr: remote.into(),
+                        flow: transport.flow.clone(),
                     },
                 );
                 self.actions
@@ -636,12 +1113,12 @@ where
 
                     return;
                 }
-                let (addr, link) = if let Some(peer) = self.inbound.remove(&fd) {
+                let (addr, link, flow) = if let Some(peer) = self.inbound.remove(&fd) {
                     self.metrics.peer(nid).inbound_connection_attempts += 1;
-                    (peer.addr, Link::Inbound)
+                    (peer.addr, Link::Inbound, peer.flow)
                 } else if let Some(peer) = self.outbound.remove(&fd) {
                     assert_eq!(nid, peer.nid);
-                    (peer.addr, Link::Outbound)
+                    (peer.addr, Link::Outbound, peer.flow)
                 } else {
                     log::error!(target: "wire", "Session for {nid} (id={id}) not found");
                     return;
@@ -734,21 +1211,40 @@ where
                 }
                 if !disconnect.contains(&id) {
                     self.peers
-                        .insert(id, Peer::connected(nid, addr.clone(), link));
+                        .insert(id, Peer::connected(nid, addr.clone(), link, flow));
                     self.service.connected(nid, addr.into(), link);
                 }
             }
             SessionEvent::Data(data) => {
+                // Streams to relay, once we're done reading from this peer.
+                let mut relays = Vec::new();
+                // Peers we relayed data to.
+                let mut sinks = Vec::new();
+
                 if let Some(Peer::Connected {
                     nid,
                     inbox,
                     streams,
+                    link,
+                    flow,
                     ..
                 }) = self.peers.get_mut(&id)
                 {
+                    let relay_limit = self.service.config().limits.relay.bytes;
                     let metrics = self.metrics.peer(*nid);
                     metrics.received_bytes += data.len();
 
+                    if self.shaper.is_enabled() {
+                        let now = self.service.local_time();
+
+                        self.shaper.received(*nid, data.len(), now);
+                        if let Some(delay) = self.shaper.download_delay(*nid, now) {
+                            log::trace!(target: "wire", "Pausing reads from {nid} for {delay}");
+                            flow.throttled.store(true, Ordering::Relaxed);
+                            self.actions.push_back(Action::SetTimer(delay.into()));
+                        }
+                    }
+
                     if inbox.input(&data).is_err() {
                         log::error!(target: "wire", "Maximum inbox size ({MAX_INBOX_SIZE}) reached for peer {nid}");
                         log::error!(target: "wire", "Unable to process messages fast enough for peer {nid}; disconnecting..");
@@ -783,6 +1279,7 @@ where
                                     },
                                     stream,
                                     channels,
+                                    relay: None,
                                 };
                                 if let Err(e) = self.worker.try_send(task) {
                                     log::error!(
@@ -791,11 +1288,63 @@ where
                                     );
                                 }
                             }
+                            Ok(Some(Frame {
+                                data: FrameData::Control(frame::Control::Relay { stream, to }),
+                                ..
+                            })) => {
+                                log::debug!(target: "wire", "Received `relay` command for stream {stream} from {nid} to {to}");
+                                relays.push((*nid, *link, stream, to));
+                            }
+                            Ok(Some(Frame {
+                                data: FrameData::Control(frame::Control::Relayed { stream, from }),
+                                ..
+                            })) => {
+                                log::debug!(target: "wire", "Received relayed stream {stream} from {from} via {nid}");
+                                metrics.streams_opened += 1;
+                                metrics.received_fetch_requests += 1;
+                                let reader_limit = self.service.config().limits.fetch_pack_receive;
+                                let Some(channels) = (stream.kind() == Ok(StreamKind::Relay))
+                                    .then(|| {
+                                        streams.register(
+                                            stream,
+                                            ChannelsConfig::new(FETCH_TIMEOUT)
+                                                .with_reader_limit(reader_limit),
+                                        )
+                                    })
+                                    .flatten()
+                                else {
+                                    log::warn!(target: "wire", "Peer attempted to open invalid or already-open relay stream {stream}");
+                                    continue;
+                                };
+
+                                let task = Task {
+                                    fetch: FetchRequest::Responder {
+                                        remote: from,
+                                        emitter: self.service.emitter(),
+                                    },
+                                    stream,
+                                    channels,
+                                    relay: Some(*nid),
+                                };
+                                if let Err(e) = self.worker.try_send(task) {
+                                    log::error!(
+                                        target: "wire",
+                                        "Worker pool failed to accept incoming relayed fetch request: {e}"
+                                    );
+                                }
+                            }
                             Ok(Some(Frame {
                                 data: FrameData::Control(frame::Control::Eof { stream }),
                                 ..
                             })) => {
-                                if let Some(s) = streams.get(&stream) {
+                                if let Some(circuit) = self.circuits.get_mut(nid, &stream) {
+                                    self.actions.push_back(Action::Send(
+                                        circuit.id,
+                                        circuit.control(frame::Control::Eof {
+                                            stream: circuit.stream,
+                                        }),
+                                    ));
+                                } else if let Some(s) = streams.get(&stream) {
                                     log::debug!(target: "wire", "Received `end-of-file` on stream {stream} from {nid}");
 
                                     if s.channels.send(ChannelEvent::Eof).is_err() {
@@ -811,7 +1360,14 @@ where
                             })) => {
                                 log::debug!(target: "wire", "Received `close` command for stream {stream} from {nid}");
 
-                                if let Some(s) = streams.unregister(&stream) {
+                                if let Some(circuit) = self.circuits.remove(nid, &stream) {
+                                    self.actions.push_back(Action::Send(
+                                        circuit.id,
+                                        circuit.control(frame::Control::Close {
+                                            stream: circuit.stream,
+                                        }),
+                                    ));
+                                } else if let Some(s) = streams.close(&stream) {
                                     log::debug!(
                                         target: "wire",
                                         "Stream {stream} of {nid} closed with {} byte(s) sent and {} byte(s) received",
@@ -832,8 +1388,46 @@ where
                                 data: FrameData::Git(data),
                                 ..
                             })) => {
-                                if let Some(s) = streams.get_mut(&stream) {
+                                if let Some(circuit) = self.circuits.get_mut(nid, &stream) {
+                                    circuit.bytes += data.len();
+
+                                    if relay_limit.exceeded_by(circuit.bytes) {
+                                        log::debug!(target: "wire", "Relay limit reached for stream {stream} of {nid}; closing..");
+
+                                        if let Some(circuit) = self.circuits.remove(nid, &stream) {
+                                            self.actions.push_back(Action::Send(
+                                                circuit.id,
+                                                circuit.control(frame::Control::Close {
+                                                    stream: circuit.stream,
+                                                }),
+                                            ));
+                                        }
+                                        self.actions.push_back(Action::Send(
+                                            id,
+                                            Frame::<service::Message>::control(
+                                                *link,
+                                                frame::Control::Close { stream },
+                                            )
+                                            .to_bytes(),
+                                        ));
+                                    } else {
+                                        if self.shaper.is_enabled() {
+                                            self.shaper.sent(
+                                                circuit.peer,
+                                                data.len(),
+                                                self.service.local_time(),
+                                            );
+                                        }
+                                        self.actions.push_back(Action::Send(
+                                            circuit.id,
+                                            Frame::<service::Message>::git(circuit.stream, data)
+                                                .to_bytes(),
+                                        ));
+                                        sinks.push(circuit.id);
+                                    }
+                                } else if let Some(s) = streams.get_mut(&stream) {
                                     metrics.received_git_bytes += data.len();
+                                    s.received_bytes += data.len();
 
                                     if s.channels.send(ChannelEvent::Data(data)).is_err() {
                                         log::error!(target: "wire", "Worker is disconnected; cannot send data");
@@ -863,6 +1457,10 @@ where
                 } else {
                     log::warn!(target: "wire", "Dropping message from unconnected peer (id={id})");
                 }
+                for (nid, link, stream, to) in relays {
+                    self.relay(id, nid, link, stream, to);
+                }
+                self.stall(id, &sinks);
             }
             SessionEvent::Terminated(err) => {
                 self.disconnect(id, DisconnectReason::Connection(Arc::new(err)));
@@ -878,10 +1476,7 @@ where
         }
     }
 
-    fn handle_error(
-        &mut self,
-        err: reactor::Error<NetAccept<WireSession<G>>, NetTransport<WireSession<G>>>,
-    ) {
+    fn handle_error(&mut self, err: reactor::Error<NetAccept<WireSession<G>>, Transport<G>>) {
         match err {
             reactor::Error::Poll(err) => {
                 // TODO: This should be a fatal error, there's nothing we can do here.
@@ -903,8 +1498,10 @@ where
                 // the peer from the map.
                 match self.peers.remove(&id) {
                     Some(mut peer) => {
-                        if let Peer::Connected { streams, .. } = &mut peer {
+                        if let Peer::Connected { streams, nid, .. } = &mut peer {
                             streams.shutdown();
+                            self.close_circuits(nid);
+                            self.unshape(nid);
                         }
 
                         if let Some(id) = peer.id() {
@@ -1002,6 +1599,11 @@ where
                     }
                     metrics.sent_bytes += data.len();
 
+                    // Gossip is never held back, but counts towards the upload limits.
+                    if self.shaper.is_enabled() {
+                        self.shaper
+                            .sent(node_id, data.len(), self.service.local_time());
+                    }
                     self.actions.push_back(reactor::Action::Send(fd, data));
                 }
                 Io::Connect(node_id, addr) => {
@@ -1025,6 +1627,7 @@ where
                     )
                     .and_then(|session| {
                         NetTransport::<WireSession<G>>::with_session(session, Link::Outbound)
+                            .map(Transport::new)
                     }) {
                         Ok(transport) => {
                             self.outbound.insert(
@@ -1033,6 +1636,7 @@ where
                                     id: None,
                                     nid: node_id,
                                     addr: addr.to_inner(),
+                                    flow: transport.flow.clone(),
                                 },
                             );
                             log::debug!(
@@ -1069,6 +1673,7 @@ where
                 Io::Fetch {
                     rid,
                     remote,
+                    relay,
                     timeout,
                     reader_limit,
                     refs_at,
@@ -1076,7 +1681,7 @@ where
                     log::trace!(target: "wire", "Processing fetch for {rid} from {remote}..");
 
                     let Some((fd, Peer::Connected { link, streams, .. })) =
-                        self.peers.lookup_mut(&remote)
+                        self.peers.lookup_mut(&relay.unwrap_or(remote))
                     else {
                         // Nb. It's possible that a peer is disconnected while an `Io::Fetch`
                         // is in the service's i/o buffer. Since the service may not purge the
@@ -1085,11 +1690,19 @@ where
                         log::error!(target: "wire", "Peer {remote} is not connected: dropping fetch");
                         continue;
                     };
-                    let (stream, channels) =
-                        streams.open(ChannelsConfig::new(timeout).with_reader_limit(reader_limit));
+                    let config = ChannelsConfig::new(timeout).with_reader_limit(reader_limit);
+                    let (stream, channels) = if relay.is_some() {
+                        streams.open_relay(config)
+                    } else {
+                        streams.open(config)
+                    };
 
                     log::debug!(target: "wire", "Opened new stream with id {stream} for {rid} and remote {remote}");
 
+                    if let Some(s) = streams.get_mut(&stream) {
+                        s.fetch = Some((rid, remote));
+                    }
+
                     let link = *link;
                     let task = Task {
                         fetch: FetchRequest::Initiator {
@@ -1099,6 +1712,7 @@ where
                         },
                         stream,
                         channels,
+                        relay,
                     };
 
                     if !self.worker.is_empty() {
@@ -1117,9 +1731,37 @@ where
                     metrics.streams_opened += 1;
                     metrics.sent_fetch_requests += 1;
 
+                    // Relayed streams are opened by asking the relay to splice them to the remote.
+                    let ctrl = if relay.is_some() {
+                        frame::Control::Relay { stream, to: remote }
+                    } else {
+                        frame::Control::Open { stream }
+                    };
+                    self.actions.push_back(Action::Send(
+                        fd,
+                        Frame::<service::Message>::control(link, ctrl).to_bytes(),
+                    ));
+                }
+                Io::CancelFetch { rid, remote, relay } => {
+                    let Some((fd, Peer::Connected { link, streams, .. })) =
+                        self.peers.lookup_mut(&relay.unwrap_or(remote))
+                    else {
+                        log::debug!(target: "wire", "Peer {remote} is not connected: ignoring fetch cancellation");
+                        continue;
+                    };
+                    let Some(stream) = streams.fetch(&rid, &remote) else {
+                        log::debug!(target: "wire", "Fetch of {rid} from {remote} is already done: ignoring cancellation");
+                        continue;
+                    };
+                    if let Some(s) = streams.close(&stream) {
+                        log::debug!(target: "wire", "Closing stream {stream} of cancelled fetch of {rid} from {remote}");
+
+                        // The worker sees the stream reset, and reports the fetch as failed.
+                        s.channels.close().ok();
+                    }
                     self.actions.push_back(Action::Send(
                         fd,
-                        Frame::<service::Message>::control(link, frame::Control::Open { stream })
+                        Frame::<service::Message>::control(*link, frame::Control::Close { stream })
                             .to_bytes(),
                     ));
                 }
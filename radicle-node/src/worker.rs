@@ -5,18 +5,22 @@ mod upload_pack;
 pub mod fetch;
 pub mod garbage;
 
+use std::collections::{BTreeMap, HashMap};
 use std::io;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
 
 use crossbeam_channel as chan;
 
 use radicle::identity::RepoId;
+use radicle::node::config::Mirror;
 use radicle::node::{notifications, Event};
 use radicle::prelude::NodeId;
+use radicle::storage::git::mirror;
 use radicle::storage::refs::RefsAt;
 use radicle::storage::{ReadRepository, ReadStorage};
 use radicle::{cob, crypto, Storage};
-use radicle_fetch::FetchLimit;
+use radicle_fetch::{FetchLimit, WriteLock};
 
 use crate::runtime::{thread, Emitter, Handle};
 use crate::service::policy;
@@ -91,6 +95,15 @@ impl UploadError {
     pub fn is_eof(&self) -> bool {
         matches!(self, UploadError::Io(e) if e.kind() == io::ErrorKind::UnexpectedEof)
     }
+
+    /// Check if the requested repository was not found.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            UploadError::Storage(e) => e.is_not_found(),
+            UploadError::Repository(e) => e.is_not_found(),
+            _ => false,
+        }
+    }
 }
 
 /// Fetch job sent to worker thread.
@@ -147,6 +160,9 @@ pub struct Task {
     pub fetch: FetchRequest,
     pub stream: StreamId,
     pub channels: Channels,
+    /// Relay node the stream is spliced through, if we aren't directly connected to the
+    /// remote.
+    pub relay: Option<NodeId>,
 }
 
 /// Worker response.
@@ -155,6 +171,8 @@ pub struct TaskResult {
     pub remote: NodeId,
     pub result: FetchResult,
     pub stream: StreamId,
+    /// Relay node the stream was spliced through, if any.
+    pub relay: Option<NodeId>,
 }
 
 #[derive(Debug, Clone)]
@@ -166,6 +184,8 @@ pub struct FetchConfig {
     /// Configuration for `git gc` garbage collection. Defaults to `1
     /// hour ago`.
     pub expiry: garbage::Expiry,
+    /// Plain Git remotes to mirror repositories to, per repository.
+    pub mirrors: BTreeMap<RepoId, Mirror>,
 }
 
 /// A worker that replicates git objects.
@@ -179,6 +199,10 @@ struct Worker {
     notifications: notifications::StoreWriter,
     cache: cob::cache::StoreWriter,
     db: radicle::node::Database,
+    /// Queue of repositories to push to their mirror.
+    mirrors: chan::Sender<(RepoId, String)>,
+    /// Locks serializing writes to repositories, shared by all workers.
+    locks: WriteLocks,
 }
 
 impl Worker {
@@ -196,10 +220,23 @@ impl Worker {
             fetch,
             channels,
             stream,
+            relay,
         } = task;
         let remote = fetch.remote();
-        let channels = channels::ChannelsFlush::new(self.handle.clone(), channels, remote, stream);
-        let result = self._process(fetch, stream, channels, self.notifications.clone());
+        // Stream data is flushed to the peer we're connected to, which is the relay, if any.
+        let channels = channels::ChannelsFlush::new(
+            self.handle.clone(),
+            channels,
+            relay.unwrap_or(remote),
+            stream,
+        );
+        let result = self._process(
+            fetch,
+            stream,
+            relay.is_some(),
+            channels,
+            self.notifications.clone(),
+        );
 
         log::trace!(target: "worker", "Sending response back to service..");
 
@@ -209,6 +246,7 @@ impl Worker {
                 remote,
                 stream,
                 result,
+                relay,
             })
             .is_err()
         {
@@ -220,6 +258,7 @@ impl Worker {
         &mut self,
         fetch: FetchRequest,
         stream: StreamId,
+        relayed: bool,
         mut channels: channels::ChannelsFlush,
         notifs: notifications::StoreWriter,
     ) -> FetchResult {
@@ -249,7 +288,7 @@ impl Worker {
                 };
                 log::debug!(target: "worker", "Spawning upload-pack process for {} on stream {stream}..", header.repo);
 
-                if let Err(e) = self.is_authorized(remote, header.repo) {
+                if let Err(e) = self.is_authorized(remote, header.repo, relayed) {
                     return FetchResult::Responder {
                         rid: Some(header.repo),
                         result: Err(e),
@@ -278,7 +317,10 @@ impl Worker {
         }
     }
 
-    fn is_authorized(&self, remote: NodeId, rid: RepoId) -> Result<(), UploadError> {
+    /// Check whether the remote is allowed to fetch the given repository from us. For relayed
+    /// streams, the identity of the remote is only vouched for by the relay, so we only serve
+    /// public repositories.
+    fn is_authorized(&self, remote: NodeId, rid: RepoId, relayed: bool) -> Result<(), UploadError> {
         let policy = self.policies.seed_policy(&rid)?.policy;
         // Check policy first, since if we're blocking then we likely don't have
         // the repository.
@@ -288,7 +330,7 @@ impl Worker {
         let repo = self.storage.repository(rid)?;
         let doc = repo.identity_doc()?;
 
-        if !doc.is_visible_to(&remote.into()) {
+        if !doc.is_visible_to(&remote.into()) || (relayed && !doc.is_public()) {
             Err(UploadError::Unauthorized(remote, rid))
         } else {
             Ok(())
@@ -307,6 +349,7 @@ impl Worker {
             limit,
             local,
             expiry,
+            mirrors,
         } = &self.fetch_config;
         // N.b. if the `rid` is blocked this will return an error, so
         // we won't continue with any further set up of the fetch.
@@ -314,6 +357,7 @@ impl Worker {
         let blocked = radicle_fetch::BlockList::from_config(&self.policies)?;
 
         let mut cache = self.cache.clone();
+        let lock = self.locks.get(rid);
         let handle = fetch::Handle::new(
             rid,
             *local,
@@ -322,6 +366,7 @@ impl Worker {
             blocked,
             channels,
             notifs,
+            lock.clone(),
         )?;
         let result = handle.fetch(
             rid,
@@ -333,6 +378,13 @@ impl Worker {
             refs_at,
         )?;
 
+        if let Some(url) = mirrors.get(&rid).and_then(|m| m.push.as_deref()) {
+            if result.canonical && self.mirrors.send((rid, url.to_owned())).is_err() {
+                log::error!(target: "worker", "Unable to mirror {rid}: mirror channel disconnected");
+            }
+        }
+
+        let _guard = lock.lock();
         if let Err(e) = garbage::collect(&self.storage, rid, *expiry) {
             // N.b. ensure that `git gc` works in debug mode.
             debug_assert!(false, "`git gc` failed: {e}");
@@ -343,6 +395,62 @@ impl Worker {
     }
 }
 
+/// Write locks of repositories, so that fetches of the same repository from different
+/// peers don't race each other's reference updates.
+#[derive(Clone, Default)]
+struct WriteLocks(Arc<Mutex<HashMap<RepoId, WriteLock>>>);
+
+impl WriteLocks {
+    /// Get the write lock of a repository.
+    fn get(&self, rid: RepoId) -> WriteLock {
+        let mut locks = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        // Forget the locks of repositories that aren't being fetched anymore.
+        locks.retain(|_, lock| !lock.is_unused());
+        locks.entry(rid).or_default().clone()
+    }
+}
+
+/// Pushes the canonical refs of repositories to their mirror, so that slow or unresponsive
+/// mirrors don't hold up fetches.
+struct Mirrors {
+    storage: Storage,
+    queue: chan::Receiver<(RepoId, String)>,
+}
+
+impl Mirrors {
+    /// Waits for repositories to mirror. Blocks until all workers are gone.
+    fn run(self) -> Result<(), chan::RecvError> {
+        loop {
+            let (rid, url) = self.queue.recv()?;
+            // Repositories that were fetched several times while we were busy are pushed once.
+            let mut pending = BTreeMap::from([(rid, url)]);
+            pending.extend(self.queue.try_iter());
+
+            for (rid, url) in pending {
+                self.push(rid, &url);
+            }
+        }
+    }
+
+    /// Push the canonical refs of a repository to its mirror. Failures are not fatal.
+    fn push(&self, rid: RepoId, url: &str) {
+        let result = self
+            .storage
+            .repository(rid)
+            .map_err(mirror::Error::from)
+            .and_then(|repo| mirror::push(&repo, url, mirror::DEFAULT_PUSH_TIMEOUT));
+
+        match result {
+            Ok(pushed) => {
+                log::debug!(target: "worker", "Mirrored {} ref(s) of {rid} to {url}", pushed.len());
+            }
+            Err(e) => {
+                log::warn!(target: "worker", "Failed to mirror {rid} to {url}: {e}");
+            }
+        }
+    }
+}
+
 /// A pool of workers. One thread is allocated for each worker.
 pub struct Pool {
     pool: Vec<thread::JoinHandle<Result<(), chan::RecvError>>>,
@@ -359,7 +467,9 @@ impl Pool {
         db: radicle::node::Database,
         config: Config,
     ) -> Result<Self, policy::Error> {
-        let mut pool = Vec::with_capacity(config.capacity);
+        let mut pool = Vec::with_capacity(config.capacity + 1);
+        let (mirrors, queue) = chan::unbounded();
+        let locks = WriteLocks::default();
         for i in 0..config.capacity {
             let policies =
                 policy::Config::new(config.policy, policy::Store::reader(&config.policies_db)?);
@@ -373,11 +483,19 @@ impl Pool {
                 notifications: notifications.clone(),
                 cache: cache.clone(),
                 db: db.clone(),
+                mirrors: mirrors.clone(),
+                locks: locks.clone(),
             };
             let thread = thread::spawn(&nid, format!("worker#{i}"), || worker.run());
 
             pool.push(thread);
         }
+        let mirrors = Mirrors {
+            storage: config.storage,
+            queue,
+        };
+        pool.push(thread::spawn(&nid, "mirrors", || mirrors.run()));
+
         Ok(Self { pool })
     }
 
@@ -16,7 +16,7 @@ use radicle::storage::{
     WriteRepository as _,
 };
 use radicle::{cob, git, node, Storage};
-use radicle_fetch::{Allowed, BlockList, FetchLimit};
+use radicle_fetch::{Allowed, BlockList, FetchLimit, WriteLock};
 
 use super::channels::ChannelsFlush;
 
@@ -28,8 +28,13 @@ pub struct FetchResult {
     pub namespaces: HashSet<PublicKey>,
     /// The fetch was a full clone.
     pub clone: bool,
+    /// Whether the canonical refs were updated.
+    pub canonical: bool,
     /// Identity doc of fetched repo.
     pub doc: DocAt,
+    /// Number of git bytes received from the remote. This is only known to the
+    /// transport, which sets it once the fetch completes.
+    pub bytes: u64,
 }
 
 impl FetchResult {
@@ -38,7 +43,9 @@ impl FetchResult {
             updated: vec![],
             namespaces: HashSet::new(),
             clone: false,
+            canonical: false,
             doc,
+            bytes: 0,
         }
     }
 }
@@ -63,11 +70,13 @@ impl Handle {
         blocked: BlockList,
         channels: ChannelsFlush,
         notifications: node::notifications::StoreWriter,
+        lock: WriteLock,
     ) -> Result<Self, error::Handle> {
         let exists = storage.contains(&rid)?;
         if exists {
             let repo = storage.repository(rid)?;
-            let handle = radicle_fetch::Handle::new(local, repo, follow, blocked, channels)?;
+            let handle = radicle_fetch::Handle::new(local, repo, follow, blocked, channels)?
+                .with_write_lock(lock);
             Ok(Handle::Pull {
                 handle,
                 notifications,
@@ -91,12 +100,14 @@ impl Handle {
     ) -> Result<FetchResult, error::Fetch> {
         use git::canonical::QuorumError::{Diverging, NoCandidates};
 
-        let (result, clone, notifs) = match self {
+        // N.b. the rest of the fetch writes to the repository as well, so we keep holding
+        // the write lock, if it was taken.
+        let (result, clone, notifs, _guard) = match self {
             Self::Clone { mut handle, tmp } => {
                 log::debug!(target: "worker", "{} cloning from {remote}", handle.local());
                 let result = radicle_fetch::clone(&mut handle, limit, remote)?;
                 mv(tmp, storage, &rid)?;
-                (result, true, None)
+                (result, true, None, None)
             }
             Self::Pull {
                 mut handle,
@@ -104,7 +115,8 @@ impl Handle {
             } => {
                 log::debug!(target: "worker", "{} pulling from {remote}", handle.local());
                 let result = radicle_fetch::pull(&mut handle, limit, remote, refs_at)?;
-                (result, false, Some(notifications))
+                let guard = handle.take_write_guard();
+                (result, false, Some(notifications), guard)
             }
         };
 
@@ -139,20 +151,35 @@ impl Handle {
                 // points to a repository that is temporary and gets moved by [`mv`].
                 let repo = storage.repository(rid)?;
                 repo.set_identity_head()?;
-                match repo.set_head() {
+                let canonical = match repo.set_head() {
                     Ok(head) => {
                         if head.is_updated() {
                             log::trace!(target: "worker", "Set HEAD to {}", head.new);
                         }
+                        head.is_updated()
                     }
                     Err(RepositoryError::Quorum(Diverging(e))) => {
-                        log::warn!(target: "worker", "Fetch could not set HEAD: {e}")
+                        log::warn!(target: "worker", "Fetch could not set HEAD: {e}");
+                        false
                     }
                     Err(RepositoryError::Quorum(NoCandidates(e))) => {
-                        log::warn!(target: "worker", "Fetch could not set HEAD: {e}")
+                        log::warn!(target: "worker", "Fetch could not set HEAD: {e}");
+                        false
                     }
                     Err(e) => return Err(e.into()),
-                }
+                };
+                let canonical = match repo.set_canonical_refs() {
+                    Ok(updates) => {
+                        for update in &updates {
+                            log::trace!(target: "worker", "Set canonical reference {}", update.name());
+                        }
+                        canonical || !updates.is_empty()
+                    }
+                    Err(e) => {
+                        log::warn!(target: "worker", "Fetch could not set canonical references: {e}");
+                        canonical
+                    }
+                };
 
                 // Notifications are only posted for pulls, not clones.
                 if let Some(mut store) = notifs {
@@ -172,6 +199,8 @@ impl Handle {
                     namespaces: remotes.into_iter().collect(),
                     doc: repo.identity_doc()?,
                     clone,
+                    canonical,
+                    bytes: 0,
                 })
             }
         }
@@ -276,7 +305,9 @@ where
         log::trace!(target: "node", "Updating cache for {name} in {repo}");
 
         let result = match r {
-            RefUpdate::Updated { new, .. } => db.set(repo, &namespace, &qualified, *new, time),
+            RefUpdate::Updated { new, .. } | RefUpdate::Forced { new, .. } => {
+                db.set(repo, &namespace, &qualified, *new, time)
+            }
             RefUpdate::Created { oid, .. } => db.set(repo, &namespace, &qualified, *oid, time),
             RefUpdate::Deleted { .. } => db.delete(repo, &namespace, &qualified),
             RefUpdate::Skipped { .. } => continue,
@@ -291,8 +322,8 @@ where
     Ok(())
 }
 
-/// Write new `RefUpdate`s that are related a `Patch` or an `Issue`
-/// COB to the COB cache.
+/// Write new `RefUpdate`s that are related a `Patch`, an `Issue` or a
+/// `Milestone` COB to the COB cache.
 fn cache_cobs<S, C>(
     rid: &RepoId,
     refs: &[RefUpdate],
@@ -303,13 +334,17 @@ where
     S: ReadRepository + cob::Store<Namespace = NodeId>,
     C: cob::cache::Update<cob::issue::Issue> + cob::cache::Update<cob::patch::Patch>,
     C: cob::cache::Remove<cob::issue::Issue> + cob::cache::Remove<cob::patch::Patch>,
+    C: cob::cache::Update<cob::milestone::Milestone>
+        + cob::cache::Remove<cob::milestone::Milestone>,
 {
     let mut issues = cob::store::Store::<cob::issue::Issue, _>::open(storage)?;
     let mut patches = cob::store::Store::<cob::patch::Patch, _>::open(storage)?;
+    let mut milestones = cob::store::Store::<cob::milestone::Milestone, _>::open(storage)?;
 
     for update in refs {
         match update {
             RefUpdate::Updated { name, .. }
+            | RefUpdate::Forced { name, .. }
             | RefUpdate::Created { name, .. }
             | RefUpdate::Deleted { name, .. } => match name.to_namespaced() {
                 Some(name) => {
@@ -320,6 +355,8 @@ where
                         update_or_remove(&mut issues, cache, rid, identifier)?;
                     } else if identifier.is_patch() {
                         update_or_remove(&mut patches, cache, rid, identifier)?;
+                    } else if identifier.is_milestone() {
+                        update_or_remove(&mut milestones, cache, rid, identifier)?;
                     } else {
                         // Unknown COB, don't cache.
                         continue;
@@ -82,8 +82,12 @@ pub struct Options {
     draft: bool,
     /// Patch base to use, when opening or updating a patch.
     base: Option<Rev>,
+    /// Patch target branch, when opening or updating a patch.
+    target: Option<git::RefString>,
     /// Patch message.
     message: cli::patch::Message,
+    /// Patch template, when opening a patch.
+    template: Option<String>,
 }
 
 /// Run the radicle remote helper using the given profile.
@@ -226,6 +230,12 @@ fn push_option(args: &[&str], opts: &mut Options) -> Result<(), Error> {
                             cli::args::rev(&val.into()).map_err(|e| Error::Base(e.into()))?;
                         opts.base = Some(base);
                     }
+                    "patch.target" => {
+                        opts.target = Some(git::RefString::try_from(val)?);
+                    }
+                    "patch.template" => {
+                        opts.template = Some(val.to_owned());
+                    }
                     other => {
                         return Err(Error::UnsupportedPushOption(other.to_owned()));
                     }
@@ -277,6 +287,24 @@ pub(crate) fn patches<'a, R: ReadRepository + cob::Store<Namespace = NodeId>>(
     }
 }
 
+/// Get the mutable issue store.
+pub(crate) fn issues_mut<'a>(
+    profile: &Profile,
+    repo: &'a storage::git::Repository,
+) -> Result<
+    cob::issue::Cache<cob::issue::Issues<'a, storage::git::Repository>, cob::cache::StoreWriter>,
+    push::Error,
+> {
+    match profile.issues_mut(repo) {
+        Ok(issues) => Ok(issues),
+        Err(err @ profile::Error::CobsCache(cob::cache::Error::OutOfDate)) => {
+            hint(cli::cob::MIGRATION_HINT);
+            Err(err.into())
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
 /// Get the mutable patch store.
 pub(crate) fn patches_mut<'a>(
     profile: &Profile,
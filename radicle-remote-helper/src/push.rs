@@ -1,5 +1,5 @@
 #![allow(clippy::too_many_arguments)]
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
@@ -9,14 +9,17 @@ use radicle::node::device::Device;
 use thiserror::Error;
 
 use radicle::cob;
+use radicle::cob::issue;
+use radicle::cob::issue::cache::Issues as _;
 use radicle::cob::object::ParseObjectId;
 use radicle::cob::patch;
 use radicle::cob::patch::cache::Patches as _;
+use radicle::cob::person;
 use radicle::crypto;
 use radicle::explorer::ExplorerResource;
 use radicle::git::canonical;
 use radicle::git::canonical::Canonical;
-use radicle::identity::Did;
+use radicle::identity::{Did, Doc};
 use radicle::node;
 use radicle::node::{Handle, NodeId};
 use radicle::storage;
@@ -89,6 +92,12 @@ pub enum Error {
     /// Error from COB patch cache.
     #[error(transparent)]
     PatchCache(#[from] patch::cache::Error),
+    /// Issue COB error.
+    #[error(transparent)]
+    Issue(#[from] radicle::cob::issue::Error),
+    /// Error from COB issue cache.
+    #[error(transparent)]
+    IssueCache(#[from] issue::cache::Error),
     /// Patch edit message error.
     #[error(transparent)]
     PatchEdit(#[from] term::patch::Error),
@@ -101,6 +110,9 @@ pub enum Error {
     /// Revision not found in store.
     #[error("revision `{0}` not found")]
     RevisionNotFound(patch::RevisionId),
+    /// Patch template not found in identity document.
+    #[error("patch template `{0}` not found")]
+    TemplateNotFound(String),
     /// Patch is empty.
     #[error("patch commits are already included in the base branch")]
     EmptyPatch,
@@ -264,7 +276,10 @@ pub fn run(
                         let identity = stored.identity()?;
                         let project = identity.project()?;
                         let canonical_ref = git::refs::branch(project.default_branch());
-                        let me = Did::from(nid);
+                        // If we push from the device of a person delegate, we vote as that person.
+                        let me = identity
+                            .delegate_of(&nid, stored)?
+                            .unwrap_or_else(|| Did::from(nid));
 
                         // If we're trying to update the canonical head, make sure
                         // we don't diverge from the current head. This only applies
@@ -276,11 +291,8 @@ pub fn run(
                             let head = working.find_reference(src.as_str())?;
                             let head = head.peel_to_commit()?.id();
 
-                            let mut canonical = Canonical::default_branch(
-                                stored,
-                                &project,
-                                identity.delegates().as_ref(),
-                            )?;
+                            let mut canonical =
+                                Canonical::default_branch(stored, &project, &identity)?;
                             let converges = canonical::converges(
                                 canonical
                                     .tips()
@@ -332,7 +344,9 @@ pub fn run(
                                 Err(e) => return Err(e.into()),
                             };
                         }
-                        push(src, &dst, *force, &nid, &working, stored, patches, &signer)
+                        push(
+                            src, &dst, *force, &nid, &working, stored, patches, &signer, profile,
+                        )
                     }
                 }
             }
@@ -354,6 +368,7 @@ pub fn run(
         let _ = stored.sign_refs(&signer)?;
 
         // N.b. if an error occurs then there may be no quorum
+        let mut canonical = false;
         if let Ok(head) = stored.set_head() {
             if head.is_updated() {
                 eprintln!(
@@ -361,8 +376,25 @@ pub fn run(
                     term::format::positive("✓"),
                     term::format::secondary(head.new),
                 );
+                canonical = true;
             }
         };
+        if let Ok(updates) = stored.set_canonical_refs() {
+            for update in &updates {
+                if let Some(new) = update.new() {
+                    eprintln!(
+                        "{} Canonical reference {} updated to {}",
+                        term::format::positive("✓"),
+                        term::format::tertiary(update.name()),
+                        term::format::secondary(new),
+                    );
+                }
+            }
+            canonical |= !updates.is_empty();
+        }
+        if canonical {
+            mirror(stored, profile);
+        }
 
         if !opts.no_sync {
             if profile.policies()?.is_seeding(&stored.id)? {
@@ -389,6 +421,36 @@ pub fn run(
     Ok(())
 }
 
+/// Push the canonical refs to the repository's mirror, if one is configured.
+/// Failures are reported, but are not fatal.
+fn mirror(stored: &storage::git::Repository, profile: &Profile) {
+    let Some(url) = profile
+        .config
+        .node
+        .mirrors
+        .get(&stored.id)
+        .and_then(|m| m.push.as_deref())
+    else {
+        return;
+    };
+    match storage::git::mirror::push(stored, url, storage::git::mirror::DEFAULT_PUSH_TIMEOUT) {
+        Ok(pushed) if pushed.is_empty() => {}
+        Ok(_) => {
+            eprintln!(
+                "{} Canonical refs mirrored to {}",
+                term::format::positive("✓"),
+                term::format::tertiary(url),
+            );
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Failed to mirror canonical refs to {url}: {e}",
+                term::format::negative("✗"),
+            );
+        }
+    }
+}
+
 /// Open a new patch.
 fn patch_open<G>(
     src: &git::RefStr,
@@ -420,42 +482,76 @@ where
     // not fail, since the reference will already exist with the correct OID.
     push_ref(src, &dst, false, working, stored.raw())?;
 
-    let (_, target) = stored.canonical_head()?;
+    let target = if let Some(branch) = opts.target {
+        let doc = stored.identity_doc()?;
+        patch::MergeTarget::branch(branch, &doc)?
+    } else {
+        patch::MergeTarget::default()
+    };
+    let target_head = target.canonical_head(stored)?;
     let head = commit.id().into();
+    let dependencies = patch_dependencies(head, target_head, &target, None, stored, &patches)?;
     let base = if let Some(base) = opts.base {
         base.resolve(working)?
     } else {
-        stored.merge_base(&target, &head)?
+        stored.merge_base(&target_head, &head)?
     };
     if base == head {
         return Err(Error::EmptyPatch);
     }
-    let (title, description) =
-        term::patch::get_create_message(opts.message, &stored.backend, &base, &head)?;
+    let doc = stored.identity_doc()?;
+    let templates = doc.templates()?.unwrap_or_default();
+    let owners = doc.code_owners()?.unwrap_or_default();
+    let template = match (templates.patch(opts.template.as_deref()), opts.template) {
+        (Some(template), Some(_)) => Some(template),
+        // The default template is only used when the patch message is written in the editor.
+        (Some(template), None) if opts.message == term::patch::Message::Edit => Some(template),
+        (Some(_), None) => None,
+        (None, Some(name)) => return Err(Error::TemplateNotFound(name)),
+        (None, None) => None,
+    };
+    let (title, description) = term::patch::get_create_message(
+        opts.message,
+        &stored.backend,
+        &base,
+        &head,
+        template.map(|t| t.body.as_str()),
+    )?;
+    let labels = template.map(|t| t.labels.as_slice()).unwrap_or_default();
 
     let patch = if opts.draft {
         patches.draft(
             &title,
             &description,
-            patch::MergeTarget::default(),
+            target,
             base,
             commit.id(),
-            &[],
+            labels,
             signer,
         )
     } else {
         patches.create(
             &title,
             &description,
-            patch::MergeTarget::default(),
+            target,
             base,
             commit.id(),
-            &[],
+            labels,
             signer,
         )
     };
     let result = match patch {
-        Ok(patch) => {
+        Ok(mut patch) => {
+            if !dependencies.is_empty() {
+                patch.depend(dependencies.keys().copied(), signer)?;
+            }
+            // Suggest the owners of the changed paths as reviewers, except for ourselves.
+            let mut reviewers = patch.latest().1.owners(stored.raw(), &owners)?;
+            reviewers.remove(&Did::from(*signer.public_key()));
+
+            if !reviewers.is_empty() {
+                patch.suggest(reviewers.iter().copied(), signer)?;
+            }
             let action = if patch.is_draft() {
                 "drafted"
             } else {
@@ -468,6 +564,8 @@ where
                 term::format::positive("✓"),
                 term::format::tertiary(patch),
             );
+            print_dependencies(&dependencies);
+            print_reviewers(&reviewers);
 
             // Create long-lived patch head reference, now that we know the Patch ID.
             //
@@ -557,26 +655,110 @@ where
         &commit.id().into(),
     )?;
 
-    let (_, target) = stored.canonical_head()?;
+    let target = if let Some(branch) = opts.target {
+        let doc = stored.identity_doc()?;
+        patch::MergeTarget::branch(branch, &doc)?
+    } else {
+        patch.target().clone()
+    };
+    let target_head = target.canonical_head(stored)?;
     let head: git::Oid = commit.id().into();
+    let dependencies = patch_dependencies(
+        head,
+        target_head,
+        &target,
+        Some(&patch_id),
+        stored,
+        &patches,
+    )?;
     let base = if let Some(base) = opts.base {
         base.resolve(working)?
     } else {
-        stored.merge_base(&target, &head)?
+        stored.merge_base(&target_head, &head)?
     };
 
+    // The dependencies of the patch are replaced by the patches it is now stacked on. Merged
+    // or archived dependencies are kept, since they are never detected as ancestors, eg. when
+    // the patch was rebased after its dependency was merged.
+    let mut kept = BTreeSet::new();
+    for id in patch.dependencies() {
+        if dependencies.contains_key(id) {
+            continue;
+        }
+        let closed = match patches.get(id) {
+            Ok(Some(dep)) => !(dep.is_open() || dep.is_draft()),
+            Ok(None) | Err(_) => true,
+        };
+        if closed {
+            kept.insert(*id);
+        }
+    }
+    let added = dependencies
+        .iter()
+        .filter(|(id, _)| !patch.depends_on(id))
+        .map(|(id, tip)| (*id, *tip))
+        .collect::<BTreeMap<_, _>>();
+    let updated = dependencies
+        .keys()
+        .copied()
+        .chain(kept)
+        .collect::<BTreeSet<_>>();
+
     let mut patch_mut = patch::PatchMut::new(patch_id, patch, &mut patches);
+    if &target != patch_mut.target() {
+        let title = patch_mut.title().to_owned();
+        patch_mut.edit(title, target, signer)?;
+    }
+    if !updated.iter().eq(patch_mut.dependencies()) {
+        let removed = patch_mut
+            .dependencies()
+            .filter(|id| !updated.contains(id))
+            .copied()
+            .collect::<Vec<_>>();
+
+        patch_mut.depend(updated, signer)?;
+        print_dependencies(&added);
+
+        for id in removed {
+            eprintln!(
+                "{} Patch no longer depends on {}",
+                term::format::positive("✓"),
+                term::format::tertiary(term::format::cob(&id)),
+            );
+        }
+    }
     let revision = patch_mut.update(message, base, head, signer)?;
     let Some(revision) = patch_mut.revision(&revision).cloned() else {
         return Err(Error::RevisionNotFound(revision));
     };
 
+    // Suggested reviewers are recomputed for every revision, since the changed paths,
+    // and therefore their owners, may have changed. Only the patch author can suggest.
+    let me = Did::from(*signer.public_key());
+    let mut requested = BTreeSet::new();
+
+    if patch_mut.author().id() == &me {
+        let owners = stored.identity_doc()?.code_owners()?.unwrap_or_default();
+        let mut reviewers = revision.owners(stored.raw(), &owners)?;
+        reviewers.remove(&me);
+
+        if reviewers.iter().ne(patch_mut.reviewers()) {
+            requested = reviewers
+                .iter()
+                .filter(|did| !patch_mut.reviewers().any(|r| r == *did))
+                .copied()
+                .collect();
+            patch_mut.suggest(reviewers, signer)?;
+        }
+    }
+
     eprintln!(
         "{} Patch {} updated to revision {}",
         term::format::positive("✓"),
         term::format::tertiary(term::format::cob(&patch_id)),
         term::format::dim(revision.id())
     );
+    print_reviewers(&requested);
 
     // In this case, the patch was already merged via git, and pushed to storage.
     // To handle this situation, we simply update the patch state to "merged".
@@ -599,6 +781,74 @@ where
     Ok(Some(ExplorerResource::Patch { id: patch_id }))
 }
 
+/// Find the open patches that the given `head` is stacked on, ie. patches with the same target
+/// whose heads are ancestors of `head`, but are not yet part of the target branch.
+///
+/// If a patch is stacked on a chain of patches, only the top of the chain is returned.
+fn patch_dependencies(
+    head: git::Oid,
+    target_head: git::Oid,
+    target: &patch::MergeTarget,
+    exclude: Option<&patch::PatchId>,
+    stored: &storage::git::Repository,
+    patches: &patch::Cache<patch::Patches<'_, storage::git::Repository>, cob::cache::StoreWriter>,
+) -> Result<BTreeMap<patch::PatchId, git::Oid>, Error> {
+    let mut candidates = BTreeMap::new();
+
+    for result in patches.opened()?.chain(patches.drafted()?) {
+        // Skip patches that failed to load.
+        let Ok((id, patch)) = result else {
+            continue;
+        };
+        let tip = *patch.head();
+
+        if Some(&id) == exclude || patch.target() != target || tip == head {
+            continue;
+        }
+        if stored.is_ancestor_of(tip, head)? && !stored.is_ancestor_of(tip, target_head)? {
+            candidates.insert(id, tip);
+        }
+    }
+
+    // Only keep patches that aren't themselves included in another candidate.
+    let mut dependencies = BTreeMap::new();
+    for (id, tip) in &candidates {
+        let mut included = false;
+        for other in candidates.values() {
+            if other != tip && stored.is_ancestor_of(*tip, *other)? {
+                included = true;
+                break;
+            }
+        }
+        if !included {
+            dependencies.insert(*id, *tip);
+        }
+    }
+    Ok(dependencies)
+}
+
+/// Let the user know which patches a patch is stacked on.
+fn print_dependencies(dependencies: &BTreeMap<patch::PatchId, git::Oid>) {
+    for id in dependencies.keys() {
+        eprintln!(
+            "{} Patch depends on {}",
+            term::format::positive("✓"),
+            term::format::tertiary(term::format::cob(id)),
+        );
+    }
+}
+
+/// Let the user know who was suggested to review a patch.
+fn print_reviewers(reviewers: &BTreeSet<Did>) {
+    for did in reviewers {
+        eprintln!(
+            "{} Review requested from {}",
+            term::format::positive("✓"),
+            term::format::tertiary(did),
+        );
+    }
+}
+
 fn push<G>(
     src: &git::RefStr,
     dst: &git::Qualified,
@@ -611,6 +861,7 @@ fn push<G>(
         cob::cache::StoreWriter,
     >,
     signer: &Device<G>,
+    profile: &Profile,
 ) -> Result<Option<ExplorerResource>, Error>
 where
     G: crypto::signature::Signer<crypto::Signature>,
@@ -634,33 +885,68 @@ where
     push_ref(src, &dst, force, working, stored.raw())?;
 
     if let Some(old) = old {
-        let proj = stored.project()?;
-        let master = &*git::Qualified::from(git::lit::refs_heads(proj.default_branch()));
-
-        // If we're pushing to the project's default branch, we want to see if any patches got
+        // If we're pushing to a branch, we want to see if any patches targeting that branch got
         // merged or reverted, and if so, update the patch COB.
-        if &*dst.strip_namespace() == master {
+        if let Some(target) = merge_target(&dst.strip_namespace(), stored)? {
             let old = old.peel_to_commit()?.id();
-            // Only delegates affect the merge state of the COB.
-            if stored.delegates()?.contains(&nid.into()) {
+            let doc = stored.identity_doc()?;
+
+            // Only delegates and their devices affect the merge state of the COB.
+            if doc.delegate_of(nid, stored)?.is_some() {
+                let persons = stored.persons()?;
+
                 patch_revert_all(
                     old.into(),
                     head.into(),
+                    &target,
                     &stored.backend,
                     &mut patches,
                     signer,
                 )?;
-                patch_merge_all(old.into(), head.into(), working, &mut patches, signer)?;
+                let merged = patch_merge_all(
+                    old.into(),
+                    head.into(),
+                    &target,
+                    &doc,
+                    &persons,
+                    working,
+                    &mut patches,
+                    signer,
+                )?;
+                if !merged.is_empty() {
+                    let mut issues = crate::issues_mut(profile, stored)?;
+                    issue_solve_all(&merged, &mut issues, signer)?;
+                }
             }
         }
     }
     Ok(Some(ExplorerResource::Tree { oid: head.into() }))
 }
 
+/// Get the patch merge target corresponding to a pushed reference, if it is a branch.
+fn merge_target(
+    refname: &git::Qualified,
+    stored: &storage::git::Repository,
+) -> Result<Option<patch::MergeTarget>, Error> {
+    let (_, category, head, tail) = refname.non_empty_components();
+
+    if category.as_str() != "heads" {
+        return Ok(None);
+    }
+    let branch = std::iter::once(head)
+        .chain(tail)
+        .collect::<git::RefString>();
+    let doc = stored.identity_doc()?;
+    let target = patch::MergeTarget::branch(branch, &doc)?;
+
+    Ok(Some(target))
+}
+
 /// Revert all patches that are no longer included in the base branch.
 fn patch_revert_all<G>(
     old: git::Oid,
     new: git::Oid,
+    target: &patch::MergeTarget,
     stored: &git::raw::Repository,
     patches: &mut patch::Cache<
         patch::Patches<'_, storage::git::Repository>,
@@ -689,6 +975,8 @@ where
         .merged()?
         // Skip patches that failed to load.
         .filter_map(|patch| patch.ok())
+        // Skip patches that target a different branch.
+        .filter(|(_, patch)| patch.target() == target)
         .collect::<Vec<_>>();
 
     for (id, patch) in merged {
@@ -726,13 +1014,16 @@ where
 fn patch_merge_all<G>(
     old: git::Oid,
     new: git::Oid,
+    target: &patch::MergeTarget,
+    doc: &Doc,
+    persons: &person::Index,
     working: &git::raw::Repository,
     patches: &mut patch::Cache<
         patch::Patches<'_, storage::git::Repository>,
         cob::cache::StoreWriter,
     >,
     signer: &Device<G>,
-) -> Result<(), Error>
+) -> Result<Vec<patch::PatchId>, Error>
 where
     G: crypto::signature::Signer<crypto::Signature>,
 {
@@ -744,14 +1035,22 @@ where
         .map(|r| r.map(git::Oid::from))
         .collect::<Result<Vec<git::Oid>, _>>()?;
     if commits.is_empty() {
-        return Ok(());
+        return Ok(vec![]);
     }
+    let mut merged = Vec::new();
+    // A malformed merge policy shouldn't prevent merges from being detected.
+    let policy = doc.merge_policy().unwrap_or_else(|e| {
+        warn(format!("ignoring invalid merge policy: {e}"));
+        None
+    });
 
     let open = patches
         .opened()?
         .chain(patches.drafted()?)
         // Skip patches that failed to load.
         .filter_map(|patch| patch.ok())
+        // Skip patches that target a different branch.
+        .filter(|(_, patch)| patch.target() == target)
         .collect::<Vec<_>>();
     for (id, patch) in open {
         // Later revisions are more likely to be merged, so we build the list backwards.
@@ -766,13 +1065,65 @@ where
         // revision that is closest to the tip of the commit chain we're pushing.
         for commit in &commits {
             if let Some((revision_id, head)) = revisions.iter().find(|(_, head)| commit == head) {
+                if let Some(readiness) = policy
+                    .as_ref()
+                    .and_then(|policy| patch.revision_readiness(revision_id, policy, doc, persons))
+                {
+                    if !readiness.is_ready() {
+                        warn(format!(
+                            "patch {} is not ready to be merged: {readiness}",
+                            term::format::cob(&id)
+                        ));
+                    }
+                }
                 let patch = patch::PatchMut::new(id, patch, patches);
                 patch_merge(patch, *revision_id, *head, working, signer)?;
+                merged.push(id);
 
                 break;
             }
         }
     }
+    Ok(merged)
+}
+
+/// Solve the open issues that are fixed by any of the given merged patches.
+fn issue_solve_all<G>(
+    merged: &[patch::PatchId],
+    issues: &mut issue::Cache<issue::Issues<'_, storage::git::Repository>, cob::cache::StoreWriter>,
+    signer: &Device<G>,
+) -> Result<(), Error>
+where
+    G: crypto::signature::Signer<crypto::Signature>,
+{
+    for patch in merged {
+        let fixed = issues
+            .linked(patch)?
+            // Skip issues that failed to load.
+            .filter_map(|issue| issue.ok())
+            .filter(|(_, issue)| {
+                issue.state() == &issue::State::Open
+                    && issue.is_linked(issue::Relation::FixedBy, patch)
+            })
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+
+        for id in fixed {
+            let mut issue = issues.get_mut(&id)?;
+            issue.lifecycle(
+                issue::State::Closed {
+                    reason: issue::CloseReason::Solved,
+                },
+                signer,
+            )?;
+            eprintln!(
+                "{} Issue {} solved by patch {}",
+                term::format::positive("✓"),
+                term::format::tertiary(id),
+                term::format::dim(term::format::cob(patch)),
+            );
+        }
+    }
     Ok(())
 }
 
@@ -11,9 +11,16 @@ fn main() -> anyhow::Result<()> {
 
     let id_oid = repo.set_identity_head()?;
     let branch = repo.set_head()?;
+    let updates = repo.set_canonical_refs()?;
 
     println!("ok: identity: {id_oid}");
     println!("ok: branch: {}", branch.new);
 
+    for update in updates {
+        if let Some(new) = update.new() {
+            println!("ok: {}: {new}", update.name());
+        }
+    }
+
     Ok(())
 }
@@ -2,10 +2,13 @@
 pub mod cache;
 pub mod common;
 pub mod external;
+pub mod filter;
 pub mod identity;
 pub mod issue;
+pub mod milestone;
 pub mod op;
 pub mod patch;
+pub mod person;
 pub mod store;
 pub mod thread;
 
@@ -59,6 +62,12 @@ impl TypedId {
         self.type_name == *patch::TYPENAME
     }
 
+    /// Returns `true` is the [`TypedId::type_name`] is for a
+    /// [`milestone::Milestone`].
+    pub fn is_milestone(&self) -> bool {
+        self.type_name == *milestone::TYPENAME
+    }
+
     /// Returns `true` is the [`TypedId::type_name`] is for an
     /// [`identity::Identity`].
     pub fn is_identity(&self) -> bool {
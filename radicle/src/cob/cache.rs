@@ -1,4 +1,5 @@
 mod migrations;
+pub mod search;
 
 use std::collections::HashMap;
 use std::convert::Infallible;
@@ -28,6 +29,8 @@ const DB_WRITE_TIMEOUT: time::Duration = time::Duration::from_secs(6);
 const MIGRATIONS: &[Migration] = &[
     Migration::Sql(include_str!("cache/migrations/1.sql")),
     Migration::Native(migrations::_2::run),
+    Migration::Sql(include_str!("cache/migrations/3.sql")),
+    Migration::Native(migrations::_4::run),
 ];
 
 /// Function signature for native migrations.
@@ -466,10 +469,16 @@ mod tests {
         assert_eq!(db.migrate_to(2, migrate::ignore).unwrap(), 2); // 1 -> 2
         assert_eq!(db.version().unwrap(), 2);
 
-        assert_eq!(db.migrate_to(1, migrate::ignore).unwrap(), 2); // No-op.
-        assert_eq!(db.version().unwrap(), 2);
+        assert_eq!(db.migrate_to(3, migrate::ignore).unwrap(), 3); // 2 -> 3
+        assert_eq!(db.version().unwrap(), 3);
 
-        assert_eq!(db.migrate_to(99, migrate::ignore).unwrap(), 2); // No-op.
-        assert_eq!(db.version().unwrap(), 2);
+        assert_eq!(db.migrate_to(4, migrate::ignore).unwrap(), 4); // 3 -> 4
+        assert_eq!(db.version().unwrap(), 4);
+
+        assert_eq!(db.migrate_to(1, migrate::ignore).unwrap(), 4); // No-op.
+        assert_eq!(db.version().unwrap(), 4);
+
+        assert_eq!(db.migrate_to(99, migrate::ignore).unwrap(), 4); // No-op.
+        assert_eq!(db.version().unwrap(), 4);
     }
 }
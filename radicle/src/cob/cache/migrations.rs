@@ -1,2 +1,4 @@
 #[path = "migrations/2.rs"]
 pub mod _2;
+#[path = "migrations/4.rs"]
+pub mod _4;
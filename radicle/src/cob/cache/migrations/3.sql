@@ -0,0 +1,9 @@
+-- Milestones
+create table if not exists "milestones" (
+  -- Milestone ID
+  "id"            text      primary key not null,
+  -- Repository ID
+  "repo"          text      not null,
+  -- Milestone in JSON format
+  "milestone"     text      not null
+) strict;
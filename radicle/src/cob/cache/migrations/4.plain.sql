@@ -0,0 +1,29 @@
+-- Search index for issues, without FTS5
+create table if not exists "issues_search" (
+  -- Issue ID
+  "id"            text      not null,
+  -- Repository ID
+  "repo"          text      not null,
+  -- Issue title
+  "title"         text      not null,
+  -- Issue description
+  "description"   text      not null,
+  -- Issue comments
+  "comments"      text      not null,
+  primary key ("id", "repo")
+) strict;
+
+-- Search index for patches, without FTS5
+create table if not exists "patches_search" (
+  -- Patch ID
+  "id"            text      not null,
+  -- Repository ID
+  "repo"          text      not null,
+  -- Patch title
+  "title"         text      not null,
+  -- Patch description
+  "description"   text      not null,
+  -- Patch comments and review comments
+  "comments"      text      not null,
+  primary key ("id", "repo")
+) strict;
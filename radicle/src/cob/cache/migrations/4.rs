@@ -0,0 +1,113 @@
+//! Migration to create the full-text search index, and index existing issues and patches.
+//!
+//! The index uses FTS5 if the linked SQLite library supports it, and plain tables otherwise.
+use std::str::FromStr;
+
+use crate::cob::cache::search;
+use crate::cob::cache::*;
+use crate::cob::issue::Issue;
+use crate::cob::patch::Patch;
+use serde_json as json;
+
+/// Run migration.
+pub fn run(
+    db: &sql::Connection,
+    migration: &Progress,
+    callback: &mut dyn MigrateCallback,
+) -> Result<usize, Error> {
+    if search::has_fts5(db)? {
+        db.execute(include_str!("4.sql"))?;
+    } else {
+        db.execute(include_str!("4.plain.sql"))?;
+    }
+
+    let issues = db
+        .prepare("SELECT id, repo, issue FROM issues")?
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    let patches = db
+        .prepare("SELECT id, repo, patch FROM patches")?
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut progress = Progress::new(issues.len() + patches.len());
+    callback.progress(MigrateProgress {
+        migration,
+        rows: &progress,
+    });
+
+    for row in issues {
+        let (id, rid) = ids(&row)?;
+        let issue =
+            json::from_str::<Issue>(row.read::<&str, _>("issue")).map_err(Error::MalformedJson)?;
+
+        search::index(db, "issues_search", &rid, &id, &issue)?;
+        progress.inc();
+        callback.progress(MigrateProgress {
+            migration,
+            rows: &progress,
+        });
+    }
+    for row in patches {
+        let (id, rid) = ids(&row)?;
+        let patch =
+            json::from_str::<Patch>(row.read::<&str, _>("patch")).map_err(Error::MalformedJson)?;
+
+        search::index(db, "patches_search", &rid, &id, &patch)?;
+        progress.inc();
+        callback.progress(MigrateProgress {
+            migration,
+            rows: &progress,
+        });
+    }
+    Ok(progress.current())
+}
+
+/// Read the object and repository IDs of a row.
+fn ids(row: &sql::Row) -> Result<(ObjectId, RepoId), Error> {
+    let id =
+        ObjectId::from_str(row.read::<&str, _>("id")).map_err(|_| Error::MalformedJsonSchema)?;
+    let rid =
+        RepoId::from_str(row.read::<&str, _>("repo")).map_err(|_| Error::MalformedJsonSchema)?;
+
+    Ok((id, rid))
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use crate::cob::cache::*;
+    use crate::cob::patch::cache::Patches as _;
+    use crate::test::arbitrary;
+    use crate::test::storage::MockRepository;
+
+    const PATCH: &str = include_str!("samples/patch.v2.json");
+
+    #[test]
+    fn test_migration_4() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut db = StoreWriter::memory().unwrap();
+        db.migrate_to(3, migrate::ignore).unwrap();
+        db.raw_query(|conn| {
+            let mut stmt = conn.prepare(
+                "INSERT INTO patches (id, repo, patch)
+                 VALUES (?1, ?2, ?3)",
+            )?;
+            stmt.bind((1, "016a91d2029ee71b9aee8d927664caf1b7885346"))?;
+            stmt.bind((2, &repo.id))?;
+            stmt.bind((3, PATCH))?;
+            stmt.next()?;
+
+            Ok::<_, sql::Error>(())
+        })
+        .unwrap();
+
+        assert_eq!(db.migrate_to(4, migrate::ignore).unwrap(), 4);
+
+        let cache = crate::cob::patch::Cache::reader(repo, db.read_only());
+        let found = |query: &str| cache.search(query).unwrap().count();
+
+        assert_eq!(found("loading indicator"), 1);
+        assert_eq!(found("min-width"), 1);
+        assert_eq!(found("spinner"), 0);
+    }
+}
@@ -0,0 +1,29 @@
+-- Full-text search index for issues
+create virtual table if not exists "issues_search" using fts5(
+  -- Issue ID
+  "id"            unindexed,
+  -- Repository ID
+  "repo"          unindexed,
+  -- Issue title
+  "title",
+  -- Issue description
+  "description",
+  -- Issue comments
+  "comments",
+  tokenize = 'porter unicode61'
+);
+
+-- Full-text search index for patches
+create virtual table if not exists "patches_search" using fts5(
+  -- Patch ID
+  "id"            unindexed,
+  -- Repository ID
+  "repo"          unindexed,
+  -- Patch title
+  "title",
+  -- Patch description
+  "description",
+  -- Patch comments and review comments
+  "comments",
+  tokenize = 'porter unicode61'
+);
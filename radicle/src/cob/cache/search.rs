@@ -0,0 +1,289 @@
+//! Full-text search over cached COBs.
+//!
+//! Searchable COBs are indexed in a search table alongside their JSON table,
+//! eg. `issues_search` for `issues`. The index is kept up to date on every
+//! [`super::Update`] and [`super::Remove`].
+//!
+//! If the linked SQLite library was built with FTS5, as is the case for the
+//! bundled SQLite built with `.cargo/config.toml`, the search table is an FTS5
+//! virtual table, and results are ordered by relevance. Otherwise, eg. when
+//! `CFLAGS` is overridden, it is a plain table that is searched by substring.
+use radicle_cob::ObjectId;
+use sqlite as sql;
+
+use crate::prelude::RepoId;
+
+/// The text of a COB that is indexed for full-text search.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SearchText {
+    /// Object title.
+    pub title: String,
+    /// Object description.
+    pub description: String,
+    /// Comments on the object, including review comments.
+    pub comments: String,
+}
+
+impl SearchText {
+    /// Check whether the text matches the given query, without an index.
+    ///
+    /// Every term in the query must be found in the text, ignoring case.
+    /// This is used when searching without a cache.
+    pub fn matches(&self, query: &str) -> bool {
+        let text = [
+            self.title.as_str(),
+            self.description.as_str(),
+            self.comments.as_str(),
+        ]
+        .join("\n")
+        .to_lowercase();
+
+        terms(query).all(|(term, _)| text.contains(&term.to_lowercase()))
+    }
+}
+
+/// A COB that can be searched.
+pub trait Searchable {
+    /// Get the text to index.
+    fn search_text(&self) -> SearchText;
+}
+
+/// Split a query into its terms. Terms ending with `*` are prefix terms.
+fn terms(query: &str) -> impl Iterator<Item = (&str, bool)> {
+    query.split_whitespace().filter_map(|term| {
+        let (term, prefix) = match term.strip_suffix('*') {
+            Some(term) => (term, true),
+            None => (term, false),
+        };
+        let term = term.trim_matches('"');
+
+        (!term.is_empty()).then_some((term, prefix))
+    })
+}
+
+/// Convert a user query into an FTS5 query.
+///
+/// Each term is quoted, so that the query syntax of FTS5 doesn't need to be
+/// known by users and can't cause errors. All terms must match. Returns `None`
+/// if the query is empty.
+pub(crate) fn fts_query(query: &str) -> Option<String> {
+    let terms = terms(query)
+        .map(|(term, prefix)| {
+            let term = term.replace('"', "\"\"");
+            if prefix {
+                format!("\"{term}\"*")
+            } else {
+                format!("\"{term}\"")
+            }
+        })
+        .collect::<Vec<_>>();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Check whether the linked SQLite library supports FTS5.
+pub(crate) fn has_fts5(db: &sql::Connection) -> Result<bool, sql::Error> {
+    let mut stmt = db.prepare("SELECT sqlite_compileoption_used('ENABLE_FTS5') AS used")?;
+    let used = match stmt.iter().next() {
+        Some(row) => row?.read::<i64, _>("used") == 1,
+        None => false,
+    };
+    Ok(used)
+}
+
+/// Check whether the given search table is an FTS5 table.
+pub(crate) fn is_fts5(db: &sql::Connection, table: &str) -> Result<bool, sql::Error> {
+    let mut stmt = db.prepare("SELECT sql FROM sqlite_master WHERE name = ?1")?;
+    stmt.bind((1, table))?;
+
+    let fts5 = match stmt.iter().next() {
+        Some(row) => row?
+            .read::<&str, _>("sql")
+            .to_lowercase()
+            .contains("using fts5"),
+        None => false,
+    };
+    Ok(fts5)
+}
+
+/// Compile a user query to an SQL expression over the given search table.
+///
+/// Values are added with `param`, which returns the name of the parameter to
+/// use. All terms must match. Without FTS5, terms match any part of the text,
+/// ignoring case, like [`SearchText::matches`]. Returns `None` if the query is
+/// empty.
+pub(crate) fn sql(
+    table: &str,
+    fts5: bool,
+    query: &str,
+    mut param: impl FnMut(String) -> String,
+) -> Option<String> {
+    if fts5 {
+        return fts_query(query).map(|query| format!("{table} MATCH {}", param(query)));
+    }
+    let exprs = terms(query)
+        .map(|(term, _)| {
+            format!(
+                "instr(lower({table}.title || char(10) || {table}.description || char(10) || {table}.comments), {}) > 0",
+                param(term.to_lowercase())
+            )
+        })
+        .collect::<Vec<_>>();
+
+    (!exprs.is_empty()).then(|| exprs.join(" AND "))
+}
+
+/// Add or replace an object in the given search table.
+pub(crate) fn index<T: Searchable>(
+    db: &sql::Connection,
+    table: &str,
+    rid: &RepoId,
+    id: &ObjectId,
+    object: &T,
+) -> Result<(), sql::Error> {
+    let text = object.search_text();
+
+    unindex(db, table, rid, id)?;
+
+    let mut stmt = db.prepare(format!(
+        "INSERT INTO {table} (id, repo, title, description, comments)
+         VALUES (?1, ?2, ?3, ?4, ?5)"
+    ))?;
+    stmt.bind((1, sql::Value::String(id.to_string())))?;
+    stmt.bind((2, rid))?;
+    stmt.bind((3, text.title.as_str()))?;
+    stmt.bind((4, text.description.as_str()))?;
+    stmt.bind((5, text.comments.as_str()))?;
+    stmt.next()?;
+
+    Ok(())
+}
+
+/// Remove an object of a repository from the given search table.
+pub(crate) fn unindex(
+    db: &sql::Connection,
+    table: &str,
+    rid: &RepoId,
+    id: &ObjectId,
+) -> Result<(), sql::Error> {
+    let mut stmt = db.prepare(format!("DELETE FROM {table} WHERE id = ?1 AND repo = ?2"))?;
+    stmt.bind((1, sql::Value::String(id.to_string())))?;
+    stmt.bind((2, rid))?;
+    stmt.next()?;
+
+    Ok(())
+}
+
+/// Remove all objects of a repository from the given search table.
+pub(crate) fn unindex_all(
+    db: &sql::Connection,
+    table: &str,
+    rid: &RepoId,
+) -> Result<(), sql::Error> {
+    let mut stmt = db.prepare(format!("DELETE FROM {table} WHERE repo = ?1"))?;
+    stmt.bind((1, rid))?;
+    stmt.next()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test::arbitrary;
+
+    impl Searchable for SearchText {
+        fn search_text(&self) -> SearchText {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(fts_query(""), None);
+        assert_eq!(fts_query("  "), None);
+        assert_eq!(
+            fts_query("flux capacitor"),
+            Some(String::from("\"flux\" \"capacitor\""))
+        );
+        assert_eq!(fts_query("cap*"), Some(String::from("\"cap\"*")));
+        assert_eq!(
+            fts_query("say \"hi AND"),
+            Some(String::from("\"say\" \"hi\" \"AND\""))
+        );
+    }
+
+    #[test]
+    fn test_sql() {
+        let mut values = Vec::new();
+        let mut param = |value| {
+            values.push(value);
+            format!("?{}", values.len())
+        };
+        assert_eq!(
+            sql("t", true, "flux cap*", &mut param),
+            Some(String::from("t MATCH ?1"))
+        );
+        assert_eq!(sql("t", false, "  ", &mut param), None);
+        assert_eq!(
+            sql("t", false, "Flux cap*", &mut param),
+            Some(String::from(
+                "instr(lower(t.title || char(10) || t.description || char(10) || t.comments), ?2) > 0 \
+                 AND instr(lower(t.title || char(10) || t.description || char(10) || t.comments), ?3) > 0"
+            ))
+        );
+        assert_eq!(values, ["\"flux\" \"cap\"*", "flux", "cap"]);
+    }
+
+    #[test]
+    fn test_plain_table() {
+        let db = sql::Connection::open(":memory:").unwrap();
+        let rid = arbitrary::gen::<RepoId>(1);
+        let id = arbitrary::oid().into();
+        let text = SearchText {
+            title: String::from("Flux capacitor"),
+            description: String::from("Needs more power"),
+            comments: String::new(),
+        };
+        db.execute(include_str!("migrations/4.plain.sql")).unwrap();
+        index(&db, "issues_search", &rid, &id, &text).unwrap();
+
+        let search = |query: &str| {
+            let mut values = Vec::new();
+            let expr = sql("issues_search", false, query, |value| {
+                values.push(value);
+                format!("?{}", values.len())
+            })
+            .unwrap();
+            let mut stmt = db
+                .prepare(format!(
+                    "SELECT COUNT(*) AS n FROM issues_search WHERE {expr}"
+                ))
+                .unwrap();
+            for (i, value) in values.iter().enumerate() {
+                stmt.bind((i + 1, value.as_str())).unwrap();
+            }
+            stmt.iter().next().unwrap().unwrap().read::<i64, _>("n")
+        };
+        assert!(!is_fts5(&db, "issues_search").unwrap());
+        assert_eq!(search("FLUX power"), 1);
+        assert_eq!(search("flux delorean"), 0);
+
+        unindex_all(&db, "issues_search", &rid).unwrap();
+        assert_eq!(search("flux"), 0);
+    }
+
+    #[test]
+    fn test_matches() {
+        let text = SearchText {
+            title: String::from("Flux capacitor"),
+            description: String::from("Needs more power"),
+            comments: String::from("1.21 Gigawatts"),
+        };
+        assert!(text.matches("flux"));
+        assert!(text.matches("POWER gigawatts"));
+        assert!(text.matches("capa*"));
+        assert!(!text.matches("flux delorean"));
+    }
+}
@@ -0,0 +1,805 @@
+//! Filter expressions for issues and patches.
+//!
+//! A filter is a list of space-separated terms, which must all match. Terms
+//! take the form `<key>:<value>`, and can be negated by prefixing them with
+//! `-`. Terms without a key are matched against the text of the object, as
+//! with [`crate::cob::cache::search`]. For example:
+//!
+//! ```text
+//! label:bug state:open updated:>2024-01-01 -label:wontfix "flux capacitor"
+//! ```
+//!
+//! The supported keys are:
+//!
+//! * `label:<label>`, the object has the given label.
+//! * `author:<did>`, the object was opened by the given author.
+//! * `assignee:<did>`, the object is assigned to the given user.
+//! * `state:<state>`, the object is in the given state. Issues are `open`,
+//!   `closed` or `solved`; patches are `draft`, `open`, `archived` or `merged`.
+//! * `created:<date>` and `updated:<date>`, the object was created or last
+//!   updated on the given date. The date is given as `YYYY-MM-DD`, and can be
+//!   prefixed with `>`, `>=`, `<` or `<=`.
+//!
+//! Filters are compiled to SQL against the COB caches, see eg.
+//! [`crate::cob::issue::cache::Issues::filter`], and can also be matched
+//! directly against objects, with [`Filter::matches`].
+use std::fmt;
+use std::str::FromStr;
+
+use sqlite as sql;
+use thiserror::Error;
+
+use crate::cob::cache::search::{self, Searchable};
+use crate::cob::common::{Label, Timestamp};
+use crate::cob::issue::{self, Issue};
+use crate::cob::patch::{self, Patch};
+use crate::prelude::Did;
+
+/// Milliseconds in a day.
+const DAY_MILLIS: u64 = 24 * 60 * 60 * 1000;
+
+/// Error parsing a filter.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Unknown key.
+    #[error("unknown filter key `{0}`, expected one of `label`, `author`, `assignee`, `state`, `created` or `updated`")]
+    UnknownKey(String),
+    /// Missing value.
+    #[error("missing value for filter key `{0}`")]
+    MissingValue(String),
+    /// Invalid label.
+    #[error("invalid label `{0}`")]
+    InvalidLabel(String),
+    /// Invalid DID.
+    #[error("invalid DID `{0}`")]
+    InvalidDid(String),
+    /// Invalid state.
+    #[error("invalid state `{0}`, expected one of `open`, `closed`, `solved`, `draft`, `archived` or `merged`")]
+    InvalidState(String),
+    /// Invalid date.
+    #[error("invalid date `{0}`, expected `YYYY-MM-DD`")]
+    InvalidDate(String),
+}
+
+/// A filter over issues or patches. All terms must match.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Filter {
+    terms: Vec<Term>,
+}
+
+impl Filter {
+    /// Check whether the filter has no terms, ie. matches everything.
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Check whether the filter has a `state` term.
+    pub fn has_state(&self) -> bool {
+        self.terms
+            .iter()
+            .any(|t| matches!(t.predicate, Predicate::State(_)))
+    }
+
+    /// Add a text term, which matches the text of the object.
+    pub fn text(mut self, text: impl ToString) -> Self {
+        self.terms.push(Term {
+            negated: false,
+            predicate: Predicate::Text(text.to_string()),
+        });
+        self
+    }
+
+    /// Check whether the given object matches the filter.
+    pub fn matches<T: Filterable>(&self, object: &T) -> bool {
+        self.terms.iter().all(|term| {
+            let matches = match &term.predicate {
+                Predicate::Label(label) => object.has_label(label),
+                Predicate::Author(did) => object.is_author(did),
+                Predicate::Assignee(did) => object.is_assignee(did),
+                Predicate::State(state) => object.has_state(*state),
+                Predicate::Created(date) => date.contains(object.created()),
+                Predicate::Updated(date) => date.contains(object.updated()),
+                Predicate::Text(text) => object.search_text().matches(text),
+            };
+            matches != term.negated
+        })
+    }
+
+    /// Compile the filter to an SQL expression over the table of `T`.
+    ///
+    /// Parameters are numbered from `first`, and their values are returned
+    /// in order. Text is matched with FTS5 if `fts5` is set, see
+    /// [`search::sql`]. An empty filter compiles to `TRUE`.
+    pub(crate) fn sql<T: Filterable>(&self, first: usize, fts5: bool) -> (String, Vec<sql::Value>) {
+        let mut params = Params {
+            values: Vec::new(),
+            first,
+        };
+        let mut exprs = Vec::new();
+        let column = T::COLUMN;
+        let table = T::TABLE;
+
+        for term in &self.terms {
+            let expr = match &term.predicate {
+                Predicate::Label(label) => {
+                    let p = params.push(label.name());
+                    format!(
+                        "EXISTS (SELECT 1 FROM json_each({table}.{column}->'$.labels') WHERE value = {p})"
+                    )
+                }
+                Predicate::Assignee(did) => {
+                    let p = params.push(T::assignee_value(did));
+                    format!(
+                        "EXISTS (SELECT 1 FROM json_each({table}.{column}->'$.assignees') WHERE value = {p})"
+                    )
+                }
+                Predicate::Author(did) => {
+                    let p = params.push(T::author_value(did));
+                    format!("{} = {p}", T::author_sql())
+                }
+                Predicate::State(state) => T::state_sql(*state),
+                Predicate::Created(date) => date.sql(&T::created_sql(), &mut params),
+                Predicate::Updated(date) => date.sql(&T::updated_sql(), &mut params),
+                Predicate::Text(text) => {
+                    match search::sql(&format!("{table}_search"), fts5, text, |v| params.push(v)) {
+                        Some(expr) => format!(
+                            "{table}.id IN (SELECT id FROM {table}_search WHERE {expr} AND repo = {table}.repo)"
+                        ),
+                        None => String::from("TRUE"),
+                    }
+                }
+            };
+            if term.negated {
+                exprs.push(format!("NOT ({expr})"));
+            } else {
+                exprs.push(format!("({expr})"));
+            }
+        }
+        if exprs.is_empty() {
+            return (String::from("TRUE"), params.values);
+        }
+        (exprs.join(" AND "), params.values)
+    }
+}
+
+impl FromStr for Filter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut terms = Vec::new();
+
+        for word in words(s) {
+            let (negated, word) = match word.strip_prefix('-') {
+                Some(word) if !word.is_empty() => (true, word),
+                _ => (false, word.as_str()),
+            };
+            let predicate = match word.split_once(':') {
+                Some((key, value)) => Predicate::parse(key, value)?,
+                None => Predicate::Text(word.to_owned()),
+            };
+            terms.push(Term { negated, predicate });
+        }
+        Ok(Self { terms })
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms = self.terms.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        write!(f, "{}", terms.join(" "))
+    }
+}
+
+/// Split a filter into words. Double quotes group words together.
+fn words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+
+    for c in s.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// A single filter term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    negated: bool,
+    predicate: Predicate,
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negated {
+            write!(f, "-")?;
+        }
+        match &self.predicate {
+            Predicate::Label(label) => write!(f, "label:{label}"),
+            Predicate::Author(did) => write!(f, "author:{did}"),
+            Predicate::Assignee(did) => write!(f, "assignee:{did}"),
+            Predicate::State(state) => write!(f, "state:{state}"),
+            Predicate::Created(date) => write!(f, "created:{date}"),
+            Predicate::Updated(date) => write!(f, "updated:{date}"),
+            Predicate::Text(text) if text.contains(char::is_whitespace) => {
+                write!(f, "\"{text}\"")
+            }
+            Predicate::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Predicate {
+    Label(Label),
+    Author(Did),
+    Assignee(Did),
+    State(State),
+    Created(Date),
+    Updated(Date),
+    Text(String),
+}
+
+impl Predicate {
+    fn parse(key: &str, value: &str) -> Result<Self, Error> {
+        if value.is_empty() {
+            return Err(Error::MissingValue(key.to_owned()));
+        }
+        match key {
+            "label" => Label::new(value)
+                .map(Self::Label)
+                .map_err(|_| Error::InvalidLabel(value.to_owned())),
+            "author" => Did::from_str(value)
+                .map(Self::Author)
+                .map_err(|_| Error::InvalidDid(value.to_owned())),
+            "assignee" => Did::from_str(value)
+                .map(Self::Assignee)
+                .map_err(|_| Error::InvalidDid(value.to_owned())),
+            "state" => State::from_str(value).map(Self::State),
+            "created" => Date::from_str(value).map(Self::Created),
+            "updated" => Date::from_str(value).map(Self::Updated),
+            other => Err(Error::UnknownKey(other.to_owned())),
+        }
+    }
+}
+
+/// States of issues and patches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    /// Open issue or patch.
+    Open,
+    /// Closed issue, for any reason.
+    Closed,
+    /// Closed issue, that was solved.
+    Solved,
+    /// Draft patch.
+    Draft,
+    /// Archived patch.
+    Archived,
+    /// Merged patch.
+    Merged,
+}
+
+impl FromStr for State {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(Self::Open),
+            "closed" => Ok(Self::Closed),
+            "solved" => Ok(Self::Solved),
+            "draft" => Ok(Self::Draft),
+            "archived" => Ok(Self::Archived),
+            "merged" => Ok(Self::Merged),
+            other => Err(Error::InvalidState(other.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Open => write!(f, "open"),
+            Self::Closed => write!(f, "closed"),
+            Self::Solved => write!(f, "solved"),
+            Self::Draft => write!(f, "draft"),
+            Self::Archived => write!(f, "archived"),
+            Self::Merged => write!(f, "merged"),
+        }
+    }
+}
+
+/// How a timestamp compares to a date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Before,
+    BeforeOrOn,
+    On,
+    AfterOrOn,
+    After,
+}
+
+/// A date filter, eg. `>=2024-01-01`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Date {
+    comparison: Comparison,
+    /// Days since the UNIX epoch.
+    days: i64,
+}
+
+impl Date {
+    /// Range of the day, in milliseconds since the epoch.
+    fn range(&self) -> (i64, i64) {
+        let start = self.days * DAY_MILLIS as i64;
+        (start, start + DAY_MILLIS as i64)
+    }
+
+    /// Check whether the timestamp matches.
+    fn contains(&self, time: Timestamp) -> bool {
+        let time = time.as_millis() as i64;
+        let (start, end) = self.range();
+
+        match self.comparison {
+            Comparison::Before => time < start,
+            Comparison::BeforeOrOn => time < end,
+            Comparison::On => time >= start && time < end,
+            Comparison::AfterOrOn => time >= start,
+            Comparison::After => time >= end,
+        }
+    }
+
+    /// Compile to an SQL expression, given an SQL expression for the timestamp.
+    fn sql(&self, time: &str, params: &mut Params) -> String {
+        let (start, end) = self.range();
+
+        match self.comparison {
+            Comparison::Before => format!("{time} < {}", params.push(start)),
+            Comparison::BeforeOrOn => format!("{time} < {}", params.push(end)),
+            Comparison::On => format!(
+                "{time} >= {} AND {time} < {}",
+                params.push(start),
+                params.push(end)
+            ),
+            Comparison::AfterOrOn => format!("{time} >= {}", params.push(start)),
+            Comparison::After => format!("{time} >= {}", params.push(end)),
+        }
+    }
+}
+
+impl FromStr for Date {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (comparison, date) = if let Some(date) = s.strip_prefix(">=") {
+            (Comparison::AfterOrOn, date)
+        } else if let Some(date) = s.strip_prefix("<=") {
+            (Comparison::BeforeOrOn, date)
+        } else if let Some(date) = s.strip_prefix('>') {
+            (Comparison::After, date)
+        } else if let Some(date) = s.strip_prefix('<') {
+            (Comparison::Before, date)
+        } else {
+            (Comparison::On, s)
+        };
+        let invalid = || Error::InvalidDate(date.to_owned());
+        let mut parts = date.splitn(3, '-');
+        let mut part = || -> Result<i64, Error> {
+            parts
+                .next()
+                .and_then(|p| p.parse::<i64>().ok())
+                .ok_or_else(invalid)
+        };
+        let (year, month, day) = (part()?, part()?, part()?);
+
+        if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            comparison,
+            days: days_from_civil(year, month, day),
+        })
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = civil_from_days(self.days);
+        let comparison = match self.comparison {
+            Comparison::Before => "<",
+            Comparison::BeforeOrOn => "<=",
+            Comparison::On => "",
+            Comparison::AfterOrOn => ">=",
+            Comparison::After => ">",
+        };
+        write!(f, "{comparison}{year:04}-{month:02}-{day:02}")
+    }
+}
+
+/// Days since the UNIX epoch of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146097 + doe - 719468
+}
+
+/// Date in the proleptic Gregorian calendar of days since the UNIX epoch.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+/// Number of days in a month of the proleptic Gregorian calendar.
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// SQL parameters of a compiled filter.
+struct Params {
+    values: Vec<sql::Value>,
+    first: usize,
+}
+
+impl Params {
+    /// Add a parameter, and return its placeholder.
+    fn push(&mut self, value: impl Into<sql::Value>) -> String {
+        self.values.push(value.into());
+        format!("?{}", self.first + self.values.len() - 1)
+    }
+}
+
+/// An object that can be filtered.
+pub trait Filterable: Searchable {
+    /// Table of the object in the COB cache.
+    const TABLE: &'static str;
+    /// JSON column of the object in its table.
+    const COLUMN: &'static str;
+
+    /// Check whether the object has the given label.
+    fn has_label(&self, label: &Label) -> bool;
+    /// Check whether the object was opened by the given author.
+    fn is_author(&self, did: &Did) -> bool;
+    /// Check whether the object is assigned to the given user.
+    fn is_assignee(&self, did: &Did) -> bool;
+    /// Check whether the object is in the given state.
+    fn has_state(&self, state: State) -> bool;
+    /// Time at which the object was created.
+    fn created(&self) -> Timestamp;
+    /// Time at which the object was last updated.
+    fn updated(&self) -> Timestamp;
+
+    /// Value of an assignee, as stored in the JSON object.
+    fn assignee_value(did: &Did) -> String;
+    /// Value of the author, as stored in the JSON object.
+    fn author_value(did: &Did) -> String;
+    /// SQL expression for the author of the object.
+    fn author_sql() -> String;
+    /// SQL expression matching the state of the object.
+    fn state_sql(state: State) -> String;
+    /// SQL expression for the creation time of the object.
+    fn created_sql() -> String;
+    /// SQL expression for the last update time of the object.
+    fn updated_sql() -> String;
+}
+
+impl Filterable for Issue {
+    const TABLE: &'static str = "issues";
+    const COLUMN: &'static str = "issue";
+
+    fn has_label(&self, label: &Label) -> bool {
+        self.labels().any(|l| l == label)
+    }
+
+    fn is_author(&self, did: &Did) -> bool {
+        self.comments()
+            .next()
+            .is_some_and(|(_, c)| &c.author() == did.as_key())
+    }
+
+    fn is_assignee(&self, did: &Did) -> bool {
+        self.assignees().any(|a| a == did)
+    }
+
+    fn has_state(&self, state: State) -> bool {
+        match (state, self.state()) {
+            (State::Open, issue::State::Open) => true,
+            (State::Closed, issue::State::Closed { .. }) => true,
+            (
+                State::Solved,
+                issue::State::Closed {
+                    reason: issue::CloseReason::Solved,
+                },
+            ) => true,
+            _ => false,
+        }
+    }
+
+    fn created(&self) -> Timestamp {
+        self.comments()
+            .next()
+            .map(|(_, c)| c.timestamp())
+            .unwrap_or_else(|| Timestamp::from_secs(0))
+    }
+
+    fn updated(&self) -> Timestamp {
+        self.comments()
+            .map(|(_, c)| c.timestamp())
+            .max()
+            .unwrap_or_else(|| Timestamp::from_secs(0))
+    }
+
+    fn assignee_value(did: &Did) -> String {
+        did.to_string()
+    }
+
+    fn author_value(did: &Did) -> String {
+        did.as_key().to_string()
+    }
+
+    fn author_sql() -> String {
+        String::from(
+            "json_extract(issues.issue, '$.thread.comments.\"' || (issues.issue->>'$.thread.timeline[0]') || '\".author')",
+        )
+    }
+
+    fn state_sql(state: State) -> String {
+        match state {
+            State::Open => String::from("issues.issue->>'$.state.status' = 'open'"),
+            State::Closed => String::from("issues.issue->>'$.state.status' = 'closed'"),
+            State::Solved => String::from(
+                "issues.issue->>'$.state.status' = 'closed' AND issues.issue->>'$.state.reason' = 'solved'",
+            ),
+            State::Draft | State::Archived | State::Merged => String::from("FALSE"),
+        }
+    }
+
+    fn created_sql() -> String {
+        String::from(
+            "json_extract(issues.issue, '$.thread.comments.\"' || (issues.issue->>'$.thread.timeline[0]') || '\".edits[0].timestamp')",
+        )
+    }
+
+    fn updated_sql() -> String {
+        String::from(
+            "(SELECT max(value->>'$.edits[0].timestamp') FROM json_each(issues.issue->'$.thread.comments'))",
+        )
+    }
+}
+
+impl Filterable for Patch {
+    const TABLE: &'static str = "patches";
+    const COLUMN: &'static str = "patch";
+
+    fn has_label(&self, label: &Label) -> bool {
+        self.labels().any(|l| l == label)
+    }
+
+    fn is_author(&self, did: &Did) -> bool {
+        self.author().id() == did
+    }
+
+    fn is_assignee(&self, did: &Did) -> bool {
+        self.assignees().any(|a| &a == did)
+    }
+
+    fn has_state(&self, state: State) -> bool {
+        matches!(
+            (state, patch::Status::from(self.state())),
+            (State::Open, patch::Status::Open)
+                | (State::Draft, patch::Status::Draft)
+                | (State::Archived, patch::Status::Archived)
+                | (State::Merged, patch::Status::Merged)
+        )
+    }
+
+    fn created(&self) -> Timestamp {
+        self.timestamp()
+    }
+
+    fn updated(&self) -> Timestamp {
+        self.updated_at()
+    }
+
+    fn assignee_value(did: &Did) -> String {
+        did.as_key().to_string()
+    }
+
+    fn author_value(did: &Did) -> String {
+        did.to_string()
+    }
+
+    fn author_sql() -> String {
+        String::from("patches.patch->>'$.author.id'")
+    }
+
+    fn state_sql(state: State) -> String {
+        match state {
+            State::Open | State::Draft | State::Archived | State::Merged => {
+                format!("patches.patch->>'$.state.status' = '{state}'")
+            }
+            State::Closed | State::Solved => String::from("FALSE"),
+        }
+    }
+
+    fn created_sql() -> String {
+        // Nb. Only revisions by the patch author count, as in [`Patch::timestamp`]
+        // and [`Patch::updated_at`].
+        String::from(
+            "(SELECT min(value->>'$.timestamp') FROM json_each(patches.patch->'$.revisions')
+              WHERE value->>'$.author.id' = patches.patch->>'$.author.id')",
+        )
+    }
+
+    fn updated_sql() -> String {
+        // Nb. Only revisions by the patch author count, as in [`Patch::timestamp`]
+        // and [`Patch::updated_at`].
+        String::from(
+            "(SELECT max(value->>'$.timestamp') FROM json_each(patches.patch->'$.revisions')
+              WHERE value->>'$.author.id' = patches.patch->>'$.author.id')",
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let did = "did:key:z6MknSLrJoTcukLrE435hVNQT4JUhbvWLX4kUzqkEStBU8Vi";
+        let filter = Filter::from_str(&format!(
+            "label:bug  author:{did} state:open updated:>2024-01-01 -label:wontfix \"flux capacitor\""
+        ))
+        .unwrap();
+
+        assert_eq!(
+            filter.terms,
+            vec![
+                Term {
+                    negated: false,
+                    predicate: Predicate::Label(Label::new("bug").unwrap()),
+                },
+                Term {
+                    negated: false,
+                    predicate: Predicate::Author(Did::from_str(did).unwrap()),
+                },
+                Term {
+                    negated: false,
+                    predicate: Predicate::State(State::Open),
+                },
+                Term {
+                    negated: false,
+                    predicate: Predicate::Updated(Date {
+                        comparison: Comparison::After,
+                        days: 19723,
+                    }),
+                },
+                Term {
+                    negated: true,
+                    predicate: Predicate::Label(Label::new("wontfix").unwrap()),
+                },
+                Term {
+                    negated: false,
+                    predicate: Predicate::Text(String::from("flux capacitor")),
+                },
+            ]
+        );
+        assert_eq!(
+            filter.to_string(),
+            format!("label:bug author:{did} state:open updated:>2024-01-01 -label:wontfix \"flux capacitor\"")
+        );
+        assert!(filter.has_state());
+        assert!(Filter::from_str("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            Filter::from_str("color:red"),
+            Err(Error::UnknownKey(_))
+        ));
+        assert!(matches!(
+            Filter::from_str("label:"),
+            Err(Error::MissingValue(_))
+        ));
+        assert!(matches!(
+            Filter::from_str("author:alice"),
+            Err(Error::InvalidDid(_))
+        ));
+        assert!(matches!(
+            Filter::from_str("state:pending"),
+            Err(Error::InvalidState(_))
+        ));
+        assert!(matches!(
+            Filter::from_str("updated:>2024-13-01"),
+            Err(Error::InvalidDate(_))
+        ));
+        assert!(matches!(
+            Filter::from_str("created:yesterday"),
+            Err(Error::InvalidDate(_))
+        ));
+    }
+
+    #[test]
+    fn test_dates() {
+        for (date, days) in [
+            ("1970-01-01", 0),
+            ("2000-03-01", 11017),
+            ("2024-02-29", 19782),
+        ] {
+            let parsed = Date::from_str(date).unwrap();
+            assert_eq!(parsed.days, days);
+            assert_eq!(parsed.to_string(), date);
+        }
+        for date in [
+            "2024-02-30",
+            "2023-02-29",
+            "1900-02-29",
+            "2024-04-31",
+            "2024-13-01",
+        ] {
+            assert!(Date::from_str(date).is_err(), "{date} is invalid");
+        }
+        assert!(Date::from_str("2000-02-29").is_ok());
+        assert!(Date::from_str("2024-12-31").is_ok());
+
+        let day = Date::from_str("2024-01-01").unwrap();
+        let (start, end) = day.range();
+        let at = |millis: i64| Timestamp::from(localtime::LocalTime::from_millis(millis as u128));
+
+        assert!(day.contains(at(start)));
+        assert!(day.contains(at(end - 1)));
+        assert!(!day.contains(at(end)));
+        assert!(Date::from_str(">2024-01-01").unwrap().contains(at(end)));
+        assert!(!Date::from_str(">2024-01-01").unwrap().contains(at(start)));
+        assert!(Date::from_str("<2024-01-01")
+            .unwrap()
+            .contains(at(start - 1)));
+        assert!(Date::from_str("<=2024-01-01")
+            .unwrap()
+            .contains(at(end - 1)));
+    }
+
+    #[test]
+    fn test_sql() {
+        let filter = Filter::from_str("label:bug -state:open").unwrap();
+        let (expr, values) = filter.sql::<Issue>(2, true);
+
+        assert_eq!(
+            expr,
+            "(EXISTS (SELECT 1 FROM json_each(issues.issue->'$.labels') WHERE value = ?2)) \
+             AND NOT (issues.issue->>'$.state.status' = 'open')"
+        );
+        assert_eq!(values, vec![sql::Value::String(String::from("bug"))]);
+        assert_eq!(Filter::default().sql::<Patch>(1, true).0, "TRUE");
+    }
+}
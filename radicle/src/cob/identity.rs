@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::sync::Arc;
 use std::{fmt, ops::Deref, str::FromStr};
 
 use crypto::{PublicKey, Signature};
@@ -15,12 +16,12 @@ use crate::node::NodeId;
 use crate::{
     cob,
     cob::{
-        op, store,
+        op, person, store,
         store::{Cob, CobAction, Transaction},
         ActorId, Timestamp, Uri,
     },
     identity::{
-        doc::{DocError, RepoId},
+        doc::{DocError, PayloadError, RepoId},
         Did,
     },
     storage::{ReadRepository, RepositoryError, WriteRepository},
@@ -121,6 +122,10 @@ pub enum ApplyError {
     GitExt(#[from] git_ext::Error),
     #[error("identity document error: {0}")]
     Doc(#[from] DocError),
+    #[error("identity document payload error: {0}")]
+    Payload(#[from] PayloadError),
+    #[error("repository: {0}")]
+    Repository(#[from] RepositoryError),
 }
 
 /// Error updating or creating proposals.
@@ -134,6 +139,8 @@ pub enum Error {
     Op(#[from] op::OpEncodingError),
     #[error(transparent)]
     Doc(#[from] DocError),
+    #[error("identity document payload error: {0}")]
+    Payload(#[from] PayloadError),
     #[error("revision {0} was not found")]
     NotFound(RevisionId),
 }
@@ -158,8 +165,24 @@ pub struct Identity {
     revisions: BTreeMap<RevisionId, Option<Revision>>,
     /// Timeline of events.
     timeline: Vec<EntryId>,
+    /// Persons of the repository, loaded when the first operation is applied.
+    #[serde(skip)]
+    persons: PersonIndex,
 }
 
+/// Persons of a repository, kept while evaluating an identity, so that they are only loaded
+/// once. This is not part of the state of the identity, and is ignored when comparing.
+#[derive(Debug, Clone, Default)]
+struct PersonIndex(Option<Arc<person::Index>>);
+
+impl PartialEq for PersonIndex {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for PersonIndex {}
+
 impl cob::store::CobWithType for Identity {
     fn type_name() -> &'static TypeName {
         &TYPENAME
@@ -190,6 +213,7 @@ impl Identity {
                 .collect(),
             revisions: BTreeMap::from_iter([(root, Some(revision))]),
             timeline: vec![root],
+            persons: PersonIndex::default(),
         }
     }
 
@@ -362,6 +386,7 @@ impl store::Cob for Identity {
             title,
             description,
             op.author.into(),
+            op.author.into(),
             root.blob,
             root.doc,
             State::Accepted,
@@ -380,9 +405,14 @@ impl store::Cob for Identity {
     ) -> Result<(), ApplyError> {
         let id = op.id;
         let concurrent = concurrent.into_iter().collect::<Vec<_>>();
+        let persons = self.persons(repo)?;
+        let devices = Devices {
+            persons: &persons,
+            heads: &op.related,
+        };
 
         for action in op.actions {
-            match self.action(action, id, op.author, op.timestamp, &concurrent, repo) {
+            match self.action(action, id, op.author, op.timestamp, &devices, repo) {
                 Ok(()) => {}
                 // This particular error is returned when there is a mismatch between the expected
                 // and the actual state of a revision, which can happen concurrently. Therefore
@@ -408,7 +438,9 @@ impl Identity {
     /// Apply a single action to the identity document.
     ///
     /// This function ensures a few things:
-    /// * Only delegates can interact with the state.
+    /// * Only delegates can interact with the state. Person delegates interact through the
+    ///   devices they had as of the person heads the operation depends on, so that later
+    ///   rotations don't change the outcome of earlier operations.
     /// * There is only ever one accepted revision; this is the "current" revision.
     /// * There can be zero or more active revisions, up to the number of delegates.
     /// * An active revision is one that can be "voted" on.
@@ -420,14 +452,13 @@ impl Identity {
         entry: EntryId,
         author: ActorId,
         timestamp: Timestamp,
-        _concurrent: &[&cob::Entry],
+        devices: &Devices,
         repo: &R,
     ) -> Result<(), ApplyError> {
         let current = self.current().clone();
-
-        if !current.is_delegate(&author.into()) {
+        let Some(delegate) = devices.delegate(&current, &author, repo)? else {
             return Err(ApplyError::UnexpectedState);
-        }
+        };
         match action {
             Action::RevisionAccept {
                 revision,
@@ -443,8 +474,8 @@ impl Identity {
                 }
                 assert_eq!(revision.parent, Some(current.id));
 
-                self.heads.insert(author.into(), id);
-                revision.accept(author, signature, &current)?;
+                self.heads.insert(delegate, id);
+                revision.accept(author, delegate, signature)?;
 
                 self.adopt(id);
             }
@@ -458,7 +489,7 @@ impl Identity {
                 }
                 assert_eq!(revision.parent, Some(current.id));
 
-                revision.reject(author)?;
+                revision.reject(delegate)?;
             }
             Action::RevisionEdit {
                 title,
@@ -518,6 +549,8 @@ impl Identity {
 
                 let doc = repo.blob(blob)?;
                 let doc = Doc::from_blob(&doc)?;
+                // Known payloads must be valid, as of the version of the document.
+                doc.verify_evaluated_payloads()?;
                 // All revisions but the first one must have a parent.
                 let Some(parent) = parent else {
                     return Err(ApplyError::MissingParent);
@@ -539,7 +572,9 @@ impl Identity {
                 };
 
                 // Verify signature over new blob, using trusted delegates.
-                if parent.verify_signature(&author, &signature, blob).is_err() {
+                if devices.delegate(parent, &author, repo)?.is_none()
+                    || author.verify(blob.as_bytes(), &signature).is_err()
+                {
                     return Err(ApplyError::InvalidSignature(author, blob));
                 }
                 let revision = Revision::new(
@@ -547,6 +582,7 @@ impl Identity {
                     title,
                     description,
                     author.into(),
+                    delegate,
                     blob,
                     doc,
                     state,
@@ -556,7 +592,7 @@ impl Identity {
                 );
                 let id = revision.id;
 
-                self.heads.insert(author.into(), id);
+                self.heads.insert(delegate, id);
                 self.revisions.insert(id, Some(revision));
 
                 if state == State::Active {
@@ -593,6 +629,20 @@ impl Identity {
         }
     }
 
+    /// Persons of the repository, loaded on first use.
+    fn persons<R: ReadRepository>(
+        &mut self,
+        repo: &R,
+    ) -> Result<Arc<person::Index>, RepositoryError> {
+        if let Some(persons) = &self.persons.0 {
+            return Ok(persons.clone());
+        }
+        let persons = Arc::new(repo.persons()?);
+        self.persons.0 = Some(persons.clone());
+
+        Ok(persons)
+    }
+
     /// A specific [`Revision`], mutably.
     fn revision_mut(&mut self, revision: &RevisionId) -> Option<&mut Revision> {
         self.revisions.get_mut(revision).and_then(|r| r.as_mut())
@@ -606,6 +656,35 @@ impl Identity {
     }
 }
 
+/// Devices of delegates, as seen by the author of an operation.
+struct Devices<'a> {
+    /// Persons of the repository.
+    persons: &'a person::Index,
+    /// Heads of person histories that the operation depends on.
+    heads: &'a [Oid],
+}
+
+impl Devices<'_> {
+    /// Get the delegate of `doc` that signs with `key`, if any.
+    fn delegate<R: ReadRepository>(
+        &self,
+        doc: &Doc,
+        key: &PublicKey,
+        repo: &R,
+    ) -> Result<Option<Did>, RepositoryError> {
+        for delegate in doc.delegates().iter() {
+            if self
+                .persons
+                .devices_as_of(delegate, doc.person_of(delegate).as_ref(), self.heads, repo)?
+                .contains(key)
+            {
+                return Ok(Some(*delegate));
+            }
+        }
+        Ok(None)
+    }
+}
+
 impl<R: ReadRepository> cob::Evaluate<R> for Identity {
     type Error = Error;
 
@@ -694,7 +773,8 @@ pub struct Revision {
     /// Parent revision.
     pub parent: Option<RevisionId>,
 
-    /// Signatures and rejections given by the delegates.
+    /// Signatures and rejections given by the delegates, keyed by delegate. The
+    /// signature of a person delegate is made by one of its devices.
     verdicts: BTreeMap<PublicKey, Verdict>,
 }
 
@@ -752,6 +832,7 @@ impl Revision {
         title: String,
         description: String,
         author: Author,
+        delegate: Did,
         blob: Oid,
         doc: Doc,
         state: State,
@@ -759,7 +840,7 @@ impl Revision {
         parent: Option<RevisionId>,
         timestamp: Timestamp,
     ) -> Self {
-        let verdicts = BTreeMap::from_iter([(*author.public_key(), Verdict::Accept(signature))]);
+        let verdicts = BTreeMap::from_iter([(*delegate.as_key(), Verdict::Accept(signature))]);
 
         Self {
             id,
@@ -778,19 +859,16 @@ impl Revision {
     fn accept(
         &mut self,
         author: PublicKey,
+        delegate: Did,
         signature: Signature,
-        current: &Revision,
     ) -> Result<(), ApplyError> {
         // Check that this is a valid signature over the new document blob id.
-        if current
-            .verify_signature(&author, &signature, self.blob)
-            .is_err()
-        {
+        if author.verify(self.blob.as_bytes(), &signature).is_err() {
             return Err(ApplyError::InvalidSignature(author, self.blob));
         }
         if self
             .verdicts
-            .insert(author, Verdict::Accept(signature))
+            .insert(*delegate.as_key(), Verdict::Accept(signature))
             .is_some()
         {
             return Err(ApplyError::DuplicateVerdict);
@@ -798,8 +876,12 @@ impl Revision {
         Ok(())
     }
 
-    fn reject(&mut self, key: PublicKey) -> Result<(), ApplyError> {
-        if self.verdicts.insert(key, Verdict::Reject).is_some() {
+    fn reject(&mut self, delegate: Did) -> Result<(), ApplyError> {
+        if self
+            .verdicts
+            .insert(*delegate.as_key(), Verdict::Reject)
+            .is_some()
+        {
             return Err(ApplyError::DuplicateVerdict);
         }
         // Mark as rejected if it's impossible for this revision to be accepted
@@ -919,6 +1001,11 @@ where
         let mut tx = Transaction::default();
         operations(&mut tx, self.store.as_ref())?;
 
+        // Depend on the persons the signer is a device of, so that their devices
+        // can be verified as of this update.
+        let persons = person::Index::load(self.store.as_ref())?;
+        tx.relate(persons.heads_of(signer.public_key()));
+
         let (doc, commit) = tx.commit(message, self.id, &mut self.store, signer)?;
         self.identity = doc;
 
@@ -927,6 +1014,8 @@ where
 
     /// Update the identity by proposing a new revision.
     /// If the signer is the only delegate, the revision is accepted automatically.
+    ///
+    /// Known payloads of the new document must be valid.
     pub fn update<G>(
         &mut self,
         title: impl ToString,
@@ -937,6 +1026,8 @@ where
     where
         G: crypto::signature::Signer<crypto::Signature>,
     {
+        doc.verify_payloads()?;
+
         let parent = self.current;
         let id = self.transaction("Propose revision", signer, |tx, repo| {
             tx.revision(title, description, doc, Some(parent), repo, signer)
@@ -1031,13 +1122,16 @@ mod lookup {
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
+    use std::collections::BTreeSet;
+
     use qcheck_macros::quickcheck;
 
     use crate::cob;
+    use crate::cob::person::{PersonId, Persons};
     use crate::crypto::PublicKey;
     use crate::identity::did::Did;
-    use crate::identity::doc::PayloadId;
-    use crate::identity::Visibility;
+    use crate::identity::doc::{Payload, PayloadId, RawDoc};
+    use crate::identity::{DelegatePersons, Visibility};
     use crate::rad;
     use crate::storage::git::Storage;
     use crate::storage::ReadStorage;
@@ -1118,6 +1212,47 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_identity_update_invalid_payload() {
+        let NodeWithRepo { node, repo } = NodeWithRepo::default();
+        let signer = &node.signer;
+        let mut identity = Identity::load_mut(&*repo).unwrap();
+        let mut doc = identity.doc().clone().edit();
+        let links = PayloadId::of::<crate::identity::Links>();
+        let r0 = identity.current;
+
+        // Known payloads that are invalid are rejected.
+        doc.payload.insert(
+            links.clone(),
+            Payload::from(serde_json::json!({ "homepage": "radicle.xyz" })),
+        );
+        let invalid = doc.clone().verified().unwrap();
+        identity
+            .update("Add links", "", &invalid, signer)
+            .unwrap_err();
+        assert_eq!(identity.current, r0);
+
+        // And so are revisions with invalid payloads that were proposed by other means,
+        // eg. received from peers.
+        identity
+            .transaction("Propose revision", signer, |tx, repo| {
+                tx.revision("Add links", "", &invalid, Some(r0), repo, signer)
+            })
+            .unwrap_err();
+        identity.reload().unwrap();
+        assert_eq!(identity.current, r0);
+
+        // Valid ones are accepted.
+        doc.payload.insert(
+            links,
+            Payload::from(serde_json::json!({ "homepage": "https://radicle.xyz" })),
+        );
+        let r1 = identity
+            .update("Add links", "", &doc.verified().unwrap(), signer)
+            .unwrap();
+        assert_eq!(identity.current, r1);
+    }
+
     #[test]
     fn test_identity_update_rejected() {
         let NodeWithRepo { node, repo } = NodeWithRepo::default();
@@ -1185,6 +1320,183 @@ mod test {
         assert_eq!(r3.state, State::Rejected);
     }
 
+    #[test]
+    fn test_identity_person_delegate() {
+        let NodeWithRepo { node, repo } = NodeWithRepo::default();
+        let bob = Device::mock();
+        let laptop = Device::mock();
+        let eve = Device::mock();
+        let signer = &node.signer;
+
+        // Bob signs with his laptop too.
+        let person = *Persons::open(&*repo)
+            .unwrap()
+            .create([*laptop.public_key()], &bob)
+            .unwrap()
+            .id();
+
+        let mut identity = Identity::load_mut(&*repo).unwrap();
+        let mut doc = identity.doc().clone().edit();
+        doc.delegate(bob.public_key().into());
+        pin(&mut doc, bob.public_key().into(), person);
+        let r1 = identity
+            .update("Add Bob", "", &doc.clone().verified().unwrap(), signer)
+            .unwrap();
+        assert_eq!(identity.current, r1);
+
+        doc.visibility = Visibility::private([]);
+        let r2 = identity
+            .update("Make private", "", &doc.clone().verified().unwrap(), signer)
+            .unwrap();
+        assert_eq!(identity.current, r1);
+
+        // Keys that aren't devices of a delegate can't vote.
+        identity.accept(&r2, &eve).unwrap_err();
+        assert_eq!(identity.current, r1);
+
+        // Bob's laptop votes for Bob.
+        identity.accept(&r2, &laptop).unwrap();
+        assert_eq!(identity.current, r2);
+        assert_eq!(
+            identity
+                .revision(&r2)
+                .unwrap()
+                .accepted()
+                .collect::<BTreeSet<_>>(),
+            BTreeSet::from([signer.public_key().into(), bob.public_key().into()])
+        );
+        assert_eq!(repo.canonical_identity_head().unwrap(), r2);
+    }
+
+    #[test]
+    fn test_identity_person_removed_device() {
+        let NodeWithRepo { node, repo } = NodeWithRepo::default();
+        let bob = Device::mock();
+        let laptop = Device::mock();
+        let signer = &node.signer;
+
+        let mut persons = Persons::open(&*repo).unwrap();
+        let mut person = persons.create([*laptop.public_key()], &bob).unwrap();
+
+        let mut identity = Identity::load_mut(&*repo).unwrap();
+        let mut doc = identity.doc().clone().edit();
+        doc.delegate(bob.public_key().into());
+        pin(&mut doc, bob.public_key().into(), *person.id());
+        identity
+            .update("Add Bob", "", &doc.clone().verified().unwrap(), signer)
+            .unwrap();
+
+        doc.visibility = Visibility::private([]);
+        let r2 = identity
+            .update("Make private", "", &doc.clone().verified().unwrap(), signer)
+            .unwrap();
+
+        // Bob's laptop votes for Bob.
+        identity.accept(&r2, &laptop).unwrap();
+        assert_eq!(identity.current, r2);
+
+        // Bob removes his laptop, eg. after losing it.
+        person.rotate([], [*laptop.public_key()], &bob).unwrap();
+
+        // The vote was made by a device of Bob at the time, so it still counts.
+        identity.reload().unwrap();
+        assert_eq!(identity.current, r2);
+
+        doc.visibility = Visibility::Public;
+        let r3 = identity
+            .update("Make public", "", &doc.clone().verified().unwrap(), signer)
+            .unwrap();
+
+        // The laptop can't vote anymore.
+        identity.accept(&r3, &laptop).unwrap_err();
+        assert_ne!(identity.current, r3);
+
+        // Bob still votes with his remaining devices.
+        identity.accept(&r3, &bob).unwrap();
+        assert_eq!(identity.current, r3);
+    }
+
+    #[test]
+    fn test_identity_person_second_root() {
+        let NodeWithRepo { node, repo } = NodeWithRepo::default();
+        let bob = Device::mock();
+        let laptop = Device::mock();
+        let signer = &node.signer;
+
+        let mut persons = Persons::open(&*repo).unwrap();
+        let person = *persons.create([*laptop.public_key()], &bob).unwrap().id();
+        // Bob's key creates another person, without the laptop.
+        persons.create([], &bob).unwrap();
+
+        let mut identity = Identity::load_mut(&*repo).unwrap();
+        let mut doc = identity.doc().clone().edit();
+        doc.delegate(bob.public_key().into());
+        pin(&mut doc, bob.public_key().into(), person);
+        identity
+            .update("Add Bob", "", &doc.clone().verified().unwrap(), signer)
+            .unwrap();
+
+        doc.visibility = Visibility::private([]);
+        let r2 = identity
+            .update("Make private", "", &doc.clone().verified().unwrap(), signer)
+            .unwrap();
+
+        // The other person doesn't take the laptop away from Bob.
+        identity.accept(&r2, &laptop).unwrap();
+        assert_eq!(identity.current, r2);
+    }
+
+    #[test]
+    fn test_identity_person_reclaimed() {
+        let NodeWithRepo { node, repo } = NodeWithRepo::default();
+        let bob = Device::mock();
+        let laptop = Device::mock();
+        let eve = Device::mock();
+        let signer = &node.signer;
+
+        let mut persons = Persons::open(&*repo).unwrap();
+        let mut person = persons.create([*laptop.public_key()], &bob).unwrap();
+
+        let mut identity = Identity::load_mut(&*repo).unwrap();
+        let mut doc = identity.doc().clone().edit();
+        doc.delegate(bob.public_key().into());
+        pin(&mut doc, bob.public_key().into(), *person.id());
+        identity
+            .update("Add Bob", "", &doc.clone().verified().unwrap(), signer)
+            .unwrap();
+
+        // Bob's key is compromised, so his laptop revokes it.
+        person.rotate([], [*bob.public_key()], &laptop).unwrap();
+
+        // Eve uses the stolen key to claim Bob again, with her own device.
+        persons.create([*eve.public_key()], &bob).unwrap();
+
+        doc.visibility = Visibility::private([]);
+        let r2 = identity
+            .update("Make private", "", &doc.clone().verified().unwrap(), signer)
+            .unwrap();
+
+        // Neither the revoked key nor Eve's device vote for Bob.
+        identity.accept(&r2, &eve).unwrap_err();
+        identity.accept(&r2, &bob).unwrap_err();
+        assert_ne!(identity.current, r2);
+
+        // Bob still votes with his laptop.
+        identity.accept(&r2, &laptop).unwrap();
+        assert_eq!(identity.current, r2);
+    }
+
+    /// Pin the person of a delegate in the document.
+    fn pin(doc: &mut RawDoc, delegate: Did, person: PersonId) {
+        doc.payload.insert(
+            PayloadId::of::<DelegatePersons>(),
+            DelegatePersons {
+                persons: [(delegate, person)].into(),
+            }
+            .into(),
+        );
+    }
+
     #[test]
     fn test_identity_updates_concurrent() {
         let network = Network::default();
@@ -43,6 +43,8 @@ pub enum Error {
     Thread(#[from] thread::Error),
     #[error("store: {0}")]
     Store(#[from] store::Error),
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
     /// Action not authorized.
     #[error("{0} not authorized to apply {1:?}")]
     NotAuthorized(ActorId, Action),
@@ -127,6 +129,47 @@ impl State {
     }
 }
 
+/// Relation of an issue to a linked object.
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Relation {
+    /// The issue blocks the linked issue.
+    Blocks,
+    /// The issue is blocked by the linked issue.
+    BlockedBy,
+    /// The issue is a duplicate of the linked issue.
+    DuplicateOf,
+    /// The issue is fixed by the linked patch.
+    FixedBy,
+    /// The issue is referenced by the linked patch.
+    References,
+}
+
+impl std::fmt::Display for Relation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let relation = match self {
+            Self::Blocks => "blocks",
+            Self::BlockedBy => "blocked by",
+            Self::DuplicateOf => "duplicate of",
+            Self::FixedBy => "fixed by",
+            Self::References => "referenced by",
+        };
+        write!(f, "{relation}")
+    }
+}
+
+/// A link from an issue to another issue or patch.
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Link {
+    /// How the issue relates to the linked object.
+    pub relation: Relation,
+    /// The linked object.
+    pub object: ObjectId,
+    /// The actor who added the link.
+    pub author: ActorId,
+}
+
 /// Issue state. Accumulates [`Action`].
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -141,6 +184,9 @@ pub struct Issue {
     pub(super) labels: BTreeSet<Label>,
     /// Discussion around this issue.
     pub(super) thread: Thread,
+    /// Links to other issues and patches.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub(super) links: BTreeSet<Link>,
 }
 
 impl cob::store::CobWithType for Issue {
@@ -169,7 +215,7 @@ impl store::Cob for Issue {
         let mut issue = Issue::new(thread);
 
         for action in actions {
-            match issue.authorization(&action, &op.author, &doc)? {
+            match issue.authorization(&action, &op.author, &doc, repo)? {
                 Authorization::Allow => {
                     issue.action(action, op.id, op.author, op.timestamp, &[], &doc, repo)?;
                 }
@@ -246,6 +292,7 @@ impl Issue {
             state: State::default(),
             labels: BTreeSet::default(),
             thread,
+            links: BTreeSet::default(),
         }
     }
 
@@ -265,6 +312,23 @@ impl Issue {
         self.labels.iter()
     }
 
+    /// Links to other issues and patches.
+    pub fn links(&self) -> impl Iterator<Item = &Link> {
+        self.links.iter()
+    }
+
+    /// Check if the issue is linked to the given object with the given relation.
+    pub fn is_linked(&self, relation: Relation, object: &ObjectId) -> bool {
+        self.find_link(relation, object).is_some()
+    }
+
+    /// Get the link to the given object with the given relation, if any.
+    pub fn find_link(&self, relation: Relation, object: &ObjectId) -> Option<&Link> {
+        self.links
+            .iter()
+            .find(|link| link.relation == relation && link.object == *object)
+    }
+
     pub fn timestamp(&self) -> Timestamp {
         self.thread
             .comments()
@@ -320,16 +384,31 @@ impl Issue {
     }
 
     /// Apply authorization rules on issue actions.
-    pub fn authorization(
+    pub fn authorization<R: ReadRepository>(
         &self,
         action: &Action,
         actor: &ActorId,
         doc: &Doc,
+        repo: &R,
     ) -> Result<Authorization, Error> {
         if doc.is_delegate(&actor.into()) {
             // A delegate is authorized to do all actions.
             return Ok(Authorization::Allow);
         }
+        let outcome = self.actor_authorization(action, actor)?;
+        if outcome != Authorization::Allow && doc.delegate_of(actor, repo)?.is_some() {
+            // So are the devices of a delegate.
+            return Ok(Authorization::Allow);
+        }
+        Ok(outcome)
+    }
+
+    /// Authorization rules on issue actions, for actors that aren't delegates.
+    fn actor_authorization(
+        &self,
+        action: &Action,
+        actor: &ActorId,
+    ) -> Result<Authorization, Error> {
         let author: ActorId = *self.author().id().as_key();
         let outcome = match action {
             // Only delegate can assign someone to an issue.
@@ -373,6 +452,20 @@ impl Issue {
             }
             // All roles can react to a comment on an issue.
             Action::CommentReact { .. } => Authorization::Allow,
+            // Only issue authors can link their issue to a patch that fixes it, since
+            // merging that patch closes the issue.
+            Action::Link {
+                relation: Relation::FixedBy,
+                ..
+            } => Authorization::from(*actor == author),
+            // All roles can link an issue to other objects.
+            Action::Link { .. } => Authorization::Allow,
+            // Issue authors can remove any link from their own issue, and all roles can
+            // remove the links they added.
+            Action::Unlink { relation, object } => match self.find_link(*relation, object) {
+                Some(link) => Authorization::from(*actor == author || *actor == link.author),
+                None => Authorization::Unknown,
+            },
         };
         Ok(outcome)
     }
@@ -389,7 +482,7 @@ impl Issue {
         doc: &Doc,
         repo: &R,
     ) -> Result<(), Error> {
-        match self.authorization(&action, &author, doc)? {
+        match self.authorization(&action, &author, doc, repo)? {
             Authorization::Allow => {
                 self.action(action, id, author, timestamp, concurrent, doc, repo)
             }
@@ -458,6 +551,19 @@ impl Issue {
             } => {
                 thread::react(&mut self.thread, entry, author, id, reaction, active)?;
             }
+            Action::Link { relation, object } => {
+                if !self.is_linked(relation, &object) {
+                    self.links.insert(Link {
+                        relation,
+                        object,
+                        author,
+                    });
+                }
+            }
+            Action::Unlink { relation, object } => {
+                self.links
+                    .retain(|link| link.relation != relation || link.object != object);
+            }
         }
         Ok(())
     }
@@ -553,6 +659,16 @@ impl<R: ReadRepository> store::Transaction<Issue, R> {
         })
     }
 
+    /// Link the issue to another object.
+    pub fn link(&mut self, relation: Relation, object: ObjectId) -> Result<(), store::Error> {
+        self.push(Action::Link { relation, object })
+    }
+
+    /// Remove a link to another object.
+    pub fn unlink(&mut self, relation: Relation, object: ObjectId) -> Result<(), store::Error> {
+        self.push(Action::Unlink { relation, object })
+    }
+
     ////////////////////////////////////////////////////////////////////////////////////////////////
 
     /// Create the issue thread.
@@ -721,6 +837,32 @@ where
         self.transaction("React", signer, |tx| tx.react(to, reaction, active))
     }
 
+    /// Link the issue to another object.
+    pub fn link<G>(
+        &mut self,
+        relation: Relation,
+        object: ObjectId,
+        signer: &Device<G>,
+    ) -> Result<EntryId, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+    {
+        self.transaction("Link", signer, |tx| tx.link(relation, object))
+    }
+
+    /// Remove a link to another object.
+    pub fn unlink<G>(
+        &mut self,
+        relation: Relation,
+        object: ObjectId,
+        signer: &Device<G>,
+    ) -> Result<EntryId, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+    {
+        self.transaction("Unlink", signer, |tx| tx.unlink(relation, object))
+    }
+
     pub fn transaction<G, F>(
         &mut self,
         message: &str,
@@ -960,6 +1102,20 @@ pub enum Action {
         reaction: Reaction,
         active: bool,
     },
+
+    /// Link the issue to another issue or patch.
+    #[serde(rename = "link")]
+    Link {
+        relation: Relation,
+        object: ObjectId,
+    },
+
+    /// Remove a link to another issue or patch.
+    #[serde(rename = "unlink")]
+    Unlink {
+        relation: Relation,
+        object: ObjectId,
+    },
 }
 
 impl CobAction for Action {
@@ -1407,6 +1563,126 @@ mod test {
         assert!(labels.contains(&wontfix_label));
     }
 
+    #[test]
+    fn test_issue_link() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut issues = Cache::no_cache(&*repo).unwrap();
+        let other = issues
+            .create("Other issue", "Blah blah blah.", &[], &[], [], &node.signer)
+            .unwrap()
+            .id;
+        let patch = ObjectId::from(arbitrary::oid());
+        let mut issue = issues
+            .create(
+                "My first issue",
+                "Blah blah blah.",
+                &[],
+                &[],
+                [],
+                &node.signer,
+            )
+            .unwrap();
+
+        issue
+            .link(Relation::BlockedBy, other, &node.signer)
+            .unwrap();
+        issue.link(Relation::FixedBy, patch, &node.signer).unwrap();
+
+        let id = issue.id;
+        let issue = issues.get(&id).unwrap().unwrap();
+        assert!(issue.is_linked(Relation::BlockedBy, &other));
+        assert!(issue.is_linked(Relation::FixedBy, &patch));
+        assert!(!issue.is_linked(Relation::References, &patch));
+        assert_eq!(issue.links().count(), 2);
+
+        let mut issue = issues.get_mut(&id).unwrap();
+        issue
+            .unlink(Relation::BlockedBy, other, &node.signer)
+            .unwrap();
+
+        let issue = issues.get(&id).unwrap().unwrap();
+        assert!(!issue.is_linked(Relation::BlockedBy, &other));
+        assert_eq!(
+            issue.links().collect::<Vec<_>>(),
+            vec![&Link {
+                relation: Relation::FixedBy,
+                object: patch,
+                author: *node.signer.public_key(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_issue_link_authorization() {
+        let t = test::setup::Network::default();
+        let mut bob_issues = Cache::no_cache(&*t.bob.repo).unwrap();
+        let mut eve_issues = Cache::no_cache(&*t.eve.repo).unwrap();
+        let other = ObjectId::from(arbitrary::oid());
+        let patch = ObjectId::from(arbitrary::oid());
+        let id = bob_issues
+            .create(
+                "Bob's issue",
+                "Blah blah blah.",
+                &[],
+                &[],
+                [],
+                &t.bob.signer,
+            )
+            .unwrap()
+            .id;
+
+        t.eve.repo.fetch(&t.bob);
+
+        // Anyone can link the issue, but only its author can say it's fixed by a patch.
+        let mut issue = eve_issues.get_mut(&id).unwrap();
+        issue
+            .link(Relation::BlockedBy, other, &t.eve.signer)
+            .unwrap();
+        issue
+            .link(Relation::FixedBy, patch, &t.eve.signer)
+            .unwrap_err();
+        issue.reload().unwrap();
+        assert!(issue.is_linked(Relation::BlockedBy, &other));
+        assert!(!issue.is_linked(Relation::FixedBy, &patch));
+
+        // Links can be removed by whoever added them.
+        issue
+            .unlink(Relation::BlockedBy, other, &t.eve.signer)
+            .unwrap();
+        issue
+            .link(Relation::References, patch, &t.eve.signer)
+            .unwrap();
+        assert!(!issue.is_linked(Relation::BlockedBy, &other));
+
+        // And by the issue author.
+        t.bob.repo.fetch(&t.eve);
+
+        let mut issue = bob_issues.get_mut(&id).unwrap();
+        assert_eq!(
+            issue
+                .find_link(Relation::References, &patch)
+                .unwrap()
+                .author,
+            *t.eve.signer.public_key()
+        );
+        issue
+            .unlink(Relation::References, patch, &t.bob.signer)
+            .unwrap();
+        issue.link(Relation::FixedBy, patch, &t.bob.signer).unwrap();
+        assert!(!issue.is_linked(Relation::References, &patch));
+        assert!(issue.is_linked(Relation::FixedBy, &patch));
+
+        // But not by others.
+        t.eve.repo.fetch(&t.bob);
+
+        let mut issue = eve_issues.get_mut(&id).unwrap();
+        issue
+            .unlink(Relation::FixedBy, patch, &t.eve.signer)
+            .unwrap_err();
+        issue.reload().unwrap();
+        assert!(issue.is_linked(Relation::FixedBy, &patch));
+    }
+
     #[test]
     fn test_issue_comment() {
         let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
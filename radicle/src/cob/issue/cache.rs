@@ -6,7 +6,9 @@ use thiserror::Error;
 
 use crate::cob;
 use crate::cob::cache;
+use crate::cob::cache::search::{self, SearchText, Searchable};
 use crate::cob::cache::{Remove, StoreReader, StoreWriter, Update};
+use crate::cob::filter::Filter;
 use crate::cob::store;
 use crate::cob::{Embed, Label, ObjectId, TypeName, Uri};
 use crate::node::device::Device;
@@ -37,6 +39,17 @@ pub trait Issues {
     /// Also see [`Issues::opened`], [`Issues::solved`] and [`Issues::closed`].
     fn list_by_status(&self, status: &State) -> Result<Self::Iter<'_>, Self::Error>;
 
+    /// List all issues in the store that link to the given object, eg. a patch.
+    fn linked(&self, object: &ObjectId) -> Result<Self::Iter<'_>, Self::Error>;
+
+    /// Search the titles, descriptions and comments of all issues in the
+    /// store. All terms of the query must match; terms ending with `*`
+    /// match as prefixes.
+    fn search(&self, query: &str) -> Result<Self::Iter<'_>, Self::Error>;
+
+    /// List all issues in the store that match the given [`Filter`].
+    fn filter(&self, filter: &Filter) -> Result<Self::Iter<'_>, Self::Error>;
+
     /// Get the [`IssueCounts`] of all the issues in the store.
     fn counts(&self) -> Result<IssueCounts, Self::Error>;
 
@@ -328,7 +341,10 @@ impl Update<Issue> for StoreWriter {
         stmt.bind((3, sql::Value::String(serde_json::to_string(&object)?)))?;
         stmt.next()?;
 
-        Ok(self.db.change_count() > 0)
+        let updated = self.db.change_count() > 0;
+        search::index(&self.db, "issues_search", rid, id, object)?;
+
+        Ok(updated)
     }
 }
 
@@ -337,6 +353,12 @@ impl Remove<Issue> for StoreWriter {
     type RemoveError = sql::Error;
 
     fn remove(&mut self, id: &ObjectId) -> Result<Self::Out, Self::RemoveError> {
+        let mut stmt = self.db.prepare("SELECT repo FROM issues WHERE id = ?1")?;
+        stmt.bind((1, sql::Value::String(id.to_string())))?;
+        let rid = match stmt.into_iter().next() {
+            Some(row) => RepoId::from_str(row?.read::<&str, _>("repo")).ok(),
+            None => None,
+        };
         let mut stmt = self.db.prepare(
             "DELETE FROM issues
              WHERE id = ?1",
@@ -345,7 +367,12 @@ impl Remove<Issue> for StoreWriter {
         stmt.bind((1, sql::Value::String(id.to_string())))?;
         stmt.next()?;
 
-        Ok(self.db.change_count() > 0)
+        let removed = self.db.change_count() > 0;
+        if let Some(rid) = rid {
+            search::unindex(&self.db, "issues_search", &rid, id)?;
+        }
+
+        Ok(removed)
     }
 
     fn remove_all(&mut self, rid: &RepoId) -> Result<Self::Out, Self::RemoveError> {
@@ -357,7 +384,10 @@ impl Remove<Issue> for StoreWriter {
         stmt.bind((1, rid))?;
         stmt.next()?;
 
-        Ok(self.db.change_count() > 0)
+        let removed = self.db.change_count() > 0;
+        search::unindex_all(&self.db, "issues_search", rid)?;
+
+        Ok(removed)
     }
 }
 
@@ -409,11 +439,70 @@ where
             .map_err(super::Error::from)
     }
 
+    fn linked(&self, object: &ObjectId) -> Result<Self::Iter<'_>, Self::Error> {
+        let object = *object;
+        self.store
+            .all()
+            .map(move |inner| NoCacheIter {
+                inner: Box::new(inner.into_iter().filter_map(move |res| match res {
+                    Ok((id, issue)) => {
+                        let linked = issue.links().any(|link| link.object == object);
+                        linked.then_some((id, issue)).map(Ok)
+                    }
+                    Err(e) => Some(Err(e.into())),
+                })),
+            })
+            .map_err(super::Error::from)
+    }
+
+    fn search(&self, query: &str) -> Result<Self::Iter<'_>, Self::Error> {
+        let query = query.to_owned();
+        self.store
+            .all()
+            .map(move |inner| NoCacheIter {
+                inner: Box::new(inner.into_iter().filter_map(move |res| match res {
+                    Ok((id, issue)) => {
+                        let found = issue.search_text().matches(&query);
+                        found.then_some((id, issue)).map(Ok)
+                    }
+                    Err(e) => Some(Err(e.into())),
+                })),
+            })
+            .map_err(super::Error::from)
+    }
+
+    fn filter(&self, filter: &Filter) -> Result<Self::Iter<'_>, Self::Error> {
+        let filter = filter.clone();
+        self.store
+            .all()
+            .map(move |inner| NoCacheIter {
+                inner: Box::new(inner.into_iter().filter_map(move |res| match res {
+                    Ok((id, issue)) => filter.matches(&issue).then_some((id, issue)).map(Ok),
+                    Err(e) => Some(Err(e.into())),
+                })),
+            })
+            .map_err(super::Error::from)
+    }
+
     fn counts(&self) -> Result<IssueCounts, Self::Error> {
         self.store.counts()
     }
 }
 
+impl Searchable for Issue {
+    fn search_text(&self) -> SearchText {
+        // Nb. Issues in the cache are expected to have a root comment, but
+        // we don't rely on it here.
+        let mut comments = self.comments().map(|(_, c)| c.body());
+
+        SearchText {
+            title: self.title().to_owned(),
+            description: comments.next().unwrap_or_default().to_owned(),
+            comments: comments.collect::<Vec<_>>().join("\n"),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("object `{1}` of type `{0}` was not found")]
@@ -473,6 +562,18 @@ where
         query::list_by_status(&self.cache.db, &self.rid(), status)
     }
 
+    fn linked(&self, object: &ObjectId) -> Result<Self::Iter<'_>, Self::Error> {
+        query::linked(&self.cache.db, &self.rid(), object)
+    }
+
+    fn search(&self, query: &str) -> Result<Self::Iter<'_>, Self::Error> {
+        query::search(&self.cache.db, &self.rid(), query)
+    }
+
+    fn filter(&self, filter: &Filter) -> Result<Self::Iter<'_>, Self::Error> {
+        query::filter(&self.cache.db, &self.rid(), filter)
+    }
+
     fn counts(&self) -> Result<IssueCounts, Self::Error> {
         query::counts(&self.cache.db, &self.rid())
     }
@@ -500,6 +601,18 @@ where
         query::list_by_status(&self.cache.db, &self.rid(), status)
     }
 
+    fn linked(&self, object: &ObjectId) -> Result<Self::Iter<'_>, Self::Error> {
+        query::linked(&self.cache.db, &self.rid(), object)
+    }
+
+    fn search(&self, query: &str) -> Result<Self::Iter<'_>, Self::Error> {
+        query::search(&self.cache.db, &self.rid(), query)
+    }
+
+    fn filter(&self, filter: &Filter) -> Result<Self::Iter<'_>, Self::Error> {
+        query::filter(&self.cache.db, &self.rid(), filter)
+    }
+
     fn counts(&self) -> Result<IssueCounts, Self::Error> {
         query::counts(&self.cache.db, &self.rid())
     }
@@ -572,6 +685,89 @@ mod query {
         })
     }
 
+    pub(super) fn linked<'a>(
+        db: &'a sql::ConnectionThreadSafe,
+        rid: &RepoId,
+        object: &ObjectId,
+    ) -> Result<IssuesIter<'a>, Error> {
+        let mut stmt = db.prepare(
+            "SELECT id, issue
+             FROM issues
+             WHERE repo = ?1
+             AND EXISTS (
+                 SELECT 1 FROM json_each(issue->'$.links')
+                 WHERE value->>'$.object' = ?2
+             )
+             ORDER BY id
+            ",
+        )?;
+        stmt.bind((1, rid))?;
+        stmt.bind((2, sql::Value::String(object.to_string())))?;
+        Ok(IssuesIter {
+            inner: stmt.into_iter(),
+        })
+    }
+
+    pub(super) fn search<'a>(
+        db: &'a sql::ConnectionThreadSafe,
+        rid: &RepoId,
+        query: &str,
+    ) -> Result<IssuesIter<'a>, Error> {
+        let fts5 = search::is_fts5(db, "issues_search")?;
+        let mut values = Vec::new();
+        let Some(expr) = search::sql("issues_search", fts5, query, |value| {
+            values.push(value);
+            format!("?{}", values.len() + 1)
+        }) else {
+            return list(db, rid);
+        };
+        let order = if fts5 {
+            "issues_search.rank"
+        } else {
+            "issues.id"
+        };
+        let mut stmt = db.prepare(format!(
+            "SELECT issues.id, issues.issue
+             FROM issues_search
+             JOIN issues ON issues.id = issues_search.id AND issues.repo = issues_search.repo
+             WHERE issues_search.repo = ?1
+             AND {expr}
+             ORDER BY {order}
+            "
+        ))?;
+        stmt.bind((1, rid))?;
+        for (i, value) in values.into_iter().enumerate() {
+            stmt.bind((i + 2, value.as_str()))?;
+        }
+        Ok(IssuesIter {
+            inner: stmt.into_iter(),
+        })
+    }
+
+    pub(super) fn filter<'a>(
+        db: &'a sql::ConnectionThreadSafe,
+        rid: &RepoId,
+        filter: &Filter,
+    ) -> Result<IssuesIter<'a>, Error> {
+        let fts5 = search::is_fts5(db, "issues_search")?;
+        let (expr, values) = filter.sql::<Issue>(2, fts5);
+        let mut stmt = db.prepare(format!(
+            "SELECT id, issue
+             FROM issues
+             WHERE repo = ?1
+             AND {expr}
+             ORDER BY id
+            "
+        ))?;
+        stmt.bind((1, rid))?;
+        for (i, value) in values.into_iter().enumerate() {
+            stmt.bind((i + 2, value))?;
+        }
+        Ok(IssuesIter {
+            inner: stmt.into_iter(),
+        })
+    }
+
     pub(super) fn counts(
         db: &sql::ConnectionThreadSafe,
         rid: &RepoId,
@@ -609,9 +805,12 @@ mod tests {
     use radicle_cob::ObjectId;
 
     use crate::cob::cache::{Store, Update, Write};
+    use crate::cob::filter::Filter;
     use crate::cob::migrate;
-    use crate::cob::thread::Thread;
-    use crate::issue::{CloseReason, Issue, IssueCounts, IssueId, State};
+    use crate::cob::thread::{Comment, Thread};
+    use crate::cob::{Label, Timestamp};
+    use crate::issue::{CloseReason, Issue, IssueCounts, IssueId, Link, Relation, State};
+    use crate::prelude::Did;
     use crate::test::arbitrary;
     use crate::test::storage::MockRepository;
 
@@ -783,6 +982,219 @@ mod tests {
         assert_eq!(issues, list);
     }
 
+    #[test]
+    fn test_linked() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut cache = memory(repo);
+        let patch = ObjectId::from(arbitrary::oid());
+        let linked = IssueId::from(arbitrary::oid());
+        let unlinked = IssueId::from(arbitrary::oid());
+        let issue = Issue {
+            links: BTreeSet::from_iter([Link {
+                relation: Relation::FixedBy,
+                object: patch,
+                author: arbitrary::gen(1),
+            }]),
+            ..Issue::new(Thread::default())
+        };
+        cache.update(&cache.rid(), &linked, &issue).unwrap();
+        cache
+            .update(&cache.rid(), &unlinked, &Issue::new(Thread::default()))
+            .unwrap();
+
+        let list = cache
+            .linked(&patch)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(list, vec![(linked, issue)]);
+        assert_eq!(cache.linked(&linked).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_search() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut cache = memory(repo);
+        let author = arbitrary::gen::<Did>(1);
+        let issue = |title: &str, bodies: &[&str]| {
+            let mut comments = bodies.iter().map(|body| {
+                (
+                    arbitrary::oid(),
+                    Comment::new(
+                        *author,
+                        body.to_string(),
+                        None,
+                        None,
+                        vec![],
+                        Timestamp::from_secs(0),
+                    ),
+                )
+            });
+            let (id, root) = comments.next().unwrap();
+            let mut thread = Thread::new(id, root);
+            for (id, comment) in comments {
+                thread.comments.insert(id, Some(comment));
+                thread.timeline.push(id);
+            }
+            Issue {
+                title: title.to_owned(),
+                ..Issue::new(thread)
+            }
+        };
+        let flux = IssueId::from(arbitrary::oid());
+        let speed = IssueId::from(arbitrary::oid());
+
+        cache
+            .update(
+                &cache.rid(),
+                &flux,
+                &issue(
+                    "Flux capacitor underpowered",
+                    &[
+                        "Power requirements exceed supply",
+                        "It needs 1.21 gigawatts",
+                    ],
+                ),
+            )
+            .unwrap();
+        cache
+            .update(
+                &cache.rid(),
+                &speed,
+                &issue("Speedometer is broken", &["Stuck at 88 mph"]),
+            )
+            .unwrap();
+
+        let search = |query: &str| {
+            cache
+                .search(query)
+                .unwrap()
+                .map(|r| r.map(|(id, _)| id))
+                .collect::<Result<BTreeSet<_>, _>>()
+                .unwrap()
+        };
+        assert_eq!(search("flux"), BTreeSet::from([flux]));
+        assert_eq!(search("GIGAWATTS"), BTreeSet::from([flux]));
+        assert_eq!(search("requirement"), BTreeSet::from([flux]));
+        assert_eq!(search("speedo*"), BTreeSet::from([speed]));
+        assert_eq!(search("flux 88"), BTreeSet::new());
+        assert_eq!(search("is OR \"AND"), BTreeSet::new());
+        assert_eq!(search(""), BTreeSet::from([flux, speed]));
+
+        super::Remove::remove(&mut cache, &flux).unwrap();
+        assert_eq!(cache.search("flux").unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_filter() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut cache = memory(repo);
+        let alice = arbitrary::gen::<Did>(1);
+        let bob = arbitrary::gen::<Did>(1);
+        let issue = |author: &Did, title: &str, comments: &[(&str, u64)]| {
+            let mut comments = comments.iter().map(|(body, time)| {
+                (
+                    arbitrary::oid(),
+                    Comment::new(
+                        **author,
+                        body.to_string(),
+                        None,
+                        None,
+                        vec![],
+                        Timestamp::from_secs(*time),
+                    ),
+                )
+            });
+            let (id, root) = comments.next().unwrap();
+            let mut thread = Thread::new(id, root);
+            for (id, comment) in comments {
+                thread.comments.insert(id, Some(comment));
+                thread.timeline.push(id);
+            }
+            Issue {
+                title: title.to_owned(),
+                ..Issue::new(thread)
+            }
+        };
+        let bug = IssueId::from(arbitrary::oid());
+        let docs = IssueId::from(arbitrary::oid());
+
+        cache
+            .update(
+                &cache.rid(),
+                &bug,
+                &Issue {
+                    labels: BTreeSet::from([Label::new("bug").unwrap()]),
+                    assignees: BTreeSet::from([bob]),
+                    ..issue(
+                        &alice,
+                        "Flux capacitor underpowered",
+                        // 2024-01-01 and 2024-03-01.
+                        &[("Needs power", 1704067200), ("1.21 gigawatts", 1709251200)],
+                    )
+                },
+            )
+            .unwrap();
+        cache
+            .update(
+                &cache.rid(),
+                &docs,
+                &Issue {
+                    labels: BTreeSet::from([
+                        Label::new("docs").unwrap(),
+                        Label::new("wontfix").unwrap(),
+                    ]),
+                    state: State::Closed {
+                        reason: CloseReason::Solved,
+                    },
+                    // 2023-06-01.
+                    ..issue(&bob, "Document the speedometer", &[("Please", 1685577600)])
+                },
+            )
+            .unwrap();
+
+        let all = cache
+            .list()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let filter = |filter: &str| {
+            let filter = Filter::from_str(filter).unwrap();
+            let found = cache
+                .filter(&filter)
+                .unwrap()
+                .map(|r| r.map(|(id, _)| id))
+                .collect::<Result<BTreeSet<_>, _>>()
+                .unwrap();
+            let matched = all
+                .iter()
+                .filter(|(_, issue)| filter.matches(issue))
+                .map(|(id, _)| *id)
+                .collect::<BTreeSet<_>>();
+            assert_eq!(
+                found, matched,
+                "filter `{filter}` doesn't match in the cache"
+            );
+
+            found
+        };
+        assert_eq!(filter(""), BTreeSet::from([bug, docs]));
+        assert_eq!(filter("label:bug"), BTreeSet::from([bug]));
+        assert_eq!(filter("-label:wontfix"), BTreeSet::from([bug]));
+        assert_eq!(filter(&format!("author:{alice}")), BTreeSet::from([bug]));
+        assert_eq!(filter(&format!("assignee:{bob}")), BTreeSet::from([bug]));
+        assert_eq!(filter("state:open"), BTreeSet::from([bug]));
+        assert_eq!(filter("state:closed"), BTreeSet::from([docs]));
+        assert_eq!(filter("state:solved"), BTreeSet::from([docs]));
+        assert_eq!(filter("state:merged"), BTreeSet::new());
+        assert_eq!(filter("created:<2024-01-01"), BTreeSet::from([docs]));
+        assert_eq!(filter("created:2024-01-01"), BTreeSet::from([bug]));
+        assert_eq!(filter("updated:>=2024-02-01"), BTreeSet::from([bug]));
+        assert_eq!(filter("updated:<=2023-06-01"), BTreeSet::from([docs]));
+        assert_eq!(filter("gigawatts"), BTreeSet::from([bug]));
+        assert_eq!(filter("-speedometer state:closed"), BTreeSet::new());
+    }
+
     #[test]
     fn test_remove() {
         let repo = arbitrary::gen::<MockRepository>(1);
@@ -0,0 +1,832 @@
+pub mod cache;
+
+use std::collections::BTreeSet;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cob;
+use crate::cob::common::{Author, Authorization, Timestamp};
+use crate::cob::issue::IssueId;
+use crate::cob::patch::PatchId;
+use crate::cob::store::Transaction;
+use crate::cob::store::{Cob, CobAction};
+use crate::cob::{op, store, ActorId, EntryId, ObjectId, TypeName};
+use crate::identity::doc::DocError;
+use crate::node::device::Device;
+use crate::node::NodeId;
+use crate::prelude::{Doc, ReadRepository, RepoId};
+use crate::storage::{HasRepoId, RepositoryError, WriteRepository};
+
+pub use cache::Cache;
+
+/// Milestone operation.
+pub type Op = cob::Op<Action>;
+
+/// Type name of a milestone.
+pub static TYPENAME: Lazy<TypeName> =
+    Lazy::new(|| FromStr::from_str("xyz.radicle.milestone").expect("type name is valid"));
+
+/// Identifier for a milestone.
+pub type MilestoneId = ObjectId;
+
+/// Error updating or creating milestones.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Error loading the identity document.
+    #[error("identity doc failed to load: {0}")]
+    Doc(#[from] DocError),
+    #[error("store: {0}")]
+    Store(#[from] store::Error),
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+    /// Action not authorized.
+    #[error("{0} not authorized to apply {1:?}")]
+    NotAuthorized(ActorId, Action),
+    /// Title is invalid.
+    #[error("invalid title: {0:?}")]
+    InvalidTitle(String),
+    /// The identity doc is missing.
+    #[error("identity document missing")]
+    MissingIdentity,
+    /// General error initializing a milestone.
+    #[error("initialization failed: {0}")]
+    Init(&'static str),
+    /// Error decoding an operation.
+    #[error("op decoding failed: {0}")]
+    Op(#[from] op::OpEncodingError),
+    #[error("failed to update milestone {id} in cache: {err}")]
+    CacheUpdate {
+        id: MilestoneId,
+        #[source]
+        err: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("failed to remove milestone {id} from cache : {err}")]
+    CacheRemove {
+        id: MilestoneId,
+        #[source]
+        err: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("failed to remove milestones from cache: {err}")]
+    CacheRemoveAll {
+        #[source]
+        err: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+}
+
+/// Milestone state.
+#[derive(Debug, Default, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum State {
+    /// The milestone is closed.
+    Closed,
+    /// The milestone is open.
+    #[default]
+    Open,
+}
+
+impl std::fmt::Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Closed => write!(f, "closed"),
+            Self::Open => write!(f, "open"),
+        }
+    }
+}
+
+impl State {
+    pub fn lifecycle_message(self) -> String {
+        match self {
+            Self::Open => "Open milestone".to_owned(),
+            Self::Closed => "Close milestone".to_owned(),
+        }
+    }
+}
+
+/// Milestone state. Accumulates [`Action`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Milestone {
+    /// Title of the milestone.
+    pub(super) title: String,
+    /// Description of the milestone.
+    pub(super) description: String,
+    /// When the milestone is due, if ever.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) due: Option<Timestamp>,
+    /// Current state of the milestone.
+    pub(super) state: State,
+    /// Issues attached to the milestone.
+    pub(super) issues: BTreeSet<IssueId>,
+    /// Patches attached to the milestone.
+    pub(super) patches: BTreeSet<PatchId>,
+    /// Author of the milestone.
+    pub(super) author: Author,
+    /// Time at which the milestone was created.
+    pub(super) timestamp: Timestamp,
+}
+
+impl cob::store::CobWithType for Milestone {
+    fn type_name() -> &'static TypeName {
+        &TYPENAME
+    }
+}
+
+impl store::Cob for Milestone {
+    type Action = Action;
+    type Error = Error;
+
+    fn from_root<R: ReadRepository>(op: Op, repo: &R) -> Result<Self, Self::Error> {
+        let doc = op.identity_doc(repo)?.ok_or(Error::MissingIdentity)?;
+        let mut actions = op.actions.into_iter();
+        let Some(Action::Edit { title, description }) = actions.next() else {
+            return Err(Error::Init("the first action must be of type `edit`"));
+        };
+        let mut milestone = Milestone::new(Author::new(op.author), op.timestamp);
+
+        milestone.action(Action::Edit { title, description })?;
+
+        for action in actions {
+            match milestone.authorization(&action, &op.author, &doc, repo)? {
+                Authorization::Allow => {
+                    milestone.action(action)?;
+                }
+                Authorization::Deny => {
+                    return Err(Error::NotAuthorized(op.author, action));
+                }
+                Authorization::Unknown => {
+                    continue;
+                }
+            }
+        }
+        Ok(milestone)
+    }
+
+    fn op<'a, R: ReadRepository, I: IntoIterator<Item = &'a cob::Entry>>(
+        &mut self,
+        op: Op,
+        _concurrent: I,
+        repo: &R,
+    ) -> Result<(), Error> {
+        let doc = op.identity_doc(repo)?.ok_or(Error::MissingIdentity)?;
+
+        for action in op.actions {
+            log::trace!(target: "milestone", "Applying {} {action:?}", op.id);
+
+            match self.authorization(&action, &op.author, &doc, repo)? {
+                Authorization::Allow => {
+                    if let Err(e) = self.action(action) {
+                        log::error!(target: "milestone", "Error applying {}: {e}", op.id);
+                        return Err(e);
+                    }
+                }
+                Authorization::Deny => return Err(Error::NotAuthorized(op.author, action)),
+                Authorization::Unknown => continue,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: ReadRepository> cob::Evaluate<R> for Milestone {
+    type Error = Error;
+
+    fn init(entry: &cob::Entry, repo: &R) -> Result<Self, Self::Error> {
+        let op = Op::try_from(entry)?;
+        let object = Milestone::from_root(op, repo)?;
+
+        Ok(object)
+    }
+
+    fn apply<'a, I: Iterator<Item = (&'a EntryId, &'a cob::Entry)>>(
+        &mut self,
+        entry: &cob::Entry,
+        concurrent: I,
+        repo: &R,
+    ) -> Result<(), Self::Error> {
+        let op = Op::try_from(entry)?;
+
+        self.op(op, concurrent.map(|(_, e)| e), repo)
+    }
+}
+
+impl Milestone {
+    /// Construct a new, empty milestone.
+    pub fn new(author: Author, timestamp: Timestamp) -> Self {
+        Self {
+            title: String::default(),
+            description: String::default(),
+            due: None,
+            state: State::default(),
+            issues: BTreeSet::default(),
+            patches: BTreeSet::default(),
+            author,
+            timestamp,
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn description(&self) -> &str {
+        self.description.as_str()
+    }
+
+    pub fn due(&self) -> Option<Timestamp> {
+        self.due
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Issues attached to this milestone.
+    pub fn issues(&self) -> impl Iterator<Item = &IssueId> {
+        self.issues.iter()
+    }
+
+    /// Patches attached to this milestone.
+    pub fn patches(&self) -> impl Iterator<Item = &PatchId> {
+        self.patches.iter()
+    }
+
+    /// Check whether the given issue or patch is attached to this milestone.
+    pub fn is_attached(&self, object: &ObjectId) -> bool {
+        self.issues.contains(object) || self.patches.contains(object)
+    }
+
+    pub fn author(&self) -> &Author {
+        &self.author
+    }
+
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    /// Apply authorization rules on milestone actions.
+    pub fn authorization<R: ReadRepository>(
+        &self,
+        _action: &Action,
+        actor: &ActorId,
+        doc: &Doc,
+        repo: &R,
+    ) -> Result<Authorization, Error> {
+        if doc.is_delegate(&actor.into()) {
+            // A delegate is authorized to do all actions.
+            return Ok(Authorization::Allow);
+        }
+        // Milestone authors can do anything with their own milestone.
+        if actor == self.author.public_key() {
+            return Ok(Authorization::Allow);
+        }
+        // Devices of a delegate can do all actions too.
+        Ok(Authorization::from(doc.delegate_of(actor, repo)?.is_some()))
+    }
+
+    /// Apply a single action to the milestone.
+    fn action(&mut self, action: Action) -> Result<(), Error> {
+        match action {
+            Action::Edit { title, description } => {
+                if title.contains('\n') || title.contains('\r') {
+                    return Err(Error::InvalidTitle(title));
+                }
+                self.title = title;
+                self.description = description;
+            }
+            Action::Due { due } => {
+                self.due = due.map(Timestamp::from_secs);
+            }
+            Action::Lifecycle { state } => {
+                self.state = state;
+            }
+            Action::Attach { issues, patches } => {
+                self.issues.extend(issues);
+                self.patches.extend(patches);
+            }
+            Action::Detach { issues, patches } => {
+                self.issues.retain(|id| !issues.contains(id));
+                self.patches.retain(|id| !patches.contains(id));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, 'g, R, C> From<MilestoneMut<'a, 'g, R, C>> for (MilestoneId, Milestone) {
+    fn from(value: MilestoneMut<'a, 'g, R, C>) -> Self {
+        (value.id, value.milestone)
+    }
+}
+
+impl<R: ReadRepository> store::Transaction<Milestone, R> {
+    /// Set the milestone title and description.
+    pub fn edit(
+        &mut self,
+        title: impl ToString,
+        description: impl ToString,
+    ) -> Result<(), store::Error> {
+        self.push(Action::Edit {
+            title: title.to_string(),
+            description: description.to_string(),
+        })
+    }
+
+    /// Set or unset the milestone due date.
+    pub fn due(&mut self, due: Option<Timestamp>) -> Result<(), store::Error> {
+        self.push(Action::Due {
+            due: due.map(|t| t.as_secs()),
+        })
+    }
+
+    /// Lifecycle a milestone.
+    pub fn lifecycle(&mut self, state: State) -> Result<(), store::Error> {
+        self.push(Action::Lifecycle { state })
+    }
+
+    /// Attach issues and patches to the milestone.
+    pub fn attach(
+        &mut self,
+        issues: impl IntoIterator<Item = IssueId>,
+        patches: impl IntoIterator<Item = PatchId>,
+    ) -> Result<(), store::Error> {
+        self.push(Action::Attach {
+            issues: issues.into_iter().collect(),
+            patches: patches.into_iter().collect(),
+        })
+    }
+
+    /// Detach issues and patches from the milestone.
+    pub fn detach(
+        &mut self,
+        issues: impl IntoIterator<Item = IssueId>,
+        patches: impl IntoIterator<Item = PatchId>,
+    ) -> Result<(), store::Error> {
+        self.push(Action::Detach {
+            issues: issues.into_iter().collect(),
+            patches: patches.into_iter().collect(),
+        })
+    }
+}
+
+pub struct MilestoneMut<'a, 'g, R, C> {
+    id: ObjectId,
+    milestone: Milestone,
+    store: &'g mut Milestones<'a, R>,
+    cache: &'g mut C,
+}
+
+impl<R, C> std::fmt::Debug for MilestoneMut<'_, '_, R, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("MilestoneMut")
+            .field("id", &self.id)
+            .field("milestone", &self.milestone)
+            .finish()
+    }
+}
+
+impl<R, C> MilestoneMut<'_, '_, R, C>
+where
+    R: WriteRepository + cob::Store<Namespace = NodeId>,
+    C: cob::cache::Update<Milestone>,
+{
+    /// Reload the milestone data from storage.
+    pub fn reload(&mut self) -> Result<(), store::Error> {
+        self.milestone = self
+            .store
+            .get(&self.id)?
+            .ok_or_else(|| store::Error::NotFound(TYPENAME.clone(), self.id))?;
+
+        Ok(())
+    }
+
+    /// Get the milestone id.
+    pub fn id(&self) -> &ObjectId {
+        &self.id
+    }
+
+    /// Set the milestone title and description.
+    pub fn edit<G>(
+        &mut self,
+        title: impl ToString,
+        description: impl ToString,
+        signer: &Device<G>,
+    ) -> Result<EntryId, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+    {
+        self.transaction("Edit", signer, |tx| tx.edit(title, description))
+    }
+
+    /// Set or unset the milestone due date.
+    pub fn due<G>(&mut self, due: Option<Timestamp>, signer: &Device<G>) -> Result<EntryId, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+    {
+        self.transaction("Due", signer, |tx| tx.due(due))
+    }
+
+    /// Lifecycle a milestone.
+    pub fn lifecycle<G>(&mut self, state: State, signer: &Device<G>) -> Result<EntryId, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+    {
+        self.transaction("Lifecycle", signer, |tx| tx.lifecycle(state))
+    }
+
+    /// Attach issues and patches to the milestone.
+    pub fn attach<G>(
+        &mut self,
+        issues: impl IntoIterator<Item = IssueId>,
+        patches: impl IntoIterator<Item = PatchId>,
+        signer: &Device<G>,
+    ) -> Result<EntryId, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+    {
+        self.transaction("Attach", signer, |tx| tx.attach(issues, patches))
+    }
+
+    /// Detach issues and patches from the milestone.
+    pub fn detach<G>(
+        &mut self,
+        issues: impl IntoIterator<Item = IssueId>,
+        patches: impl IntoIterator<Item = PatchId>,
+        signer: &Device<G>,
+    ) -> Result<EntryId, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+    {
+        self.transaction("Detach", signer, |tx| tx.detach(issues, patches))
+    }
+
+    pub fn transaction<G, F>(
+        &mut self,
+        message: &str,
+        signer: &Device<G>,
+        operations: F,
+    ) -> Result<EntryId, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+        F: FnOnce(&mut Transaction<Milestone, R>) -> Result<(), store::Error>,
+    {
+        let mut tx = Transaction::default();
+        operations(&mut tx)?;
+
+        let (milestone, commit) = tx.commit(message, self.id, &mut self.store.raw, signer)?;
+        self.cache
+            .update(&self.store.as_ref().id(), &self.id, &milestone)
+            .map_err(|e| Error::CacheUpdate {
+                id: self.id,
+                err: e.into(),
+            })?;
+        self.milestone = milestone;
+
+        Ok(commit)
+    }
+}
+
+impl<R, C> Deref for MilestoneMut<'_, '_, R, C> {
+    type Target = Milestone;
+
+    fn deref(&self) -> &Self::Target {
+        &self.milestone
+    }
+}
+
+pub struct Milestones<'a, R> {
+    raw: store::Store<'a, Milestone, R>,
+}
+
+impl<'a, R> Deref for Milestones<'a, R> {
+    type Target = store::Store<'a, Milestone, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.raw
+    }
+}
+
+impl<R> HasRepoId for Milestones<'_, R>
+where
+    R: ReadRepository,
+{
+    fn rid(&self) -> RepoId {
+        self.raw.as_ref().id()
+    }
+}
+
+/// Detailed information on milestone states.
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneCounts {
+    pub open: usize,
+    pub closed: usize,
+}
+
+impl MilestoneCounts {
+    /// Total count.
+    pub fn total(&self) -> usize {
+        self.open + self.closed
+    }
+}
+
+impl<'a, R> Milestones<'a, R>
+where
+    R: ReadRepository + cob::Store<Namespace = NodeId>,
+{
+    /// Open a milestones store.
+    pub fn open(repository: &'a R) -> Result<Self, RepositoryError> {
+        let identity = repository.identity_head()?;
+        let raw = store::Store::open(repository)?.identity(identity);
+
+        Ok(Self { raw })
+    }
+}
+
+impl<'a, R> Milestones<'a, R>
+where
+    R: WriteRepository + cob::Store<Namespace = NodeId>,
+{
+    /// Create a new milestone.
+    pub fn create<'g, G, C>(
+        &'g mut self,
+        title: impl ToString,
+        description: impl ToString,
+        due: Option<Timestamp>,
+        cache: &'g mut C,
+        signer: &Device<G>,
+    ) -> Result<MilestoneMut<'a, 'g, R, C>, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+        C: cob::cache::Update<Milestone>,
+    {
+        let (id, milestone) =
+            Transaction::initial("Create milestone", &mut self.raw, signer, |tx, _| {
+                tx.edit(title, description)?;
+
+                if due.is_some() {
+                    tx.due(due)?;
+                }
+                Ok(())
+            })?;
+        cache
+            .update(&self.raw.as_ref().id(), &id, &milestone)
+            .map_err(|e| Error::CacheUpdate { id, err: e.into() })?;
+
+        Ok(MilestoneMut {
+            id,
+            milestone,
+            store: self,
+            cache,
+        })
+    }
+
+    /// Remove a milestone.
+    pub fn remove<C, G>(&self, id: &ObjectId, signer: &Device<G>) -> Result<(), store::Error>
+    where
+        C: cob::cache::Remove<Milestone>,
+        G: crypto::signature::Signer<crypto::Signature>,
+    {
+        self.raw.remove(id, signer)
+    }
+}
+
+impl<'a, R> Milestones<'a, R>
+where
+    R: ReadRepository + cob::Store,
+{
+    /// Get a milestone.
+    pub fn get(&self, id: &ObjectId) -> Result<Option<Milestone>, store::Error> {
+        self.raw.get(id)
+    }
+
+    /// Get a milestone mutably.
+    pub fn get_mut<'g, C>(
+        &'g mut self,
+        id: &ObjectId,
+        cache: &'g mut C,
+    ) -> Result<MilestoneMut<'a, 'g, R, C>, store::Error> {
+        let milestone = self
+            .raw
+            .get(id)?
+            .ok_or_else(move || store::Error::NotFound(TYPENAME.clone(), *id))?;
+
+        Ok(MilestoneMut {
+            id: *id,
+            milestone,
+            store: self,
+            cache,
+        })
+    }
+
+    /// Milestones count by state.
+    pub fn counts(&self) -> Result<MilestoneCounts, Error> {
+        let all = self.all()?;
+        let state_groups =
+            all.filter_map(|s| s.ok())
+                .fold(MilestoneCounts::default(), |mut state, (_, m)| {
+                    match m.state() {
+                        State::Open => state.open += 1,
+                        State::Closed => state.closed += 1,
+                    }
+                    state
+                });
+
+        Ok(state_groups)
+    }
+}
+
+/// Milestone action.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Action {
+    /// Edit milestone title and description.
+    #[serde(rename = "edit")]
+    Edit { title: String, description: String },
+
+    /// Set or unset the due date, in seconds since the epoch.
+    #[serde(rename = "due")]
+    Due { due: Option<u64> },
+
+    /// Transition to a different state.
+    #[serde(rename = "lifecycle")]
+    Lifecycle { state: State },
+
+    /// Attach issues and patches to the milestone.
+    #[serde(rename = "attach")]
+    Attach {
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        issues: BTreeSet<IssueId>,
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        patches: BTreeSet<PatchId>,
+    },
+
+    /// Detach issues and patches from the milestone.
+    #[serde(rename = "detach")]
+    Detach {
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        issues: BTreeSet<IssueId>,
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        patches: BTreeSet<PatchId>,
+    },
+}
+
+impl CobAction for Action {}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::cob::milestone::cache::Milestones as _;
+    use crate::cob::person::Persons;
+    use crate::cob::ActorId;
+    use crate::identity::doc::PayloadId;
+    use crate::identity::DelegatePersons;
+    use crate::node::device::Device;
+    use crate::test::arbitrary;
+    use crate::{assert_matches, test};
+
+    #[test]
+    fn test_milestone_create_and_get() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut milestones = Cache::no_cache(&*repo).unwrap();
+        let due = Timestamp::from_secs(1_700_000_000);
+        let created = milestones
+            .create("v1.0", "First stable release", Some(due), &node.signer)
+            .unwrap();
+        let id = *created.id();
+        let milestone = milestones.get(&id).unwrap().unwrap();
+
+        assert_eq!(milestone.title(), "v1.0");
+        assert_eq!(milestone.description(), "First stable release");
+        assert_eq!(milestone.due(), Some(due));
+        assert_eq!(milestone.state(), &State::Open);
+        assert_eq!(milestone.author().id(), &node.signer.public_key().into());
+        assert_eq!(milestone.issues().count(), 0);
+        assert_eq!(milestone.patches().count(), 0);
+    }
+
+    #[test]
+    fn test_milestone_attach_and_detach() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut milestones = Cache::no_cache(&*repo).unwrap();
+        let mut milestone = milestones.create("v1.0", "", None, &node.signer).unwrap();
+        let issue = IssueId::from(arbitrary::oid());
+        let patch = PatchId::from(arbitrary::oid());
+
+        milestone.attach([issue], [patch], &node.signer).unwrap();
+        assert!(milestone.is_attached(&issue));
+        assert!(milestone.is_attached(&patch));
+
+        milestone.detach([issue], [], &node.signer).unwrap();
+        milestone.reload().unwrap();
+        assert!(!milestone.is_attached(&issue));
+        assert!(milestone.is_attached(&patch));
+        assert_eq!(milestone.patches().collect::<Vec<_>>(), vec![&patch]);
+    }
+
+    #[test]
+    fn test_milestone_edit_and_lifecycle() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut milestones = Cache::no_cache(&*repo).unwrap();
+        let mut milestone = milestones
+            .create("v1.0", "", Some(Timestamp::from_secs(1)), &node.signer)
+            .unwrap();
+
+        milestone.edit("v1.1", "Bugfixes", &node.signer).unwrap();
+        milestone.due(None, &node.signer).unwrap();
+        milestone.lifecycle(State::Closed, &node.signer).unwrap();
+
+        let id = *milestone.id();
+        let milestone = milestones.get(&id).unwrap().unwrap();
+
+        assert_eq!(milestone.title(), "v1.1");
+        assert_eq!(milestone.description(), "Bugfixes");
+        assert_eq!(milestone.due(), None);
+        assert_eq!(milestone.state(), &State::Closed);
+    }
+
+    #[test]
+    fn test_milestone_invalid_title() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut milestones = Cache::no_cache(&*repo).unwrap();
+        let mut milestone = milestones.create("v1.0", "", None, &node.signer).unwrap();
+
+        assert_matches!(
+            milestone.edit("v1.0\nv2.0", "", &node.signer),
+            Err(Error::Store(_)) | Err(Error::InvalidTitle(_))
+        );
+        assert_matches!(
+            milestones.create("v1.0\n", "", None, &node.signer),
+            Err(Error::Store(_)) | Err(Error::InvalidTitle(_))
+        );
+    }
+
+    #[test]
+    fn test_milestone_authorization() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let mut milestone = Milestone::new(
+            Author::new(arbitrary::gen::<ActorId>(1)),
+            Timestamp::from_secs(0),
+        );
+        let laptop = Device::mock();
+        let person = *Persons::open(&*repo)
+            .unwrap()
+            .create([*laptop.public_key()], &node.signer)
+            .unwrap()
+            .id();
+        let doc = repo
+            .identity_doc()
+            .unwrap()
+            .doc
+            .with_edits(|raw| {
+                raw.payload.insert(
+                    PayloadId::of::<DelegatePersons>(),
+                    DelegatePersons {
+                        persons: [(node.signer.public_key().into(), person)].into(),
+                    }
+                    .into(),
+                );
+            })
+            .unwrap();
+        let delegate = *node.signer.public_key();
+        let author = *milestone.author().public_key();
+        let other = arbitrary::gen::<ActorId>(1);
+        let action = Action::Lifecycle {
+            state: State::Closed,
+        };
+
+        assert_eq!(
+            milestone
+                .authorization(&action, &delegate, &doc, &*repo)
+                .unwrap(),
+            Authorization::Allow
+        );
+        assert_eq!(
+            milestone
+                .authorization(&action, laptop.public_key(), &doc, &*repo)
+                .unwrap(),
+            Authorization::Allow
+        );
+        assert_eq!(
+            milestone
+                .authorization(&action, &author, &doc, &*repo)
+                .unwrap(),
+            Authorization::Allow
+        );
+        assert_eq!(
+            milestone
+                .authorization(&action, &other, &doc, &*repo)
+                .unwrap(),
+            Authorization::Deny
+        );
+
+        milestone.action(action).unwrap();
+        assert_eq!(milestone.state(), &State::Closed);
+    }
+}
@@ -0,0 +1,752 @@
+use std::ops::ControlFlow;
+use std::str::FromStr;
+
+use sqlite as sql;
+use thiserror::Error;
+
+use crate::cob;
+use crate::cob::cache;
+use crate::cob::cache::{Remove, StoreReader, StoreWriter, Update};
+use crate::cob::store;
+use crate::cob::{ObjectId, Timestamp, TypeName};
+use crate::node::device::Device;
+use crate::node::NodeId;
+use crate::prelude::RepoId;
+use crate::storage::{HasRepoId, ReadRepository, RepositoryError, SignRepository, WriteRepository};
+
+use super::{Milestone, MilestoneCounts, MilestoneId, MilestoneMut, State};
+
+/// A set of read-only methods for a [`Milestone`] store.
+pub trait Milestones {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// An iterator for returning a set of milestones from the store.
+    type Iter<'a>: Iterator<Item = Result<(MilestoneId, Milestone), Self::Error>> + 'a
+    where
+        Self: 'a;
+
+    /// Get the `Milestone`, identified by `id`, returning `None` if it
+    /// was not found.
+    fn get(&self, id: &MilestoneId) -> Result<Option<Milestone>, Self::Error>;
+
+    /// List all milestones that are in the store.
+    fn list(&self) -> Result<Self::Iter<'_>, Self::Error>;
+
+    /// List all milestones in the store that match the provided `status`.
+    ///
+    /// Also see [`Milestones::opened`] and [`Milestones::closed`].
+    fn list_by_status(&self, status: &State) -> Result<Self::Iter<'_>, Self::Error>;
+
+    /// Get the [`MilestoneCounts`] of all the milestones in the store.
+    fn counts(&self) -> Result<MilestoneCounts, Self::Error>;
+
+    /// List all open milestones in the store.
+    fn opened(&self) -> Result<Self::Iter<'_>, Self::Error> {
+        self.list_by_status(&State::Open)
+    }
+
+    /// List all closed milestones in the store.
+    fn closed(&self) -> Result<Self::Iter<'_>, Self::Error> {
+        self.list_by_status(&State::Closed)
+    }
+
+    /// Returns `true` if there are no milestones in the store.
+    fn is_empty(&self) -> Result<bool, Self::Error> {
+        Ok(self.counts()?.total() == 0)
+    }
+}
+
+/// [`Milestones`] store that can also [`Update`] and [`Remove`]
+/// [`Milestone`] in/from the store.
+pub trait MilestonesMut: Milestones + Update<Milestone> + Remove<Milestone> {}
+
+impl<T> MilestonesMut for T where T: Milestones + Update<Milestone> + Remove<Milestone> {}
+
+/// A `Milestone` store that relies on the `cache` for reads and as a
+/// write-through cache.
+///
+/// The `store` is used for the main storage when performing a
+/// write-through. It is also used for identifying which `RepoId` is
+/// being used for the `cache`.
+pub struct Cache<R, C> {
+    store: R,
+    cache: C,
+}
+
+impl<R, C> Cache<R, C> {
+    pub fn new(store: R, cache: C) -> Self {
+        Self { store, cache }
+    }
+
+    pub fn rid(&self) -> RepoId
+    where
+        R: HasRepoId,
+    {
+        self.store.rid()
+    }
+}
+
+impl<'a, R, C> Cache<super::Milestones<'a, R>, C> {
+    /// Create a new [`Milestone`] using the [`super::Milestones`] as the
+    /// main storage, and writing the update to the `cache`.
+    pub fn create<'g, G>(
+        &'g mut self,
+        title: impl ToString,
+        description: impl ToString,
+        due: Option<Timestamp>,
+        signer: &Device<G>,
+    ) -> Result<MilestoneMut<'a, 'g, R, C>, super::Error>
+    where
+        R: ReadRepository + WriteRepository + cob::Store<Namespace = NodeId>,
+        G: crypto::signature::Signer<crypto::Signature>,
+        C: Update<Milestone>,
+    {
+        self.store
+            .create(title, description, due, &mut self.cache, signer)
+    }
+
+    /// Remove the given `id` from the [`super::Milestones`] storage, and
+    /// removing the entry from the `cache`.
+    pub fn remove<G>(&mut self, id: &MilestoneId, signer: &Device<G>) -> Result<(), super::Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+        R: ReadRepository + SignRepository + cob::Store<Namespace = NodeId>,
+        C: Remove<Milestone>,
+    {
+        self.store.remove(id, signer)?;
+        self.cache
+            .remove(id)
+            .map_err(|e| super::Error::CacheRemove {
+                id: *id,
+                err: e.into(),
+            })?;
+        Ok(())
+    }
+
+    /// Read the given `id` from the [`super::Milestones`] store and
+    /// writing it to the `cache`.
+    pub fn write(&mut self, id: &MilestoneId) -> Result<(), super::Error>
+    where
+        R: ReadRepository + cob::Store,
+        C: Update<Milestone>,
+    {
+        let milestone = self
+            .store
+            .get(id)?
+            .ok_or_else(|| store::Error::NotFound((*super::TYPENAME).clone(), *id))?;
+        self.update(&self.rid(), id, &milestone)
+            .map_err(|e| super::Error::CacheUpdate {
+                id: *id,
+                err: e.into(),
+            })?;
+        Ok(())
+    }
+
+    /// Read all the milestones from the [`super::Milestones`] store and
+    /// writing them to `cache`.
+    ///
+    /// The `callback` is used for reporting success, failures, and
+    /// progress to the caller. The caller may also decide to continue
+    /// or break from the process.
+    pub fn write_all(
+        &mut self,
+        on_milestone: impl Fn(
+            &Result<(MilestoneId, Milestone), store::Error>,
+            &cache::Progress,
+        ) -> ControlFlow<()>,
+    ) -> Result<(), super::Error>
+    where
+        R: ReadRepository + cob::Store,
+        C: Update<Milestone> + Remove<Milestone>,
+    {
+        // Start by clearing the cache. This will get rid of milestones that are cached but
+        // no longer exist in storage.
+        self.remove_all(&self.rid())
+            .map_err(|e| super::Error::CacheRemoveAll { err: e.into() })?;
+
+        let milestones = self.store.all()?;
+        let mut progress = cache::Progress::new(milestones.len());
+        for milestone in self.store.all()? {
+            progress.inc();
+            match on_milestone(&milestone, &progress) {
+                ControlFlow::Continue(()) => match milestone {
+                    Ok((id, milestone)) => {
+                        self.update(&self.rid(), &id, &milestone)
+                            .map_err(|e| super::Error::CacheUpdate { id, err: e.into() })?;
+                    }
+                    Err(_) => continue,
+                },
+                ControlFlow::Break(()) => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, R> Cache<super::Milestones<'a, R>, cache::NoCache>
+where
+    R: ReadRepository + cob::Store<Namespace = NodeId>,
+{
+    /// Get a `Cache` that does no write-through modifications and
+    /// uses the [`super::Milestones`] store for all reads and writes.
+    pub fn no_cache(repository: &'a R) -> Result<Self, RepositoryError> {
+        let store = super::Milestones::open(repository)?;
+        Ok(Self {
+            store,
+            cache: cache::NoCache,
+        })
+    }
+
+    /// Get the [`MilestoneMut`], identified by `id`.
+    pub fn get_mut<'g>(
+        &'g mut self,
+        id: &ObjectId,
+    ) -> Result<MilestoneMut<'a, 'g, R, cache::NoCache>, super::Error> {
+        let milestone = self
+            .store
+            .get(id)?
+            .ok_or_else(move || store::Error::NotFound(super::TYPENAME.clone(), *id))?;
+
+        Ok(MilestoneMut {
+            id: *id,
+            milestone,
+            store: &mut self.store,
+            cache: &mut self.cache,
+        })
+    }
+}
+
+impl<R> Cache<R, StoreReader> {
+    pub fn reader(store: R, cache: StoreReader) -> Self {
+        Self { store, cache }
+    }
+}
+
+impl<R> Cache<R, StoreWriter> {
+    pub fn open(store: R, cache: StoreWriter) -> Self {
+        Self { store, cache }
+    }
+}
+
+impl<'a, R> Cache<super::Milestones<'a, R>, StoreWriter>
+where
+    R: ReadRepository + cob::Store,
+{
+    /// Get the [`MilestoneMut`], identified by `id`, using the
+    /// `StoreWriter` for retrieving the `Milestone`.
+    pub fn get_mut<'g>(
+        &'g mut self,
+        id: &ObjectId,
+    ) -> Result<MilestoneMut<'a, 'g, R, StoreWriter>, Error> {
+        let milestone = Milestones::get(self, id)?
+            .ok_or_else(move || Error::NotFound(super::TYPENAME.clone(), *id))?;
+
+        Ok(MilestoneMut {
+            id: *id,
+            milestone,
+            store: &mut self.store,
+            cache: &mut self.cache,
+        })
+    }
+}
+
+impl<R, C> cache::Update<Milestone> for Cache<R, C>
+where
+    C: cache::Update<Milestone>,
+{
+    type Out = <C as cache::Update<Milestone>>::Out;
+    type UpdateError = <C as cache::Update<Milestone>>::UpdateError;
+
+    fn update(
+        &mut self,
+        rid: &RepoId,
+        id: &ObjectId,
+        object: &Milestone,
+    ) -> Result<Self::Out, Self::UpdateError> {
+        self.cache.update(rid, id, object)
+    }
+}
+
+impl<R, C> cache::Remove<Milestone> for Cache<R, C>
+where
+    C: cache::Remove<Milestone>,
+{
+    type Out = <C as cache::Remove<Milestone>>::Out;
+    type RemoveError = <C as cache::Remove<Milestone>>::RemoveError;
+
+    fn remove(&mut self, id: &ObjectId) -> Result<Self::Out, Self::RemoveError> {
+        self.cache.remove(id)
+    }
+
+    fn remove_all(&mut self, rid: &RepoId) -> Result<Self::Out, Self::RemoveError> {
+        self.cache.remove_all(rid)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateError {
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Sql(#[from] sql::Error),
+}
+
+impl Update<Milestone> for StoreWriter {
+    type Out = bool;
+    type UpdateError = UpdateError;
+
+    fn update(
+        &mut self,
+        rid: &RepoId,
+        id: &ObjectId,
+        object: &Milestone,
+    ) -> Result<Self::Out, Self::UpdateError> {
+        let mut stmt = self.db.prepare(
+            "INSERT INTO milestones (id, repo, milestone)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT DO UPDATE
+             SET milestone = (?3)",
+        )?;
+
+        stmt.bind((1, sql::Value::String(id.to_string())))?;
+        stmt.bind((2, rid))?;
+        stmt.bind((3, sql::Value::String(serde_json::to_string(&object)?)))?;
+        stmt.next()?;
+
+        Ok(self.db.change_count() > 0)
+    }
+}
+
+impl Remove<Milestone> for StoreWriter {
+    type Out = bool;
+    type RemoveError = sql::Error;
+
+    fn remove(&mut self, id: &ObjectId) -> Result<Self::Out, Self::RemoveError> {
+        let mut stmt = self.db.prepare(
+            "DELETE FROM milestones
+             WHERE id = ?1",
+        )?;
+
+        stmt.bind((1, sql::Value::String(id.to_string())))?;
+        stmt.next()?;
+
+        Ok(self.db.change_count() > 0)
+    }
+
+    fn remove_all(&mut self, rid: &RepoId) -> Result<Self::Out, Self::RemoveError> {
+        let mut stmt = self.db.prepare(
+            "DELETE FROM milestones
+             WHERE repo = ?1",
+        )?;
+
+        stmt.bind((1, rid))?;
+        stmt.next()?;
+
+        Ok(self.db.change_count() > 0)
+    }
+}
+
+pub struct NoCacheIter<'a> {
+    inner: Box<dyn Iterator<Item = Result<(MilestoneId, Milestone), super::Error>> + 'a>,
+}
+
+impl Iterator for NoCacheIter<'_> {
+    type Item = Result<(MilestoneId, Milestone), super::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<R> Milestones for Cache<super::Milestones<'_, R>, cache::NoCache>
+where
+    R: ReadRepository + cob::Store,
+{
+    type Error = super::Error;
+    type Iter<'b>
+        = NoCacheIter<'b>
+    where
+        Self: 'b;
+
+    fn get(&self, id: &MilestoneId) -> Result<Option<Milestone>, Self::Error> {
+        self.store.get(id).map_err(super::Error::from)
+    }
+
+    fn list(&self) -> Result<Self::Iter<'_>, Self::Error> {
+        self.store
+            .all()
+            .map(|inner| NoCacheIter {
+                inner: Box::new(inner.into_iter().map(|res| res.map_err(super::Error::from))),
+            })
+            .map_err(super::Error::from)
+    }
+
+    fn list_by_status(&self, status: &State) -> Result<Self::Iter<'_>, Self::Error> {
+        let status = *status;
+        self.store
+            .all()
+            .map(move |inner| NoCacheIter {
+                inner: Box::new(inner.into_iter().filter_map(move |res| {
+                    match res {
+                        Ok((id, milestone)) => (status == milestone.state)
+                            .then_some((id, milestone))
+                            .map(Ok),
+                        Err(e) => Some(Err(e.into())),
+                    }
+                })),
+            })
+            .map_err(super::Error::from)
+    }
+
+    fn counts(&self) -> Result<MilestoneCounts, Self::Error> {
+        self.store.counts()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("object `{1}` of type `{0}` was not found")]
+    NotFound(TypeName, ObjectId),
+    #[error(transparent)]
+    Object(#[from] cob::object::ParseObjectId),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Sql(#[from] sql::Error),
+}
+
+/// Iterator that returns a set of milestones based on an SQL query.
+///
+/// The query is expected to return rows with columns identified by
+/// the `id` and `milestone` names.
+pub struct MilestonesIter<'a> {
+    inner: sql::CursorWithOwnership<'a>,
+}
+
+impl MilestonesIter<'_> {
+    fn parse_row(row: sql::Row) -> Result<(MilestoneId, Milestone), Error> {
+        let id = MilestoneId::from_str(row.read::<&str, _>("id"))?;
+        let milestone = serde_json::from_str::<Milestone>(row.read::<&str, _>("milestone"))?;
+        Ok((id, milestone))
+    }
+}
+
+impl Iterator for MilestonesIter<'_> {
+    type Item = Result<(MilestoneId, Milestone), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.inner.next()?;
+        Some(row.map_err(Error::from).and_then(MilestonesIter::parse_row))
+    }
+}
+
+impl<R> Milestones for Cache<R, StoreWriter>
+where
+    R: HasRepoId,
+{
+    type Error = Error;
+    type Iter<'b>
+        = MilestonesIter<'b>
+    where
+        Self: 'b;
+
+    fn get(&self, id: &MilestoneId) -> Result<Option<Milestone>, Self::Error> {
+        query::get(&self.cache.db, &self.rid(), id)
+    }
+
+    fn list(&self) -> Result<Self::Iter<'_>, Self::Error> {
+        query::list(&self.cache.db, &self.rid())
+    }
+
+    fn list_by_status(&self, status: &State) -> Result<Self::Iter<'_>, Self::Error> {
+        query::list_by_status(&self.cache.db, &self.rid(), status)
+    }
+
+    fn counts(&self) -> Result<MilestoneCounts, Self::Error> {
+        query::counts(&self.cache.db, &self.rid())
+    }
+}
+
+impl<R> Milestones for Cache<R, StoreReader>
+where
+    R: HasRepoId,
+{
+    type Error = Error;
+    type Iter<'b>
+        = MilestonesIter<'b>
+    where
+        Self: 'b;
+
+    fn get(&self, id: &MilestoneId) -> Result<Option<Milestone>, Self::Error> {
+        query::get(&self.cache.db, &self.rid(), id)
+    }
+
+    fn list(&self) -> Result<Self::Iter<'_>, Self::Error> {
+        query::list(&self.cache.db, &self.rid())
+    }
+
+    fn list_by_status(&self, status: &State) -> Result<Self::Iter<'_>, Self::Error> {
+        query::list_by_status(&self.cache.db, &self.rid(), status)
+    }
+
+    fn counts(&self) -> Result<MilestoneCounts, Self::Error> {
+        query::counts(&self.cache.db, &self.rid())
+    }
+}
+
+/// Helper SQL queries for [`Milestones`] trait implementations.
+mod query {
+    use sqlite as sql;
+
+    use super::*;
+
+    pub(super) fn get(
+        db: &sql::ConnectionThreadSafe,
+        rid: &RepoId,
+        id: &MilestoneId,
+    ) -> Result<Option<Milestone>, Error> {
+        let id = sql::Value::String(id.to_string());
+        let mut stmt = db.prepare(
+            "SELECT milestone
+             FROM milestones
+             WHERE id = ?1 and repo = ?2",
+        )?;
+
+        stmt.bind((1, id))?;
+        stmt.bind((2, rid))?;
+
+        match stmt.into_iter().next().transpose()? {
+            None => Ok(None),
+            Some(row) => {
+                let milestone = row.read::<&str, _>("milestone");
+                let milestone = serde_json::from_str(milestone)?;
+                Ok(Some(milestone))
+            }
+        }
+    }
+
+    pub(super) fn list<'a>(
+        db: &'a sql::ConnectionThreadSafe,
+        rid: &RepoId,
+    ) -> Result<MilestonesIter<'a>, Error> {
+        let mut stmt = db.prepare(
+            "SELECT id, milestone
+             FROM milestones
+             WHERE repo = ?1
+            ",
+        )?;
+        stmt.bind((1, rid))?;
+        Ok(MilestonesIter {
+            inner: stmt.into_iter(),
+        })
+    }
+
+    pub(super) fn list_by_status<'a>(
+        db: &'a sql::ConnectionThreadSafe,
+        rid: &RepoId,
+        filter: &State,
+    ) -> Result<MilestonesIter<'a>, Error> {
+        let mut stmt = db.prepare(
+            "SELECT id, milestone
+             FROM milestones
+             WHERE repo = ?1
+             AND milestone->>'$.state.status' = ?2
+             ORDER BY id
+            ",
+        )?;
+        stmt.bind((1, rid))?;
+        stmt.bind((2, sql::Value::String(filter.to_string())))?;
+        Ok(MilestonesIter {
+            inner: stmt.into_iter(),
+        })
+    }
+
+    pub(super) fn counts(
+        db: &sql::ConnectionThreadSafe,
+        rid: &RepoId,
+    ) -> Result<MilestoneCounts, Error> {
+        let mut stmt = db.prepare(
+            "SELECT
+                 milestone->'$.state' AS state,
+                 COUNT(*) AS count
+             FROM milestones
+             WHERE repo = ?1
+             GROUP BY milestone->'$.state.status'",
+        )?;
+        stmt.bind((1, rid))?;
+
+        stmt.into_iter()
+            .try_fold(MilestoneCounts::default(), |mut counts, row| {
+                let row = row?;
+                let count = row.read::<i64, _>("count") as usize;
+                let status = serde_json::from_str::<State>(row.read::<&str, _>("state"))?;
+                match status {
+                    State::Closed => counts.closed += count,
+                    State::Open => counts.open += count,
+                }
+                Ok(counts)
+            })
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::cob::cache::{Store, Update, Write};
+    use crate::cob::common::{Author, Timestamp};
+    use crate::cob::migrate;
+    use crate::cob::milestone::{Milestone, MilestoneCounts, MilestoneId, State};
+    use crate::cob::ActorId;
+    use crate::test::arbitrary;
+    use crate::test::storage::MockRepository;
+
+    use super::{Cache, Milestones};
+
+    fn memory(store: MockRepository) -> Cache<MockRepository, Store<Write>> {
+        let cache = Store::<Write>::memory()
+            .unwrap()
+            .with_migrations(migrate::ignore)
+            .unwrap();
+        Cache { store, cache }
+    }
+
+    fn milestone(title: impl ToString) -> Milestone {
+        Milestone {
+            title: title.to_string(),
+            ..Milestone::new(
+                Author::new(arbitrary::gen::<ActorId>(1)),
+                Timestamp::from_secs(0),
+            )
+        }
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut cache = memory(repo);
+        assert!(cache.is_empty().unwrap());
+
+        let id = MilestoneId::from(arbitrary::oid());
+        cache.update(&cache.rid(), &id, &milestone("v1")).unwrap();
+
+        assert!(!cache.is_empty().unwrap())
+    }
+
+    #[test]
+    fn test_counts() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut cache = memory(repo);
+        let n_open = arbitrary::gen::<u8>(0);
+        let n_closed = arbitrary::gen::<u8>(1);
+        let open_ids = (0..n_open)
+            .map(|_| MilestoneId::from(arbitrary::oid()))
+            .collect::<BTreeSet<MilestoneId>>();
+        let closed_ids = (0..n_closed)
+            .map(|_| MilestoneId::from(arbitrary::oid()))
+            .collect::<BTreeSet<MilestoneId>>();
+
+        for id in open_ids.iter() {
+            cache.update(&cache.rid(), id, &milestone(id)).unwrap();
+        }
+        for id in closed_ids.iter() {
+            let milestone = Milestone {
+                state: State::Closed,
+                ..milestone(id)
+            };
+            cache.update(&cache.rid(), id, &milestone).unwrap();
+        }
+
+        assert_eq!(
+            cache.counts().unwrap(),
+            MilestoneCounts {
+                open: open_ids.len(),
+                closed: closed_ids.len()
+            }
+        );
+    }
+
+    #[test]
+    fn test_get_and_list() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut cache = memory(repo);
+        let ids = (0..arbitrary::gen::<u8>(1))
+            .map(|_| MilestoneId::from(arbitrary::oid()))
+            .collect::<BTreeSet<MilestoneId>>();
+        let mut milestones = Vec::with_capacity(ids.len());
+
+        for id in ids.iter() {
+            let milestone = Milestone {
+                issues: BTreeSet::from_iter([MilestoneId::from(arbitrary::oid())]),
+                ..milestone(id)
+            };
+            cache.update(&cache.rid(), id, &milestone).unwrap();
+            milestones.push((*id, milestone));
+        }
+
+        for (id, milestone) in milestones.iter() {
+            assert_eq!(Some(milestone), cache.get(id).unwrap().as_ref());
+        }
+        assert_eq!(
+            cache.get(&MilestoneId::from(arbitrary::oid())).unwrap(),
+            None
+        );
+
+        let mut list = cache
+            .list()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        list.sort_by_key(|(id, _)| *id);
+        milestones.sort_by_key(|(id, _)| *id);
+        assert_eq!(milestones, list);
+    }
+
+    #[test]
+    fn test_list_by_status() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut cache = memory(repo);
+        let open = MilestoneId::from(arbitrary::oid());
+        let closed = MilestoneId::from(arbitrary::oid());
+
+        cache.update(&cache.rid(), &open, &milestone("v1")).unwrap();
+        cache
+            .update(
+                &cache.rid(),
+                &closed,
+                &Milestone {
+                    state: State::Closed,
+                    ..milestone("v0")
+                },
+            )
+            .unwrap();
+
+        let opened = cache
+            .opened()
+            .unwrap()
+            .map(|r| r.map(|(id, _)| id))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let closed_ = cache
+            .closed()
+            .unwrap()
+            .map(|r| r.map(|(id, _)| id))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(opened, vec![open]);
+        assert_eq!(closed_, vec![closed]);
+    }
+
+    #[test]
+    fn test_remove() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut cache = memory(repo);
+        let id = MilestoneId::from(arbitrary::oid());
+
+        cache.update(&cache.rid(), &id, &milestone("v1")).unwrap();
+        assert!(cache.get(&id).unwrap().is_some());
+        super::Remove::remove(&mut cache, &id).unwrap();
+        assert_eq!(None, cache.get(&id).unwrap());
+    }
+}
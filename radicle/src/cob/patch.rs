@@ -20,11 +20,11 @@ use crate::cob::store::{Cob, CobAction};
 use crate::cob::thread;
 use crate::cob::thread::Thread;
 use crate::cob::thread::{Comment, CommentId, Edit, Reactions};
-use crate::cob::{op, store, ActorId, Embed, EntryId, ObjectId, TypeName, Uri};
+use crate::cob::{op, person, store, ActorId, Embed, EntryId, ObjectId, TypeName, Uri};
 use crate::crypto::PublicKey;
 use crate::git;
 use crate::identity::doc::{DocAt, DocError};
-use crate::identity::PayloadError;
+use crate::identity::{CodeOwners, MergePolicy, PayloadError};
 use crate::node::device::Device;
 use crate::prelude::*;
 use crate::storage;
@@ -120,6 +120,9 @@ pub enum Error {
     /// Store error.
     #[error("store: {0}")]
     Store(#[from] store::Error),
+    /// Repository error.
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
     #[error("op decoding failed: {0}")]
     Op(#[from] op::OpEncodingError),
     /// Action not authorized by the author
@@ -168,6 +171,18 @@ pub enum Action {
     Lifecycle { state: Lifecycle },
     #[serde(rename = "assign")]
     Assign { assignees: BTreeSet<Did> },
+    /// Declare the patches this patch depends on, eg. when the patch is
+    /// based on the head of another patch.
+    #[serde(rename = "depend")]
+    Depend { dependencies: BTreeSet<PatchId> },
+    /// Suggest reviewers for this patch, eg. the code owners of the paths
+    /// changed by the latest revision.
+    ///
+    /// Unlike assignees, which are chosen by delegates, suggested reviewers
+    /// are maintained by the patch author, and replaced whenever a new
+    /// revision changes a different set of paths.
+    #[serde(rename = "suggest")]
+    Suggest { reviewers: BTreeSet<Did> },
     #[serde(rename = "merge")]
     Merge {
         revision: RevisionId,
@@ -377,7 +392,7 @@ impl<R: WriteRepository> Merged<'_, R> {
 }
 
 /// Where a patch is intended to be merged.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum MergeTarget {
     /// Intended for the default branch of the project delegates.
@@ -386,9 +401,26 @@ pub enum MergeTarget {
     /// If it were otherwise, patches could become un-mergeable.
     #[default]
     Delegates,
+    /// Intended for the given branch of the project delegates, eg. `release/1.x`.
+    /// As with [`MergeTarget::Delegates`], the tip of the branch is computed from
+    /// the "current" delegation set.
+    Branch(git::RefString),
 }
 
 impl MergeTarget {
+    /// Get the merge target for the given branch name, eg. `release/1.x`.
+    ///
+    /// The project's default branch always maps to [`MergeTarget::Delegates`].
+    pub fn branch(name: git::RefString, doc: &Doc) -> Result<Self, PayloadError> {
+        let proj = doc.project()?;
+
+        if &name == proj.default_branch() {
+            Ok(MergeTarget::Delegates)
+        } else {
+            Ok(MergeTarget::Branch(name))
+        }
+    }
+
     /// Get the head of the target branch.
     pub fn head<R: ReadRepository>(&self, repo: &R) -> Result<git::Oid, RepositoryError> {
         match self {
@@ -396,6 +428,40 @@ impl MergeTarget {
                 let (_, target) = repo.head()?;
                 Ok(target)
             }
+            MergeTarget::Branch(_) => self.canonical_head(repo),
+        }
+    }
+
+    /// Compute the canonical head of the target branch.
+    ///
+    /// Unlike [`MergeTarget::head`], this ignores any existing `HEAD` reference.
+    pub fn canonical_head<R: ReadRepository>(&self, repo: &R) -> Result<git::Oid, RepositoryError> {
+        match self {
+            MergeTarget::Delegates => {
+                let (_, target) = repo.canonical_head()?;
+                Ok(target)
+            }
+            MergeTarget::Branch(name) => repo.canonical_reference(&git::refs::branch(name)),
+        }
+    }
+
+    /// Get the qualified name of the target branch, eg. `refs/heads/master`.
+    pub fn refname(&self, doc: &Doc) -> Result<git::Qualified<'static>, PayloadError> {
+        match self {
+            MergeTarget::Delegates => {
+                let proj = doc.project()?;
+                Ok(git::refs::branch(proj.default_branch()))
+            }
+            MergeTarget::Branch(name) => Ok(git::refs::branch(name)),
+        }
+    }
+}
+
+impl fmt::Display for MergeTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeTarget::Delegates => write!(f, "delegates"),
+            MergeTarget::Branch(name) => write!(f, "{name}"),
         }
     }
 }
@@ -430,6 +496,16 @@ pub struct Patch {
     pub(super) revisions: BTreeMap<RevisionId, Option<Revision>>,
     /// Users assigned to review this patch.
     pub(super) assignees: BTreeSet<ActorId>,
+    /// Patches this patch depends on.
+    /// Dependencies can be changed at will by the patch author.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub(super) dependencies: BTreeSet<PatchId>,
+    /// Users suggested to review this patch.
+    /// Suggestions can be changed at will by the patch author, and are
+    /// usually derived from the code owners of the latest revision, whereas
+    /// assignees are chosen by delegates.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub(super) reviewers: BTreeSet<Did>,
     /// Timeline of operations.
     pub(super) timeline: Vec<EntryId>,
     /// Reviews index. Keeps track of reviews for better performance.
@@ -448,6 +524,8 @@ impl Patch {
             merges: BTreeMap::default(),
             revisions: BTreeMap::from_iter([(id, Some(revision))]),
             assignees: BTreeSet::default(),
+            dependencies: BTreeSet::default(),
+            reviewers: BTreeSet::default(),
             timeline: vec![id.into_inner()],
             reviews: BTreeMap::default(),
         }
@@ -464,8 +542,8 @@ impl Patch {
     }
 
     /// Target this patch is meant to be merged in.
-    pub fn target(&self) -> MergeTarget {
-        self.target
+    pub fn target(&self) -> &MergeTarget {
+        &self.target
     }
 
     /// Timestamp of the first revision of the patch.
@@ -560,6 +638,30 @@ impl Patch {
         self.assignees.iter().map(Did::from)
     }
 
+    /// Patches this patch depends on.
+    pub fn dependencies(&self) -> impl Iterator<Item = &PatchId> {
+        self.dependencies.iter()
+    }
+
+    /// Check if this patch depends on the given patch.
+    pub fn depends_on(&self, id: &PatchId) -> bool {
+        self.dependencies.contains(id)
+    }
+
+    /// Users suggested to review this patch.
+    pub fn reviewers(&self) -> impl Iterator<Item = &Did> {
+        self.reviewers.iter()
+    }
+
+    /// Suggested reviewers who haven't yet reviewed the latest revision.
+    pub fn pending_reviewers(&self) -> impl Iterator<Item = &Did> {
+        let (_, latest) = self.latest();
+
+        self.reviewers
+            .iter()
+            .filter(|did| latest.review_by(did.as_key()).is_none())
+    }
+
     /// Get the merges.
     pub fn merges(&self) -> impl Iterator<Item = (&ActorId, &Merge)> {
         self.merges.iter()
@@ -619,6 +721,70 @@ impl Patch {
         self.latest().1.timestamp()
     }
 
+    /// Evaluate whether the latest revision of this patch is ready to be merged,
+    /// according to the given merge policy.
+    ///
+    /// Only reviews by delegates of the given document, or by their devices,
+    /// count as approvals, once per delegate.
+    pub fn merge_readiness(
+        &self,
+        policy: &MergePolicy,
+        doc: &Doc,
+        persons: &person::Index,
+    ) -> MergeReadiness {
+        let (revision, latest) = self.latest();
+
+        Self::readiness(revision, latest, policy, doc, persons)
+    }
+
+    /// Evaluate whether the given revision of this patch is ready to be merged,
+    /// according to the given merge policy. Returns `None` if the revision
+    /// doesn't exist or was redacted.
+    pub fn revision_readiness(
+        &self,
+        revision: &RevisionId,
+        policy: &MergePolicy,
+        doc: &Doc,
+        persons: &person::Index,
+    ) -> Option<MergeReadiness> {
+        self.revision(revision)
+            .map(|r| Self::readiness(*revision, r, policy, doc, persons))
+    }
+
+    fn readiness(
+        revision: RevisionId,
+        latest: &Revision,
+        policy: &MergePolicy,
+        doc: &Doc,
+        persons: &person::Index,
+    ) -> MergeReadiness {
+        let mut blockers = Vec::new();
+
+        let approvals = latest
+            .reviews()
+            .filter(|(_, review)| review.verdict() == Some(Verdict::Accept))
+            .filter_map(|(author, _)| doc.delegate_in(author, persons))
+            .collect::<BTreeSet<_>>()
+            .len();
+        if approvals < policy.approvals {
+            blockers.push(MergeBlocker::Approvals {
+                approvals,
+                required: policy.approvals,
+            });
+        }
+        if policy.resolved {
+            let unresolved = latest
+                .reviews()
+                .flat_map(|(_, review)| review.comments())
+                .filter(|(_, comment)| comment.reply_to().is_none() && !comment.is_resolved())
+                .count();
+            if unresolved > 0 {
+                blockers.push(MergeBlocker::Unresolved(unresolved));
+            }
+        }
+        MergeReadiness { revision, blockers }
+    }
+
     /// Check if the patch is merged.
     pub fn is_merged(&self) -> bool {
         matches!(self.state(), State::Merged { .. })
@@ -640,20 +806,37 @@ impl Patch {
     }
 
     /// Apply authorization rules on patch actions.
-    pub fn authorization(
+    pub fn authorization<R: ReadRepository>(
         &self,
         action: &Action,
         actor: &ActorId,
         doc: &Doc,
+        repo: &R,
     ) -> Result<Authorization, Error> {
         if doc.is_delegate(&actor.into()) {
             // A delegate is authorized to do all actions.
             return Ok(Authorization::Allow);
         }
+        let outcome = self.actor_authorization(action, actor)?;
+        if outcome != Authorization::Allow && doc.delegate_of(actor, repo)?.is_some() {
+            // So are the devices of a delegate.
+            return Ok(Authorization::Allow);
+        }
+        Ok(outcome)
+    }
+
+    /// Authorization rules on patch actions, for actors that aren't delegates.
+    fn actor_authorization(
+        &self,
+        action: &Action,
+        actor: &ActorId,
+    ) -> Result<Authorization, Error> {
         let author = self.author().id().as_key();
         let outcome = match action {
             // The patch author can edit the patch and change its state.
             Action::Edit { .. } => Authorization::from(actor == author),
+            Action::Depend { .. } => Authorization::from(actor == author),
+            Action::Suggest { .. } => Authorization::from(actor == author),
             Action::Lifecycle { state } => Authorization::from(match state {
                 Lifecycle::Open { .. } => actor == author,
                 Lifecycle::Draft { .. } => actor == author,
@@ -670,7 +853,7 @@ impl Patch {
             }
             Action::Assign { .. } => Authorization::Deny,
             Action::Merge { .. } => match self.target() {
-                MergeTarget::Delegates => Authorization::Deny,
+                MergeTarget::Delegates | MergeTarget::Branch(_) => Authorization::Deny,
             },
             // Anyone can submit a review.
             Action::Review { .. } => Authorization::Allow,
@@ -762,7 +945,7 @@ impl Patch {
         doc: &DocAt,
         repo: &R,
     ) -> Result<(), Error> {
-        match self.authorization(&action, &author, doc)? {
+        match self.authorization(&action, &author, doc, repo)? {
             Authorization::Allow => {
                 self.action(action, id, author, timestamp, concurrent, doc, repo)
             }
@@ -818,6 +1001,12 @@ impl Patch {
             Action::Assign { assignees } => {
                 self.assignees = BTreeSet::from_iter(assignees.into_iter().map(ActorId::from));
             }
+            Action::Depend { dependencies } => {
+                self.dependencies = dependencies;
+            }
+            Action::Suggest { reviewers } => {
+                self.reviewers = reviewers;
+            }
             Action::RevisionEdit {
                 revision,
                 description,
@@ -1047,24 +1236,19 @@ impl Patch {
                 if lookup::revision_mut(self, &revision)?.is_none() {
                     return Ok(());
                 };
-                match self.target() {
-                    MergeTarget::Delegates => {
-                        let proj = identity.project()?;
-                        let branch = git::refs::branch(proj.default_branch());
-
-                        // Nb. We don't return an error in case the merge commit is not an
-                        // ancestor of the default branch. The default branch can change
-                        // *after* the merge action is created, which is out of the control
-                        // of the merge author. We simply skip it, which allows archiving in
-                        // case of a rebase off the master branch, or a redaction of the
-                        // merge.
-                        let Ok(head) = repo.reference_oid(&author, &branch) else {
-                            return Ok(());
-                        };
-                        if commit != head && !repo.is_ancestor_of(commit, head)? {
-                            return Ok(());
-                        }
-                    }
+                let branch = self.target().refname(identity)?;
+
+                // Nb. We don't return an error in case the merge commit is not an
+                // ancestor of the target branch. The target branch can change
+                // *after* the merge action is created, which is out of the control
+                // of the merge author. We simply skip it, which allows archiving in
+                // case of a rebase off the master branch, or a redaction of the
+                // merge.
+                let Ok(head) = repo.reference_oid(&author, &branch) else {
+                    return Ok(());
+                };
+                if commit != head && !repo.is_ancestor_of(commit, head)? {
+                    return Ok(());
                 }
                 self.merges.insert(
                     author,
@@ -1075,15 +1259,18 @@ impl Patch {
                     },
                 );
 
-                let mut merges = self.merges.iter().fold(
-                    HashMap::<(RevisionId, git::Oid), usize>::new(),
-                    |mut acc, (_, merge)| {
-                        *acc.entry((merge.revision, merge.commit)).or_default() += 1;
-                        acc
-                    },
-                );
+                // Count merges per delegate, since a delegate may merge from several devices.
+                let mut merges = HashMap::<(RevisionId, git::Oid), BTreeSet<Did>>::new();
+                for (author, merge) in self.merges.iter() {
+                    if let Some(delegate) = identity.delegate_of(author, repo)? {
+                        merges
+                            .entry((merge.revision, merge.commit))
+                            .or_default()
+                            .insert(delegate);
+                    }
+                }
                 // Discard revisions that weren't merged by a threshold of delegates.
-                merges.retain(|_, count| *count >= identity.threshold());
+                merges.retain(|_, delegates| delegates.len() >= identity.threshold());
 
                 match merges.into_keys().collect::<Vec<_>>().as_slice() {
                     [] => {
@@ -1207,7 +1394,7 @@ impl store::Cob for Patch {
         let mut patch = Patch::new(title, target, (RevisionId(op.id), revision));
 
         for action in actions {
-            match patch.authorization(&action, &op.author, &doc)? {
+            match patch.authorization(&action, &op.author, &doc, repo)? {
                 Authorization::Allow => {
                     patch.action(action, op.id, op.author, op.timestamp, &[], &doc, repo)?;
                 }
@@ -1497,6 +1684,35 @@ impl Revision {
     pub fn review_by(&self, author: &ActorId) -> Option<&Review> {
         self.reviews.get(author)
     }
+
+    /// Paths changed by this revision, relative to its base.
+    pub fn changed_paths(
+        &self,
+        repo: &git::raw::Repository,
+    ) -> Result<BTreeSet<String>, git::raw::Error> {
+        let base = repo.find_commit(self.base.into())?.tree()?;
+        let head = repo.find_commit(self.oid.into())?.tree()?;
+        let diff = repo.diff_tree_to_tree(Some(&base), Some(&head), None)?;
+        let paths = diff
+            .deltas()
+            .flat_map(|delta| [delta.old_file().path(), delta.new_file().path()])
+            .flatten()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+
+        Ok(paths)
+    }
+
+    /// Code owners of the paths changed by this revision.
+    pub fn owners(
+        &self,
+        repo: &git::raw::Repository,
+        owners: &CodeOwners,
+    ) -> Result<BTreeSet<Did>, git::raw::Error> {
+        let paths = self.changed_paths(repo)?;
+
+        Ok(owners.owners(paths.iter().map(|p| p.as_str())))
+    }
 }
 
 /// Patch state.
@@ -1591,6 +1807,59 @@ pub struct Merge {
     pub timestamp: Timestamp,
 }
 
+/// The result of evaluating a patch against a [`MergePolicy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeReadiness {
+    /// The revision that was evaluated.
+    pub revision: RevisionId,
+    /// Requirements of the policy that are not met.
+    pub blockers: Vec<MergeBlocker>,
+}
+
+impl MergeReadiness {
+    /// Check if all requirements of the policy are met.
+    pub fn is_ready(&self) -> bool {
+        self.blockers.is_empty()
+    }
+}
+
+impl fmt::Display for MergeReadiness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ready() {
+            return write!(f, "ready");
+        }
+        let blockers = self
+            .blockers
+            .iter()
+            .map(|b| b.to_string())
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", blockers.join(", "))
+    }
+}
+
+/// A requirement of a [`MergePolicy`] that is not met by a patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeBlocker {
+    /// Not enough accepting reviews from delegates.
+    Approvals { approvals: usize, required: usize },
+    /// Review comments that are not resolved.
+    Unresolved(usize),
+}
+
+impl fmt::Display for MergeBlocker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Approvals {
+                approvals,
+                required,
+            } => write!(f, "{approvals}/{required} approvals"),
+            Self::Unresolved(1) => write!(f, "1 unresolved comment"),
+            Self::Unresolved(n) => write!(f, "{n} unresolved comments"),
+        }
+    }
+}
+
 /// A patch review verdict.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -1963,6 +2232,26 @@ impl<R: ReadRepository> store::Transaction<Patch, R> {
             labels: labels.into_iter().collect(),
         })
     }
+
+    /// Set the patches this patch depends on.
+    pub fn depend(
+        &mut self,
+        dependencies: impl IntoIterator<Item = PatchId>,
+    ) -> Result<(), store::Error> {
+        self.push(Action::Depend {
+            dependencies: dependencies.into_iter().collect(),
+        })
+    }
+
+    /// Set the suggested reviewers of a patch.
+    pub fn suggest(
+        &mut self,
+        reviewers: impl IntoIterator<Item = Did>,
+    ) -> Result<(), store::Error> {
+        self.push(Action::Suggest {
+            reviewers: reviewers.into_iter().collect(),
+        })
+    }
 }
 
 pub struct PatchMut<'a, 'g, R, C> {
@@ -2029,7 +2318,7 @@ where
     /// Edit patch metadata.
     pub fn edit<G, S>(
         &mut self,
-        title: String,
+        title: S,
         target: MergeTarget,
         signer: &Device<G>,
     ) -> Result<EntryId, Error>
@@ -2446,6 +2735,30 @@ where
     {
         self.transaction("Label", signer, |tx| tx.label(labels))
     }
+
+    /// Set the patches this patch depends on.
+    pub fn depend<G>(
+        &mut self,
+        dependencies: impl IntoIterator<Item = PatchId>,
+        signer: &Device<G>,
+    ) -> Result<EntryId, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+    {
+        self.transaction("Depend", signer, |tx| tx.depend(dependencies))
+    }
+
+    /// Set the suggested reviewers of a patch.
+    pub fn suggest<G>(
+        &mut self,
+        reviewers: impl IntoIterator<Item = Did>,
+        signer: &Device<G>,
+    ) -> Result<EntryId, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+    {
+        self.transaction("Suggest", signer, |tx| tx.suggest(reviewers))
+    }
 }
 
 impl<R, C> Deref for PatchMut<'_, '_, R, C> {
@@ -2902,11 +3215,16 @@ mod test {
 
     use super::*;
     use crate::cob::common::CodeRange;
+    use crate::cob::person::Persons;
     use crate::cob::test::Actor;
     use crate::crypto::test::signer::MockSigner;
     use crate::identity;
+    use crate::identity::doc::PayloadId;
+    use crate::identity::{DelegatePersons, Identity};
+    use crate::node::device::Device;
     use crate::patch::cache::Patches as _;
     use crate::profile::env;
+    use crate::rad;
     use crate::test;
     use crate::test::arbitrary;
     use crate::test::arbitrary::gen;
@@ -2979,7 +3297,7 @@ mod test {
             .create(
                 "My first patch",
                 "Blah blah blah.",
-                target,
+                target.clone(),
                 branch.base,
                 branch.oid,
                 &[],
@@ -2994,7 +3312,7 @@ mod test {
         assert_eq!(patch.description(), "Blah blah blah.");
         assert_eq!(patch.author().id(), &author);
         assert_eq!(patch.state(), &State::Open { conflicts: vec![] });
-        assert_eq!(patch.target(), target);
+        assert_eq!(patch.target(), &target);
         assert_eq!(patch.version(), 0);
 
         let (rev_id, revision) = patch.latest();
@@ -3073,6 +3391,219 @@ mod test {
         assert_eq!(merge.commit, branch.base);
     }
 
+    #[test]
+    fn test_patch_merge_target_branch() {
+        let alice = test::setup::NodeWithRepo::default();
+        let checkout = alice.repo.checkout();
+        let branch = checkout.branch_with([("README", b"Hello World!")]);
+        let release = git::refname!("release/1.x");
+        let mut patches = Cache::no_cache(&*alice.repo).unwrap();
+        let mut patch = patches
+            .create(
+                "My first patch",
+                "Blah blah blah.",
+                MergeTarget::Branch(release.clone()),
+                branch.base,
+                branch.oid,
+                &[],
+                &alice.signer,
+            )
+            .unwrap();
+
+        let id = patch.id;
+        let (rid, _) = patch.revisions().next().unwrap();
+
+        // The target branch doesn't exist yet, so the merge is ignored.
+        let _merge = patch.merge(rid, branch.base, &alice.signer).unwrap();
+        let patch = patches.get(&id).unwrap().unwrap();
+        assert_eq!(patch.target(), &MergeTarget::Branch(release.clone()));
+        assert!(patch.is_open());
+
+        alice
+            .repo
+            .raw()
+            .reference(
+                git::refs::branch(&release)
+                    .with_namespace(alice.signer.public_key().into())
+                    .as_str(),
+                *branch.oid,
+                false,
+                "test",
+            )
+            .unwrap();
+        assert_eq!(patch.target().head(&*alice.repo).unwrap(), branch.oid);
+
+        let mut patch = patches.get_mut(&id).unwrap();
+        let _merge = patch.merge(rid, branch.oid, &alice.signer).unwrap();
+        let patch = patches.get(&id).unwrap().unwrap();
+
+        assert!(patch.is_merged());
+    }
+
+    #[test]
+    fn test_patch_merge_device() {
+        let alice = test::setup::NodeWithRepo::default();
+        let laptop = Device::mock();
+        let checkout = alice.repo.checkout();
+        let branch = checkout.branch_with([("README", b"Hello World!")]);
+        let person = *Persons::open(&*alice.repo)
+            .unwrap()
+            .create([*laptop.public_key()], &alice.signer)
+            .unwrap()
+            .id();
+
+        // Alice merges from her laptop.
+        let mut identity = Identity::load_mut(&*alice.repo).unwrap();
+        let doc = identity
+            .doc()
+            .clone()
+            .with_edits(|raw| {
+                raw.payload.insert(
+                    PayloadId::of::<DelegatePersons>(),
+                    DelegatePersons {
+                        persons: [(alice.signer.public_key().into(), person)].into(),
+                    }
+                    .into(),
+                );
+            })
+            .unwrap();
+        let rev = identity
+            .update("Pin person", "", &doc, &alice.signer)
+            .unwrap();
+        alice.repo.set_identity_head_to(rev).unwrap();
+        rad::fork_remote(
+            alice.repo.id,
+            alice.signer.public_key(),
+            &laptop,
+            &alice.storage,
+        )
+        .unwrap();
+
+        let mut patches = Cache::no_cache(&*alice.repo).unwrap();
+        let mut patch = patches
+            .create(
+                "My first patch",
+                "Blah blah blah.",
+                MergeTarget::Delegates,
+                branch.base,
+                branch.oid,
+                &[],
+                &alice.signer,
+            )
+            .unwrap();
+        let id = patch.id;
+        let (rid, _) = patch.revisions().next().unwrap();
+
+        patch.label([Label::new("bug").unwrap()], &laptop).unwrap();
+        let _merge = patch.merge(rid, branch.base, &laptop).unwrap();
+        let patch = patches.get(&id).unwrap().unwrap();
+
+        assert!(patch.is_merged());
+        assert_eq!(patch.labels().count(), 1);
+
+        // Other keys still can't merge.
+        let mut patch = patches.get_mut(&id).unwrap();
+        assert!(patch.merge(rid, branch.base, &Device::mock()).is_err());
+    }
+
+    #[test]
+    fn test_patch_depend() {
+        let alice = test::setup::NodeWithRepo::default();
+        let checkout = alice.repo.checkout();
+        let a = checkout.branch_with([("README", b"Hello World!")]);
+        let b = checkout.branch_with([("CONTRIBUTING", b"Hello Again!")]);
+        let mut patches = Cache::no_cache(&*alice.repo).unwrap();
+        let first = patches
+            .create(
+                "First patch",
+                "Blah blah blah.",
+                MergeTarget::Delegates,
+                a.base,
+                a.oid,
+                &[],
+                &alice.signer,
+            )
+            .unwrap()
+            .id;
+        let mut second = patches
+            .create(
+                "Second patch",
+                "Blah blah blah.",
+                MergeTarget::Delegates,
+                a.oid,
+                b.oid,
+                &[],
+                &alice.signer,
+            )
+            .unwrap();
+        assert_eq!(second.dependencies().count(), 0);
+
+        second.depend([first], &alice.signer).unwrap();
+
+        let id = second.id;
+        let second = patches.get(&id).unwrap().unwrap();
+        assert!(second.depends_on(&first));
+        assert_eq!(second.dependencies().collect::<Vec<_>>(), vec![&first]);
+
+        let mut second = patches.get_mut(&id).unwrap();
+        second.depend([], &alice.signer).unwrap();
+
+        let second = patches.get(&id).unwrap().unwrap();
+        assert!(!second.depends_on(&first));
+    }
+
+    #[test]
+    fn test_patch_suggest_owners() {
+        let alice = test::setup::NodeWithRepo::default();
+        let bob = Did::from(arbitrary::gen::<PublicKey>(1));
+        let alice_did = Did::from(*alice.signer.public_key());
+        let checkout = alice.repo.checkout();
+        let branch = checkout.branch_with([("CONTRIBUTING", b"Hello World!")]);
+        let mut patches = Cache::no_cache(&*alice.repo).unwrap();
+        let mut patch = patches
+            .create(
+                "My first patch",
+                "Blah blah blah.",
+                MergeTarget::Delegates,
+                branch.base,
+                branch.oid,
+                &[],
+                &alice.signer,
+            )
+            .unwrap();
+        let owners: CodeOwners = serde_json::from_value(serde_json::json!({
+            "rules": [
+                { "pattern": "*", "owners": [bob] },
+                { "pattern": "CONTRIBUTING", "owners": [alice_did, bob] },
+            ]
+        }))
+        .unwrap();
+        let (revision_id, revision) = patch.latest();
+        let raw = alice.repo.raw();
+
+        assert!(revision
+            .changed_paths(raw)
+            .unwrap()
+            .contains("CONTRIBUTING"));
+        let reviewers = revision.owners(raw, &owners).unwrap();
+        assert_eq!(reviewers, BTreeSet::from([alice_did, bob]));
+        patch.suggest(reviewers, &alice.signer).unwrap();
+        patch
+            .review(
+                revision_id,
+                Some(Verdict::Accept),
+                None,
+                vec![],
+                &alice.signer,
+            )
+            .unwrap();
+
+        let id = patch.id;
+        let patch = patches.get(&id).unwrap().unwrap();
+        assert_eq!(patch.reviewers().count(), 2);
+        assert_eq!(patch.pending_reviewers().collect::<Vec<_>>(), vec![&bob]);
+    }
+
     #[test]
     fn test_patch_review() {
         let alice = test::setup::NodeWithRepo::default();
@@ -3125,6 +3656,152 @@ mod test {
             .unwrap_err();
     }
 
+    #[test]
+    fn test_patch_merge_readiness() {
+        let alice = test::setup::NodeWithRepo::default();
+        let checkout = alice.repo.checkout();
+        let branch = checkout.branch_with([("README", b"Hello World!")]);
+        let doc = alice.repo.identity_doc().unwrap();
+        let persons = alice.repo.persons().unwrap();
+        let mut patches = Cache::no_cache(&*alice.repo).unwrap();
+        let mut patch = patches
+            .create(
+                "My first patch",
+                "Blah blah blah.",
+                MergeTarget::Delegates,
+                branch.base,
+                branch.oid,
+                &[],
+                &alice.signer,
+            )
+            .unwrap();
+        let policy = MergePolicy {
+            approvals: 1,
+            resolved: true,
+        };
+        let (revision, _) = patch.latest();
+
+        assert!(patch
+            .merge_readiness(&MergePolicy::default(), &doc, &persons)
+            .is_ready());
+        assert_eq!(
+            patch.merge_readiness(&policy, &doc, &persons).blockers,
+            vec![MergeBlocker::Approvals {
+                approvals: 0,
+                required: 1
+            }]
+        );
+
+        let review = patch
+            .review(revision, Some(Verdict::Accept), None, vec![], &alice.signer)
+            .unwrap();
+        let comment = patch
+            .review_comment(review, "Nit", None, None, [], &alice.signer)
+            .unwrap();
+        assert_eq!(
+            patch.merge_readiness(&policy, &doc, &persons).blockers,
+            vec![MergeBlocker::Unresolved(1)]
+        );
+
+        patch
+            .resolve_review_comment(review, comment, &alice.signer)
+            .unwrap();
+        let readiness = patch.merge_readiness(&policy, &doc, &persons);
+        assert_eq!(readiness.revision, revision);
+        assert!(readiness.is_ready());
+
+        // A new revision isn't approved, but the reviewed one still is.
+        let update = checkout.branch_with([("README", b"Hello Radicle!")]);
+        let update = patch
+            .update("Updated", branch.base, update.oid, &alice.signer)
+            .unwrap();
+        assert!(!patch.merge_readiness(&policy, &doc, &persons).is_ready());
+        assert!(patch
+            .revision_readiness(&revision, &policy, &doc, &persons)
+            .unwrap()
+            .is_ready());
+        assert_eq!(
+            patch
+                .revision_readiness(&update, &policy, &doc, &persons)
+                .unwrap()
+                .blockers,
+            vec![MergeBlocker::Approvals {
+                approvals: 0,
+                required: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_patch_merge_readiness_devices() {
+        let alice = test::setup::NodeWithRepo::default();
+        let laptop = Device::mock();
+        let checkout = alice.repo.checkout();
+        let branch = checkout.branch_with([("README", b"Hello World!")]);
+        let person = *Persons::open(&*alice.repo)
+            .unwrap()
+            .create([*laptop.public_key()], &alice.signer)
+            .unwrap()
+            .id();
+        let doc = alice
+            .repo
+            .identity_doc()
+            .unwrap()
+            .doc
+            .with_edits(|raw| {
+                raw.payload.insert(
+                    PayloadId::of::<DelegatePersons>(),
+                    DelegatePersons {
+                        persons: [(alice.signer.public_key().into(), person)].into(),
+                    }
+                    .into(),
+                );
+            })
+            .unwrap();
+        let persons = alice.repo.persons().unwrap();
+        let mut patches = Cache::no_cache(&*alice.repo).unwrap();
+        let mut patch = patches
+            .create(
+                "My first patch",
+                "Blah blah blah.",
+                MergeTarget::Delegates,
+                branch.base,
+                branch.oid,
+                &[],
+                &alice.signer,
+            )
+            .unwrap();
+        let (revision, _) = patch.latest();
+        let policy = MergePolicy {
+            approvals: 2,
+            resolved: false,
+        };
+
+        // Alice approves from her laptop.
+        patch
+            .review(revision, Some(Verdict::Accept), None, vec![], &laptop)
+            .unwrap();
+        assert_eq!(
+            patch.merge_readiness(&policy, &doc, &persons).blockers,
+            vec![MergeBlocker::Approvals {
+                approvals: 1,
+                required: 2
+            }]
+        );
+
+        // Approving again with another device of the same delegate doesn't count twice.
+        patch
+            .review(revision, Some(Verdict::Accept), None, vec![], &alice.signer)
+            .unwrap();
+        assert_eq!(
+            patch.merge_readiness(&policy, &doc, &persons).blockers,
+            vec![MergeBlocker::Approvals {
+                approvals: 1,
+                required: 2
+            }]
+        );
+    }
+
     #[test]
     fn test_patch_review_revision_redact() {
         let alice = test::setup::NodeWithRepo::default();
@@ -3642,6 +4319,19 @@ mod test {
             })
         );
 
+        assert_eq!(
+            serde_json::to_value(Action::Edit {
+                title: String::from("Fix build"),
+                target: MergeTarget::Branch(git::refname!("release/1.x")),
+            })
+            .unwrap(),
+            json!({
+                "type": "edit",
+                "title": "Fix build",
+                "target": { "branch": "release/1.x" },
+            })
+        );
+
         assert_eq!(
             serde_json::to_value(CodeRange::Lines { range: 4..8 }).unwrap(),
             json!({
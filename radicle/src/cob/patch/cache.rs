@@ -5,8 +5,10 @@ use sqlite as sql;
 use thiserror::Error;
 
 use crate::cob;
+use crate::cob::cache::search::{self, SearchText, Searchable};
 use crate::cob::cache::{self, StoreReader};
 use crate::cob::cache::{Remove, StoreWriter, Update};
+use crate::cob::filter::Filter;
 use crate::cob::store;
 use crate::cob::{Label, ObjectId, TypeName};
 use crate::git;
@@ -46,6 +48,14 @@ pub trait Patches {
     /// [`Patches::drafted`], [`Patches::merged`].
     fn list_by_status(&self, status: &Status) -> Result<Self::Iter<'_>, Self::Error>;
 
+    /// Search the titles, descriptions, comments and review comments of all
+    /// patches in the store. All terms of the query must match; terms ending
+    /// with `*` match as prefixes.
+    fn search(&self, query: &str) -> Result<Self::Iter<'_>, Self::Error>;
+
+    /// List all patches in the store that match the given [`Filter`].
+    fn filter(&self, filter: &Filter) -> Result<Self::Iter<'_>, Self::Error>;
+
     /// Get the [`PatchCounts`] of all the patches in the store.
     fn counts(&self) -> Result<PatchCounts, Self::Error>;
 
@@ -69,6 +79,20 @@ pub trait Patches {
         self.list_by_status(&Status::Merged)
     }
 
+    /// List all patches in the store that depend on the patch identified by `id`.
+    fn dependents(&self, id: &PatchId) -> Result<Vec<(PatchId, Patch)>, Self::Error> {
+        let mut dependents = Vec::new();
+
+        for result in self.list()? {
+            let (other, patch) = result?;
+
+            if patch.depends_on(id) {
+                dependents.push((other, patch));
+            }
+        }
+        Ok(dependents)
+    }
+
     /// Returns `true` if there are no patches in the store.
     fn is_empty(&self) -> Result<bool, Self::Error> {
         Ok(self.counts()?.total() == 0)
@@ -370,7 +394,10 @@ impl Update<Patch> for StoreWriter {
         stmt.bind((3, sql::Value::String(serde_json::to_string(&object)?)))?;
         stmt.next()?;
 
-        Ok(self.db.change_count() > 0)
+        let updated = self.db.change_count() > 0;
+        search::index(&self.db, "patches_search", rid, id, object)?;
+
+        Ok(updated)
     }
 }
 
@@ -379,6 +406,12 @@ impl Remove<Patch> for StoreWriter {
     type RemoveError = sql::Error;
 
     fn remove(&mut self, id: &ObjectId) -> Result<Self::Out, Self::RemoveError> {
+        let mut stmt = self.db.prepare("SELECT repo FROM patches WHERE id = ?1")?;
+        stmt.bind((1, sql::Value::String(id.to_string())))?;
+        let rid = match stmt.into_iter().next() {
+            Some(row) => RepoId::from_str(row?.read::<&str, _>("repo")).ok(),
+            None => None,
+        };
         let mut stmt = self.db.prepare(
             "DELETE FROM patches
              WHERE id = ?1",
@@ -387,7 +420,12 @@ impl Remove<Patch> for StoreWriter {
         stmt.bind((1, sql::Value::String(id.to_string())))?;
         stmt.next()?;
 
-        Ok(self.db.change_count() > 0)
+        let removed = self.db.change_count() > 0;
+        if let Some(rid) = rid {
+            search::unindex(&self.db, "patches_search", &rid, id)?;
+        }
+
+        Ok(removed)
     }
 
     fn remove_all(&mut self, rid: &RepoId) -> Result<Self::Out, Self::RemoveError> {
@@ -399,7 +437,35 @@ impl Remove<Patch> for StoreWriter {
         stmt.bind((1, rid))?;
         stmt.next()?;
 
-        Ok(self.db.change_count() > 0)
+        let removed = self.db.change_count() > 0;
+        search::unindex_all(&self.db, "patches_search", rid)?;
+
+        Ok(removed)
+    }
+}
+
+impl Searchable for Patch {
+    fn search_text(&self) -> SearchText {
+        let mut comments = Vec::new();
+
+        for (i, (_, revision)) in self.revisions().enumerate() {
+            // The description of the root revision is the patch description.
+            if i > 0 {
+                comments.push(revision.description());
+            }
+            comments.extend(revision.replies().map(|(_, c)| c.body()));
+
+            for (_, review) in revision.reviews() {
+                comments.extend(review.summary());
+                comments.extend(review.comments().map(|(_, c)| c.body()));
+            }
+        }
+
+        SearchText {
+            title: self.title().to_owned(),
+            description: self.description().to_owned(),
+            comments: comments.join("\n"),
+        }
     }
 }
 
@@ -469,6 +535,14 @@ where
         query::list_by_status(&self.cache.db, &self.rid(), status)
     }
 
+    fn search(&self, query: &str) -> Result<Self::Iter<'_>, Self::Error> {
+        query::search(&self.cache.db, &self.rid(), query)
+    }
+
+    fn filter(&self, filter: &Filter) -> Result<Self::Iter<'_>, Self::Error> {
+        query::filter(&self.cache.db, &self.rid(), filter)
+    }
+
     fn counts(&self) -> Result<PatchCounts, Self::Error> {
         query::counts(&self.cache.db, &self.rid())
     }
@@ -530,6 +604,35 @@ where
             .map_err(super::Error::from)
     }
 
+    fn search(&self, query: &str) -> Result<Self::Iter<'_>, Self::Error> {
+        let query = query.to_owned();
+        self.store
+            .all()
+            .map(move |inner| NoCacheIter {
+                inner: Box::new(inner.into_iter().filter_map(move |res| match res {
+                    Ok((id, patch)) => {
+                        let found = patch.search_text().matches(&query);
+                        found.then_some((id, patch)).map(Ok)
+                    }
+                    Err(e) => Some(Err(e.into())),
+                })),
+            })
+            .map_err(super::Error::from)
+    }
+
+    fn filter(&self, filter: &Filter) -> Result<Self::Iter<'_>, Self::Error> {
+        let filter = filter.clone();
+        self.store
+            .all()
+            .map(move |inner| NoCacheIter {
+                inner: Box::new(inner.into_iter().filter_map(move |res| match res {
+                    Ok((id, patch)) => filter.matches(&patch).then_some((id, patch)).map(Ok),
+                    Err(e) => Some(Err(e.into())),
+                })),
+            })
+            .map_err(super::Error::from)
+    }
+
     fn counts(&self) -> Result<PatchCounts, Self::Error> {
         self.store.counts().map_err(super::Error::from)
     }
@@ -561,6 +664,14 @@ where
         query::list_by_status(&self.cache.db, &self.rid(), status)
     }
 
+    fn search(&self, query: &str) -> Result<Self::Iter<'_>, Self::Error> {
+        query::search(&self.cache.db, &self.rid(), query)
+    }
+
+    fn filter(&self, filter: &Filter) -> Result<Self::Iter<'_>, Self::Error> {
+        query::filter(&self.cache.db, &self.rid(), filter)
+    }
+
     fn counts(&self) -> Result<PatchCounts, Self::Error> {
         query::counts(&self.cache.db, &self.rid())
     }
@@ -669,6 +780,66 @@ mod query {
         })
     }
 
+    pub(super) fn search<'a>(
+        db: &'a sql::ConnectionThreadSafe,
+        rid: &RepoId,
+        query: &str,
+    ) -> Result<PatchesIter<'a>, Error> {
+        let fts5 = search::is_fts5(db, "patches_search")?;
+        let mut values = Vec::new();
+        let Some(expr) = search::sql("patches_search", fts5, query, |value| {
+            values.push(value);
+            format!("?{}", values.len() + 1)
+        }) else {
+            return list(db, rid);
+        };
+        let order = if fts5 {
+            "patches_search.rank"
+        } else {
+            "patches.id"
+        };
+        let mut stmt = db.prepare(format!(
+            "SELECT patches.id, patches.patch
+             FROM patches_search
+             JOIN patches ON patches.id = patches_search.id AND patches.repo = patches_search.repo
+             WHERE patches_search.repo = ?1
+             AND {expr}
+             ORDER BY {order}
+            "
+        ))?;
+        stmt.bind((1, rid))?;
+        for (i, value) in values.into_iter().enumerate() {
+            stmt.bind((i + 2, value.as_str()))?;
+        }
+        Ok(PatchesIter {
+            inner: stmt.into_iter(),
+        })
+    }
+
+    pub(super) fn filter<'a>(
+        db: &'a sql::ConnectionThreadSafe,
+        rid: &RepoId,
+        filter: &Filter,
+    ) -> Result<PatchesIter<'a>, Error> {
+        let fts5 = search::is_fts5(db, "patches_search")?;
+        let (expr, values) = filter.sql::<Patch>(2, fts5);
+        let mut stmt = db.prepare(format!(
+            "SELECT id, patch
+             FROM patches
+             WHERE repo = ?1
+             AND {expr}
+             ORDER BY id
+            "
+        ))?;
+        stmt.bind((1, rid))?;
+        for (i, value) in values.into_iter().enumerate() {
+            stmt.bind((i + 2, value))?;
+        }
+        Ok(PatchesIter {
+            inner: stmt.into_iter(),
+        })
+    }
+
     pub(super) fn counts(
         db: &sql::ConnectionThreadSafe,
         rid: &RepoId,
@@ -710,11 +881,13 @@ mod tests {
     use radicle_cob::ObjectId;
 
     use crate::cob::cache::{Store, Update, Write};
+    use crate::cob::filter::Filter;
     use crate::cob::migrate;
     use crate::cob::thread::{Comment, Thread};
-    use crate::cob::Author;
+    use crate::cob::{Author, Label, Timestamp};
     use crate::patch::{
-        ByRevision, MergeTarget, Patch, PatchCounts, PatchId, Revision, RevisionId, State, Status,
+        ByRevision, MergeTarget, Patch, PatchCounts, PatchId, Review, ReviewId, Revision,
+        RevisionId, State, Status,
     };
     use crate::prelude::Did;
     use crate::profile::env;
@@ -954,6 +1127,149 @@ mod tests {
         assert_eq!(patches, list);
     }
 
+    #[test]
+    fn test_search() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut cache = memory(repo);
+        let flux = PatchId::from(arbitrary::oid());
+        let speed = PatchId::from(arbitrary::oid());
+        let (id, mut rev) = revision();
+        let review = Review::new(
+            ReviewId::from(arbitrary::oid()),
+            rev.author.clone(),
+            None,
+            Some(String::from("Needs more gigawatts")),
+            vec![],
+            env::local_time().into(),
+        );
+        rev.reviews.insert(*rev.author.public_key(), review);
+
+        cache
+            .update(
+                &cache.rid(),
+                &flux,
+                &Patch::new(
+                    String::from("Power the flux capacitor"),
+                    MergeTarget::Delegates,
+                    (id, rev),
+                ),
+            )
+            .unwrap();
+        cache
+            .update(
+                &cache.rid(),
+                &speed,
+                &Patch::new(
+                    String::from("Fix speedometer"),
+                    MergeTarget::Delegates,
+                    revision(),
+                ),
+            )
+            .unwrap();
+
+        let search = |query: &str| {
+            cache
+                .search(query)
+                .unwrap()
+                .map(|r| r.map(|(id, _)| id))
+                .collect::<Result<BTreeSet<_>, _>>()
+                .unwrap()
+        };
+        assert_eq!(search("flux"), BTreeSet::from([flux]));
+        assert_eq!(search("gigawatts"), BTreeSet::from([flux]));
+        assert_eq!(search("speed*"), BTreeSet::from([speed]));
+        assert_eq!(search("comment"), BTreeSet::from([flux, speed]));
+        assert_eq!(search("flux speedometer"), BTreeSet::new());
+
+        super::Remove::remove(&mut cache, &flux).unwrap();
+        assert_eq!(cache.search("flux").unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_filter() {
+        let repo = arbitrary::gen::<MockRepository>(1);
+        let mut cache = memory(repo);
+        let bob = arbitrary::gen::<Did>(1);
+        let flux = PatchId::from(arbitrary::oid());
+        let speed = PatchId::from(arbitrary::oid());
+        let revision = |time: u64| {
+            let (id, mut rev) = revision();
+            rev.timestamp = Timestamp::from_secs(time);
+            (id, rev)
+        };
+        // 2024-01-01, updated on 2024-03-01.
+        let mut patch = Patch::new(
+            String::from("Power the flux capacitor"),
+            MergeTarget::Delegates,
+            revision(1704067200),
+        );
+        let (id, mut rev) = revision(1709251200);
+        let alice = patch.author.id;
+        rev.author = patch.author.clone();
+        patch.revisions.insert(id, Some(rev));
+        patch.timeline.push(id.into_inner());
+        patch.labels = BTreeSet::from([Label::new("bug").unwrap()]);
+        patch.assignees = BTreeSet::from([*bob]);
+
+        cache.update(&cache.rid(), &flux, &patch).unwrap();
+        cache
+            .update(
+                &cache.rid(),
+                &speed,
+                &Patch {
+                    state: State::Draft,
+                    labels: BTreeSet::from([Label::new("wontfix").unwrap()]),
+                    // 2023-06-01.
+                    ..Patch::new(
+                        String::from("Fix speedometer"),
+                        MergeTarget::Delegates,
+                        revision(1685577600),
+                    )
+                },
+            )
+            .unwrap();
+
+        let all = cache
+            .list()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let filter = |filter: &str| {
+            let filter = Filter::from_str(filter).unwrap();
+            let found = cache
+                .filter(&filter)
+                .unwrap()
+                .map(|r| r.map(|(id, _)| id))
+                .collect::<Result<BTreeSet<_>, _>>()
+                .unwrap();
+            let matched = all
+                .iter()
+                .filter(|(_, patch)| filter.matches(patch))
+                .map(|(id, _)| *id)
+                .collect::<BTreeSet<_>>();
+            assert_eq!(
+                found, matched,
+                "filter `{filter}` doesn't match in the cache"
+            );
+
+            found
+        };
+        assert_eq!(filter(""), BTreeSet::from([flux, speed]));
+        assert_eq!(filter("label:bug"), BTreeSet::from([flux]));
+        assert_eq!(filter("-label:wontfix"), BTreeSet::from([flux]));
+        assert_eq!(filter(&format!("author:{alice}")), BTreeSet::from([flux]));
+        assert_eq!(filter(&format!("assignee:{bob}")), BTreeSet::from([flux]));
+        assert_eq!(filter("state:open"), BTreeSet::from([flux]));
+        assert_eq!(filter("state:draft"), BTreeSet::from([speed]));
+        assert_eq!(filter("state:closed"), BTreeSet::new());
+        assert_eq!(filter("created:<2024-01-01"), BTreeSet::from([speed]));
+        assert_eq!(filter("created:2024-01-01"), BTreeSet::from([flux]));
+        assert_eq!(filter("updated:2024-01-01"), BTreeSet::new());
+        assert_eq!(filter("updated:>=2024-03-01"), BTreeSet::from([flux]));
+        assert_eq!(filter("speed*"), BTreeSet::from([speed]));
+        assert_eq!(filter("-flux -state:draft"), BTreeSet::new());
+    }
+
     #[test]
     fn test_list_by_status() {
         let repo = arbitrary::gen::<MockRepository>(1);
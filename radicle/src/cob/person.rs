@@ -0,0 +1,635 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Deref;
+use std::str::FromStr;
+
+use crypto::PublicKey;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cob;
+use crate::cob::common::Timestamp;
+use crate::cob::store::Transaction;
+use crate::cob::store::{Cob, CobAction};
+use crate::cob::{op, store, ActorId, EntryId, ObjectId, TypeName};
+use crate::git;
+use crate::identity::Did;
+use crate::node::device::Device;
+use crate::node::NodeId;
+use crate::prelude::ReadRepository;
+use crate::storage::WriteRepository;
+
+/// Person operation.
+pub type Op = cob::Op<Action>;
+
+/// Type name of a person.
+pub static TYPENAME: Lazy<TypeName> =
+    Lazy::new(|| FromStr::from_str("xyz.radicle.person").expect("type name is valid"));
+
+/// Identifier for a person.
+pub type PersonId = ObjectId;
+
+/// Error updating or creating persons.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("store: {0}")]
+    Store(#[from] store::Error),
+    /// Rotation not authorized, ie. the author is not a current device.
+    #[error("{0} is not a device of this person")]
+    NotAuthorized(ActorId),
+    /// Rotation is invalid.
+    #[error("invalid rotation: {0}")]
+    InvalidRotation(&'static str),
+    /// General error initializing a person.
+    #[error("initialization failed: {0}")]
+    Init(&'static str),
+    /// Error decoding an operation.
+    #[error("op decoding failed: {0}")]
+    Op(#[from] op::OpEncodingError),
+}
+
+/// A signed change to the set of devices of a person.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rotation {
+    /// The operation that carried the rotation.
+    pub entry: EntryId,
+    /// The device that signed the rotation.
+    pub author: PublicKey,
+    /// Devices added.
+    pub add: BTreeSet<PublicKey>,
+    /// Devices removed.
+    pub remove: BTreeSet<PublicKey>,
+    /// Time at which the rotation was made.
+    pub timestamp: Timestamp,
+}
+
+/// A person, ie. a stable identity that signs with one or more device keys.
+///
+/// The identifier of a person is the [`Did`] of the key that created it. This
+/// is the [`Did`] that is listed in an identity document to delegate to the
+/// person, along with the person itself, see [`Index`]. Device keys can then
+/// be added and removed by any current device, including removing the key that
+/// created the person, eg. after it was compromised.
+///
+/// Accumulates [`Action`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Person {
+    /// Stable identifier of the person.
+    pub(super) did: Did,
+    /// Current device keys of the person.
+    pub(super) devices: BTreeSet<PublicKey>,
+    /// History of device rotations, in the order they were applied.
+    pub(super) rotations: Vec<Rotation>,
+}
+
+impl cob::store::CobWithType for Person {
+    fn type_name() -> &'static TypeName {
+        &TYPENAME
+    }
+}
+
+impl store::Cob for Person {
+    type Action = Action;
+    type Error = Error;
+
+    fn from_root<R: ReadRepository>(op: Op, _repo: &R) -> Result<Self, Self::Error> {
+        let mut actions = op.actions.into_iter();
+        let Some(Action::Rotate { add, remove }) = actions.next() else {
+            return Err(Error::Init("the first action must be of type `rotate`"));
+        };
+        let mut person = Person::new(op.author);
+
+        person.rotate(op.id, op.author, add, remove, op.timestamp)?;
+
+        for action in actions {
+            person.action(action, op.id, op.author, op.timestamp)?;
+        }
+        Ok(person)
+    }
+
+    fn op<'a, R: ReadRepository, I: IntoIterator<Item = &'a cob::Entry>>(
+        &mut self,
+        op: Op,
+        _concurrent: I,
+        _repo: &R,
+    ) -> Result<(), Error> {
+        for action in op.actions {
+            log::trace!(target: "person", "Applying {} {action:?}", op.id);
+
+            if let Err(e) = self.action(action, op.id, op.author, op.timestamp) {
+                log::warn!(target: "person", "Error applying {}: {e}", op.id);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: ReadRepository> cob::Evaluate<R> for Person {
+    type Error = Error;
+
+    fn init(entry: &cob::Entry, repo: &R) -> Result<Self, Self::Error> {
+        let op = Op::try_from(entry)?;
+        let object = Person::from_root(op, repo)?;
+
+        Ok(object)
+    }
+
+    fn apply<'a, I: Iterator<Item = (&'a EntryId, &'a cob::Entry)>>(
+        &mut self,
+        entry: &cob::Entry,
+        concurrent: I,
+        repo: &R,
+    ) -> Result<(), Self::Error> {
+        let op = Op::try_from(entry)?;
+
+        self.op(op, concurrent.map(|(_, e)| e), repo)
+    }
+}
+
+impl Person {
+    /// Construct a new person, with the given key as its only device.
+    fn new(key: PublicKey) -> Self {
+        Self {
+            did: key.into(),
+            devices: BTreeSet::from([key]),
+            rotations: Vec::new(),
+        }
+    }
+
+    /// Stable identifier of the person.
+    pub fn did(&self) -> &Did {
+        &self.did
+    }
+
+    /// Current device keys of the person.
+    pub fn devices(&self) -> impl Iterator<Item = &PublicKey> {
+        self.devices.iter()
+    }
+
+    /// Check whether the given key is a current device of the person.
+    pub fn is_device(&self, key: &PublicKey) -> bool {
+        self.devices.contains(key)
+    }
+
+    /// Device keys of the person as of the given heads of its history, ie.
+    /// taking into account only the rotations reachable from `heads`. Returns
+    /// `None` if none of the heads are part of the history of this person.
+    ///
+    /// Since the result only depends on `heads`, it doesn't change when the
+    /// person rotates its keys later on.
+    pub fn devices_as_of<R: ReadRepository>(
+        &self,
+        heads: &[git::Oid],
+        repo: &R,
+    ) -> Result<Option<BTreeSet<PublicKey>>, git::ext::Error> {
+        let mut devices = BTreeSet::from([*self.did.as_key()]);
+        let mut related = false;
+
+        for rotation in &self.rotations {
+            let mut reachable = false;
+            for head in heads {
+                if *head == rotation.entry || repo.is_ancestor_of(rotation.entry, *head)? {
+                    reachable = true;
+                    break;
+                }
+            }
+            if reachable {
+                devices.extend(rotation.add.iter().copied());
+                devices.retain(|key| !rotation.remove.contains(key));
+                related = true;
+            }
+        }
+        Ok(related.then_some(devices))
+    }
+
+    /// History of device rotations.
+    pub fn rotations(&self) -> impl DoubleEndedIterator<Item = &Rotation> {
+        self.rotations.iter()
+    }
+
+    /// Apply a single action to the person.
+    fn action(
+        &mut self,
+        action: Action,
+        entry: EntryId,
+        author: ActorId,
+        timestamp: Timestamp,
+    ) -> Result<(), Error> {
+        match action {
+            Action::Rotate { add, remove } => self.rotate(entry, author, add, remove, timestamp),
+        }
+    }
+
+    /// Rotate device keys. Only current devices can rotate keys, and a person
+    /// must be left with at least one device.
+    fn rotate(
+        &mut self,
+        entry: EntryId,
+        author: ActorId,
+        add: BTreeSet<PublicKey>,
+        remove: BTreeSet<PublicKey>,
+        timestamp: Timestamp,
+    ) -> Result<(), Error> {
+        if !self.is_device(&author) {
+            return Err(Error::NotAuthorized(author));
+        }
+        if !add.is_disjoint(&remove) {
+            return Err(Error::InvalidRotation(
+                "a device cannot be both added and removed",
+            ));
+        }
+        let mut devices = self.devices.clone();
+        devices.extend(add.iter().copied());
+        devices.retain(|key| !remove.contains(key));
+
+        if devices.is_empty() {
+            return Err(Error::InvalidRotation("all devices cannot be removed"));
+        }
+        self.devices = devices;
+        self.rotations.push(Rotation {
+            entry,
+            author,
+            add,
+            remove,
+            timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+/// Persons of a repository, indexed by their identifier.
+///
+/// Since a person is created with the key of its identifier, anyone holding
+/// that key can create a person claiming the identifier, eg. after the key was
+/// compromised and removed from the devices of the person. Therefore, only the
+/// person that a delegate is pinned to in the identity document is taken into
+/// account, see [`crate::identity::DelegatePersons`]. A delegate that isn't
+/// pinned to a person only has its own key as device.
+#[derive(Debug, Default, Clone)]
+pub struct Index {
+    persons: BTreeMap<PersonId, (Person, BTreeSet<EntryId>)>,
+}
+
+impl Index {
+    /// Load all persons of a repository, along with the heads of their
+    /// histories.
+    pub fn load<R>(repo: &R) -> Result<Self, store::Error>
+    where
+        R: ReadRepository + cob::Store,
+    {
+        let mut persons = BTreeMap::new();
+
+        for cob in cob::list::<Person, _>(repo, &TYPENAME)? {
+            let tips = cob.history().tips();
+
+            persons.insert(*cob.id(), (cob.object, tips));
+        }
+        Ok(Self { persons })
+    }
+
+    /// Get the current device keys of the given delegate, which is pinned to
+    /// the given person, if any.
+    pub fn devices(&self, delegate: &Did, person: Option<&PersonId>) -> BTreeSet<PublicKey> {
+        match self.person(delegate, person) {
+            Some(person) => person.devices.clone(),
+            None => BTreeSet::from([*delegate.as_key()]),
+        }
+    }
+
+    /// Get the device keys of the given delegate, which is pinned to the given
+    /// person, as of the given heads of its history. If the heads aren't part
+    /// of the history of the person, its current devices are used, so that
+    /// a removed key can't sign by leaving out the rotation that removed it.
+    /// See [`Person::devices_as_of`].
+    pub fn devices_as_of<R: ReadRepository>(
+        &self,
+        delegate: &Did,
+        person: Option<&PersonId>,
+        heads: &[git::Oid],
+        repo: &R,
+    ) -> Result<BTreeSet<PublicKey>, git::ext::Error> {
+        match self.person(delegate, person) {
+            Some(person) => Ok(person
+                .devices_as_of(heads, repo)?
+                .unwrap_or_else(|| person.devices.clone())),
+            None => Ok(BTreeSet::from([*delegate.as_key()])),
+        }
+    }
+
+    /// Get the heads of the histories of all persons that have the given key
+    /// as a current device.
+    pub fn heads_of(&self, key: &PublicKey) -> BTreeSet<EntryId> {
+        self.persons
+            .values()
+            .filter(|(person, _)| person.is_device(key))
+            .flat_map(|(_, tips)| tips.iter().copied())
+            .collect()
+    }
+
+    /// Get the given person, if it exists and claims the given delegate.
+    fn person(&self, delegate: &Did, person: Option<&PersonId>) -> Option<&Person> {
+        self.persons
+            .get(person?)
+            .map(|(person, _)| person)
+            .filter(|person| person.did() == delegate)
+    }
+}
+
+impl<R: ReadRepository> store::Transaction<Person, R> {
+    /// Add and remove device keys.
+    pub fn rotate(
+        &mut self,
+        add: impl IntoIterator<Item = PublicKey>,
+        remove: impl IntoIterator<Item = PublicKey>,
+    ) -> Result<(), store::Error> {
+        self.push(Action::Rotate {
+            add: add.into_iter().collect(),
+            remove: remove.into_iter().collect(),
+        })
+    }
+}
+
+pub struct PersonMut<'a, 'g, R> {
+    id: ObjectId,
+    person: Person,
+    store: &'g mut Persons<'a, R>,
+}
+
+impl<R> std::fmt::Debug for PersonMut<'_, '_, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("PersonMut")
+            .field("id", &self.id)
+            .field("person", &self.person)
+            .finish()
+    }
+}
+
+impl<R> PersonMut<'_, '_, R>
+where
+    R: WriteRepository + cob::Store<Namespace = NodeId>,
+{
+    /// Reload the person data from storage.
+    pub fn reload(&mut self) -> Result<(), store::Error> {
+        self.person = self
+            .store
+            .get(&self.id)?
+            .ok_or_else(|| store::Error::NotFound(TYPENAME.clone(), self.id))?;
+
+        Ok(())
+    }
+
+    /// Get the person object id.
+    pub fn id(&self) -> &ObjectId {
+        &self.id
+    }
+
+    /// Add and remove device keys.
+    pub fn rotate<G>(
+        &mut self,
+        add: impl IntoIterator<Item = PublicKey>,
+        remove: impl IntoIterator<Item = PublicKey>,
+        signer: &Device<G>,
+    ) -> Result<EntryId, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+    {
+        self.transaction("Rotate", signer, |tx| tx.rotate(add, remove))
+    }
+
+    pub fn transaction<G, F>(
+        &mut self,
+        message: &str,
+        signer: &Device<G>,
+        operations: F,
+    ) -> Result<EntryId, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+        F: FnOnce(&mut Transaction<Person, R>) -> Result<(), store::Error>,
+    {
+        let mut tx = Transaction::default();
+        operations(&mut tx)?;
+
+        let (person, commit) = tx.commit(message, self.id, &mut self.store.raw, signer)?;
+        self.person = person;
+
+        Ok(commit)
+    }
+}
+
+impl<R> Deref for PersonMut<'_, '_, R> {
+    type Target = Person;
+
+    fn deref(&self) -> &Self::Target {
+        &self.person
+    }
+}
+
+pub struct Persons<'a, R> {
+    raw: store::Store<'a, Person, R>,
+}
+
+impl<'a, R> Deref for Persons<'a, R> {
+    type Target = store::Store<'a, Person, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.raw
+    }
+}
+
+impl<'a, R> Persons<'a, R>
+where
+    R: ReadRepository + cob::Store<Namespace = NodeId>,
+{
+    /// Open a persons store.
+    pub fn open(repository: &'a R) -> Result<Self, store::Error> {
+        let raw = store::Store::open(repository)?;
+
+        Ok(Self { raw })
+    }
+}
+
+impl<'a, R> Persons<'a, R>
+where
+    R: WriteRepository + cob::Store<Namespace = NodeId>,
+{
+    /// Create a new person, identified by the signer's key. The signer is
+    /// always a device of the new person, along with the given `devices`.
+    pub fn create<'g, G>(
+        &'g mut self,
+        devices: impl IntoIterator<Item = PublicKey>,
+        signer: &Device<G>,
+    ) -> Result<PersonMut<'a, 'g, R>, Error>
+    where
+        G: crypto::signature::Signer<crypto::Signature>,
+    {
+        let (id, person) =
+            Transaction::initial("Create person", &mut self.raw, signer, |tx, _| {
+                tx.rotate(devices, [])
+            })?;
+
+        Ok(PersonMut {
+            id,
+            person,
+            store: self,
+        })
+    }
+}
+
+impl<'a, R> Persons<'a, R>
+where
+    R: ReadRepository + cob::Store,
+{
+    /// Get a person.
+    pub fn get(&self, id: &ObjectId) -> Result<Option<Person>, store::Error> {
+        self.raw.get(id)
+    }
+
+    /// Get a person mutably.
+    pub fn get_mut<'g>(&'g mut self, id: &ObjectId) -> Result<PersonMut<'a, 'g, R>, store::Error> {
+        let person = self
+            .raw
+            .get(id)?
+            .ok_or_else(move || store::Error::NotFound(TYPENAME.clone(), *id))?;
+
+        Ok(PersonMut {
+            id: *id,
+            person,
+            store: self,
+        })
+    }
+}
+
+/// Person action.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Action {
+    /// Add and remove device keys.
+    #[serde(rename = "rotate")]
+    Rotate {
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        add: BTreeSet<PublicKey>,
+        #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+        remove: BTreeSet<PublicKey>,
+    },
+}
+
+impl CobAction for Action {}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_person_create_and_rotate() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let laptop = Device::mock();
+        let phone = Device::mock();
+        let mut persons = Persons::open(&*repo).unwrap();
+        let mut person = persons
+            .create([*laptop.public_key()], &node.signer)
+            .unwrap();
+        let id = *person.id();
+        let did = Did::from(*node.signer.public_key());
+
+        assert_eq!(person.did(), &did);
+        assert!(person.is_device(node.signer.public_key()));
+        assert!(person.is_device(laptop.public_key()));
+
+        // Another device can rotate keys, including removing the founding key.
+        person
+            .rotate([*phone.public_key()], [*node.signer.public_key()], &laptop)
+            .unwrap();
+
+        let person = persons.get(&id).unwrap().unwrap();
+        assert_eq!(person.did(), &did);
+        assert_eq!(
+            person.devices().collect::<BTreeSet<_>>(),
+            BTreeSet::from([laptop.public_key(), phone.public_key()])
+        );
+        assert_eq!(person.rotations().count(), 2);
+        assert_eq!(
+            Index::load(&*repo).unwrap().devices(&did, Some(&id)),
+            BTreeSet::from([*laptop.public_key(), *phone.public_key()])
+        );
+    }
+
+    #[test]
+    fn test_person_rotate_unauthorized() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let eve = Device::mock();
+        let mut persons = Persons::open(&*repo).unwrap();
+        let mut person = persons.create([], &node.signer).unwrap();
+
+        // Only current devices can rotate keys.
+        assert!(person.rotate([*eve.public_key()], [], &eve).is_err());
+        // A person can't be left without devices.
+        assert!(person
+            .rotate([], [*node.signer.public_key()], &node.signer)
+            .is_err());
+
+        person.reload().unwrap();
+        assert_eq!(
+            person.devices().collect::<Vec<_>>(),
+            vec![node.signer.public_key()]
+        );
+    }
+
+    #[test]
+    fn test_devices_not_a_person() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let did = Did::from(*node.signer.public_key());
+
+        assert_eq!(
+            Index::load(&*repo).unwrap().devices(&did, None),
+            BTreeSet::from([*node.signer.public_key()])
+        );
+    }
+
+    #[test]
+    fn test_devices_of_pinned_person() {
+        let test::setup::NodeWithRepo { node, repo, .. } = test::setup::NodeWithRepo::default();
+        let laptop = Device::mock();
+        let thief = Device::mock();
+        let did = Did::from(*node.signer.public_key());
+        let mut persons = Persons::open(&*repo).unwrap();
+        let mut person = persons
+            .create([*laptop.public_key()], &node.signer)
+            .unwrap();
+        let id = *person.id();
+
+        // The key that created the person is compromised, and removed by the laptop.
+        person
+            .rotate([], [*node.signer.public_key()], &laptop)
+            .unwrap();
+
+        // Whoever holds the key tries to claim the identifier again.
+        let claim = *persons
+            .create([*thief.public_key()], &node.signer)
+            .unwrap()
+            .id();
+        let index = Index::load(&*repo).unwrap();
+
+        // Only the pinned person counts.
+        assert_eq!(
+            index.devices(&did, Some(&id)),
+            BTreeSet::from([*laptop.public_key()])
+        );
+        assert_eq!(
+            index.devices(&did, Some(&claim)),
+            BTreeSet::from([*node.signer.public_key(), *thief.public_key()])
+        );
+        // A person that doesn't claim the delegate is ignored.
+        assert_eq!(
+            index.devices(&Did::from(*laptop.public_key()), Some(&id)),
+            BTreeSet::from([*laptop.public_key()])
+        );
+    }
+}
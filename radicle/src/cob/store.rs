@@ -216,13 +216,18 @@ where
         message: &str,
         actions: impl Into<NonEmpty<T::Action>>,
         embeds: Vec<Embed<Uri>>,
+        related: Vec<git::Oid>,
         signer: &Device<G>,
     ) -> Result<Updated<T>, Error>
     where
         G: crypto::signature::Signer<crypto::Signature>,
     {
         let actions = actions.into();
-        let related = actions.iter().flat_map(T::Action::parents).collect();
+        let related = actions
+            .iter()
+            .flat_map(T::Action::parents)
+            .chain(related)
+            .collect();
         let changes = actions.try_map(encoding::encode)?;
         let embeds = embeds
             .into_iter()
@@ -368,6 +373,7 @@ where
 pub struct Transaction<T: Cob + cob::Evaluate<R>, R> {
     actions: Vec<T::Action>,
     embeds: Vec<Embed<Uri>>,
+    related: Vec<git::Oid>,
 
     // Internal state kept for validation of the transaction.
     // If an action that produces an identifier is added to
@@ -385,6 +391,7 @@ impl<T: Cob + CobWithType + cob::Evaluate<R>, R> Default for Transaction<T, R> {
         Self {
             actions: Vec::new(),
             embeds: Vec::new(),
+            related: Vec::new(),
             produces_identifier: None,
             repo: PhantomData,
             type_name: T::type_name().clone(),
@@ -400,6 +407,7 @@ where
         Self {
             actions,
             embeds,
+            related: Vec::new(),
             produces_identifier: None,
             repo: PhantomData,
             type_name,
@@ -476,6 +484,13 @@ where
         Ok(())
     }
 
+    /// Make the transaction depend on other objects, in addition to the
+    /// parents of its actions. For example, identity updates depend on the
+    /// state of the persons that made them.
+    pub fn relate(&mut self, related: impl IntoIterator<Item = git::Oid>) {
+        self.related.extend(related);
+    }
+
     /// Commit transaction.
     ///
     /// Returns an operation that can be applied onto an in-memory state.
@@ -497,7 +512,15 @@ where
             head,
             object: CollaborativeObject { object, .. },
             ..
-        } = store.update(&self.type_name, id, msg, actions, self.embeds, signer)?;
+        } = store.update(
+            &self.type_name,
+            id,
+            msg,
+            actions,
+            self.embeds,
+            self.related,
+            signer,
+        )?;
 
         Ok((object, head))
     }
@@ -1,13 +1,14 @@
 use std::collections::BTreeMap;
 use std::fmt;
 
-use nonempty::NonEmpty;
 use raw::Repository;
 use thiserror::Error;
 
+use crate::identity::crefs::RefPattern;
+use crate::identity::Doc;
 use crate::prelude::Did;
 use crate::prelude::Project;
-use crate::storage::ReadRepository;
+use crate::storage::{ReadRepository, RepositoryError};
 
 use super::raw;
 use super::{lit, Oid, Qualified};
@@ -17,7 +18,8 @@ use super::{lit, Oid, Qualified};
 ///
 /// The general construction of `Canonical` is by using the
 /// [`Canonical::reference`] constructor. For the default branch of a
-/// [`Project`], use [`Canonical::default_branch`].
+/// [`Project`], use [`Canonical::default_branch`], and for all the references
+/// matching a pattern, use [`Canonical::matching`].
 ///
 /// `Canonical` can then be used for performing calculations about the
 /// canonicity of the reference, most importantly the [`Canonical::quorum`].
@@ -34,6 +36,10 @@ pub enum QuorumError {
     /// Could not determine a base candidate from the given set of delegates.
     #[error("could not determine canonical reference tip, {0}")]
     NoCandidates(NoCandidates),
+    /// Could not determine a quorum [`Oid`], since more than one target has
+    /// enough votes.
+    #[error("could not determine canonical reference target, {0}")]
+    Conflicting(Conflicting),
     /// An error occurred from [`git2`].
     #[error(transparent)]
     Git(#[from] git2::Error),
@@ -83,51 +89,158 @@ impl fmt::Display for Diverging {
     }
 }
 
+/// Conflicting targets were found during the [`Canonical::quorum_exact`]
+/// calculation.
+///
+/// The [`fmt::Display`] is used in [`QuorumError`], to provide information on
+/// the threshold and the conflicting targets.
+#[derive(Debug)]
+pub struct Conflicting {
+    threshold: usize,
+    targets: Vec<Oid>,
+}
+
+impl fmt::Display for Conflicting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Conflicting { threshold, targets } = self;
+        let targets = targets
+            .iter()
+            .map(|oid| oid.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(
+            f,
+            "found conflicting targets {targets}, with at least {threshold} vote(s) each"
+        )
+    }
+}
+
 impl Canonical {
     /// Construct the set of canonical tips of the `Project::default_branch` for
-    /// the given `delegates`.
+    /// the delegates of the given `doc`.
     pub fn default_branch<S>(
         repo: &S,
         project: &Project,
-        delegates: &NonEmpty<Did>,
-    ) -> Result<Self, raw::Error>
+        doc: &Doc,
+    ) -> Result<Self, RepositoryError>
     where
         S: ReadRepository,
     {
-        Self::reference(
-            repo,
-            delegates,
-            &lit::refs_heads(project.default_branch()).into(),
-        )
+        Self::reference(repo, doc, &lit::refs_heads(project.default_branch()).into())
     }
 
-    /// Construct the set of canonical tips given for the given `delegates` and
-    /// the reference `name`.
-    pub fn reference<S>(
-        repo: &S,
-        delegates: &NonEmpty<Did>,
-        name: &Qualified,
-    ) -> Result<Self, raw::Error>
+    /// Construct the set of canonical tips given for the delegates of the given
+    /// `doc` and the reference `name`.
+    ///
+    /// A delegate that is a [`crate::identity::Person`] votes with the tip of
+    /// any of its current devices. See [`Canonical::vote`].
+    pub fn reference<S>(repo: &S, doc: &Doc, name: &Qualified) -> Result<Self, RepositoryError>
     where
         S: ReadRepository,
     {
+        let persons = repo.persons()?;
         let mut tips = BTreeMap::new();
-        for delegate in delegates.iter() {
-            match repo.reference_oid(delegate, name) {
-                Ok(tip) => {
-                    tips.insert(*delegate, tip);
+        for delegate in doc.delegates().iter() {
+            let mut votes = Vec::new();
+            for device in persons.devices(delegate, doc.person_of(delegate).as_ref()) {
+                match repo.reference_oid(&device, name) {
+                    Ok(tip) => votes.push(tip),
+                    Err(e) if super::ext::is_not_found_err(&e) => {}
+                    Err(e) => return Err(e.into()),
                 }
-                Err(e) if super::ext::is_not_found_err(&e) => {
+            }
+            if votes.is_empty() {
+                log::warn!(
+                    target: "radicle",
+                    "Missing `refs/namespaces/{}/{name}` while calculating the canonical reference",
+                    delegate.as_key()
+                );
+            }
+            if let Some(tip) = Self::vote(repo, delegate, name, votes) {
+                tips.insert(*delegate, tip);
+            }
+        }
+        Ok(Canonical { tips })
+    }
+
+    /// Construct the sets of canonical tips of all the references of the
+    /// delegates of the given `doc` that match `pattern`, keyed by reference name.
+    pub fn matching<S>(
+        repo: &S,
+        doc: &Doc,
+        pattern: &RefPattern,
+    ) -> Result<BTreeMap<Qualified<'static>, Self>, RepositoryError>
+    where
+        S: ReadRepository,
+    {
+        let persons = repo.persons()?;
+        let mut votes = BTreeMap::<_, BTreeMap<Did, Vec<Oid>>>::new();
+        for delegate in doc.delegates().iter() {
+            for device in persons.devices(delegate, doc.person_of(delegate).as_ref()) {
+                for (name, oid) in repo.references_of(&device)?.iter() {
+                    let Some(name) = Qualified::from_refstr(name) else {
+                        continue;
+                    };
+                    if !pattern.matches(&name) {
+                        continue;
+                    }
+                    votes
+                        .entry(name.to_owned())
+                        .or_default()
+                        .entry(*delegate)
+                        .or_default()
+                        .push(*oid);
+                }
+            }
+        }
+        let refs = votes
+            .into_iter()
+            .map(|(name, votes)| {
+                let tips = votes
+                    .into_iter()
+                    .filter_map(|(delegate, tips)| {
+                        Self::vote(repo, &delegate, &name, tips).map(|tip| (delegate, tip))
+                    })
+                    .collect();
+
+                (name, Canonical { tips })
+            })
+            .collect();
+
+        Ok(refs)
+    }
+
+    /// Reduce the tips of a delegate's devices to a single vote, ie. the tip
+    /// that all the other tips are ancestors of. If the tips diverge, the
+    /// delegate doesn't vote.
+    fn vote<S>(repo: &S, delegate: &Did, name: &Qualified, tips: Vec<Oid>) -> Option<Oid>
+    where
+        S: ReadRepository,
+    {
+        let mut tips = tips.into_iter();
+        let mut vote = tips.next()?;
+
+        for tip in tips {
+            if tip == vote {
+                continue;
+            }
+            match (
+                repo.is_ancestor_of(vote, tip),
+                repo.is_ancestor_of(tip, vote),
+            ) {
+                (Ok(true), _) => vote = tip,
+                (_, Ok(true)) => {}
+                _ => {
                     log::warn!(
                         target: "radicle",
-                        "Missing `refs/namespaces/{}/{name}` while calculating the canonical reference",
-                        delegate.as_key()
+                        "Devices of {delegate} have diverging tips {vote} and {tip} for `{name}`, ignoring their vote"
                     );
+                    return None;
                 }
-                Err(e) => return Err(e),
             }
         }
-        Ok(Canonical { tips })
+        Some(vote)
     }
 
     /// Return the set of [`Did`]s and their [`Oid`] tip.
@@ -244,6 +357,51 @@ impl Canonical {
         }
         Ok((*longest).into())
     }
+
+    /// Computes the quorum target based on the tips of `Canonical`, and the
+    /// threshold, without considering ancestry. This is the target that at
+    /// least `threshold` tips are equal to, and is used for references that
+    /// aren't expected to move, such as tags.
+    ///
+    /// Annotated tags are compared by the object they point to, since delegates
+    /// tagging the same commit each create their own tag object. If all the tips
+    /// voting for the target are the same tag object, that tag is the result.
+    /// Otherwise, the result is the target itself, ie. a lightweight tag.
+    ///
+    /// Returns an error if no target, or more than one target, passes the
+    /// threshold.
+    pub fn quorum_exact(
+        &self,
+        threshold: usize,
+        repo: &raw::Repository,
+    ) -> Result<Oid, QuorumError> {
+        let mut votes = BTreeMap::<Oid, Vec<Oid>>::new();
+        for tip in self.tips.values() {
+            let object = repo.find_object(**tip, None)?;
+            let target = match object.kind() {
+                Some(raw::ObjectType::Tag) => object.peel(raw::ObjectType::Any)?.id(),
+                _ => object.id(),
+            };
+            votes.entry(target.into()).or_default().push(*tip);
+        }
+        votes.retain(|_, tips| tips.len() >= threshold);
+
+        let mut targets = votes.into_iter();
+        match (targets.next(), targets.next()) {
+            (Some((target, tips)), None) => match tips.split_first() {
+                Some((tip, rest)) if rest.iter().all(|t| t == tip) => Ok(*tip),
+                _ => Ok(target),
+            },
+            (None, _) => Err(QuorumError::NoCandidates(NoCandidates { threshold })),
+            (Some((first, _)), Some((second, _))) => Err(QuorumError::Conflicting(Conflicting {
+                threshold,
+                targets: [first, second]
+                    .into_iter()
+                    .chain(targets.map(|(target, _)| target))
+                    .collect(),
+            })),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -252,9 +410,14 @@ mod tests {
 
     use super::*;
     use crate::assert_matches;
+    use crate::cob::person::Persons;
     use crate::git;
+    use crate::identity::doc::PayloadId;
+    use crate::identity::DelegatePersons;
     use crate::node::device::Device;
+    use crate::storage::WriteRepository;
     use crate::test::fixtures;
+    use crate::test::setup::NodeWithRepo;
 
     /// Test helper to construct a Canonical and get the quorum
     fn quorum(
@@ -561,4 +724,149 @@ mod tests {
             Err(QuorumError::NoCandidates(_))
         );
     }
+
+    #[test]
+    fn test_quorum_exact() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, c0) = fixtures::repository(tmp.path());
+        let c1 = fixtures::commit("C1", &[c0], &repo);
+        let c2 = fixtures::commit("C2", &[*c1], &repo);
+        let canonical = |heads: &[Oid]| Canonical {
+            tips: heads
+                .iter()
+                .enumerate()
+                .map(|(i, head)| {
+                    let signer = Device::mock_from_seed([(i + 1) as u8; 32]);
+                    (Did::from(signer.public_key()), *head)
+                })
+                .collect(),
+        };
+
+        assert_eq!(canonical(&[c1, c1, c2]).quorum_exact(2, &repo).unwrap(), c1);
+        assert_eq!(canonical(&[c2]).quorum_exact(1, &repo).unwrap(), c2);
+        // Unlike `quorum`, ancestors don't get a vote.
+        assert_matches!(
+            canonical(&[c1, c2]).quorum_exact(2, &repo),
+            Err(QuorumError::NoCandidates(_))
+        );
+        assert_matches!(
+            canonical(&[c1, c2]).quorum_exact(1, &repo),
+            Err(QuorumError::Conflicting(_))
+        );
+        assert_matches!(
+            canonical(&[]).quorum_exact(1, &repo),
+            Err(QuorumError::NoCandidates(_))
+        );
+    }
+
+    #[test]
+    fn test_quorum_exact_annotated_tags() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (repo, c0) = fixtures::repository(tmp.path());
+        let c1 = fixtures::commit("C1", &[c0], &repo);
+        let c2 = fixtures::commit("C2", &[*c1], &repo);
+        let sig = git::raw::Signature::now("anonymous", "anonymous@radicle.xyz").unwrap();
+        let tag = |target: Oid, message: &str| -> Oid {
+            let object = repo.find_object(*target, None).unwrap();
+            repo.tag_annotation_create("v1.0", &object, &sig, message)
+                .unwrap()
+                .into()
+        };
+        let canonical = |tips: &[Oid]| Canonical {
+            tips: tips
+                .iter()
+                .enumerate()
+                .map(|(i, tip)| {
+                    let signer = Device::mock_from_seed([(i + 1) as u8; 32]);
+                    (Did::from(signer.public_key()), *tip)
+                })
+                .collect(),
+        };
+        let (a, b, c) = (tag(c1, "Alice's"), tag(c1, "Bob's"), tag(c2, "Eve's"));
+
+        // Delegates who tag the same commit agree, even though their tags differ.
+        assert_eq!(canonical(&[a, b]).quorum_exact(2, &repo).unwrap(), c1);
+        assert_eq!(canonical(&[a, c1]).quorum_exact(2, &repo).unwrap(), c1);
+        // When they agree on the tag itself, the tag is kept.
+        assert_eq!(canonical(&[a, a, c]).quorum_exact(2, &repo).unwrap(), a);
+        assert_matches!(
+            canonical(&[a, c]).quorum_exact(2, &repo),
+            Err(QuorumError::NoCandidates(_))
+        );
+        assert_matches!(
+            canonical(&[a, c]).quorum_exact(1, &repo),
+            Err(QuorumError::Conflicting(_))
+        );
+    }
+
+    #[test]
+    fn test_person_devices() {
+        let NodeWithRepo { node, repo } = NodeWithRepo::default();
+        let laptop = Device::mock();
+        let master = git::Qualified::from(lit::refs_heads(git::refname!("master")));
+        let did = Did::from(node.signer.public_key());
+        let head = repo
+            .reference_oid(node.signer.public_key(), &master)
+            .unwrap();
+
+        // The laptop pushes a commit on top of the delegate's head.
+        let parent = repo.raw().find_commit(*head).unwrap();
+        let ahead = repo
+            .raw()
+            .commit(
+                None,
+                &parent.author(),
+                &parent.committer(),
+                "Ahead",
+                &parent.tree().unwrap(),
+                &[&parent],
+            )
+            .unwrap();
+        repo.raw()
+            .reference(
+                &format!("refs/namespaces/{}/{master}", laptop.public_key()),
+                ahead,
+                false,
+                "test",
+            )
+            .unwrap();
+
+        // The laptop isn't a device of the delegate yet.
+        let doc = repo.identity_doc().unwrap();
+        let canonical = Canonical::reference(&*repo, &doc, &master).unwrap();
+        assert_eq!(canonical.tips().collect::<Vec<_>>(), vec![(&did, &head)]);
+
+        // Nor is it once the delegate creates a person with it, until the person is
+        // pinned in the identity document.
+        let person = *Persons::open(&*repo)
+            .unwrap()
+            .create([*laptop.public_key()], &node.signer)
+            .unwrap()
+            .id();
+
+        let canonical = Canonical::reference(&*repo, &doc, &master).unwrap();
+        assert_eq!(canonical.tips().collect::<Vec<_>>(), vec![(&did, &head)]);
+
+        // Then the delegate votes with the most advanced tip of its devices.
+        let doc = doc
+            .doc
+            .clone()
+            .with_edits(|raw| {
+                raw.payload.insert(
+                    PayloadId::of::<DelegatePersons>(),
+                    DelegatePersons {
+                        persons: [(did, person)].into(),
+                    }
+                    .into(),
+                );
+            })
+            .unwrap();
+
+        let canonical = Canonical::reference(&*repo, &doc, &master).unwrap();
+        assert_eq!(
+            canonical.tips().collect::<Vec<_>>(),
+            vec![(&did, &ahead.into())]
+        );
+        assert_eq!(canonical.quorum(1, repo.raw()).unwrap(), ahead.into());
+    }
 }
@@ -1,11 +1,31 @@
 #![warn(clippy::unwrap_used)]
+pub mod ci;
+pub mod crefs;
 pub mod did;
 pub mod doc;
+mod glob;
+pub mod links;
+pub mod maintainers;
+pub mod merge;
+pub mod owners;
+pub mod payloads;
+pub mod persons;
 pub mod project;
+pub mod templates;
 
+pub use ci::Ci;
+pub use crefs::CanonicalRefs;
 pub use crypto::PublicKey;
 pub use did::Did;
 pub use doc::{Doc, DocAt, DocError, IdError, PayloadError, RawDoc, RepoId, Visibility};
+pub use links::Links;
+pub use maintainers::Maintainers;
+pub use merge::MergePolicy;
+pub use owners::CodeOwners;
+pub use payloads::TypedPayload;
+pub use persons::DelegatePersons;
 pub use project::Project;
+pub use templates::{Template, Templates};
 
 pub use crate::cob::identity::{Action, Error, Identity, IdentityMut, TYPENAME};
+pub use crate::cob::person::{Person, PersonId};
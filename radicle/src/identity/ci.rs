@@ -0,0 +1,135 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize};
+use thiserror::Error;
+
+use crate::identity::doc;
+use crate::identity::doc::Payload;
+
+/// Invalid CI job name.
+#[derive(Debug, Error)]
+#[error("invalid job name: {0}")]
+pub struct JobNameError(&'static str);
+
+/// Continuous integration jobs, run by CI brokers against new patch revisions.
+///
+/// Stored in the identity document under the `xyz.radicle.ci` payload.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Ci {
+    /// Jobs, keyed by name.
+    #[serde(default)]
+    pub jobs: BTreeMap<JobName, Job>,
+}
+
+/// A valid CI job name.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct JobName(String);
+
+impl JobName {
+    /// Return a string reference to the name.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for JobName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<JobName> for String {
+    fn from(name: JobName) -> Self {
+        name.0
+    }
+}
+
+impl TryFrom<String> for JobName {
+    type Error = JobNameError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if s.is_empty() {
+            return Err(JobNameError("name cannot be empty"));
+        } else if s.len() > doc::MAX_STRING_LENGTH {
+            return Err(JobNameError("name cannot exceed 255 bytes"));
+        }
+        if !s
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ['-', '_', '.'].contains(&c))
+        {
+            return Err(JobNameError(
+                "only alphanumeric characters, '-', '_' and '.' are allowed",
+            ));
+        }
+        Ok(Self(s))
+    }
+}
+
+/// A CI job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Job {
+    /// Container image the job runs in, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// Commands to run, in order. There is at least one command.
+    #[serde(deserialize_with = "commands")]
+    pub run: Vec<String>,
+}
+
+/// Deserialize a non-empty list of non-empty commands.
+fn commands<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let commands = Vec::<String>::deserialize(deserializer)?;
+
+    if commands.is_empty() {
+        return Err(serde::de::Error::custom(
+            "job must run at least one command",
+        ));
+    }
+    if commands.iter().any(|c| c.trim().is_empty()) {
+        return Err(serde::de::Error::custom("job commands cannot be empty"));
+    }
+    Ok(commands)
+}
+
+impl From<Ci> for Payload {
+    fn from(ci: Ci) -> Self {
+        let value =
+            serde_json::to_value(ci).expect("Payload::from: could not convert CI into value");
+
+        Self::from(value)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ci_json() {
+        let ci: Ci = serde_json::from_str(
+            r#"{
+              "jobs": {
+                "test": { "image": "rust:1.80", "run": ["cargo test"] },
+                "lint": { "run": ["cargo fmt --check", "cargo clippy"] }
+              }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(ci.jobs.len(), 2);
+        assert_eq!(
+            ci.jobs[&JobName::try_from(String::from("test")).unwrap()].image,
+            Some(String::from("rust:1.80"))
+        );
+        assert_eq!(serde_json::from_str::<Ci>("{}").unwrap(), Ci::default());
+
+        assert!(serde_json::from_str::<Ci>(r#"{ "jobs": { "test": { "run": [] } } }"#).is_err());
+        assert!(serde_json::from_str::<Ci>(r#"{ "jobs": { "test": { "run": [" "] } } }"#).is_err());
+        assert!(serde_json::from_str::<Ci>(r#"{ "jobs": { "a b": { "run": ["ls"] } } }"#).is_err());
+        assert!(serde_json::from_str::<Ci>(r#"{ "job": {} }"#).is_err());
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::num::NonZeroUsize;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::git;
+use crate::identity::doc;
+use crate::identity::doc::Payload;
+use crate::identity::glob;
+
+/// Invalid reference pattern.
+#[derive(Debug, Error)]
+#[error("invalid reference pattern: {0}")]
+pub struct RefPatternError(&'static str);
+
+/// References, other than the default branch, that delegates agree on, eg.
+/// release tags.
+///
+/// Stored in the identity document under the `xyz.radicle.crefs` payload.
+/// The canonical tip of every delegate reference matching one of the rules is
+/// computed with the rule's threshold, and written to the top-level references
+/// of the repository. When more than one rule matches a reference, the rule
+/// with the longest pattern applies.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CanonicalRefs {
+    /// Rules, keyed by reference pattern.
+    #[serde(default)]
+    pub rules: BTreeMap<RefPattern, Rule>,
+}
+
+impl CanonicalRefs {
+    /// Get the rule that applies to the given reference, if any.
+    pub fn rule(&self, name: &git::Qualified) -> Option<(&RefPattern, &Rule)> {
+        self.rules
+            .iter()
+            .filter(|(pattern, _)| pattern.matches(name))
+            .max_by_key(|(pattern, _)| pattern.as_str().len())
+    }
+}
+
+/// A canonical reference rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Rule {
+    /// Number of delegates that must agree on the reference. If there are
+    /// fewer delegates, the reference can't be canonical.
+    pub threshold: NonZeroUsize,
+}
+
+/// A pattern of branches or tags, eg. `refs/tags/v*`.
+///
+/// Patterns are qualified references under `refs/heads` or `refs/tags`, where
+/// `*` matches any sequence of characters, including `/`, as in Git refspecs,
+/// and `?` matches any single character.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RefPattern(String);
+
+impl RefPattern {
+    /// Check whether the given qualified reference matches.
+    pub fn matches(&self, name: &git::Qualified) -> bool {
+        glob::wildcard(&self.0, name.as_str())
+    }
+
+    /// Whether the pattern only matches tags.
+    pub fn is_tags(&self) -> bool {
+        self.0.starts_with("refs/tags/")
+    }
+
+    /// Return a string reference to the pattern.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for RefPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<RefPattern> for String {
+    fn from(pattern: RefPattern) -> Self {
+        pattern.0
+    }
+}
+
+impl TryFrom<String> for RefPattern {
+    type Error = RefPatternError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if s.len() > doc::MAX_STRING_LENGTH {
+            return Err(RefPatternError("pattern cannot exceed 255 bytes"));
+        }
+        if !s.starts_with("refs/heads/") && !s.starts_with("refs/tags/") {
+            return Err(RefPatternError(
+                "pattern must start with `refs/heads/` or `refs/tags/`",
+            ));
+        }
+        // Any reference matching the pattern must be valid, so the pattern
+        // must be valid with its wildcards substituted.
+        if git::RefString::try_from(s.replace(['*', '?'], "x")).is_err() {
+            return Err(RefPatternError("pattern is not a valid reference name"));
+        }
+        Ok(Self(s))
+    }
+}
+
+impl From<CanonicalRefs> for Payload {
+    fn from(crefs: CanonicalRefs) -> Self {
+        let value = serde_json::to_value(crefs)
+            .expect("Payload::from: could not convert canonical refs into value");
+
+        Self::from(value)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    fn pattern(s: &str) -> RefPattern {
+        RefPattern::try_from(s.to_owned()).unwrap()
+    }
+
+    fn qualified(s: &str) -> git::Qualified<'static> {
+        git::Qualified::from_refstr(git::RefString::try_from(s).unwrap())
+            .unwrap()
+            .to_owned()
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        for (p, name, expected) in [
+            ("refs/tags/v*", "refs/tags/v1.0.0", true),
+            ("refs/tags/v*", "refs/tags/1.0.0", false),
+            ("refs/tags/v*", "refs/heads/v1.0.0", false),
+            ("refs/heads/release/*", "refs/heads/release/1.x", true),
+            ("refs/heads/release/*", "refs/heads/release/1.x/fix", true),
+            ("refs/heads/release/*", "refs/heads/release", false),
+            ("refs/heads/next", "refs/heads/next", true),
+            ("refs/heads/next", "refs/heads/nextgen", false),
+            ("refs/tags/v?.0", "refs/tags/v1.0", true),
+            ("refs/tags/v?.0", "refs/tags/v10.0", false),
+        ] {
+            assert_eq!(
+                pattern(p).matches(&qualified(name)),
+                expected,
+                "`{p}` should {}match `{name}`",
+                if expected { "" } else { "not " }
+            );
+        }
+    }
+
+    #[test]
+    fn test_pattern_invalid() {
+        for p in [
+            "",
+            "refs/rad/*",
+            "refs/cobs/*",
+            "heads/*",
+            "refs/heads/",
+            "refs/heads/a..b",
+            "refs/heads/a b",
+        ] {
+            assert!(
+                RefPattern::try_from(p.to_owned()).is_err(),
+                "`{p}` is invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_canonical_refs() {
+        let crefs: CanonicalRefs = serde_json::from_str(
+            r#"{
+              "rules": {
+                "refs/tags/*": { "threshold": 1 },
+                "refs/tags/v*": { "threshold": 2 }
+              }
+            }"#,
+        )
+        .unwrap();
+
+        let (p, rule) = crefs.rule(&qualified("refs/tags/v1.0.0")).unwrap();
+        assert_eq!(p.as_str(), "refs/tags/v*");
+        assert_eq!(rule.threshold.get(), 2);
+
+        let (p, rule) = crefs.rule(&qualified("refs/tags/nightly")).unwrap();
+        assert_eq!(p.as_str(), "refs/tags/*");
+        assert_eq!(rule.threshold.get(), 1);
+
+        assert!(crefs.rule(&qualified("refs/heads/master")).is_none());
+        assert!(serde_json::from_str::<CanonicalRefs>(
+            r#"{ "rules": { "refs/tags/*": { "threshold": 0 } } }"#
+        )
+        .is_err());
+    }
+}
@@ -15,11 +15,15 @@ use serde::{de, Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::canonical::formatter::CanonicalFormatter;
-use crate::cob::identity;
+use crate::cob::{identity, person};
 use crate::crypto;
 use crate::crypto::Signature;
 use crate::git;
-use crate::identity::{project::Project, Did};
+use crate::identity::payloads::{self, TypedPayload};
+use crate::identity::{
+    crefs::CanonicalRefs, merge::MergePolicy, owners::CodeOwners, persons::DelegatePersons,
+    project::Project, templates::Templates, Did, PersonId,
+};
 use crate::node::device::Device;
 use crate::storage;
 use crate::storage::{ReadRepository, RepositoryError};
@@ -79,7 +83,7 @@ impl DocError {
 ///
 /// If an invalid version is found – either the `0` version, or an unrecognized
 /// future version – the parsing of a version will fail.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub struct Version(NonZeroU32);
 
 impl Version {
@@ -215,6 +219,14 @@ impl PayloadId {
                 .expect("PayloadId::project: type name is valid"),
         )
     }
+
+    /// Payload type of the given typed payload.
+    pub fn of<T: TypedPayload>() -> Self {
+        Self(
+            // SAFETY: Typed payload identifiers are valid, see `payloads::test_registry`.
+            TypeName::from_str(T::ID).expect("PayloadId::of: type name is valid"),
+        )
+    }
 }
 
 #[derive(Debug, Error)]
@@ -223,6 +235,8 @@ pub enum PayloadError {
     Json(#[from] serde_json::Error),
     #[error("payload '{0}' not found in identity document")]
     NotFound(PayloadId),
+    #[error("invalid payload '{0}': {1}")]
+    Invalid(PayloadId, serde_json::Error),
 }
 
 /// A `Payload` is a free-form JSON value that can be associated with an
@@ -257,6 +271,22 @@ impl Deref for Payload {
     }
 }
 
+/// Get the project payload out of the given document payloads.
+fn project(payload: &BTreeMap<PayloadId, Payload>) -> Result<Project, PayloadError> {
+    typed_payload(payload)?.ok_or_else(|| PayloadError::NotFound(PayloadId::of::<Project>()))
+}
+
+/// Get a typed payload, if it exists, out of the given document payloads.
+fn typed_payload<T: TypedPayload>(
+    payload: &BTreeMap<PayloadId, Payload>,
+) -> Result<Option<T>, PayloadError> {
+    payload
+        .get(&PayloadId::of::<T>())
+        .map(|value| serde_json::from_value((**value).clone()))
+        .transpose()
+        .map_err(PayloadError::from)
+}
+
 /// A verified identity document at a specific commit.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DocAt {
@@ -400,13 +430,18 @@ impl RawDoc {
 
     /// Get the project payload, if it exists and is valid, out of this document.
     pub fn project(&self) -> Result<Project, PayloadError> {
-        let value = self
-            .payload
-            .get(&PayloadId::project())
-            .ok_or_else(|| PayloadError::NotFound(PayloadId::project()))?;
-        let proj: Project = serde_json::from_value((**value).clone())?;
+        project(&self.payload)
+    }
 
-        Ok(proj)
+    /// Get a typed payload, if it exists and is valid, out of this document.
+    pub fn typed_payload<T: TypedPayload>(&self) -> Result<Option<T>, PayloadError> {
+        typed_payload(&self.payload)
+    }
+
+    /// Verify that all the payloads of this document that are in the
+    /// [`payloads::REGISTRY`] are valid.
+    pub fn verify_payloads(&self) -> Result<(), PayloadError> {
+        payloads::verify_all(&self.payload)
     }
 
     /// Check if the given `did` is in the set of [`RawDoc::delegates`].
@@ -700,13 +735,60 @@ impl Doc {
 
     /// Get the project payload, if it exists and is valid, out of this document.
     pub fn project(&self) -> Result<Project, PayloadError> {
-        let value = self
-            .payload
-            .get(&PayloadId::project())
-            .ok_or_else(|| PayloadError::NotFound(PayloadId::project()))?;
-        let proj: Project = serde_json::from_value((**value).clone())?;
+        project(&self.payload)
+    }
+
+    /// Get the merge policy payload, if it exists and is valid, out of this document.
+    pub fn merge_policy(&self) -> Result<Option<MergePolicy>, PayloadError> {
+        self.typed_payload()
+    }
+
+    /// Get the issue and patch templates payload, if it exists and is valid, out of this document.
+    pub fn templates(&self) -> Result<Option<Templates>, PayloadError> {
+        self.typed_payload()
+    }
+
+    /// Get the code owners payload, if it exists and is valid, out of this document.
+    pub fn code_owners(&self) -> Result<Option<CodeOwners>, PayloadError> {
+        self.typed_payload()
+    }
 
-        Ok(proj)
+    /// Get the canonical references payload, if it exists and is valid, out of this document.
+    pub fn canonical_refs(&self) -> Result<Option<CanonicalRefs>, PayloadError> {
+        self.typed_payload()
+    }
+
+    /// Get the delegate persons payload, if it exists and is valid, out of this document.
+    pub fn delegate_persons(&self) -> Result<Option<DelegatePersons>, PayloadError> {
+        self.typed_payload()
+    }
+
+    /// Get the person that the given delegate is pinned to, if any.
+    ///
+    /// Since known payloads are verified when identity revisions are evaluated,
+    /// an invalid payload is treated as if no person was pinned.
+    pub fn person_of(&self, delegate: &Did) -> Option<PersonId> {
+        self.delegate_persons()
+            .ok()
+            .flatten()
+            .and_then(|persons| persons.get(delegate).copied())
+    }
+
+    /// Get a typed payload, if it exists and is valid, out of this document.
+    pub fn typed_payload<T: TypedPayload>(&self) -> Result<Option<T>, PayloadError> {
+        typed_payload(&self.payload)
+    }
+
+    /// Verify that all the payloads of this document that are in the
+    /// [`payloads::REGISTRY`] are valid.
+    pub fn verify_payloads(&self) -> Result<(), PayloadError> {
+        payloads::verify_all(&self.payload)
+    }
+
+    /// Verify the payloads of this document that must be valid when identity
+    /// revisions are evaluated. See [`payloads::TypedPayload::SINCE`].
+    pub fn verify_evaluated_payloads(&self) -> Result<(), PayloadError> {
+        payloads::verify_evaluated(&self.version, &self.payload)
     }
 
     /// Return the associated [`Visibility`] of this document.
@@ -744,6 +826,38 @@ impl Doc {
         self.delegates.contains(did)
     }
 
+    /// Get the delegate that signs with the given key, if any. This is either
+    /// the delegate with that key, or a person delegate whose pinned person has
+    /// the key as one of its current devices.
+    pub fn delegate_of<R: ReadRepository>(
+        &self,
+        key: &crypto::PublicKey,
+        repo: &R,
+    ) -> Result<Option<Did>, RepositoryError> {
+        let did = Did::from(*key);
+
+        if self.is_delegate(&did) {
+            return Ok(Some(did));
+        }
+        if !self.delegates.iter().any(|d| self.person_of(d).is_some()) {
+            // Without pinned persons, delegates only sign with their own keys.
+            return Ok(None);
+        }
+        Ok(self.delegate_in(key, &repo.persons()?))
+    }
+
+    /// Like [`Doc::delegate_of`], using the given, already loaded persons.
+    pub fn delegate_in(&self, key: &crypto::PublicKey, persons: &person::Index) -> Option<Did> {
+        self.delegates
+            .iter()
+            .find(|delegate| {
+                persons
+                    .devices(delegate, self.person_of(delegate).as_ref())
+                    .contains(key)
+            })
+            .copied()
+    }
+
     /// Check whether this document and the associated repository is visible to
     /// the given peer.
     pub fn is_visible_to(&self, did: &Did) -> bool {
@@ -0,0 +1,88 @@
+//! Wildcard matching, shared by the patterns of identity payloads.
+
+/// Match `text` against `pattern`, where elements of the pattern for which
+/// `star` holds match any sequence of elements, including an empty one, and
+/// other elements match a single element of the text if `one` holds.
+///
+/// Instead of backtracking over every star, only the last star seen is
+/// retried, which takes at most `O(pattern.len() * text.len())` steps.
+pub(crate) fn matches<P, T>(
+    pattern: &[P],
+    text: &[T],
+    star: impl Fn(&P) -> bool,
+    one: impl Fn(&P, &T) -> bool,
+) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last star in the pattern, and of the text it was tried at.
+    let mut retry: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && star(&pattern[p]) {
+            retry = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && one(&pattern[p], &text[t]) {
+            p += 1;
+            t += 1;
+        } else if let Some((star, start)) = retry {
+            // Let the last star match one more element.
+            retry = Some((star, start + 1));
+            p = star + 1;
+            t = start + 1;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(star)
+}
+
+/// Match text against a pattern with `*` and `?` wildcards, where `*` matches
+/// any sequence of characters, and `?` matches any single character.
+pub(crate) fn wildcard(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    matches(&pattern, &text, |p| *p == '*', |p, t| *p == '?' || p == t)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_wildcard() {
+        assert!(wildcard("", ""));
+        assert!(wildcard("*", ""));
+        assert!(wildcard("*.rs", "lib.rs"));
+        assert!(wildcard("l?b.*", "lib.rs"));
+        assert!(wildcard("*i*b*", "lib"));
+        assert!(wildcard("**", "lib.rs"));
+        assert!(wildcard("a*b*c", "aXbYbZc"));
+        assert!(wildcard("é?", "éé"));
+        assert!(!wildcard("", "lib.rs"));
+        assert!(!wildcard("*.rs", "lib.rsx"));
+        assert!(!wildcard("?", ""));
+        assert!(!wildcard("a*b*c", "aXbYbZ"));
+    }
+
+    #[test]
+    fn test_wildcard_many_stars() {
+        // This would take exponential time with a backtracking matcher.
+        let pattern = "a*".repeat(32) + "b";
+        let text = "a".repeat(64);
+
+        assert!(!wildcard(&pattern, &text));
+        assert!(wildcard(&pattern, &(text + "b")));
+    }
+
+    #[test]
+    fn test_matches_segments() {
+        let segments = |pattern: &[&str], path: &[&str]| {
+            matches(pattern, path, |p| *p == "**", |p, s| wildcard(p, s))
+        };
+        assert!(segments(&["**", "*.rs"], &["src", "lib.rs"]));
+        assert!(segments(&["**", "*.rs"], &["lib.rs"]));
+        assert!(segments(&["src", "**"], &["src", "a", "b"]));
+        assert!(!segments(&["src", "**", "*.rs"], &["lib.rs"]));
+        assert!(!segments(&["**", "*.rs"], &["src", "lib.md"]));
+    }
+}
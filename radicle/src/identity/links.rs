@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::identity::doc;
+use crate::identity::doc::Payload;
+
+/// Invalid link URL.
+#[derive(Debug, Error)]
+#[error("invalid URL: {0}")]
+pub struct UrlError(&'static str);
+
+/// External links of a repository, eg. its homepage or chat, keyed by name.
+///
+/// Stored in the identity document under the `xyz.radicle.links` payload.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Links(pub BTreeMap<String, Url>);
+
+impl Links {
+    /// Get the link with the given name, eg. `homepage`.
+    pub fn get(&self, name: &str) -> Option<&Url> {
+        self.0.get(name)
+    }
+}
+
+/// An `http` or `https` URL.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Url(String);
+
+impl Url {
+    /// Return a string reference to the URL.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<Url> for String {
+    fn from(url: Url) -> Self {
+        url.0
+    }
+}
+
+impl TryFrom<String> for Url {
+    type Error = UrlError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if s.len() > doc::MAX_STRING_LENGTH {
+            return Err(UrlError("URL cannot exceed 255 bytes"));
+        }
+        let Some(rest) = s
+            .strip_prefix("https://")
+            .or_else(|| s.strip_prefix("http://"))
+        else {
+            return Err(UrlError("only `http` and `https` URLs are allowed"));
+        };
+        if rest.is_empty() || rest.starts_with('/') {
+            return Err(UrlError("URL must have a host"));
+        }
+        if s.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(UrlError("URL cannot contain whitespace"));
+        }
+        Ok(Self(s))
+    }
+}
+
+impl From<Links> for Payload {
+    fn from(links: Links) -> Self {
+        let value =
+            serde_json::to_value(links).expect("Payload::from: could not convert links into value");
+
+        Self::from(value)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_links_json() {
+        let links: Links = serde_json::from_str(
+            r#"{ "homepage": "https://radicle.xyz", "chat": "https://radicle.zulipchat.com" }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            links.get("homepage").map(Url::as_str),
+            Some("https://radicle.xyz")
+        );
+
+        for url in [
+            "ftp://radicle.xyz",
+            "https://",
+            "https:///path",
+            "https://a b",
+            "radicle.xyz",
+        ] {
+            assert!(
+                serde_json::from_str::<Links>(&format!(r#"{{ "homepage": "{url}" }}"#)).is_err(),
+                "{url} should be invalid"
+            );
+        }
+    }
+}
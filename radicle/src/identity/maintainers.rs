@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::identity::doc::Payload;
+use crate::identity::Did;
+use crate::node::Alias;
+
+/// Maintainers of a repository, who aren't necessarily delegates.
+///
+/// Stored in the identity document under the `xyz.radicle.maintainers` payload.
+/// Unlike delegates, maintainers have no authority over the repository; this
+/// payload is informational.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Maintainers {
+    /// Maintainers, keyed by DID.
+    #[serde(default)]
+    pub maintainers: BTreeMap<Did, Maintainer>,
+}
+
+impl Maintainers {
+    /// Check whether the given user is a maintainer.
+    pub fn contains(&self, did: &Did) -> bool {
+        self.maintainers.contains_key(did)
+    }
+}
+
+/// A repository maintainer.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Maintainer {
+    /// Preferred alias of the maintainer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<Alias>,
+    /// What the maintainer is responsible for, eg. "releases".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+}
+
+impl From<Maintainers> for Payload {
+    fn from(maintainers: Maintainers) -> Self {
+        let value = serde_json::to_value(maintainers)
+            .expect("Payload::from: could not convert maintainers into value");
+
+        Self::from(value)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_maintainers_json() {
+        let did = "did:key:z6MknSLrJoTcukLrE435hVNQT4JUhbvWLX4kUzqkEStBU8Vi";
+        let maintainers: Maintainers = serde_json::from_str(&format!(
+            r#"{{ "maintainers": {{ "{did}": {{ "alias": "alice", "role": "releases" }} }} }}"#
+        ))
+        .unwrap();
+        let maintainer = &maintainers.maintainers[&did.parse().unwrap()];
+
+        assert_eq!(maintainer.alias, Some(Alias::new("alice")));
+        assert_eq!(maintainer.role.as_deref(), Some("releases"));
+
+        assert!(
+            serde_json::from_str::<Maintainers>(r#"{ "maintainers": { "alice": {} } }"#).is_err()
+        );
+        assert!(serde_json::from_str::<Maintainers>(&format!(
+            r#"{{ "maintainers": {{ "{did}": {{ "alias": "al ice" }} }} }}"#
+        ))
+        .is_err());
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use crate::identity::doc::Payload;
+
+/// Repository policy that determines when a patch is ready to be merged.
+///
+/// Stored in the identity document under the `xyz.radicle.merge` payload.
+/// Repositories without this payload don't impose any requirements.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergePolicy {
+    /// Number of accepting reviews from delegates required on the latest revision.
+    #[serde(default)]
+    pub approvals: usize,
+    /// Whether all review comments on the latest revision must be resolved.
+    #[serde(default)]
+    pub resolved: bool,
+}
+
+impl From<MergePolicy> for Payload {
+    fn from(policy: MergePolicy) -> Self {
+        let value = serde_json::to_value(policy)
+            .expect("Payload::from: could not convert merge policy into value");
+
+        Self::from(value)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_merge_policy_json() {
+        let policy: MergePolicy =
+            serde_json::from_str(r#"{ "approvals": 2, "resolved": true }"#).unwrap();
+        assert_eq!(
+            policy,
+            MergePolicy {
+                approvals: 2,
+                resolved: true
+            }
+        );
+
+        let policy: MergePolicy = serde_json::from_str(r#"{}"#).unwrap();
+        assert_eq!(policy, MergePolicy::default());
+    }
+}
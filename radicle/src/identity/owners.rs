@@ -0,0 +1,231 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::identity::doc;
+use crate::identity::doc::Payload;
+use crate::identity::glob;
+use crate::identity::Did;
+
+/// Invalid path pattern.
+#[derive(Debug, Error)]
+#[error("invalid path pattern: {0}")]
+pub struct PatternError(&'static str);
+
+/// Owners of paths in the repository, who are expected to review changes
+/// to these paths.
+///
+/// Stored in the identity document under the `xyz.radicle.owners` payload.
+/// As with `CODEOWNERS` files, when more than one rule matches a path, the
+/// last matching rule wins.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CodeOwners {
+    /// Ownership rules, in order of increasing precedence.
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    /// Get the owners of the given path, if any.
+    pub fn owners_of(&self, path: &str) -> &[Did] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.pattern.matches(path))
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Get the owners of all the given paths.
+    pub fn owners<'a>(&self, paths: impl IntoIterator<Item = &'a str>) -> BTreeSet<Did> {
+        paths
+            .into_iter()
+            .flat_map(|path| self.owners_of(path))
+            .copied()
+            .collect()
+    }
+}
+
+/// A code ownership rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Rule {
+    /// Paths covered by the rule.
+    pub pattern: Pattern,
+    /// Owners of the paths. A rule without owners unsets the owners of paths
+    /// matched by earlier rules.
+    #[serde(default)]
+    pub owners: Vec<Did>,
+}
+
+/// A path pattern, with the same semantics as patterns in `.gitignore`
+/// files, except that patterns can't be negated.
+///
+/// * `*` matches anything except `/`, and `?` matches any one character
+///   except `/`.
+/// * `**` matches any number of directories.
+/// * Patterns without a `/`, other than a trailing one, match at any depth.
+///   Other patterns are relative to the repository root.
+/// * Patterns matching a directory match all the paths under it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Pattern {
+    pattern: String,
+    segments: Vec<String>,
+}
+
+impl Pattern {
+    /// Check whether the given path, relative to the repository root, matches.
+    pub fn matches(&self, path: &str) -> bool {
+        let path = path.trim_start_matches('/').split('/').collect::<Vec<_>>();
+
+        // Patterns matching a directory match everything under it.
+        (1..=path.len()).any(|n| segments(&self.segments, &path[..n]))
+    }
+
+    /// Return a string reference to the pattern.
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.pattern.fmt(f)
+    }
+}
+
+impl From<Pattern> for String {
+    fn from(pattern: Pattern) -> Self {
+        pattern.pattern
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = PatternError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if s.len() > doc::MAX_STRING_LENGTH {
+            return Err(PatternError("pattern cannot exceed 255 bytes"));
+        }
+        if s.starts_with('!') {
+            return Err(PatternError("patterns cannot be negated"));
+        }
+        let trimmed = s.trim_end_matches('/');
+        let anchored = trimmed.contains('/');
+        let trimmed = trimmed.trim_start_matches('/');
+
+        if trimmed.is_empty() {
+            return Err(PatternError("pattern cannot be empty"));
+        }
+        let mut segments = Vec::new();
+        if !anchored {
+            segments.push(String::from("**"));
+        }
+        for segment in trimmed.split('/') {
+            if segment.is_empty() || segment == "." || segment == ".." {
+                return Err(PatternError("pattern has an invalid path component"));
+            }
+            segments.push(segment.to_owned());
+        }
+        Ok(Self {
+            pattern: s,
+            segments,
+        })
+    }
+}
+
+/// Match path segments against pattern segments, where `**` matches any
+/// number of segments, and other segments may have `*` and `?` wildcards.
+fn segments(pattern: &[String], path: &[&str]) -> bool {
+    glob::matches(
+        pattern,
+        path,
+        |p| p == "**",
+        |p, segment| glob::wildcard(p, segment),
+    )
+}
+
+impl From<CodeOwners> for Payload {
+    fn from(owners: CodeOwners) -> Self {
+        let value = serde_json::to_value(owners)
+            .expect("Payload::from: could not convert code owners into value");
+
+        Self::from(value)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    fn pattern(s: &str) -> Pattern {
+        Pattern::try_from(s.to_owned()).unwrap()
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        for (p, path, expected) in [
+            ("*.rs", "main.rs", true),
+            ("*.rs", "src/node/main.rs", true),
+            ("*.rs", "src/main.rsx", false),
+            ("docs", "docs/README.md", true),
+            ("docs", "radicle/docs/index.md", true),
+            ("docs/", "docs/README.md", true),
+            ("/docs", "radicle/docs/index.md", false),
+            ("src/node", "src/node/service.rs", true),
+            ("src/node", "radicle/src/node/service.rs", false),
+            ("src/*.rs", "src/lib.rs", true),
+            ("src/*.rs", "src/node/lib.rs", false),
+            ("src/**/*.rs", "src/lib.rs", true),
+            ("src/**/*.rs", "src/node/wire/lib.rs", true),
+            ("**/tests", "a/b/tests/t.rs", true),
+            ("lib?.rs", "src/lib1.rs", true),
+            ("lib?.rs", "src/lib.rs", false),
+        ] {
+            assert_eq!(
+                pattern(p).matches(path),
+                expected,
+                "`{p}` should {}match `{path}`",
+                if expected { "" } else { "not " }
+            );
+        }
+    }
+
+    #[test]
+    fn test_pattern_invalid() {
+        for p in ["", "/", "!docs", "src//node", "../src"] {
+            assert!(Pattern::try_from(p.to_owned()).is_err(), "`{p}` is invalid");
+        }
+    }
+
+    #[test]
+    fn test_code_owners() {
+        let alice = "did:key:z6MknSLrJoTcukLrE435hVNQT4JUhbvWLX4kUzqkEStBU8Vi";
+        let bob = "did:key:z6MkvZwzK6p6hvDWAiYFV2Q7VLpK8DjxEMWx6ELvh6U8uJKJ";
+        let owners: CodeOwners = serde_json::from_str(&format!(
+            r#"{{
+              "rules": [
+                {{ "pattern": "*", "owners": ["{alice}"] }},
+                {{ "pattern": "src/node/", "owners": ["{bob}"] }},
+                {{ "pattern": "src/node/vendor" }}
+              ]
+            }}"#
+        ))
+        .unwrap();
+        let alice = alice.parse::<Did>().unwrap();
+        let bob = bob.parse::<Did>().unwrap();
+
+        assert_eq!(owners.owners_of("README.md"), &[alice]);
+        assert_eq!(owners.owners_of("src/node/lib.rs"), &[bob]);
+        assert!(owners.owners_of("src/node/vendor/x.rs").is_empty());
+        assert_eq!(
+            owners.owners(["README.md", "src/node/lib.rs"]),
+            BTreeSet::from([alice, bob])
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Registry of identity document payloads known to this crate.
+//!
+//! Identity documents can carry any payload, but the payloads listed in
+//! [`REGISTRY`] have a typed representation, and are validated when the
+//! document is updated, and when identity revisions are evaluated. Payloads
+//! that aren't in the registry are left as-is.
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::identity::doc::{Payload, PayloadError, PayloadId, Version, IDENTITY_VERSION};
+use crate::identity::{
+    CanonicalRefs, Ci, CodeOwners, DelegatePersons, Links, Maintainers, MergePolicy, Project,
+    Templates,
+};
+
+/// A payload with a typed representation.
+pub trait TypedPayload: Serialize + DeserializeOwned {
+    /// Payload identifier, eg. `xyz.radicle.project`.
+    const ID: &'static str;
+    /// Human-readable name of the payload.
+    const NAME: &'static str;
+    /// Document version from which the payload is verified when identity
+    /// revisions are evaluated, or `None` if it is only verified when a
+    /// revision is proposed.
+    ///
+    /// Payloads that are registered after documents carrying them may already
+    /// have been accepted must use a new document version, so that these
+    /// documents are evaluated the same way by all versions of Radicle.
+    const SINCE: Option<Version> = Some(IDENTITY_VERSION);
+}
+
+impl TypedPayload for Project {
+    const ID: &'static str = "xyz.radicle.project";
+    const NAME: &'static str = "Project";
+    // Project payloads predate the registry.
+    const SINCE: Option<Version> = None;
+}
+
+impl TypedPayload for MergePolicy {
+    const ID: &'static str = "xyz.radicle.merge";
+    const NAME: &'static str = "Merge policy";
+}
+
+impl TypedPayload for Templates {
+    const ID: &'static str = "xyz.radicle.templates";
+    const NAME: &'static str = "Templates";
+}
+
+impl TypedPayload for Ci {
+    const ID: &'static str = "xyz.radicle.ci";
+    const NAME: &'static str = "Continuous integration";
+}
+
+impl TypedPayload for Maintainers {
+    const ID: &'static str = "xyz.radicle.maintainers";
+    const NAME: &'static str = "Maintainers";
+}
+
+impl TypedPayload for Links {
+    const ID: &'static str = "xyz.radicle.links";
+    const NAME: &'static str = "Links";
+}
+
+impl TypedPayload for CodeOwners {
+    const ID: &'static str = "xyz.radicle.owners";
+    const NAME: &'static str = "Code owners";
+}
+
+impl TypedPayload for CanonicalRefs {
+    const ID: &'static str = "xyz.radicle.crefs";
+    const NAME: &'static str = "Canonical references";
+}
+
+impl TypedPayload for DelegatePersons {
+    const ID: &'static str = "xyz.radicle.persons";
+    const NAME: &'static str = "Delegate persons";
+}
+
+/// Payloads known to this crate.
+pub static REGISTRY: &[Known] = &[
+    Known::of::<Project>(),
+    Known::of::<MergePolicy>(),
+    Known::of::<Templates>(),
+    Known::of::<Ci>(),
+    Known::of::<Maintainers>(),
+    Known::of::<Links>(),
+    Known::of::<CodeOwners>(),
+    Known::of::<CanonicalRefs>(),
+    Known::of::<DelegatePersons>(),
+];
+
+/// A registered payload type.
+#[derive(Debug, Clone, Copy)]
+pub struct Known {
+    /// Payload identifier.
+    pub id: &'static str,
+    /// Human-readable name of the payload.
+    pub name: &'static str,
+    /// Document version from which the payload is verified on evaluation.
+    pub since: Option<Version>,
+    normalize: fn(&serde_json::Value) -> Result<serde_json::Value, serde_json::Error>,
+}
+
+impl Known {
+    /// Register the given payload type.
+    pub const fn of<T: TypedPayload>() -> Self {
+        Self {
+            id: T::ID,
+            name: T::NAME,
+            since: T::SINCE,
+            normalize: normalize::<T>,
+        }
+    }
+
+    /// Decode the payload into its typed representation, and encode it back.
+    ///
+    /// Fails if the payload is invalid. The returned value only contains the
+    /// fields of the typed representation.
+    pub fn normalize(&self, payload: &Payload) -> Result<serde_json::Value, serde_json::Error> {
+        (self.normalize)(payload)
+    }
+}
+
+fn normalize<T: TypedPayload>(
+    value: &serde_json::Value,
+) -> Result<serde_json::Value, serde_json::Error> {
+    let typed = T::deserialize(value)?;
+    serde_json::to_value(typed)
+}
+
+/// Look up a payload type in the registry.
+pub fn lookup(id: &PayloadId) -> Option<&'static Known> {
+    let id = id.to_string();
+    REGISTRY.iter().find(|known| known.id == id)
+}
+
+/// Verify a payload against its typed representation, if it is known.
+pub fn verify(id: &PayloadId, payload: &Payload) -> Result<(), PayloadError> {
+    if let Some(known) = lookup(id) {
+        known
+            .normalize(payload)
+            .map_err(|e| PayloadError::Invalid(id.clone(), e))?;
+    }
+    Ok(())
+}
+
+/// Verify all the known payloads of a document.
+pub fn verify_all(payload: &BTreeMap<PayloadId, Payload>) -> Result<(), PayloadError> {
+    payload.iter().try_for_each(|(id, p)| verify(id, p))
+}
+
+/// Verify the known payloads of a document of the given version that are
+/// verified when identity revisions are evaluated. See [`TypedPayload::SINCE`].
+pub fn verify_evaluated(
+    version: &Version,
+    payload: &BTreeMap<PayloadId, Payload>,
+) -> Result<(), PayloadError> {
+    payload
+        .iter()
+        .filter(|(id, _)| {
+            lookup(id)
+                .and_then(|known| known.since)
+                .is_some_and(|since| since <= *version)
+        })
+        .try_for_each(|(id, p)| verify(id, p))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::str::FromStr;
+
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        for known in REGISTRY {
+            let id = PayloadId::from_str(known.id).unwrap();
+            assert_eq!(lookup(&id).unwrap().id, known.id);
+        }
+        assert_eq!(PayloadId::of::<Ci>().to_string(), Ci::ID);
+        assert_eq!(PayloadId::project().to_string(), Project::ID);
+        assert!(lookup(&PayloadId::from_str("com.example.unknown").unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_verify() {
+        let links = PayloadId::from_str(Links::ID).unwrap();
+        let unknown = PayloadId::from_str("com.example.unknown").unwrap();
+
+        verify(
+            &links,
+            &Payload::from(json!({ "homepage": "https://radicle.xyz" })),
+        )
+        .unwrap();
+        verify(&unknown, &Payload::from(json!({ "homepage": 42 }))).unwrap();
+
+        assert!(matches!(
+            verify(&links, &Payload::from(json!({ "homepage": "radicle.xyz" }))),
+            Err(PayloadError::Invalid(id, _)) if id == links
+        ));
+        assert!(verify_all(&BTreeMap::from_iter([
+            (unknown, Payload::from(json!(null))),
+            (links.clone(), Payload::from(json!({ "homepage": 42 }))),
+        ]))
+        .is_err());
+
+        // Project payloads are only verified when proposing revisions.
+        let project = BTreeMap::from_iter([(PayloadId::project(), Payload::from(json!(null)))]);
+        assert!(verify_all(&project).is_err());
+        verify_evaluated(&IDENTITY_VERSION, &project).unwrap();
+        assert!(verify_evaluated(
+            &IDENTITY_VERSION,
+            &BTreeMap::from_iter([(links, Payload::from(json!({ "homepage": 42 })))]),
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cob::person::PersonId;
+use crate::identity::doc::Payload;
+use crate::identity::Did;
+
+/// Persons that delegates of a repository sign as.
+///
+/// Stored in the identity document under the `xyz.radicle.persons` payload.
+/// Since anyone holding the key of a delegate can create a person claiming
+/// that delegate, only the person listed here is taken into account. A
+/// delegate that isn't listed only signs with its own key.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DelegatePersons {
+    /// Persons, keyed by delegate.
+    #[serde(default)]
+    pub persons: BTreeMap<Did, PersonId>,
+}
+
+impl DelegatePersons {
+    /// Get the person of the given delegate, if any.
+    pub fn get(&self, delegate: &Did) -> Option<&PersonId> {
+        self.persons.get(delegate)
+    }
+}
+
+impl From<DelegatePersons> for Payload {
+    fn from(persons: DelegatePersons) -> Self {
+        let value = serde_json::to_value(persons)
+            .expect("Payload::from: could not convert persons into value");
+
+        Self::from(value)
+    }
+}
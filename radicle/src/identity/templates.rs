@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cob::Label;
+use crate::identity::doc::Payload;
+
+/// Issue and patch templates published by the repository delegates.
+///
+/// Stored in the identity document under the `xyz.radicle.templates` payload.
+/// Templates are keyed by name; the template named [`Templates::DEFAULT`] is
+/// used when no template is chosen explicitly.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Templates {
+    /// Templates for new issues.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub issue: BTreeMap<String, Template>,
+    /// Templates for new patches.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub patch: BTreeMap<String, Template>,
+}
+
+impl Templates {
+    /// Name of the template used when none is chosen.
+    pub const DEFAULT: &'static str = "default";
+
+    /// Get the issue template with the given name, or the default issue template.
+    pub fn issue(&self, name: Option<&str>) -> Option<&Template> {
+        self.issue.get(name.unwrap_or(Self::DEFAULT))
+    }
+
+    /// Get the patch template with the given name, or the default patch template.
+    pub fn patch(&self, name: Option<&str>) -> Option<&Template> {
+        self.patch.get(name.unwrap_or(Self::DEFAULT))
+    }
+}
+
+/// A template for the description and labels of a new issue or patch.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Template {
+    /// Initial description.
+    #[serde(default)]
+    pub body: String,
+    /// Labels added by default.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<Label>,
+}
+
+impl From<Templates> for Payload {
+    fn from(templates: Templates) -> Self {
+        let value = serde_json::to_value(templates)
+            .expect("Payload::from: could not convert templates into value");
+
+        Self::from(value)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_templates_json() {
+        let templates: Templates = serde_json::from_str(
+            r#"{
+              "issue": {
+                "default": { "body": "Describe the problem." },
+                "bug": { "body": "Steps to reproduce:", "labels": ["bug"] }
+              }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(templates.issue(None).unwrap().body, "Describe the problem.");
+        assert_eq!(
+            templates.issue(Some("bug")).unwrap().labels,
+            vec![Label::new("bug").unwrap()]
+        );
+        assert_eq!(templates.issue(Some("feature")), None);
+        assert_eq!(templates.patch(None), None);
+
+        let templates: Templates = serde_json::from_str(r#"{}"#).unwrap();
+        assert_eq!(templates, Templates::default());
+    }
+}
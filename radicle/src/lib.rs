@@ -33,7 +33,7 @@ pub mod test;
 pub mod version;
 pub mod web;
 
-pub use cob::{external, issue, patch};
+pub use cob::{external, issue, milestone, patch};
 pub use node::Node;
 pub use profile::Profile;
 pub use storage::git::Storage;
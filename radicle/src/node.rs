@@ -666,6 +666,10 @@ pub enum Command {
 
     /// Subscribe to events.
     Subscribe,
+
+    /// Query the event log.
+    #[serde(rename_all = "camelCase")]
+    EventLog { filter: events::Filter },
 }
 
 impl Command {
@@ -693,6 +697,33 @@ pub struct Session {
     pub link: Link,
     pub addr: Address,
     pub state: State,
+    /// Traffic exchanged with the peer since the node started.
+    #[serde(default)]
+    pub traffic: Traffic,
+}
+
+/// Bytes exchanged with a peer.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Traffic {
+    /// Bytes sent to the peer.
+    pub sent: u64,
+    /// Bytes received from the peer.
+    pub received: u64,
+    /// Git bytes exchanged with the peer, per repository. Only includes
+    /// fetches that have completed.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub repos: BTreeMap<RepoId, RepoTraffic>,
+}
+
+/// Git bytes exchanged with a peer for a single repository.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoTraffic {
+    /// Bytes sent to the peer.
+    pub sent: u64,
+    /// Bytes received from the peer.
+    pub received: u64,
 }
 
 impl Session {
@@ -1041,6 +1072,8 @@ pub trait Handle: Clone + Sync + Send {
     fn session(&self, node: NodeId) -> Result<Option<Session>, Self::Error>;
     /// Subscribe to node events.
     fn subscribe(&self, timeout: time::Duration) -> Result<Self::Events, Self::Error>;
+    /// Query the event log for past events.
+    fn event_log(&self, filter: events::Filter) -> Result<Vec<events::Logged>, Self::Error>;
     /// Return debug information as a JSON value.
     fn debug(&self) -> Result<json::Value, Self::Error>;
 }
@@ -1333,6 +1366,15 @@ impl Handle for Node {
         self.call(Command::Subscribe, timeout)
     }
 
+    fn event_log(&self, filter: events::Filter) -> Result<Vec<events::Logged>, Error> {
+        let events = self
+            .call::<Vec<events::Logged>>(Command::EventLog { filter }, DEFAULT_TIMEOUT)?
+            .next()
+            .ok_or(Error::EmptyResponse)??;
+
+        Ok(events)
+    }
+
     fn sessions(&self) -> Result<Self::Sessions, Error> {
         let sessions = self
             .call::<Vec<Session>>(Command::Sessions, DEFAULT_TIMEOUT)?
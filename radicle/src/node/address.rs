@@ -6,7 +6,7 @@ use std::ops::{Deref, DerefMut};
 use std::{hash, net};
 
 use cyphernet::addr::HostName;
-use localtime::LocalTime;
+use localtime::{LocalDuration, LocalTime};
 use nonempty::NonEmpty;
 
 use crate::collections::RandomMap;
@@ -171,6 +171,62 @@ impl KnownAddress {
     }
 }
 
+/// Node score. Tracks how useful a node has been to us as a peer.
+/// Nodes with a higher score are preferred as peers and fetch sources.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Score {
+    /// Number of successful fetches from this node.
+    pub fetches: u64,
+    /// Number of failed fetches from this node.
+    pub failures: u64,
+    /// Total time spent on successful fetches, in milliseconds.
+    pub fetch_time: u64,
+    /// Total number of git bytes received from this node in successful fetches.
+    pub bytes: u64,
+    /// Number of protocol violations by this node.
+    pub violations: u64,
+    /// Total time we were connected to this node, in milliseconds.
+    pub uptime: u64,
+}
+
+impl Score {
+    /// Points gained for a successful fetch, and lost for a failed one.
+    pub const FETCH_POINTS: i64 = 10;
+    /// Points lost for a protocol violation.
+    pub const VIOLATION_POINTS: i64 = 100;
+    /// Bytes received for which a point is gained.
+    pub const BYTES_PER_POINT: u64 = 1024 * 1024;
+    /// Connection uptime for which a point is gained.
+    pub const UPTIME_PER_POINT: LocalDuration = LocalDuration::from_mins(60);
+    /// Average fetch time for which a point is lost.
+    pub const FETCH_TIME_PER_POINT: LocalDuration = LocalDuration::from_secs(1);
+
+    /// Average time taken by a successful fetch, if any.
+    pub fn latency(&self) -> Option<LocalDuration> {
+        self.fetch_time
+            .checked_div(self.fetches)
+            .map(|ms| LocalDuration::from_millis(ms as u128))
+    }
+
+    /// Overall score value. Higher is better, and nodes we know nothing about have
+    /// a value of zero.
+    pub fn value(&self) -> i64 {
+        let latency = self
+            .latency()
+            .map(|l| l.as_millis() / Self::FETCH_TIME_PER_POINT.as_millis())
+            .unwrap_or_default();
+
+        (self.fetches as i64)
+            .saturating_sub(self.failures as i64)
+            .saturating_mul(Self::FETCH_POINTS)
+            .saturating_sub((self.violations as i64).saturating_mul(Self::VIOLATION_POINTS))
+            .saturating_add((self.bytes / Self::BYTES_PER_POINT) as i64)
+            .saturating_add((self.uptime / Self::UPTIME_PER_POINT.as_millis() as u64) as i64)
+            .saturating_sub(latency as i64)
+    }
+}
+
 /// Address source. Specifies where an address originated from.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -182,6 +238,8 @@ pub enum Source {
     /// An address that came from some source external to the system, eg.
     /// specified by the user or added directly to the address manager.
     Imported,
+    /// An address that was discovered on the local network.
+    Local,
 }
 
 impl std::fmt::Display for Source {
@@ -190,6 +248,7 @@ impl std::fmt::Display for Source {
             Self::Peer => write!(f, "Peer"),
             Self::Bootstrap => write!(f, "Bootstrap"),
             Self::Imported => write!(f, "Imported"),
+            Self::Local => write!(f, "Local"),
         }
     }
 }
@@ -3,12 +3,12 @@ use std::net::IpAddr;
 use std::num::TryFromIntError;
 use std::str::FromStr;
 
-use localtime::LocalTime;
+use localtime::{LocalDuration, LocalTime};
 use sqlite as sql;
 use thiserror::Error;
 
 use crate::node;
-use crate::node::address::{AddressType, KnownAddress, Node, Source};
+use crate::node::address::{AddressType, KnownAddress, Node, Score, Source};
 use crate::node::UserAgent;
 use crate::node::{Address, Alias, AliasError, AliasStore, Database, NodeId, Penalty, Severity};
 use crate::prelude::Timestamp;
@@ -39,6 +39,8 @@ pub struct AddressEntry {
     pub version: u8,
     /// Node penalty.
     pub penalty: Penalty,
+    /// Node score.
+    pub score: Score,
     /// Node address.
     pub address: KnownAddress,
 }
@@ -95,6 +97,17 @@ pub trait Store {
         addr: &Address,
         severity: Severity,
     ) -> Result<(), Error>;
+    /// Get the score of a node. Returns the default score for unknown nodes.
+    fn score(&self, nid: &NodeId) -> Result<Score, Error>;
+    /// Record a successful fetch from a node, which took the given time and
+    /// transferred the given number of bytes.
+    fn fetch_succeeded(&self, nid: &NodeId, time: LocalDuration, bytes: u64) -> Result<(), Error>;
+    /// Record a failed fetch from a node.
+    fn fetch_failed(&self, nid: &NodeId) -> Result<(), Error>;
+    /// Record a protocol violation by a node.
+    fn misbehaved(&self, nid: &NodeId) -> Result<(), Error>;
+    /// Record the time we were connected to a node for.
+    fn record_uptime(&self, nid: &NodeId, uptime: LocalDuration) -> Result<(), Error>;
 }
 
 pub trait StoreExt {
@@ -290,9 +303,11 @@ impl Store for Database {
         let mut stmt = self
             .db
             .prepare(
-                "SELECT a.node, a.type, a.value, a.source, a.last_success, a.last_attempt, a.banned, n.version, n.penalty
+                "SELECT a.node, a.type, a.value, a.source, a.last_success, a.last_attempt, a.banned, n.version, n.penalty,
+                        s.fetches, s.failures, s.fetch_time, s.bytes, s.violations, s.uptime
                  FROM addresses AS a
                  JOIN nodes AS n ON a.node = n.id
+                 LEFT JOIN scores AS s ON a.node = s.node
                  ORDER BY n.penalty ASC, n.id ASC",
             )?
             .into_iter();
@@ -311,11 +326,13 @@ impl Store for Database {
             let banned = row.read::<i64, _>("banned").is_positive();
             let penalty = row.read::<i64, _>("penalty");
             let penalty = Penalty(penalty as u8); // Clamped at `u8::MAX`.
+            let score = read_score(&row);
 
             entries.push(AddressEntry {
                 node,
                 version,
                 penalty,
+                score,
                 address: KnownAddress {
                     addr,
                     source,
@@ -422,6 +439,93 @@ impl Store for Database {
             Ok(())
         })
     }
+
+    fn score(&self, nid: &NodeId) -> Result<Score, Error> {
+        let mut stmt = self.db.prepare(
+            "SELECT fetches, failures, fetch_time, bytes, violations, uptime
+             FROM scores
+             WHERE node = ?1",
+        )?;
+        stmt.bind((1, nid))?;
+
+        if let Some(row) = stmt.into_iter().next() {
+            Ok(read_score(&row?))
+        } else {
+            Ok(Score::default())
+        }
+    }
+
+    fn fetch_succeeded(&self, nid: &NodeId, time: LocalDuration, bytes: u64) -> Result<(), Error> {
+        let mut stmt = self.db.prepare(
+            "INSERT INTO scores (node, fetches, fetch_time, bytes)
+             VALUES (?1, 1, ?2, ?3)
+             ON CONFLICT DO UPDATE
+             SET fetches = fetches + 1, fetch_time = fetch_time + ?2, bytes = bytes + ?3",
+        )?;
+        stmt.bind((1, nid))?;
+        stmt.bind((2, i64::try_from(time.as_millis()).unwrap_or(i64::MAX)))?;
+        stmt.bind((3, i64::try_from(bytes).unwrap_or(i64::MAX)))?;
+        stmt.next()?;
+
+        Ok(())
+    }
+
+    fn fetch_failed(&self, nid: &NodeId) -> Result<(), Error> {
+        let mut stmt = self.db.prepare(
+            "INSERT INTO scores (node, failures)
+             VALUES (?1, 1)
+             ON CONFLICT DO UPDATE
+             SET failures = failures + 1",
+        )?;
+        stmt.bind((1, nid))?;
+        stmt.next()?;
+
+        Ok(())
+    }
+
+    fn misbehaved(&self, nid: &NodeId) -> Result<(), Error> {
+        let mut stmt = self.db.prepare(
+            "INSERT INTO scores (node, violations)
+             VALUES (?1, 1)
+             ON CONFLICT DO UPDATE
+             SET violations = violations + 1",
+        )?;
+        stmt.bind((1, nid))?;
+        stmt.next()?;
+
+        Ok(())
+    }
+
+    fn record_uptime(&self, nid: &NodeId, uptime: LocalDuration) -> Result<(), Error> {
+        let mut stmt = self.db.prepare(
+            "INSERT INTO scores (node, uptime)
+             VALUES (?1, ?2)
+             ON CONFLICT DO UPDATE
+             SET uptime = uptime + ?2",
+        )?;
+        stmt.bind((1, nid))?;
+        stmt.bind((2, i64::try_from(uptime.as_millis()).unwrap_or(i64::MAX)))?;
+        stmt.next()?;
+
+        Ok(())
+    }
+}
+
+/// Read a node score from a row. Missing scores are read as the default score.
+fn read_score(row: &sql::Row) -> Score {
+    let read = |column: &str| {
+        row.read::<Option<i64>, _>(column)
+            .unwrap_or_default()
+            .max(0) as u64
+    };
+    Score {
+        fetches: read("fetches"),
+        failures: read("failures"),
+        fetch_time: read("fetch_time"),
+        bytes: read("bytes"),
+        violations: read("violations"),
+        uptime: read("uptime"),
+    }
 }
 
 pub struct NodeAliasIter<'a> {
@@ -505,6 +609,7 @@ impl TryFrom<&sql::Value> for Source {
                 "bootstrap" => Ok(Source::Bootstrap),
                 "peer" => Ok(Source::Peer),
                 "imported" => Ok(Source::Imported),
+                "local" => Ok(Source::Local),
                 _ => Err(err),
             },
             _ => Err(err),
@@ -518,6 +623,7 @@ impl sql::BindableWithIndex for Source {
             Self::Bootstrap => "bootstrap".bind(stmt, i),
             Self::Peer => "peer".bind(stmt, i),
             Self::Imported => "imported".bind(stmt, i),
+            Self::Local => "local".bind(stmt, i),
         }
     }
 }
@@ -850,6 +956,7 @@ mod test {
                 node: id,
                 version: 3,
                 penalty: Penalty::default(),
+                score: Score::default(),
                 address: ka.clone(),
             });
             cache
@@ -907,6 +1014,59 @@ mod test {
         assert_eq!(node.penalty, Penalty(4));
     }
 
+    #[test]
+    fn test_score() {
+        let alice = arbitrary::gen::<NodeId>(1);
+        let addr = arbitrary::gen::<Address>(1);
+        let mut db = Database::memory().unwrap();
+        let ka = KnownAddress::new(addr, Source::Peer);
+        let timestamp = Timestamp::from(LocalTime::now());
+
+        db.insert(
+            &alice,
+            1,
+            node::Features::SEED,
+            &Alias::new("alice"),
+            16,
+            &UserAgent::default(),
+            timestamp,
+            [ka],
+        )
+        .unwrap();
+        assert_eq!(db.score(&alice).unwrap(), Score::default());
+        assert_eq!(db.score(&alice).unwrap().value(), 0);
+
+        db.fetch_succeeded(&alice, LocalDuration::from_secs(3), 3 * 1024 * 1024)
+            .unwrap();
+        db.fetch_succeeded(&alice, LocalDuration::from_secs(1), 0)
+            .unwrap();
+        db.fetch_failed(&alice).unwrap();
+        db.record_uptime(&alice, LocalDuration::from_mins(120))
+            .unwrap();
+
+        let score = db.score(&alice).unwrap();
+        assert_eq!(
+            score,
+            Score {
+                fetches: 2,
+                failures: 1,
+                fetch_time: 4000,
+                bytes: 3 * 1024 * 1024,
+                violations: 0,
+                uptime: 120 * 60 * 1000,
+            }
+        );
+        assert_eq!(score.latency(), Some(LocalDuration::from_secs(2)));
+        // One net fetch, 3 MiB, two hours and two seconds of latency.
+        assert_eq!(score.value(), 10 + 3 + 2 - 2);
+
+        let entry = db.entries().unwrap().next().unwrap();
+        assert_eq!(entry.score, score);
+
+        db.misbehaved(&alice).unwrap();
+        assert_eq!(db.score(&alice).unwrap().value(), 13 - 100);
+    }
+
     #[test]
     fn test_disconnected_ban() {
         let alice = arbitrary::gen::<NodeId>(1);
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::ops::Deref;
 use std::str::FromStr;
 use std::{fmt, net};
@@ -8,6 +8,7 @@ use localtime::LocalDuration;
 use serde::{Deserialize, Serialize};
 use serde_json as json;
 
+use crate::identity::RepoId;
 use crate::node;
 use crate::node::policy::{Scope, SeedingPolicy};
 use crate::node::{Address, Alias, NodeId};
@@ -118,6 +119,11 @@ pub struct Limits {
     pub gossip_max_age: LocalDuration,
     /// Maximum number of concurrent fetches per peer connection.
     pub fetch_concurrency: usize,
+    /// Maximum number of seeds to fetch a repository from at the same time.
+    /// When a fetch succeeds, the other fetches of the same refs that nobody
+    /// is waiting on are cancelled.
+    #[serde(default = "defaults::fetch_sources")]
+    pub fetch_sources: usize,
     /// Maximum number of open files.
     pub max_open_files: usize,
     /// Rate limitter settings.
@@ -129,6 +135,12 @@ pub struct Limits {
     /// Channel limits.
     #[serde(default)]
     pub fetch_pack_receive: FetchPackSizeLimit,
+    /// Stream relaying limits.
+    #[serde(default)]
+    pub relay: RelayLimits,
+    /// Bandwidth limits.
+    #[serde(default)]
+    pub bandwidth: BandwidthLimits,
 }
 
 impl Default for Limits {
@@ -138,10 +150,13 @@ impl Default for Limits {
             routing_max_age: LocalDuration::from_mins(7 * 24 * 60), // One week
             gossip_max_age: LocalDuration::from_mins(2 * 7 * 24 * 60), // Two weeks
             fetch_concurrency: 1,
+            fetch_sources: defaults::fetch_sources(),
             max_open_files: 4096,
             rate: RateLimits::default(),
             connection: ConnectionLimits::default(),
             fetch_pack_receive: FetchPackSizeLimit::default(),
+            relay: RelayLimits::default(),
+            bandwidth: BandwidthLimits::default(),
         }
     }
 }
@@ -220,6 +235,11 @@ impl FetchPackSizeLimit {
         bytesize::ByteSize::gib(size).into()
     }
 
+    /// Get the limit in bytes.
+    pub fn as_bytes(&self) -> u64 {
+        self.limit.as_u64()
+    }
+
     /// Check if this limit is exceeded by the number of `bytes` provided.
     pub fn exceeded_by(&self, bytes: usize) -> bool {
         bytes >= self.limit.as_u64() as usize
@@ -252,6 +272,133 @@ impl Default for ConnectionLimits {
     }
 }
 
+/// Limits on the streams a relay node splices between its peers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RelayLimits {
+    /// Max number of streams relayed at the same time.
+    pub circuits: usize,
+    /// Max number of bytes relayed in either direction of a single stream.
+    pub bytes: FetchPackSizeLimit,
+}
+
+impl Default for RelayLimits {
+    fn default() -> Self {
+        Self {
+            circuits: 32,
+            bytes: FetchPackSizeLimit::default(),
+        }
+    }
+}
+
+/// Bandwidth limits. Traffic in a direction is not limited if its limit is unset.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct BandwidthLimits {
+    /// Upload limit across all peers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upload: Option<ByteRate>,
+    /// Download limit across all peers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download: Option<ByteRate>,
+    /// Upload limit for any single peer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer_upload: Option<ByteRate>,
+    /// Download limit for any single peer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer_download: Option<ByteRate>,
+}
+
+/// Data transfer rate, in bytes per second.
+///
+/// Written as a byte quantity per second, eg. `"512 KiB/s"` or `"2 MB/s"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+#[cfg_attr(
+    feature = "schemars",
+    derive(schemars::JsonSchema),
+    schemars(transparent)
+)]
+pub struct ByteRate {
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(with = "crate::schemars_ext::bytesize::ByteRate")
+    )]
+    bytes_per_second: u64,
+}
+
+impl ByteRate {
+    /// New `ByteRate` in bytes per second.
+    pub fn bytes_per_second(rate: u64) -> Self {
+        Self {
+            bytes_per_second: rate,
+        }
+    }
+
+    /// New `ByteRate` in kibibytes per second.
+    pub fn kibibytes_per_second(rate: u64) -> Self {
+        Self::bytes_per_second(bytesize::ByteSize::kib(rate).as_u64())
+    }
+
+    /// New `ByteRate` in mebibytes per second.
+    pub fn mebibytes_per_second(rate: u64) -> Self {
+        Self::bytes_per_second(bytesize::ByteSize::mib(rate).as_u64())
+    }
+
+    /// Get the rate in bytes per second.
+    pub fn as_bytes_per_second(&self) -> u64 {
+        self.bytes_per_second
+    }
+}
+
+impl From<ByteRate> for String {
+    fn from(rate: ByteRate) -> Self {
+        rate.to_string()
+    }
+}
+
+impl TryFrom<String> for ByteRate {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl FromStr for ByteRate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(size) = s.strip_suffix("/s") else {
+            return Err(format!(
+                "invalid rate {s:?}: expected a unit per second, eg. \"1 MiB/s\""
+            ));
+        };
+        let size = size.trim_end().parse::<bytesize::ByteSize>()?;
+
+        Ok(Self::bytes_per_second(size.as_u64()))
+    }
+}
+
+impl fmt::Display for ByteRate {
+    /// Formats the rate in the largest binary unit that represents it exactly.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rate = self.bytes_per_second;
+        let (value, unit) = [
+            (bytesize::GIB, "GiB"),
+            (bytesize::MIB, "MiB"),
+            (bytesize::KIB, "KiB"),
+        ]
+        .into_iter()
+        .find(|(size, _)| rate > 0 && rate % size == 0)
+        .map_or((rate, "B"), |(size, unit)| (rate / size, unit));
+
+        write!(f, "{value} {unit}/s")
+    }
+}
+
 /// Rate limts for a single connection.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -389,6 +536,50 @@ pub enum AddressConfig {
     Forward,
 }
 
+/// Local network discovery configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DiscoveryConfig {
+    /// Multicast group and port on which nodes announce themselves.
+    #[serde(default = "defaults::discovery_group")]
+    #[cfg_attr(feature = "schemars", schemars(example = &"239.255.82.65:8776"))]
+    pub group: net::SocketAddrV4,
+    /// Address of the local network interface to announce on and listen to.
+    /// If unspecified, the operating system picks one.
+    #[serde(default = "defaults::discovery_interface")]
+    pub interface: net::Ipv4Addr,
+    /// Time between announcements, in seconds.
+    #[serde(default = "defaults::discovery_interval")]
+    pub interval: u64,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            group: defaults::discovery_group(),
+            interface: defaults::discovery_interface(),
+            interval: defaults::discovery_interval(),
+        }
+    }
+}
+
+/// Mirror configuration of a repository.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Mirror {
+    /// Git URL that canonical branches and tags are pushed to, every time
+    /// the canonical refs of the repository are updated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(example = &"git@github.com:acme/acme.git"))]
+    pub push: Option<String>,
+    /// Upstream Git URL that branches and tags are imported from, into the
+    /// local namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import: Option<String>,
+}
+
 /// Default seeding policy. Applies when no repository policies for the given repo are found.
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "default")]
@@ -457,6 +648,14 @@ pub struct Config {
     /// Onion address config.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub onion: Option<AddressConfig>,
+    /// Local network discovery. When set, the node announces itself to the local
+    /// network and adds the peers it discovers there to its address book.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discovery: Option<DiscoveryConfig>,
+    /// Address to serve node metrics on, over HTTP, in the Prometheus text format.
+    /// Metrics are disabled if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<net::SocketAddr>,
     /// Peer-to-peer network.
     #[serde(default)]
     pub network: Network,
@@ -471,6 +670,10 @@ pub struct Config {
     /// Whether or not our node should relay messages.
     #[serde(default, deserialize_with = "crate::serde_ext::ok_or_default")]
     pub relay: Relay,
+    /// Whether our node splices fetch streams between peers that can't connect to each
+    /// other. Only takes effect if we also relay messages.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub relay_streams: bool,
     /// Configured service limits.
     #[serde(default)]
     pub limits: Limits,
@@ -480,6 +683,9 @@ pub struct Config {
     /// Default seeding policy.
     #[serde(default)]
     pub seeding_policy: DefaultSeedingPolicy,
+    /// Plain Git remotes to mirror repositories to and from, per repository.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mirrors: BTreeMap<RepoId, Mirror>,
     /// Extra fields that aren't supported.
     #[serde(flatten, skip_serializing)]
     pub extra: json::Map<String, json::Value>,
@@ -503,11 +709,15 @@ impl Config {
             network: Network::default(),
             proxy: None,
             onion: None,
+            discovery: None,
+            metrics: None,
             relay: Relay::default(),
+            relay_streams: false,
             limits: Limits::default(),
             workers: DEFAULT_WORKERS,
             log: defaults::log(),
             seeding_policy: DefaultSeedingPolicy::default(),
+            mirrors: BTreeMap::new(),
             extra: json::Map::default(),
         }
     }
@@ -538,8 +748,22 @@ impl Config {
         }
     }
 
+    /// Are we splicing fetch streams between our peers?
+    pub fn is_stream_relay(&self) -> bool {
+        self.relay_streams && self.is_relay()
+    }
+
     pub fn features(&self) -> node::Features {
-        node::Features::SEED
+        let features = node::Features::SEED
+            .with(node::Features::SEED_HINTS)
+            .with(node::Features::SUBSCRIBE_DELTA)
+            .with(node::Features::RELAYED);
+
+        if self.is_stream_relay() {
+            features.with(node::Features::RELAY)
+        } else {
+            features
+        }
     }
 }
 
@@ -550,8 +774,28 @@ mod defaults {
         super::DEFAULT_WORKERS
     }
 
+    /// Number of seeds to fetch a repository from at the same time.
+    pub fn fetch_sources() -> usize {
+        3
+    }
+
     /// Log level.
     pub fn log() -> log::Level {
         log::Level::Info
     }
+
+    /// Local network discovery multicast group.
+    pub fn discovery_group() -> std::net::SocketAddrV4 {
+        std::net::SocketAddrV4::new(std::net::Ipv4Addr::new(239, 255, 82, 65), 8776)
+    }
+
+    /// Local network discovery interface.
+    pub fn discovery_interface() -> std::net::Ipv4Addr {
+        std::net::Ipv4Addr::UNSPECIFIED
+    }
+
+    /// Local network discovery announcement interval, in seconds.
+    pub fn discovery_interval() -> u64 {
+        30
+    }
 }
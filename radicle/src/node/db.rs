@@ -34,6 +34,8 @@ const MIGRATIONS: &[&str] = &[
     include_str!("db/migrations/4.sql"),
     include_str!("db/migrations/5.sql"),
     include_str!("db/migrations/6.sql"),
+    include_str!("db/migrations/7.sql"),
+    include_str!("db/migrations/8.sql"),
 ];
 
 #[derive(Error, Debug)]
@@ -0,0 +1,21 @@
+-- Node scores, tracking how useful a node has been to us as a peer.
+create table if not exists "scores" (
+  -- Node ID.
+  --
+  -- Nb. We don't use a foreign key constraint because we can't guarantee
+  -- that we'll have received a node announcement from this node.
+  "node"               text      primary key not null,
+  -- Number of successful fetches from this node.
+  "fetches"            integer   not null default 0,
+  -- Number of failed fetches from this node.
+  "failures"           integer   not null default 0,
+  -- Total time spent on successful fetches, in milliseconds.
+  "fetch_time"         integer   not null default 0,
+  -- Total git bytes received in successful fetches.
+  "bytes"              integer   not null default 0,
+  -- Number of protocol violations.
+  "violations"         integer   not null default 0,
+  -- Total time connected to this node, in milliseconds.
+  "uptime"             integer   not null default 0
+  --
+) strict;
@@ -0,0 +1,20 @@
+-- Node event log. Holds the most recent events emitted by the node.
+create table if not exists "events" (
+  -- Event sequence number.
+  "id"                 integer   primary key autoincrement,
+  -- When the event was emitted, in milliseconds since epoch.
+  "timestamp"          integer   not null,
+  -- Event type, eg. "refsFetched".
+  "type"               text      not null,
+  -- Repository the event is about, if any.
+  "rid"                text,
+  -- Node the event is about, if any.
+  "nid"                text,
+  -- Event, encoded as JSON.
+  "event"              text      not null
+  --
+) strict;
+
+create index if not exists "events_timestamp" on "events" ("timestamp");
+create index if not exists "events_rid" on "events" ("rid");
+create index if not exists "events_nid" on "events" ("nid");
@@ -1,5 +1,7 @@
 //! Events for `upload-pack` processes.
+pub mod store;
 pub mod upload_pack;
+pub use store::Store;
 pub use upload_pack::UploadPack;
 
 use std::ops::Deref;
@@ -60,6 +62,16 @@ pub enum Event {
         /// The node identifier of the other node.
         nid: NodeId,
     },
+    /// The node was told of seeds for a repository by another node, eg. because
+    /// it asked for them, or because the other node couldn't serve a fetch.
+    SeedsHinted {
+        /// The identifier of the repository in question.
+        rid: RepoId,
+        /// The node identifier of the other node.
+        nid: NodeId,
+        /// The seeds hinted by the other node.
+        seeds: Vec<NodeId>,
+    },
     /// The node has connected directly to another node.
     PeerConnected {
         /// The node identifier of the other node.
@@ -124,12 +136,107 @@ pub enum Event {
     UploadPack(upload_pack::UploadPack),
 }
 
+impl Event {
+    /// The repository this event is about, if any.
+    pub fn rid(&self) -> Option<RepoId> {
+        match self {
+            Self::RefsFetched { rid, .. }
+            | Self::RefsSynced { rid, .. }
+            | Self::SeedDiscovered { rid, .. }
+            | Self::SeedDropped { rid, .. }
+            | Self::SeedsHinted { rid, .. }
+            | Self::LocalRefsAnnounced { rid, .. }
+            | Self::RefsAnnounced { rid, .. } => Some(*rid),
+            Self::UploadPack(
+                UploadPack::Done { rid, .. }
+                | UploadPack::Write { rid, .. }
+                | UploadPack::Error { rid, .. }
+                | UploadPack::PackProgress { rid, .. },
+            ) => Some(*rid),
+            Self::PeerConnected { .. }
+            | Self::PeerDisconnected { .. }
+            | Self::InventoryAnnounced { .. }
+            | Self::NodeAnnounced { .. } => None,
+        }
+    }
+
+    /// The remote node this event is about, if any.
+    pub fn nid(&self) -> Option<NodeId> {
+        match self {
+            Self::RefsFetched { remote, .. } | Self::RefsSynced { remote, .. } => Some(*remote),
+            Self::SeedDiscovered { nid, .. }
+            | Self::SeedDropped { nid, .. }
+            | Self::SeedsHinted { nid, .. }
+            | Self::PeerConnected { nid }
+            | Self::PeerDisconnected { nid, .. }
+            | Self::InventoryAnnounced { nid, .. }
+            | Self::RefsAnnounced { nid, .. }
+            | Self::NodeAnnounced { nid, .. } => Some(*nid),
+            Self::UploadPack(
+                UploadPack::Done { remote, .. }
+                | UploadPack::Write { remote, .. }
+                | UploadPack::Error { remote, .. }
+                | UploadPack::PackProgress { remote, .. },
+            ) => Some(*remote),
+            Self::LocalRefsAnnounced { .. } => None,
+        }
+    }
+
+    /// Whether this event is worth keeping in the event log. Progress updates are not.
+    pub fn is_loggable(&self) -> bool {
+        !matches!(
+            self,
+            Self::UploadPack(UploadPack::Write { .. } | UploadPack::PackProgress { .. })
+        )
+    }
+}
+
 impl From<upload_pack::UploadPack> for Event {
     fn from(value: upload_pack::UploadPack) -> Self {
         Self::UploadPack(value)
     }
 }
 
+/// An event recorded in the node's event log.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Logged {
+    /// When the event was emitted.
+    pub timestamp: Timestamp,
+    /// The event.
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+/// Event log query.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Filter {
+    /// Only return events emitted at or after this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<Timestamp>,
+    /// Only return events emitted before this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<Timestamp>,
+    /// Only return events about this repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rid: Option<RepoId>,
+    /// Only return events about this node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nid: Option<NodeId>,
+    /// Return at most this many events, the most recent ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+impl Filter {
+    /// Check whether an event matches the repository and node of this filter.
+    pub fn matches(&self, event: &Event) -> bool {
+        self.rid.map_or(true, |rid| event.rid() == Some(rid))
+            && self.nid.map_or(true, |nid| event.nid() == Some(nid))
+    }
+}
+
 /// Events feed.
 pub struct Events(chan::Receiver<Event>);
 
@@ -0,0 +1,182 @@
+use std::fmt;
+
+use sqlite as sql;
+use thiserror::Error;
+
+use crate::node::db::{self, Database};
+use crate::prelude::Timestamp;
+use crate::sql::transaction;
+
+use super::{Event, Filter, Logged};
+
+/// Maximum number of events kept in the event log. Older events are pruned.
+pub const MAX_EVENTS: i64 = 1 << 16;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    /// An Internal error.
+    #[error("internal error: {0}")]
+    Internal(#[from] sql::Error),
+    /// Database error.
+    #[error("database error: {0}")]
+    Database(#[from] db::Error),
+    /// Event (de)serialization error.
+    #[error("invalid event: {0}")]
+    Event(#[from] serde_json::Error),
+}
+
+/// Event log store. Holds the most recent events emitted by the node, in the `events` table
+/// of the node database.
+#[derive(Clone)]
+pub struct Store {
+    db: Database,
+}
+
+impl fmt::Debug for Store {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Store(..)")
+    }
+}
+
+impl From<Database> for Store {
+    fn from(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+impl Store {
+    /// Create a new in-memory event log.
+    pub fn memory() -> Result<Self, Error> {
+        Ok(Self::from(Database::memory()?))
+    }
+
+    /// Record events in a single transaction. Prunes the oldest events if the log is full.
+    pub fn record(&mut self, events: &[(Event, Timestamp)]) -> Result<(), Error> {
+        transaction(&self.db, |db| {
+            let mut stmt = db.prepare(
+                "INSERT INTO `events` (timestamp, type, rid, nid, event)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for (event, timestamp) in events {
+                let json = serde_json::to_value(event)?;
+                let typ = json["type"].as_str().unwrap_or_default().to_owned();
+
+                stmt.reset()?;
+                stmt.bind((1, timestamp))?;
+                stmt.bind((2, typ.as_str()))?;
+                stmt.bind((3, event.rid().map(|r| r.to_string()).as_deref()))?;
+                stmt.bind((4, event.nid().map(|n| n.to_string()).as_deref()))?;
+                stmt.bind((5, json.to_string().as_str()))?;
+                stmt.next()?;
+            }
+            let mut stmt =
+                db.prepare("DELETE FROM `events` WHERE id <= (SELECT MAX(id) FROM `events`) - ?1")?;
+            stmt.bind((1, MAX_EVENTS))?;
+            stmt.next()?;
+
+            Ok(())
+        })
+    }
+
+    /// Get the events matching the filter, oldest first.
+    pub fn events(&self, filter: &Filter) -> Result<Vec<Logged>, Error> {
+        let mut stmt = self.db.prepare(
+            "SELECT timestamp, event FROM (
+               SELECT id, timestamp, event FROM `events`
+               WHERE timestamp >= ?1 AND timestamp < ?2
+               AND (?3 IS NULL OR rid = ?3)
+               AND (?4 IS NULL OR nid = ?4)
+               ORDER BY id DESC
+               LIMIT ?5
+             ) ORDER BY id",
+        )?;
+        stmt.bind((1, &filter.since.unwrap_or(Timestamp::EPOCH)))?;
+        stmt.bind((2, &filter.until.unwrap_or(Timestamp::MAX)))?;
+        stmt.bind((3, filter.rid.map(|r| r.to_string()).as_deref()))?;
+        stmt.bind((4, filter.nid.map(|n| n.to_string()).as_deref()))?;
+        stmt.bind((5, filter.limit.map_or(-1, |l| l as i64)))?;
+
+        let mut events = Vec::new();
+        for row in stmt.into_iter() {
+            let row = row?;
+            let timestamp = row.try_read::<Timestamp, _>("timestamp")?;
+            let event = serde_json::from_str(row.try_read::<&str, _>("event")?)?;
+
+            events.push(Logged { timestamp, event });
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::assert_matches;
+    use crate::node::NodeId;
+    use crate::prelude::RepoId;
+    use crate::test::arbitrary;
+
+    #[test]
+    fn test_events_filter() {
+        let mut db = Store::memory().unwrap();
+        let rid = arbitrary::gen::<RepoId>(1);
+        let alice = arbitrary::gen::<NodeId>(1);
+        let bob = arbitrary::gen::<NodeId>(1);
+        let t = Timestamp::EPOCH + 1000;
+
+        db.record(&[(Event::PeerConnected { nid: alice }, t)])
+            .unwrap();
+        db.record(&[
+            (Event::SeedDiscovered { rid, nid: bob }, t + 1),
+            (
+                Event::PeerDisconnected {
+                    nid: alice,
+                    reason: String::from("timeout"),
+                },
+                t + 2,
+            ),
+        ])
+        .unwrap();
+
+        let all = db.events(&Filter::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].timestamp, t);
+        assert_matches!(all[2].event, Event::PeerDisconnected { .. });
+
+        let since = db
+            .events(&Filter {
+                since: Some(t + 1),
+                ..Filter::default()
+            })
+            .unwrap();
+        assert_eq!(since.len(), 2);
+
+        let by_rid = db
+            .events(&Filter {
+                rid: Some(rid),
+                ..Filter::default()
+            })
+            .unwrap();
+        assert_eq!(by_rid.len(), 1);
+        assert_matches!(by_rid[0].event, Event::SeedDiscovered { nid, .. } if nid == bob);
+
+        let by_nid = db
+            .events(&Filter {
+                nid: Some(alice),
+                until: Some(t + 2),
+                ..Filter::default()
+            })
+            .unwrap();
+        assert_eq!(by_nid.len(), 1);
+
+        let latest = db
+            .events(&Filter {
+                limit: Some(2),
+                ..Filter::default()
+            })
+            .unwrap();
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].timestamp, t + 1);
+    }
+}
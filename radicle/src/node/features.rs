@@ -14,6 +14,19 @@ impl Features {
     /// `SEED` is the base feature set all seed nodes must support.
     pub const SEED: Features = Features(0b00000001);
 
+    /// `SEED_HINTS` means the node can be asked which seeds it knows for a repository.
+    pub const SEED_HINTS: Features = Features(0b00000010);
+
+    /// `SUBSCRIBE_DELTA` means the node accepts incremental changes to its peers' subscriptions.
+    pub const SUBSCRIBE_DELTA: Features = Features(0b00000100);
+
+    /// `RELAY` means the node splices streams between two of its peers, so that peers which
+    /// can't accept inbound connections can still be fetched from. Relaying is opt-in.
+    pub const RELAY: Features = Features(0b00001000);
+
+    /// `RELAYED` means the node understands streams relayed to it by a `RELAY` node.
+    pub const RELAYED: Features = Features(0b00010000);
+
     /// Returns [`Features`] with the other features added.
     #[must_use]
     pub fn with(self, other: Features) -> Features {
@@ -33,13 +33,18 @@ use super::{PrivateNetwork, ReplicationFactor};
 /// [`Fetcher::ready_to_fetch`].
 ///
 /// To then retrieve the next available node for fetching, the caller uses
-/// [`Fetcher::next_fetch`].
+/// [`Fetcher::next_fetch`]. The fetch is then considered in-flight until it is
+/// marked as complete or failed. Multiple fetches may be in-flight at the same
+/// time, so that the caller doesn't have to wait on slow seeds one after the
+/// other; the number of successful fetches still needed to reach the target is
+/// given by [`Fetcher::remaining`].
 ///
 /// To mark that fetch as complete, we call [`Fetcher::fetch_complete`], with
 /// the result. At this point, the [`Fetcher`] returns a [`ControlFlow`] to let
 /// the caller know if they should continue processing nodes, to reach the
 /// desired target, or they can exit the loop knowing they have successfully
-/// reached the target.
+/// reached the target. Any fetches still in-flight at that point are not waited
+/// for, and are reported by [`Success::pending`].
 ///
 /// The caller may also call [`Fetcher::fetch_failed`] to mark a fetch for a
 /// given node as failed – this is useful for reasons when the caller cannot
@@ -47,6 +52,9 @@ use super::{PrivateNetwork, ReplicationFactor};
 ///
 /// Finally, if the caller wishes to exit from the fetching process and get the
 /// final set of results, they may call [`Fetcher::finish`].
+///
+/// Results are always reported in the order in which the fetches were started,
+/// regardless of the order in which they completed.
 #[derive(Debug)]
 #[must_use]
 pub struct Fetcher {
@@ -54,6 +62,10 @@ pub struct Fetcher {
     fetch_from: VecDeque<Ready>,
     candidates: VecDeque<Candidate>,
     results: FetchResults,
+    /// Nodes in the order their fetches were started, or failed.
+    order: Vec<NodeId>,
+    /// Fetches that were started but did not complete yet.
+    in_flight: BTreeSet<NodeId>,
     local_node: NodeId,
 }
 
@@ -79,6 +91,8 @@ impl Fetcher {
             fetch_from: VecDeque::new(),
             candidates: config.candidates,
             results: FetchResults::default(),
+            order: Vec::new(),
+            in_flight: BTreeSet::new(),
             local_node: config.local_node,
         })
     }
@@ -88,7 +102,10 @@ impl Fetcher {
     pub fn next_node(&mut self) -> Option<NodeId> {
         let local_node = self.local_node;
         let results = &self.results;
-        let include_node = |node: &NodeId| results.get(node).is_none() && local_node != *node;
+        let in_flight = &self.in_flight;
+        let include_node = |node: &NodeId| {
+            results.get(node).is_none() && !in_flight.contains(node) && local_node != *node
+        };
 
         // Find the first candidate that passes the `include_node` filter, or we
         // exhaust the candidate list
@@ -102,16 +119,52 @@ impl Fetcher {
     ///
     /// Note that this [`NodeId`] must have been added to the [`Fetcher`] using
     /// the [`Fetcher::ready_to_fetch`] method.
+    ///
+    /// The returned node is marked as in-flight until its fetch is completed
+    /// or failed.
     pub fn next_fetch(&mut self) -> Option<(NodeId, Address)> {
-        self.fetch_from
+        let (node, addr) = self
+            .fetch_from
             .pop_front()
             .map(|Ready { node, addr }| (node, addr))
-            .filter(|(node, _)| self.include_node(node))
+            .filter(|(node, _)| self.include_node(node))?;
+
+        self.order.push(node);
+        self.in_flight.insert(node);
+
+        Some((node, addr))
+    }
+
+    /// Get the nodes that are currently being fetched from.
+    pub fn in_flight(&self) -> &BTreeSet<NodeId> {
+        &self.in_flight
+    }
+
+    /// Get the number of successful fetches still needed to reach the target,
+    /// not counting fetches that are in-flight.
+    pub fn remaining(&self) -> usize {
+        let (preferred, succeeded) = self.success_counts();
+        let replicas = self.target.replicas();
+        let replicas = replicas
+            .upper_bound()
+            .unwrap_or(replicas.lower_bound())
+            .saturating_sub(succeeded);
+
+        if self.target.seeds.is_empty() {
+            replicas
+        } else {
+            let seeds = self.target.seeds.len().saturating_sub(preferred);
+            seeds.min(replicas)
+        }
+        .saturating_sub(self.in_flight.len())
     }
 
     /// Mark a fetch as failed for the [`NodeId`], using the provided `reason`.
     pub fn fetch_failed(&mut self, node: NodeId, reason: impl ToString) {
         let reason = reason.to_string();
+        if !self.in_flight.remove(&node) {
+            self.order.push(node);
+        }
         self.results.push(node, FetchResult::Failed { reason })
     }
 
@@ -128,6 +181,9 @@ impl Fetcher {
         node: NodeId,
         result: FetchResult,
     ) -> ControlFlow<Success, Progress> {
+        if !self.in_flight.remove(&node) {
+            self.order.push(node);
+        }
         self.results.push(node, result);
         self.finished()
     }
@@ -138,12 +194,15 @@ impl Fetcher {
     /// [`Fetcher`]'s target was reached.
     pub fn finish(self) -> FetcherResult {
         let progress = self.progress();
+        let results = self.ordered_results();
         match self.is_target_reached() {
             None => {
                 let missing = self.missing_seeds();
-                FetcherResult::target_error(progress, self.target, self.results, missing)
+                FetcherResult::target_error(progress, self.target, results, missing)
+            }
+            Some(outcome) => {
+                FetcherResult::target_reached(outcome, progress, results, self.in_flight)
             }
-            Some(outcome) => FetcherResult::target_reached(outcome, progress, self.results),
         }
     }
 
@@ -177,11 +236,19 @@ impl Fetcher {
                 ControlFlow::Break(Success {
                     outcome,
                     progress,
-                    results: self.results.clone(),
+                    results: self.ordered_results(),
+                    pending: self.in_flight.clone(),
                 })
             })
     }
 
+    /// Get the results, in the order in which the fetches were started.
+    fn ordered_results(&self) -> FetchResults {
+        let mut results = self.results.to_vec();
+        results.sort_by_key(|(nid, _)| self.order.iter().position(|n| n == nid));
+        results.into()
+    }
+
     fn is_target_reached(&self) -> Option<SuccessfulOutcome> {
         let (preferred, succeeded) = self.success_counts();
         if !self.target.seeds.is_empty() && preferred >= self.target.seeds.len() {
@@ -202,10 +269,12 @@ impl Fetcher {
         }
     }
 
-    /// Ensure that node does not already have a result and is not the local
-    /// node.
+    /// Ensure that node does not already have a result, is not being fetched
+    /// from, and is not the local node.
     fn include_node(&self, node: &NodeId) -> bool {
-        self.results.get(node).is_none() && self.local_node != *node
+        self.results.get(node).is_none()
+            && !self.in_flight.contains(node)
+            && self.local_node != *node
     }
 
     fn missing_seeds(&self) -> BTreeSet<NodeId> {
@@ -322,6 +391,7 @@ pub struct Success {
     outcome: SuccessfulOutcome,
     progress: Progress,
     results: FetchResults,
+    pending: BTreeSet<NodeId>,
 }
 
 impl Success {
@@ -339,6 +409,13 @@ impl Success {
     pub fn outcome(&self) -> &SuccessfulOutcome {
         &self.outcome
     }
+
+    /// Get the nodes that were still being fetched from when the target was
+    /// reached. Their results are not waited for, though the fetches still run
+    /// to completion on the node.
+    pub fn pending(&self) -> &BTreeSet<NodeId> {
+        &self.pending
+    }
 }
 
 /// An unsuccessful `Fetcher` process result, where the target was not reached.
@@ -403,11 +480,13 @@ impl FetcherResult {
         outcome: SuccessfulOutcome,
         progress: Progress,
         results: FetchResults,
+        pending: BTreeSet<NodeId>,
     ) -> Self {
         Self::TargetReached(Success {
             outcome,
             progress,
             results,
+            pending,
         })
     }
 
@@ -833,4 +912,58 @@ mod test {
         let result = fetcher.finish();
         assert!(matches!(result, FetcherResult::TargetError(_)));
     }
+
+    #[test]
+    fn concurrent_fetches() {
+        let local = arbitrary::gen::<NodeId>(0);
+        let replicas = ReplicationFactor::must_reach(2);
+        let seeds = arbitrary::set::<NodeId>(3..=3)
+            .into_iter()
+            .collect::<Vec<_>>();
+        let config = FetcherConfig::public(BTreeSet::new(), replicas, local)
+            .with_candidates(seeds.iter().copied().map(Candidate::new));
+        let success = || FetchResult::Success {
+            updated: vec![],
+            namespaces: HashSet::new(),
+            clone: false,
+        };
+
+        let mut fetcher = Fetcher::new(config).expect("fetcher should be constructed correctly");
+        assert_eq!(fetcher.remaining(), 2);
+
+        while let Some(node) = fetcher.next_node() {
+            fetcher.ready_to_fetch(node, arbitrary::gen::<Address>(0));
+        }
+        let mut started = Vec::new();
+        while let Some((node, _)) = fetcher.next_fetch() {
+            started.push(node);
+        }
+        assert_eq!(started, seeds);
+        assert_eq!(fetcher.in_flight().len(), 3);
+        assert_eq!(fetcher.remaining(), 0);
+
+        // The last seed to be started completes first.
+        let ControlFlow::Continue(progress) = fetcher.fetch_complete(seeds[2], success()) else {
+            panic!("target should not be reached with one fetch");
+        };
+        assert_eq!(progress.succeeded(), 1);
+
+        let ControlFlow::Break(success) = fetcher.fetch_complete(seeds[1], success()) else {
+            panic!("target should be reached with two fetches");
+        };
+        assert_eq!(
+            *success.outcome(),
+            SuccessfulOutcome::MinReplicas { succeeded: 2 }
+        );
+        assert_eq!(success.pending(), &BTreeSet::from([seeds[0]]));
+        // Results are in the order the fetches were started.
+        assert_eq!(
+            success
+                .fetch_results()
+                .iter()
+                .map(|(nid, _)| *nid)
+                .collect::<Vec<_>>(),
+            vec![seeds[1], seeds[2]]
+        );
+    }
 }
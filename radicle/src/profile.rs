@@ -28,7 +28,7 @@ use crate::crypto::PublicKey;
 use crate::node::device::{BoxedDevice, Device};
 use crate::node::policy::config::store::Read;
 use crate::node::{
-    notifications, policy, policy::Scope, Alias, AliasStore, Handle as _, Node, UserAgent,
+    events, notifications, policy, policy::Scope, Alias, AliasStore, Handle as _, Node, UserAgent,
 };
 use crate::prelude::{Did, NodeId, RepoId};
 use crate::storage::git::transport;
@@ -544,6 +544,20 @@ impl Home {
         Ok(db)
     }
 
+    /// Return a read-only handle to the event log of the node.
+    pub fn events(&self) -> Result<events::Store, events::store::Error> {
+        let db = self.database()?;
+
+        Ok(events::Store::from(db))
+    }
+
+    /// Return a read-write handle to the event log of the node.
+    pub fn events_mut(&self) -> Result<events::Store, events::store::Error> {
+        let db = self.database_mut()?;
+
+        Ok(events::Store::from(db))
+    }
+
     /// Return a read-write handle to the policies store of the node.
     pub fn policies_mut(&self) -> Result<policy::store::StoreWriter, policy::store::Error> {
         let path = self.node().join(node::POLICIES_DB_FILE);
@@ -662,6 +676,44 @@ impl Home {
 
         Ok(cob::patch::Cache::open(store, db))
     }
+
+    /// Return a read-only handle for the milestones cache.
+    pub fn milestones<'a, R>(
+        &self,
+        repository: &'a R,
+    ) -> Result<
+        cob::milestone::Cache<cob::milestone::Milestones<'a, R>, cob::cache::StoreReader>,
+        Error,
+    >
+    where
+        R: ReadRepository + cob::Store<Namespace = NodeId>,
+    {
+        let db = self.cobs_db()?;
+        let store = cob::milestone::Milestones::open(repository)?;
+
+        db.check_version()?;
+
+        Ok(cob::milestone::Cache::reader(store, db))
+    }
+
+    /// Return a read-write handle for the milestones cache.
+    pub fn milestones_mut<'a, R>(
+        &self,
+        repository: &'a R,
+    ) -> Result<
+        cob::milestone::Cache<cob::milestone::Milestones<'a, R>, cob::cache::StoreWriter>,
+        Error,
+    >
+    where
+        R: ReadRepository + cob::Store<Namespace = NodeId>,
+    {
+        let db = self.cobs_db_mut()?;
+        let store = cob::milestone::Milestones::open(repository)?;
+
+        db.check_version()?;
+
+        Ok(cob::milestone::Cache::open(store, db))
+    }
 }
 
 // Private methods.
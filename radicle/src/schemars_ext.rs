@@ -44,6 +44,16 @@ pub(crate) mod bytesize {
     pub(crate) struct ByteSize(
         #[schemars(regex(pattern = r"^\d+(\.\d+)? ((K|M|G|T|P)i?B?|B)$"))] String,
     );
+
+    /// A byte quantity per second, see [`ByteSize`].
+    #[derive(JsonSchema)]
+    #[schemars(
+        description = "Data transfer rates, as byte quantities per second.",
+        extend("examples" = ["1 MiB/s", "500 KB/s", "200 B/s"]),
+    )]
+    pub(crate) struct ByteRate(
+        #[schemars(regex(pattern = r"^\d+(\.\d+)? ((K|M|G|T|P)i?B?|B)/s$"))] String,
+    );
 }
 
 pub(crate) mod localtime {
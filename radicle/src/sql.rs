@@ -10,11 +10,15 @@ use crate::node::{Address, UserAgent};
 
 /// Run an SQL query inside a transaction.
 /// Commits the transaction on success, and rolls back on error.
+///
+/// The write lock is taken when the transaction begins, so that queries which read before
+/// they write wait for other connections to the database, instead of failing when another
+/// connection wrote in the meantime.
 pub fn transaction<T, E: From<sql::Error>>(
     db: &sql::Connection,
     query: impl FnOnce(&sql::Connection) -> Result<T, E>,
 ) -> Result<T, E> {
-    db.execute("BEGIN")?;
+    db.execute("BEGIN IMMEDIATE")?;
 
     match query(db) {
         Ok(result) => {
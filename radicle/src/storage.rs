@@ -192,10 +192,29 @@ pub type RemoteId = PublicKey;
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum RefUpdate {
-    Updated { name: RefString, old: Oid, new: Oid },
-    Created { name: RefString, oid: Oid },
-    Deleted { name: RefString, oid: Oid },
-    Skipped { name: RefString, oid: Oid },
+    Updated {
+        name: RefString,
+        old: Oid,
+        new: Oid,
+    },
+    /// A non-fast-forward update, ie. the new target doesn't descend from the old one.
+    Forced {
+        name: RefString,
+        old: Oid,
+        new: Oid,
+    },
+    Created {
+        name: RefString,
+        oid: Oid,
+    },
+    Deleted {
+        name: RefString,
+        oid: Oid,
+    },
+    Skipped {
+        name: RefString,
+        oid: Oid,
+    },
 }
 
 impl RefUpdate {
@@ -218,6 +237,7 @@ impl RefUpdate {
     pub fn old(&self) -> Option<Oid> {
         match self {
             RefUpdate::Updated { old, .. } => Some(*old),
+            RefUpdate::Forced { old, .. } => Some(*old),
             RefUpdate::Created { .. } => None,
             RefUpdate::Deleted { oid, .. } => Some(*oid),
             RefUpdate::Skipped { oid, .. } => Some(*oid),
@@ -229,6 +249,7 @@ impl RefUpdate {
     pub fn new(&self) -> Option<Oid> {
         match self {
             RefUpdate::Updated { new, .. } => Some(*new),
+            RefUpdate::Forced { new, .. } => Some(*new),
             RefUpdate::Created { oid, .. } => Some(*oid),
             RefUpdate::Deleted { .. } => None,
             RefUpdate::Skipped { .. } => None,
@@ -239,6 +260,7 @@ impl RefUpdate {
     pub fn name(&self) -> &RefStr {
         match self {
             RefUpdate::Updated { name, .. } => name.as_refstr(),
+            RefUpdate::Forced { name, .. } => name.as_refstr(),
             RefUpdate::Created { name, .. } => name.as_refstr(),
             RefUpdate::Deleted { name, .. } => name.as_refstr(),
             RefUpdate::Skipped { name, .. } => name.as_refstr(),
@@ -250,6 +272,11 @@ impl RefUpdate {
         matches!(self, RefUpdate::Updated { .. })
     }
 
+    /// Is it a forced update.
+    pub fn is_forced(&self) -> bool {
+        matches!(self, RefUpdate::Forced { .. })
+    }
+
     /// Is it a create.
     pub fn is_created(&self) -> bool {
         matches!(self, RefUpdate::Created { .. })
@@ -267,6 +294,9 @@ impl fmt::Display for RefUpdate {
             Self::Updated { name, old, new } => {
                 write!(f, "~ {old:.7}..{new:.7} {name}")
             }
+            Self::Forced { name, old, new } => {
+                write!(f, "+ {old:.7}...{new:.7} {name}")
+            }
             Self::Created { name, oid } => {
                 write!(f, "* 0000000..{oid:.7} {name}")
             }
@@ -489,6 +519,12 @@ pub trait ReadRepository: Sized + ValidateRepository {
     /// Returns the [`Oid`] as well as the qualified reference name.
     fn canonical_head(&self) -> Result<(Qualified, Oid), RepositoryError>;
 
+    /// Compute the canonical tip of the given reference, eg. `refs/heads/release/1.x`.
+    ///
+    /// The tip is computed from the delegates' references of the same name,
+    /// using the threshold of the identity document.
+    fn canonical_reference(&self, name: &Qualified) -> Result<Oid, RepositoryError>;
+
     /// Get the head of the `rad/id` reference in this repository.
     ///
     /// Returns the reference pointed to by `rad/id` if it is set. Otherwise, computes the canonical
@@ -575,6 +611,15 @@ pub trait ReadRepository: Sized + ValidateRepository {
         Ok(doc.delegates().clone().into())
     }
 
+    /// Get the persons of the repository, which delegates can be.
+    ///
+    /// A delegate that is a [`crate::identity::Person`] signs with any of its
+    /// devices. Any other delegate only signs with its own key. Callers should
+    /// load the index once and look up all the delegates they need in it.
+    fn persons(&self) -> Result<cob::person::Index, RepositoryError> {
+        Ok(cob::person::Index::default())
+    }
+
     /// Get the repository's identity document.
     fn identity_doc(&self) -> Result<DocAt, RepositoryError> {
         let head = self.identity_head()?;
@@ -627,6 +672,13 @@ pub trait WriteRepository: ReadRepository + SignRepository {
     /// Set the repository head to the canonical branch.
     /// This computes the head based on the delegate set.
     fn set_head(&self) -> Result<SetHead, RepositoryError>;
+    /// Set the canonical references declared in the identity document, other
+    /// than the default branch, to their tips agreed by quorum.
+    /// See [`crate::identity::CanonicalRefs`].
+    ///
+    /// Returns the references that were updated. References without a quorum
+    /// are left unchanged.
+    fn set_canonical_refs(&self) -> Result<Vec<RefUpdate>, RepositoryError>;
     /// Set the repository 'rad/id' to the canonical commit, agreed by quorum.
     fn set_identity_head(&self) -> Result<Oid, RepositoryError> {
         let head = self.canonical_identity_head()?;
@@ -1,5 +1,7 @@
 #![warn(clippy::unwrap_used)]
 pub mod cob;
+pub mod fsck;
+pub mod mirror;
 pub mod transport;
 
 use std::collections::{BTreeMap, BTreeSet, HashMap};
@@ -11,7 +13,7 @@ use crypto::Verified;
 use once_cell::sync::Lazy;
 use tempfile::TempDir;
 
-use crate::git::canonical::Canonical;
+use crate::git::canonical::{Canonical, QuorumError};
 use crate::identity::doc::DocError;
 use crate::identity::{Doc, DocAt, RepoId};
 use crate::identity::{Identity, Project};
@@ -20,8 +22,8 @@ use crate::node::SyncedAt;
 use crate::storage::refs;
 use crate::storage::refs::{Refs, SignedRefs, SignedRefsAt};
 use crate::storage::{
-    ReadRepository, ReadStorage, Remote, Remotes, RepositoryInfo, SetHead, SignRepository,
-    WriteRepository, WriteStorage,
+    ReadRepository, ReadStorage, RefUpdate, Remote, Remotes, RepositoryInfo, SetHead,
+    SignRepository, WriteRepository, WriteStorage,
 };
 use crate::{git, node};
 
@@ -338,6 +340,14 @@ pub enum Validation {
     },
     #[error("missing `refs/namespaces/{0}/refs/rad/sigrefs`")]
     MissingRadSigRefs(RemoteId),
+    #[error(
+        "`refs/namespaces/{remote}/refs/rad/sigrefs` {received} does not descend from {current}"
+    )]
+    DivergedRadSigRefs {
+        remote: RemoteId,
+        current: Oid,
+        received: Oid,
+    },
 }
 
 impl Repository {
@@ -407,25 +417,9 @@ impl Repository {
             if *local == id || delegates.contains(&id) {
                 continue;
             }
-
-            let glob = git::refname!("refs/namespaces")
-                .join(git::Component::from(&id))
-                .with_pattern(git::refspec::STAR);
-            let refs = match self.references_glob(&glob) {
-                Ok(refs) => refs,
-                Err(e) => {
-                    log::error!(target: "storage", "Failed to clean up remote '{id}': {e}");
-                    continue;
-                }
-            };
-            for (refname, _) in refs {
-                if let Ok(mut r) = self.backend.find_reference(refname.as_str()) {
-                    if let Err(e) = r.delete() {
-                        log::error!(target: "storage", "Failed to clean up reference '{refname}': {e}");
-                    }
-                } else {
-                    log::error!(target: "storage", "Failed to clean up reference '{refname}'");
-                }
+            if let Err(e) = self.remove_remote(&id) {
+                log::error!(target: "storage", "Failed to clean up remote '{id}': {e}");
+                continue;
             }
             deleted.push(id);
         }
@@ -433,6 +427,26 @@ impl Repository {
         Ok(deleted)
     }
 
+    /// Remove all the references of a remote.
+    ///
+    /// N.b. failure to delete a reference will not result in an early exit.
+    pub fn remove_remote(&self, id: &RemoteId) -> Result<(), Error> {
+        let glob = git::refname!("refs/namespaces")
+            .join(git::Component::from(id))
+            .with_pattern(git::refspec::STAR);
+
+        for (refname, _) in self.references_glob(&glob)? {
+            if let Ok(mut r) = self.backend.find_reference(refname.as_str()) {
+                if let Err(e) = r.delete() {
+                    log::error!(target: "storage", "Failed to clean up reference '{refname}': {e}");
+                }
+            } else {
+                log::error!(target: "storage", "Failed to clean up reference '{refname}'");
+            }
+        }
+        Ok(())
+    }
+
     /// Create the repository's identity branch.
     pub fn init<G, S>(
         doc: &Doc,
@@ -500,6 +514,21 @@ impl Repository {
         Doc::load_at(oid, self).map(|d| d.into())
     }
 
+    /// Get the root of the identity history that leads to the given commit.
+    ///
+    /// Only first parents are followed, since identity changes may also have
+    /// the heads of person histories as parents, which come after the tips.
+    fn identity_root_from(&self, head: Oid) -> Result<Oid, RepositoryError> {
+        let mut revwalk = self.revwalk(head)?;
+        revwalk.simplify_first_parent()?;
+
+        let root = revwalk
+            .last()
+            .ok_or(RepositoryError::Doc(DocError::Missing))??;
+
+        Ok(root.into())
+    }
+
     pub fn remote_ids(
         &self,
     ) -> Result<impl Iterator<Item = Result<RemoteId, refs::Error>> + '_, git2::Error> {
@@ -752,11 +781,17 @@ impl ReadRepository for Repository {
         let project = doc.project()?;
         let branch_ref = git::refs::branch(project.default_branch());
         let raw = self.raw();
-        let oid = Canonical::default_branch(self, &project, doc.delegates().into())?
-            .quorum(doc.threshold(), raw)?;
+        let oid = Canonical::default_branch(self, &project, &doc)?.quorum(doc.threshold(), raw)?;
         Ok((branch_ref, oid))
     }
 
+    fn canonical_reference(&self, name: &Qualified) -> Result<Oid, RepositoryError> {
+        let doc = self.identity_doc()?;
+        let oid = Canonical::reference(self, &doc, name)?.quorum(doc.threshold(), self.raw())?;
+
+        Ok(oid)
+    }
+
     fn identity_head(&self) -> Result<Oid, RepositoryError> {
         let result = self
             .backend
@@ -777,12 +812,8 @@ impl ReadRepository for Repository {
 
     fn identity_root(&self) -> Result<Oid, RepositoryError> {
         let oid = self.backend.refname_to_id(CANONICAL_IDENTITY.as_str())?;
-        let root = self
-            .revwalk(oid.into())?
-            .last()
-            .ok_or(RepositoryError::Doc(DocError::Missing))??;
 
-        Ok(root.into())
+        self.identity_root_from(oid.into())
     }
 
     fn identity_root_of(&self, remote: &RemoteId) -> Result<Oid, RepositoryError> {
@@ -792,12 +823,12 @@ impl ReadRepository for Repository {
             return Ok(root);
         }
         let oid = self.identity_head_of(remote)?;
-        let root = self
-            .revwalk(oid)?
-            .last()
-            .ok_or(RepositoryError::Doc(DocError::Missing))??;
 
-        Ok(root.into())
+        self.identity_root_from(oid)
+    }
+
+    fn persons(&self) -> Result<crate::cob::person::Index, RepositoryError> {
+        crate::cob::person::Index::load(self).map_err(RepositoryError::from)
     }
 
     fn canonical_identity_head(&self) -> Result<Oid, RepositoryError> {
@@ -853,6 +884,64 @@ impl WriteRepository for Repository {
         Ok(SetHead { old, new })
     }
 
+    fn set_canonical_refs(&self) -> Result<Vec<RefUpdate>, RepositoryError> {
+        let doc = self.identity_doc()?;
+        let Some(crefs) = doc.canonical_refs()? else {
+            return Ok(vec![]);
+        };
+        // N.b. the default branch is set by `set_head`, with the threshold of the document.
+        let default_branch = doc
+            .project()
+            .ok()
+            .map(|p| git::refs::branch(p.default_branch()).to_owned());
+        let mut old = BTreeMap::new();
+        let mut new = BTreeMap::new();
+
+        for (pattern, rule) in crefs.rules.iter() {
+            for (name, canonical) in Canonical::matching(self, &doc, pattern)? {
+                if Some(&name) == default_branch.as_ref() {
+                    continue;
+                }
+                // Only the rule with the longest matching pattern applies.
+                if crefs.rule(&name).map(|(p, _)| p) != Some(pattern) {
+                    continue;
+                }
+                let threshold = rule.threshold.get();
+                let quorum = if pattern.is_tags() {
+                    canonical.quorum_exact(threshold, self.raw())
+                } else {
+                    canonical.quorum(threshold, self.raw())
+                };
+                let oid = match quorum {
+                    Ok(oid) => oid,
+                    Err(QuorumError::Git(e)) => return Err(e.into()),
+                    Err(e) => {
+                        log::warn!(target: "storage", "Could not set canonical reference {name}: {e}");
+                        continue;
+                    }
+                };
+                if let Ok(prev) = self.backend.refname_to_id(name.as_str()) {
+                    old.insert(name.to_ref_string(), prev.into());
+                }
+                new.insert(name.to_ref_string(), oid);
+            }
+        }
+        let updates = Refs::from(old).diff(&Refs::from(new), self);
+
+        for update in &updates {
+            if let Some(oid) = update.new() {
+                log::debug!(target: "storage", "Setting ref: {} -> {}", update.name(), oid);
+                self.raw().reference(
+                    update.name().as_str(),
+                    *oid,
+                    true,
+                    "set-canonical-ref (radicle)",
+                )?;
+            }
+        }
+        Ok(updates)
+    }
+
     fn set_identity_head_to(&self, commit: Oid) -> Result<(), RepositoryError> {
         log::debug!(target: "storage", "Setting ref: {} -> {}", *CANONICAL_IDENTITY, commit);
         self.raw().reference(
@@ -279,6 +279,10 @@ impl<R: storage::ReadRepository> ReadRepository for DraftStore<'_, R> {
         self.repo.canonical_head()
     }
 
+    fn canonical_reference(&self, name: &Qualified) -> Result<Oid, RepositoryError> {
+        self.repo.canonical_reference(name)
+    }
+
     fn path(&self) -> &std::path::Path {
         self.repo.path()
     }
@@ -369,6 +373,10 @@ impl<R: storage::ReadRepository> ReadRepository for DraftStore<'_, R> {
     fn merge_base(&self, left: &Oid, right: &Oid) -> Result<Oid, git::ext::Error> {
         self.repo.merge_base(left, right)
     }
+
+    fn persons(&self) -> Result<crate::cob::person::Index, RepositoryError> {
+        self.repo.persons()
+    }
 }
 
 impl<R: storage::WriteRepository> cob::object::Storage for DraftStore<'_, R> {
@@ -0,0 +1,475 @@
+//! Storage integrity checks.
+//!
+//! Unlike [`Repository::validate`], which only compares the refs of each remote against its
+//! signed refs, an audit also verifies that every ref points to a complete history, and that
+//! every collaborative object can be loaded.
+use std::collections::{BTreeSet, HashSet};
+
+use nonempty::NonEmpty;
+use serde::Serialize;
+
+use crate::cob;
+use crate::git;
+use crate::git::Oid;
+use crate::identity::RepoId;
+use crate::storage::{
+    ReadRepository, RemoteId, RemoteRepository, RepositoryError, ValidateRepository,
+};
+
+use super::{Error, RefsAt, Repository, Validation};
+
+/// A problem found while auditing a repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum Problem {
+    /// The canonical identity document could not be loaded.
+    Identity { error: String },
+    /// The signed refs of a remote could not be loaded or verified.
+    #[serde(rename_all = "camelCase")]
+    Sigrefs { remote: RemoteId, error: String },
+    /// The refs of a remote don't match its signed refs.
+    #[serde(rename_all = "camelCase")]
+    Validation { remote: RemoteId, error: String },
+    /// A ref points to an object that is missing, or to an incomplete history.
+    #[serde(rename_all = "camelCase")]
+    Object {
+        remote: Option<RemoteId>,
+        refname: git::RefString,
+        oid: Option<Oid>,
+        error: String,
+    },
+    /// A collaborative object could not be loaded.
+    #[serde(rename_all = "camelCase")]
+    Cob {
+        type_name: cob::TypeName,
+        id: cob::ObjectId,
+        error: String,
+    },
+}
+
+impl Problem {
+    /// The remote this problem is confined to, if any.
+    pub fn remote(&self) -> Option<&RemoteId> {
+        match self {
+            Self::Sigrefs { remote, .. } | Self::Validation { remote, .. } => Some(remote),
+            Self::Object { remote, .. } => remote.as_ref(),
+            Self::Identity { .. } | Self::Cob { .. } => None,
+        }
+    }
+}
+
+/// The outcome of a repository audit.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+    /// The audited repository.
+    pub rid: RepoId,
+    /// Number of remotes checked.
+    pub remotes: usize,
+    /// Number of refs checked.
+    pub refs: usize,
+    /// Number of collaborative objects checked.
+    pub cobs: usize,
+    /// Problems found.
+    pub problems: Vec<Problem>,
+}
+
+impl Report {
+    /// Whether the repository is free of problems.
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Remotes that have at least one problem.
+    pub fn corrupted(&self) -> BTreeSet<RemoteId> {
+        self.problems
+            .iter()
+            .filter_map(|p| p.remote())
+            .copied()
+            .collect()
+    }
+
+    /// Whether any collaborative object failed to load.
+    pub fn has_corrupted_cobs(&self) -> bool {
+        self.problems
+            .iter()
+            .any(|p| matches!(p, Problem::Cob { .. }))
+    }
+}
+
+/// Audit a repository, checking the identity, the signed refs of every remote, the history of
+/// every ref and every collaborative object.
+pub fn audit(repo: &Repository) -> Result<Report, Error> {
+    let mut report = Report {
+        rid: repo.id,
+        remotes: 0,
+        refs: 0,
+        cobs: 0,
+        problems: Vec::new(),
+    };
+
+    if let Err(e) = repo.identity_doc() {
+        report.problems.push(Problem::Identity {
+            error: e.to_string(),
+        });
+    }
+
+    let mut seen = HashSet::new();
+    let mut remotes = BTreeSet::new();
+    let mut cobs = BTreeSet::new();
+
+    for r in repo.backend.references()? {
+        let r = r?;
+        let Some(refname) = r.name().and_then(|n| git::RefString::try_from(n).ok()) else {
+            continue;
+        };
+        // N.b. we don't use `remote_ids`, since it skips remotes without signed refs.
+        let remote = git::parse_ref_namespaced::<RemoteId>(refname.as_str())
+            .ok()
+            .map(|(remote, _)| remote);
+        let oid = r.target().map(Oid::from);
+
+        report.refs += 1;
+        remotes.extend(remote);
+
+        let result = r
+            .resolve()
+            .and_then(|r| r.peel(git2::ObjectType::Any))
+            .and_then(|o| reachable(&repo.backend, o, &mut seen));
+
+        if let Err(e) = result {
+            report.problems.push(Problem::Object {
+                remote,
+                refname: refname.clone(),
+                oid,
+                error: e.message().to_owned(),
+            });
+            continue;
+        }
+        if let Some(cob) = cob::object::parse_refstr(&refname) {
+            cobs.insert(cob);
+        }
+    }
+    report.remotes = remotes.len();
+
+    for remote in remotes {
+        match repo.remote(&remote) {
+            Ok(r) => {
+                for v in repo.validate_remote(&r)? {
+                    report.problems.push(Problem::Validation {
+                        remote,
+                        error: v.to_string(),
+                    });
+                }
+            }
+            Err(e) => {
+                let error = match RefsAt::new(repo, remote) {
+                    Err(e) if git::is_not_found_err(&e) => {
+                        Validation::MissingRadSigRefs(remote).to_string()
+                    }
+                    _ => e.to_string(),
+                };
+                report.problems.push(Problem::Sigrefs { remote, error });
+            }
+        }
+    }
+    report.cobs = cobs.len();
+
+    for (type_name, id) in cobs {
+        let error = match cob::get::<NonEmpty<cob::Entry>, _>(repo, &type_name, &id) {
+            Ok(Some(_)) => continue,
+            Ok(None) => String::from("no valid root entry found"),
+            Err(e) => e.to_string(),
+        };
+        report.problems.push(Problem::Cob {
+            type_name,
+            id,
+            error,
+        });
+    }
+    Ok(report)
+}
+
+/// Remove the remotes that have problems, except for the local one, which we can't recover,
+/// and those of delegates, which the repository can't do without. Returns the removed remotes.
+///
+/// The removed remotes can be fetched again from seeds.
+pub fn prune(
+    repo: &Repository,
+    local: &RemoteId,
+    report: &Report,
+) -> Result<Vec<RemoteId>, RepositoryError> {
+    let delegates = repo
+        .delegates()?
+        .into_iter()
+        .map(|did| *did)
+        .collect::<BTreeSet<_>>();
+    let mut removed = Vec::new();
+
+    for remote in report.corrupted() {
+        // N.b. like with `Repository::clean`, it is fatal to delete local or delegates.
+        if remote == *local || delegates.contains(&remote) {
+            continue;
+        }
+        repo.remove_remote(&remote)?;
+        removed.push(remote);
+    }
+    Ok(removed)
+}
+
+/// Check that the history of the given object is complete, ie. that every commit, tree and
+/// blob it refers to is present. Objects in `seen` are skipped, and the objects walked are
+/// only added to it if the history is complete.
+fn reachable(
+    repo: &git2::Repository,
+    object: git2::Object,
+    seen: &mut HashSet<git2::Oid>,
+) -> Result<(), git2::Error> {
+    let odb = repo.odb()?;
+    let mut stack = vec![(object.id(), object.kind())];
+    let mut visited = Vec::new();
+
+    while let Some((oid, kind)) = stack.pop() {
+        if !seen.insert(oid) {
+            continue;
+        }
+        visited.push(oid);
+
+        if let Err(e) = children(repo, &odb, oid, kind, &mut stack) {
+            // Any of the objects walked so far may lead to the missing object, so none of them
+            // can be skipped when checking other refs.
+            for oid in visited {
+                seen.remove(&oid);
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Check that an object is present, and push the objects it refers to onto the stack.
+fn children(
+    repo: &git2::Repository,
+    odb: &git2::Odb,
+    oid: git2::Oid,
+    kind: Option<git2::ObjectType>,
+    stack: &mut Vec<(git2::Oid, Option<git2::ObjectType>)>,
+) -> Result<(), git2::Error> {
+    use git2::ObjectType;
+
+    match kind {
+        Some(ObjectType::Commit) => {
+            let commit = repo.find_commit(oid)?;
+
+            stack.push((commit.tree_id(), Some(ObjectType::Tree)));
+            stack.extend(commit.parent_ids().map(|id| (id, Some(ObjectType::Commit))));
+        }
+        Some(ObjectType::Tree) => {
+            let tree = repo.find_tree(oid)?;
+
+            for entry in tree.iter() {
+                // N.b. submodule commits are not part of this repository.
+                if let kind @ Some(ObjectType::Tree | ObjectType::Blob) = entry.kind() {
+                    stack.push((entry.id(), kind));
+                }
+            }
+        }
+        Some(ObjectType::Tag) => {
+            let tag = repo.find_tag(oid)?;
+            stack.push((tag.target_id(), tag.target_type()));
+        }
+        // Blobs are complete once found. We only read their header, to avoid loading
+        // their contents.
+        _ => {
+            odb.read_header(oid)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::node::device::Device;
+    use crate::storage::git::{transport, Storage};
+    use crate::storage::ReadStorage;
+    use crate::test::{arbitrary, fixtures};
+
+    #[test]
+    fn test_audit() {
+        let tmp = tempfile::tempdir().unwrap();
+        let signer = Device::mock();
+        let local = *signer.public_key();
+        let storage = Storage::open(tmp.path().join("storage"), fixtures::user()).unwrap();
+
+        transport::local::register(storage.clone());
+
+        let (rid, _, _, _) =
+            fixtures::project(tmp.path().join("project"), &storage, &signer).unwrap();
+        let repo = storage.repository(rid).unwrap();
+        let head = repo.backend.head().unwrap().target().unwrap();
+        let report = audit(&repo).unwrap();
+
+        assert!(report.is_ok(), "{:?}", report.problems);
+        assert_eq!(report.remotes, 1);
+        assert_eq!(report.cobs, 1);
+
+        // A remote without signed refs.
+        let remote = arbitrary::gen::<RemoteId>(1);
+        repo.backend
+            .reference(
+                &format!("refs/namespaces/{remote}/refs/heads/master"),
+                head,
+                false,
+                "test",
+            )
+            .unwrap();
+        // An unsigned ref of the local remote.
+        repo.backend
+            .reference(
+                &format!("refs/namespaces/{local}/refs/heads/unsigned"),
+                head,
+                false,
+                "test",
+            )
+            .unwrap();
+
+        let report = audit(&repo).unwrap();
+        assert_eq!(report.remotes, 2);
+        assert_eq!(report.corrupted(), BTreeSet::from([local, remote]));
+        assert!(report.problems.contains(&Problem::Validation {
+            remote: local,
+            error: String::from("found unsigned ref `refs/heads/unsigned`"),
+        }));
+        assert!(report
+            .problems
+            .iter()
+            .any(|p| matches!(p, Problem::Sigrefs { remote: r, .. } if *r == remote)));
+
+        // The local remote is never pruned.
+        assert_eq!(prune(&repo, &local, &report).unwrap(), vec![remote]);
+
+        let report = audit(&repo).unwrap();
+        assert_eq!(report.corrupted(), BTreeSet::from([local]));
+
+        // A ref pointing to a missing object.
+        let missing = arbitrary::oid();
+        fs::write(
+            repo.path().join("refs/heads/dangling"),
+            format!("{missing}\n"),
+        )
+        .unwrap();
+
+        let report = audit(&repo).unwrap();
+        assert!(report.problems.iter().any(|p| matches!(
+            p,
+            Problem::Object { remote: None, oid, .. } if *oid == Some(missing)
+        )));
+
+        // A commit whose tree refers to a missing blob.
+        let blob = repo.backend.blob(b"lost").unwrap();
+        let mut tree = repo.backend.treebuilder(None).unwrap();
+        tree.insert("LOST", blob, git2::FileMode::Blob.into())
+            .unwrap();
+        let tree = repo.backend.find_tree(tree.write().unwrap()).unwrap();
+        let sig = git2::Signature::now("anonymous", "anonymous@radicle.xyz").unwrap();
+        let commit = repo
+            .backend
+            .commit(Some("refs/heads/lost"), &sig, &sig, "Lost", &tree, &[])
+            .unwrap();
+        let hex = blob.to_string();
+        fs::remove_file(repo.path().join("objects").join(&hex[..2]).join(&hex[2..])).unwrap();
+
+        let report = audit(&repo).unwrap();
+        assert!(report.problems.iter().any(|p| matches!(
+            p,
+            Problem::Object { remote: None, oid, .. } if *oid == Some(commit.into())
+        )));
+    }
+
+    #[test]
+    fn test_audit_shared_missing_commit() {
+        let tmp = tempfile::tempdir().unwrap();
+        let signer = Device::mock();
+        let storage = Storage::open(tmp.path().join("storage"), fixtures::user()).unwrap();
+
+        transport::local::register(storage.clone());
+
+        let (rid, _, _, _) =
+            fixtures::project(tmp.path().join("project"), &storage, &signer).unwrap();
+        let repo = storage.repository(rid).unwrap();
+        let head = repo.backend.head().unwrap().peel_to_commit().unwrap();
+
+        // A commit whose parent is missing, shared by two remotes.
+        let sig = git2::Signature::now("anonymous", "anonymous@radicle.xyz").unwrap();
+        let tree = head.tree().unwrap();
+        let parent = repo
+            .backend
+            .commit(None, &sig, &sig, "Lost", &tree, &[&head])
+            .unwrap();
+        let parent = repo.backend.find_commit(parent).unwrap();
+        let child = repo
+            .backend
+            .commit(None, &sig, &sig, "Orphaned", &tree, &[&parent])
+            .unwrap();
+        let hex = parent.id().to_string();
+        fs::remove_file(repo.path().join("objects").join(&hex[..2]).join(&hex[2..])).unwrap();
+
+        let alice = arbitrary::gen::<RemoteId>(1);
+        let bob = arbitrary::gen::<RemoteId>(1);
+        for remote in [alice, bob] {
+            repo.backend
+                .reference(
+                    &format!("refs/namespaces/{remote}/refs/heads/master"),
+                    child,
+                    false,
+                    "test",
+                )
+                .unwrap();
+        }
+
+        // N.b. the repository is re-opened, since libgit2 caches the objects it has loaded.
+        let repo = storage.repository(rid).unwrap();
+        let report = audit(&repo).unwrap();
+        for remote in [alice, bob] {
+            assert!(report.problems.iter().any(|p| matches!(
+                p,
+                Problem::Object { remote: Some(r), oid, .. } if *r == remote && *oid == Some(child.into())
+            )));
+        }
+    }
+
+    #[test]
+    fn test_prune_keeps_delegates() {
+        let tmp = tempfile::tempdir().unwrap();
+        let signer = Device::mock();
+        let delegate = *signer.public_key();
+        let storage = Storage::open(tmp.path().join("storage"), fixtures::user()).unwrap();
+
+        transport::local::register(storage.clone());
+
+        let (rid, _, _, _) =
+            fixtures::project(tmp.path().join("project"), &storage, &signer).unwrap();
+        let repo = storage.repository(rid).unwrap();
+        let head = repo.backend.head().unwrap().target().unwrap();
+
+        // Corrupt the delegate's remote, as seen from another node.
+        let local = arbitrary::gen::<RemoteId>(1);
+        repo.backend
+            .reference(
+                &format!("refs/namespaces/{delegate}/refs/heads/unsigned"),
+                head,
+                false,
+                "test",
+            )
+            .unwrap();
+
+        let report = audit(&repo).unwrap();
+        assert_eq!(report.corrupted(), BTreeSet::from([delegate]));
+        assert_eq!(prune(&repo, &local, &report).unwrap(), vec![]);
+        assert!(repo.remote(&delegate).is_ok());
+    }
+}
@@ -0,0 +1,399 @@
+//! Mirroring of repositories to and from plain Git remotes.
+//!
+//! The canonical branches and tags of a repository, ie. its top-level `refs/heads` and
+//! `refs/tags`, can be pushed to a Git remote for users and tools that don't run Radicle.
+//! Conversely, branches and tags can be imported from an upstream Git remote into the local
+//! namespace, and published from there.
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::{io, thread, time};
+
+use crypto::signature::Signer;
+use thiserror::Error;
+
+use crate::git;
+use crate::node::device::Device;
+use crate::storage::{ReadRepository, RefUpdate, SignRepository};
+
+use super::{Repository, RepositoryError};
+
+/// Refspecs of the canonical refs pushed to a mirror.
+///
+/// N.b. the mirror follows the canonical refs, so updates are forced.
+pub const PUSH_REFSPECS: [&str; 2] = ["+refs/heads/*:refs/heads/*", "+refs/tags/*:refs/tags/*"];
+/// Default time a push to a mirror may take before it is aborted.
+pub const DEFAULT_PUSH_TIMEOUT: time::Duration = time::Duration::from_secs(10 * 60);
+/// Default time a fetch from an upstream Git remote may take before it is aborted.
+pub const DEFAULT_IMPORT_TIMEOUT: time::Duration = time::Duration::from_secs(10 * 60);
+/// Prefix under which the refs of an upstream remote are staged while importing them.
+const STAGING_PREFIX: &str = "refs/tmp/mirror";
+
+/// Environment of the `git` processes we spawn. Since mirroring can happen in the background,
+/// we never want to prompt for credentials.
+const GIT_ENV: [(&str, &str); 1] = [("GIT_TERMINAL_PROMPT", "0")];
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("git: {0}")]
+    Git(#[from] io::Error),
+    #[error(transparent)]
+    Raw(#[from] git::raw::Error),
+    #[error(transparent)]
+    Storage(#[from] super::Error),
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+/// Result of an import from an upstream Git remote.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Import {
+    /// Updates to the local namespace, with ref names relative to the namespace.
+    pub updates: Vec<RefUpdate>,
+    /// Upstream refs that weren't imported, because they would have rewritten the history of a
+    /// local branch, or moved a local tag.
+    pub rejected: Vec<git::RefString>,
+}
+
+/// Push the canonical branches and tags of the repository to the Git remote at `url`.
+/// Returns the names of the refs that were updated on the remote.
+///
+/// The push is aborted if it doesn't complete within `timeout`.
+pub fn push(
+    repo: &Repository,
+    url: &str,
+    timeout: time::Duration,
+) -> Result<Vec<git::RefString>, Error> {
+    // N.b. the URL is passed after `--`, so that it is never parsed as an option.
+    let mut args = vec!["push", "--porcelain", "--", url];
+    args.extend(PUSH_REFSPECS);
+
+    let output = run(repo.path(), args, timeout)?;
+    let mut pushed = Vec::new();
+
+    // Lines of the porcelain output are of the form `<flag> \t <from>:<to> \t <summary>`.
+    for line in output.lines() {
+        let mut fields = line.split('\t');
+        let (Some(flag), Some(refs)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        // Refs that were already up to date are not reported.
+        if flag == "=" {
+            continue;
+        }
+        if let Some(name) = refs
+            .split_once(':')
+            .and_then(|(_, to)| git::RefString::try_from(to).ok())
+        {
+            pushed.push(name);
+        }
+    }
+    Ok(pushed)
+}
+
+/// Import the branches and tags of the upstream Git remote at `url` into the namespace of the
+/// given device, and sign the refs if anything changed.
+///
+/// Upstream refs are only imported if they are new, or if they fast-forward a local branch.
+/// Other refs are left untouched and reported as rejected. The canonical refs are not updated;
+/// see [`crate::storage::WriteRepository::set_head`].
+///
+/// The fetch is aborted if it doesn't complete within `timeout`.
+pub fn import<G>(
+    repo: &Repository,
+    url: &str,
+    device: &Device<G>,
+    timeout: time::Duration,
+) -> Result<Import, Error>
+where
+    G: Signer<crypto::Signature>,
+{
+    let remote = device.public_key();
+    let staging = format!("{STAGING_PREFIX}/{remote}");
+    let before = repo.references_of(remote)?;
+    let heads = format!("+refs/heads/*:{staging}/refs/heads/*");
+    let tags = format!("+refs/tags/*:{staging}/refs/tags/*");
+
+    let result = run(
+        repo.path(),
+        [
+            "fetch",
+            "--no-tags",
+            "--no-write-fetch-head",
+            "--",
+            url,
+            heads.as_str(),
+            tags.as_str(),
+        ],
+        timeout,
+    )
+    .map_err(Error::from)
+    .and_then(|_| apply(repo, remote, &staging));
+
+    // N.b. the staged refs are removed whether or not the import succeeded.
+    let staged = repo
+        .backend
+        .references_glob(&format!("{staging}/*"))?
+        .filter_map(|r| r.ok().and_then(|r| r.name().map(String::from)))
+        .collect::<Vec<_>>();
+    for name in staged {
+        repo.backend.find_reference(&name)?.delete()?;
+    }
+    let rejected = result?;
+    let after = repo.references_of(remote)?;
+    let updates = before.diff(&after, repo);
+
+    if !updates.is_empty() {
+        repo.sign_refs(device)?;
+    }
+    Ok(Import { updates, rejected })
+}
+
+/// Update the namespace of `remote` with the refs staged under `staging`, as long as they are
+/// new or fast-forwards. Returns the names of the rejected refs.
+fn apply(
+    repo: &Repository,
+    remote: &crypto::PublicKey,
+    staging: &str,
+) -> Result<Vec<git::RefString>, Error> {
+    let mut rejected = Vec::new();
+
+    for r in repo.backend.references_glob(&format!("{staging}/*"))? {
+        let r = r?;
+        let (Some(staged), Some(new)) = (r.name(), r.target()) else {
+            continue;
+        };
+        let Some(name) = staged
+            .strip_prefix(staging)
+            .and_then(|n| n.strip_prefix('/'))
+            .and_then(|n| git::RefString::try_from(n).ok())
+        else {
+            continue;
+        };
+        let target = format!("refs/namespaces/{remote}/{name}");
+
+        match repo.backend.refname_to_id(&target) {
+            Ok(old) if old == new => {}
+            Ok(old)
+                if name.starts_with("refs/heads/")
+                    && repo.backend.graph_descendant_of(new, old)? =>
+            {
+                repo.backend.reference_matching(
+                    &target,
+                    new,
+                    true,
+                    old,
+                    "radicle: fast-forward from mirror",
+                )?;
+            }
+            Ok(_) => rejected.push(name),
+            Err(e) if git::is_not_found_err(&e) => {
+                repo.backend
+                    .reference(&target, new, false, "radicle: import from mirror")?;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(rejected)
+}
+
+/// Run a `git` command in the given repository, and kill it if it doesn't exit within
+/// `timeout`. Returns the standard output of the command.
+fn run<'a>(
+    repo: &Path,
+    args: impl IntoIterator<Item = &'a str>,
+    timeout: time::Duration,
+) -> Result<String, io::Error> {
+    let mut child = Command::new("git")
+        .current_dir(repo)
+        .envs(GIT_ENV)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let deadline = time::Instant::now() + timeout;
+    // Nb. the outputs are read on their own threads, so that the child never blocks on a full
+    // pipe while we wait for it.
+    let stdout = child.stdout.take().map(reader);
+    let stderr = child.stderr.take().map(reader);
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if time::Instant::now() >= deadline {
+            child.kill().ok();
+            child.wait().ok();
+
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("git did not complete within {}s", timeout.as_secs()),
+            ));
+        }
+        thread::sleep(time::Duration::from_millis(100));
+    };
+    let output = |out: Option<thread::JoinHandle<String>>| {
+        out.and_then(|h| h.join().ok()).unwrap_or_default()
+    };
+
+    if status.success() {
+        Ok(output(stdout))
+    } else {
+        Err(io::Error::other(output(stderr)))
+    }
+}
+
+/// Read a process output to the end, on a separate thread.
+fn reader(mut out: impl io::Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buf = String::new();
+        out.read_to_string(&mut buf).ok();
+        buf
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::git::url;
+    use crate::storage::git::transport;
+    use crate::storage::refs::SignedRefsAt;
+    use crate::storage::{ReadStorage, WriteRepository};
+    use crate::test::fixtures;
+    use crate::Storage;
+
+    #[test]
+    fn test_push_import() {
+        let tmp = tempfile::tempdir().unwrap();
+        let signer = Device::mock();
+        let storage = Storage::open(tmp.path().join("storage"), fixtures::user()).unwrap();
+
+        transport::local::register(storage.clone());
+
+        let (rid, _, _, head) =
+            fixtures::project(tmp.path().join("project"), &storage, &signer).unwrap();
+        let repo = storage.repository(rid).unwrap();
+        let mirror = git2::Repository::init_bare(tmp.path().join("mirror.git")).unwrap();
+        let url = url::File::new(mirror.path()).to_string();
+
+        repo.set_head().unwrap();
+
+        let pushed = push(&repo, &url, DEFAULT_PUSH_TIMEOUT).unwrap();
+        assert_eq!(pushed, vec![git::refname!("refs/heads/master")]);
+        assert_eq!(mirror.refname_to_id("refs/heads/master").unwrap(), head);
+        // Nothing to push the second time.
+        assert_eq!(push(&repo, &url, DEFAULT_PUSH_TIMEOUT).unwrap(), vec![]);
+
+        // A new branch is created upstream.
+        let commit = mirror.find_commit(head).unwrap();
+        let sig = git2::Signature::now("anonymous", "anonymous@radicle.xyz").unwrap();
+        let feature = mirror
+            .commit(
+                Some("refs/heads/feature"),
+                &sig,
+                &sig,
+                "Feature",
+                &commit.tree().unwrap(),
+                &[&commit],
+            )
+            .unwrap();
+
+        let imported = import(&repo, &url, &signer, DEFAULT_IMPORT_TIMEOUT).unwrap();
+        assert_eq!(
+            imported.updates,
+            vec![RefUpdate::Created {
+                name: git::refname!("refs/heads/feature"),
+                oid: feature.into(),
+            }]
+        );
+        assert_eq!(imported.rejected, vec![]);
+
+        // The imported branch is signed.
+        let sigrefs = SignedRefsAt::load(*signer.public_key(), &repo)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sigrefs.head(git::refname!("feature")), Some(feature.into()));
+        assert_eq!(
+            import(&repo, &url, &signer, DEFAULT_IMPORT_TIMEOUT).unwrap(),
+            Import::default()
+        );
+        // No staged refs are left behind.
+        assert_eq!(
+            repo.backend
+                .references_glob(&format!("{STAGING_PREFIX}/*"))
+                .unwrap()
+                .count(),
+            0
+        );
+
+        // The upstream branch is rewritten, and fast-forwarded.
+        let rewrite = mirror
+            .commit(
+                None,
+                &sig,
+                &sig,
+                "Rewrite",
+                &commit.tree().unwrap(),
+                &[&commit],
+            )
+            .unwrap();
+        mirror
+            .reference("refs/heads/feature", rewrite, true, "test")
+            .unwrap();
+        let rewritten = mirror.find_commit(rewrite).unwrap();
+        let forward = mirror
+            .commit(
+                None,
+                &sig,
+                &sig,
+                "Forward",
+                &commit.tree().unwrap(),
+                &[&rewritten],
+            )
+            .unwrap();
+        mirror
+            .reference("refs/heads/master", forward, true, "test")
+            .unwrap();
+
+        // Only the fast-forward is imported.
+        let imported = import(&repo, &url, &signer, DEFAULT_IMPORT_TIMEOUT).unwrap();
+        assert_eq!(
+            imported.updates,
+            vec![RefUpdate::Updated {
+                name: git::refname!("refs/heads/master"),
+                old: head.into(),
+                new: forward.into(),
+            }]
+        );
+        assert_eq!(imported.rejected, vec![git::refname!("refs/heads/feature")]);
+
+        let sigrefs = SignedRefsAt::load(*signer.public_key(), &repo)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sigrefs.head(git::refname!("feature")), Some(feature.into()));
+    }
+
+    #[test]
+    fn test_url_is_not_an_option() {
+        let tmp = tempfile::tempdir().unwrap();
+        let signer = Device::mock();
+        let storage = Storage::open(tmp.path().join("storage"), fixtures::user()).unwrap();
+
+        transport::local::register(storage.clone());
+
+        let (rid, _, _, _) =
+            fixtures::project(tmp.path().join("project"), &storage, &signer).unwrap();
+        let repo = storage.repository(rid).unwrap();
+        let marker = tmp.path().join("marker");
+
+        repo.set_head().unwrap();
+
+        let url = format!("--receive-pack=touch {}", marker.display());
+        assert!(push(&repo, &url, DEFAULT_PUSH_TIMEOUT).is_err());
+
+        let url = format!("--upload-pack=touch {}", marker.display());
+        assert!(import(&repo, &url, &signer, DEFAULT_IMPORT_TIMEOUT).is_err());
+        assert!(!marker.exists());
+    }
+}
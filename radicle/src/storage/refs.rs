@@ -112,6 +112,47 @@ impl Refs {
         self.0.get(&branch).copied()
     }
 
+    /// Compute the ref updates going from these refs to the `new` ones.
+    ///
+    /// Updates to a target that doesn't descend from the previous one are
+    /// [`storage::RefUpdate::Forced`]. If either object is missing from `repo`,
+    /// the update is assumed to be a fast-forward.
+    pub fn diff<R: ReadRepository>(&self, new: &Refs, repo: &R) -> Vec<storage::RefUpdate> {
+        use storage::RefUpdate;
+
+        let mut updates = Vec::new();
+
+        for (name, oid) in new.iter() {
+            let name = name.clone();
+            let update = match self.0.get(&name) {
+                None => RefUpdate::Created { name, oid: *oid },
+                Some(old) if old == oid => continue,
+                Some(old) if repo.is_ancestor_of(*old, *oid).unwrap_or(true) => {
+                    RefUpdate::Updated {
+                        name,
+                        old: *old,
+                        new: *oid,
+                    }
+                }
+                Some(old) => RefUpdate::Forced {
+                    name,
+                    old: *old,
+                    new: *oid,
+                },
+            };
+            updates.push(update);
+        }
+        for (name, oid) in self.iter() {
+            if !new.contains_key(name) {
+                updates.push(RefUpdate::Deleted {
+                    name: name.clone(),
+                    oid: *oid,
+                });
+            }
+        }
+        updates
+    }
+
     /// Create refs from a canonical representation.
     pub fn from_canonical(bytes: &[u8]) -> Result<Self, canonical::Error> {
         let reader = BufReader::new(bytes);
@@ -434,11 +475,59 @@ impl SignedRefsAt {
         })
     }
 
+    /// Load the [`SignedRefs`] that these ones replaced, ie. the ones found
+    /// at the parent commit of [`SignedRefsAt::at`].
+    ///
+    /// This will return `None` if this is the first `rad/sigrefs` commit.
+    pub fn parent<S>(&self, repo: &S) -> Result<Option<Self>, Error>
+    where
+        S: ReadRepository,
+    {
+        let commit = repo.commit(self.at)?;
+        let Some(parent) = commit.parent_ids().next() else {
+            return Ok(None);
+        };
+        Self::load_at(parent.into(), self.sigrefs.id, repo).map(Some)
+    }
+
+    /// Iterate over the history of these [`SignedRefs`], starting with
+    /// these, and following the parent chain back to the first commit.
+    pub fn history<S>(self, repo: &S) -> History<'_, S>
+    where
+        S: ReadRepository,
+    {
+        History {
+            next: Some(Ok(self)),
+            repo,
+        }
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (&git::RefString, &Oid)> {
         self.sigrefs.refs.iter()
     }
 }
 
+/// The history of a remote's `rad/sigrefs`, newest first.
+///
+/// Construct using [`SignedRefsAt::history`].
+pub struct History<'a, S> {
+    next: Option<Result<SignedRefsAt, Error>>,
+    repo: &'a S,
+}
+
+impl<S: ReadRepository> Iterator for History<'_, S> {
+    type Item = Result<SignedRefsAt, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+
+        if let Ok(current) = &current {
+            self.next = current.parent(self.repo).transpose();
+        }
+        Some(current)
+    }
+}
+
 impl Deref for SignedRefsAt {
     type Target = SignedRefs<Verified>;
 
@@ -656,4 +745,48 @@ mod tests {
             if local == london_rid && remote == paris_rid
         );
     }
+
+    #[test]
+    fn test_history() {
+        let tmp = tempfile::tempdir().unwrap();
+        let alice = Device::mock();
+        let storage = &Storage::open(tmp.path().join("storage"), fixtures::user()).unwrap();
+
+        transport::local::register(storage.clone());
+
+        let (rid, _, _, _) =
+            fixtures::project(tmp.path().join("project"), storage, &alice).unwrap();
+        let repo = storage.repository_mut(rid).unwrap();
+        let first = SignedRefsAt::load(*alice.public_key(), &repo)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(first.parent(&repo).unwrap(), None);
+
+        // Add a branch and sign the refs again.
+        let head = repo.raw().head().unwrap().target().unwrap();
+        repo.raw()
+            .reference(
+                git::refs::storage::branch_of(alice.public_key(), &git::refname!("feature"))
+                    .as_str(),
+                head,
+                false,
+                "",
+            )
+            .unwrap();
+        repo.sign_refs(&alice).unwrap();
+
+        let second = SignedRefsAt::load(*alice.public_key(), &repo)
+            .unwrap()
+            .unwrap();
+        assert_ne!(first.at, second.at);
+        assert_eq!(second.parent(&repo).unwrap(), Some(first.clone()));
+
+        let history = second
+            .clone()
+            .history(&repo)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(history, vec![second, first]);
+    }
 }
@@ -211,6 +211,10 @@ impl ReadRepository for MockRepository {
         todo!()
     }
 
+    fn canonical_reference(&self, _name: &fmt::Qualified) -> Result<Oid, RepositoryError> {
+        todo!()
+    }
+
     fn path(&self) -> &std::path::Path {
         todo!()
     }
@@ -330,6 +334,10 @@ impl WriteRepository for MockRepository {
         todo!()
     }
 
+    fn set_canonical_refs(&self) -> Result<Vec<RefUpdate>, RepositoryError> {
+        todo!()
+    }
+
     fn set_identity_head_to(&self, _commit: Oid) -> Result<(), RepositoryError> {
         todo!()
     }